@@ -0,0 +1,10 @@
+fn main() {
+    // Delay-load wpcap.dll so the service starts on hosts without Npcap and
+    // falls back to Winsock (see net_pcap::npcap_available)
+    if std::env::var("CARGO_CFG_TARGET_OS").as_deref() == Ok("windows")
+        && std::env::var("CARGO_CFG_TARGET_ENV").as_deref() == Ok("msvc")
+    {
+        println!("cargo:rustc-link-arg-bin=dantesync=/DELAYLOAD:wpcap.dll");
+        println!("cargo:rustc-link-arg-bin=dantesync=delayimp.lib");
+    }
+}
@@ -0,0 +1,26 @@
+//! Feed arbitrary bytes to every parser of untrusted network/capture input.
+//! Run with: cargo +nightly fuzz run ptp_parse
+
+#![no_main]
+
+use dantesync::capture::parse_pcap;
+use dantesync::ptp::{
+    PtpV1FollowUpBody, PtpV1Header, PtpV1SyncMessageBody, PtpV2Header, PtpV2PdelayReqBody,
+    PtpV2PdelayRespBody,
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(header) = PtpV1Header::parse(data) {
+        let _ = header.message_type;
+        let _ = PtpV1SyncMessageBody::parse(PtpV1Header::body(data));
+        let _ = PtpV1FollowUpBody::parse(PtpV1Header::body(data))
+            .map(|b| b.precise_origin_timestamp.to_nanos());
+    }
+    if PtpV2Header::parse(data).is_ok() {
+        let body = data.get(PtpV2Header::SIZE..).unwrap_or(&[]);
+        let _ = PtpV2PdelayReqBody::parse(body);
+        let _ = PtpV2PdelayRespBody::parse(body);
+    }
+    let _ = parse_pcap(data);
+});
@@ -607,6 +607,8 @@ $BinPath = "`"$ExePath`" --service"
 
 try {
     New-Service -Name $ServiceName -BinaryPathName $BinPath -DisplayName "DanteSync" -StartupType Automatic -Description "Synchronizes system time with Dante PTP Master"
+    # Restart automatically if the service exits (e.g. watchdog detected a stalled sync loop)
+    sc.exe failure $ServiceName reset= 86400 actions= restart/5000/restart/5000/restart/30000 | Out-Null
 } catch {
     Write-Error "Failed to create service. Ensure you are running as Administrator. Error: $_"
 }
@@ -111,9 +111,16 @@ Wants=network-online.target
 # Run as root for port 319, adjtimex, and RTC ioctl access
 User=root
 Group=root
+# READY is sent once PTP sync is established (system.ready_timeout_secs caps the wait)
+Type=notify
+TimeoutStartSec=180
 ExecStart=/usr/local/bin/dantesync
+# SIGHUP reloads config.json and reopens log files without dropping sync
+ExecReload=/bin/kill -HUP \$MAINPID
 Restart=always
 RestartSec=5
+# Restart if the sync loop stops making progress (pinged by the watchdog thread)
+WatchdogSec=120
 # High priority for timestamping accuracy
 CPUSchedulingPolicy=fifo
 CPUSchedulingPriority=50
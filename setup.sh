@@ -69,9 +69,15 @@ Wants=network-online.target
 [Service]
 User=root
 Group=root
+# READY is sent once PTP sync is established (system.ready_timeout_secs caps the wait)
+Type=notify
+TimeoutStartSec=180
 ExecStart=$INSTALL_DIR/$TARGET_BIN --ntp-server $NTP_SERVER
+ExecReload=/bin/kill -HUP \$MAINPID
 Restart=always
 RestartSec=5
+# Restart if the sync loop stops making progress (pinged by the watchdog thread)
+WatchdogSec=120
 # Realtime Priority
 CPUSchedulingPolicy=fifo
 CPUSchedulingPriority=50
@@ -0,0 +1,216 @@
+//! PTP-only test harness - replays captured packets through the real controller
+//!
+//! Feeds a capture (DanteSync JSON dump or classic pcap) into `PtpController`
+//! with a recording mock clock, so servo behaviour for a field capture can be
+//! reproduced and bisected without Dante hardware.
+//!
+//! Packets are paced in real time using the captured receive timestamps
+//! because the servo measures its interval with `Instant`. Replay is
+//! open-loop: the recorded timestamps already contain whatever corrections the
+//! original machine applied, so the printed adjustments show what this build
+//! would have commanded, not a closed-loop simulation.
+
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use dantesync::capture::read_capture_file;
+use dantesync::clock::SystemClock;
+use dantesync::config::SystemConfig;
+use dantesync::controller::PtpController;
+use dantesync::error::{self, DanteSyncError};
+use dantesync::simulate::ReplayNetwork;
+use dantesync::status::{format_duration, SyncStatus};
+use dantesync::traits::NtpSource;
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+#[derive(Parser, Debug)]
+#[command(about = "Replay captured PTP packets through the sync controller")]
+struct Args {
+    /// Capture file (.json from DanteSync, otherwise read as pcap)
+    capture: PathBuf,
+
+    /// Optional JSON file with a "system" config section to replay with
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// Print every frequency adjustment, not just mode changes
+    #[arg(long, short)]
+    verbose: bool,
+}
+
+#[derive(Debug, Clone)]
+enum ClockEvent {
+    Frequency(f64),
+    Step(Duration, i8),
+}
+
+/// Mock clock that records every adjustment instead of touching the OS
+struct RecordingClock {
+    events: Rc<RefCell<Vec<(Instant, ClockEvent)>>>,
+}
+
+impl SystemClock for RecordingClock {
+    fn adjust_frequency(&mut self, factor: f64) -> error::Result<()> {
+        self.events
+            .borrow_mut()
+            .push((Instant::now(), ClockEvent::Frequency(factor)));
+        Ok(())
+    }
+
+    fn step_clock(&mut self, offset: Duration, sign: i8) -> error::Result<()> {
+        self.events
+            .borrow_mut()
+            .push((Instant::now(), ClockEvent::Step(offset, sign)));
+        Ok(())
+    }
+}
+
+/// NTP is never available during replay
+struct NoNtp;
+
+impl NtpSource for NoNtp {
+    fn get_offset(&self) -> error::Result<(Duration, i8)> {
+        Err(DanteSyncError::Ntp("NTP disabled in replay".to_string()))
+    }
+}
+
+fn load_config(path: Option<&PathBuf>) -> Result<SystemConfig> {
+    let mut config = match path {
+        Some(p) => {
+            let value: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(p)?)?;
+            let system = value.get("system").cloned().unwrap_or(value);
+            serde_json::from_value(system)?
+        }
+        None => SystemConfig::default(),
+    };
+    // Captured timestamps are already calibrated - skip the live-only phases
+    config.filters.calibration_samples = 0;
+    config.filters.warmup_secs = 0.0;
+    config.ntp_tracking = false;
+    Ok(config)
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+
+    let mut packets = read_capture_file(&args.capture)?;
+    packets.sort_by_key(|p| p.timestamp);
+    let first_ts = packets
+        .first()
+        .map(|p| p.timestamp)
+        .ok_or_else(|| anyhow!("No PTP packets in {:?}", args.capture))?;
+    let span = packets
+        .last()
+        .unwrap()
+        .timestamp
+        .duration_since(first_ts)
+        .unwrap_or(Duration::ZERO);
+    println!(
+        "=== PTP Replay: {} packets over {:.1}s from {:?} ===\n",
+        packets.len(),
+        span.as_secs_f64(),
+        args.capture
+    );
+
+    let config = load_config(args.config.as_ref())?;
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let started = Instant::now();
+
+    let network = ReplayNetwork::new(packets);
+    let clock = RecordingClock {
+        events: events.clone(),
+    };
+    let status = Arc::new(RwLock::new(SyncStatus::default()));
+    let mut controller = PtpController::new(clock, network, NoNtp, status.clone(), config);
+    controller.set_ntp_tracking(false);
+
+    println!(
+        "{:>8}  {:<12} {:>6} {:>12} {:>12}",
+        "Time(s)", "Mode", "Lock", "Drift(ppm)", "Rate(us/s)"
+    );
+    println!("{}", "-".repeat(56));
+
+    let mut last_mode = String::new();
+    let mut last_locked = false;
+    let mut printed_events = 0;
+
+    loop {
+        controller.process_loop_iteration()?;
+
+        if args.verbose {
+            let events = events.borrow();
+            for (at, event) in &events[printed_events..] {
+                let t = at.duration_since(started).as_secs_f64();
+                match event {
+                    ClockEvent::Frequency(f) => {
+                        println!("{:>8.2}  adjust {:+.3} ppm", t, (f - 1.0) * 1e6)
+                    }
+                    ClockEvent::Step(d, sign) => {
+                        println!(
+                            "{:>8.2}  step {}{:?}",
+                            t,
+                            if *sign < 0 { "-" } else { "+" },
+                            d
+                        )
+                    }
+                }
+            }
+            printed_events = events.len();
+        }
+
+        if let Ok(s) = status.read() {
+            if s.mode != last_mode || s.is_locked != last_locked {
+                println!(
+                    "{:>8.2}  {:<12} {:>6} {:>12.3} {:>12.2}",
+                    started.elapsed().as_secs_f64(),
+                    s.mode,
+                    if s.is_locked { "yes" } else { "no" },
+                    s.drift_ppm,
+                    s.smoothed_rate_ppm
+                );
+                last_mode = s.mode.clone();
+                last_locked = s.is_locked;
+            }
+        }
+
+        if controller.network().remaining() == 0 {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    let events = events.borrow();
+    let freq_count = events
+        .iter()
+        .filter(|(_, e)| matches!(e, ClockEvent::Frequency(_)))
+        .count();
+    let last_ppm = events.iter().rev().find_map(|(_, e)| match e {
+        ClockEvent::Frequency(f) => Some((f - 1.0) * 1e6),
+        _ => None,
+    });
+    let final_status = status.read().map(|s| s.clone()).unwrap_or_default();
+
+    println!("\n=== Summary ===");
+    println!("Final mode:        {}", final_status.mode);
+    println!("Locked:            {}", final_status.is_locked);
+    println!("Unlocks:           {}", controller.unlock_count());
+    for (label, settle) in [
+        ("Time to lock:     ", controller.time_to_lock()),
+        ("Time to NANO:     ", controller.time_to_nano()),
+    ] {
+        match settle {
+            Some(d) => println!("{} {}", label, format_duration(d.as_secs())),
+            None => println!("{} not reached", label),
+        }
+    }
+    println!("Freq adjustments:  {}", freq_count);
+    println!("Clock steps:       {}", events.len() - freq_count);
+    match last_ppm {
+        Some(ppm) => println!("Last adjustment:   {:+.3} ppm", ppm),
+        None => println!("Last adjustment:   none"),
+    }
+    Ok(())
+}
@@ -7,12 +7,18 @@ fn main() {
 
 #[cfg(windows)]
 mod app {
+    use dantesync::config::NotificationConfig;
+    use dantesync::status::{
+        format_duration, format_mac, instance_suffix, ipc_pipe_name, validate_instance_name,
+        IpcCommand, IpcEncoding, NetworkQuality, SyncStatus, STATUS_SCHEMA_VERSION,
+    };
     use serde::Deserialize;
-    use std::cell::RefCell;
+    use std::cell::{Cell, RefCell};
+    use std::collections::HashMap;
     use std::sync::atomic::{AtomicBool, Ordering};
     use std::sync::Arc;
-    use std::time::Duration;
-    use tokio::io::AsyncReadExt;
+    use std::time::{Duration, Instant};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
     use tokio::net::windows::named_pipe::ClientOptions;
     use tray_icon::{
         menu::{Menu, MenuEvent, MenuItem},
@@ -25,6 +31,26 @@ mod app {
     use winit::event_loop::{ControlFlow, EventLoopBuilder};
     use winrt_notification::{Sound, Toast};
 
+    // ========================================================================
+    // SERVICE INSTANCE - Which dantesync --instance-name this tray follows
+    // ========================================================================
+
+    /// `--instance-name <name>` (or `=<name>`), else DANTESYNC_INSTANCE.
+    /// An invalid name falls back to the default instance.
+    fn instance_name() -> Option<String> {
+        let mut args = std::env::args().skip(1);
+        let mut name = None;
+        while let Some(arg) = args.next() {
+            if arg == "--instance-name" {
+                name = args.next();
+            } else if let Some(value) = arg.strip_prefix("--instance-name=") {
+                name = Some(value.to_string());
+            }
+        }
+        name.or_else(|| std::env::var("DANTESYNC_INSTANCE").ok())
+            .filter(|name| validate_instance_name(name).is_ok())
+    }
+
     // ========================================================================
     // SINGLE INSTANCE CHECK - Prevent multiple tray apps
     // ========================================================================
@@ -34,10 +60,14 @@ mod app {
     }
 
     impl SingleInstanceGuard {
-        /// Try to acquire single-instance lock. Returns None if another instance is running.
-        fn try_acquire() -> Option<Self> {
+        /// Try to acquire single-instance lock (one tray per service instance).
+        /// Returns None if another tray for the same instance is running.
+        fn try_acquire(instance: Option<&str>) -> Option<Self> {
             unsafe {
-                let mutex_name: Vec<u16> = "Global\\DanteSyncTrayMutex\0".encode_utf16().collect();
+                let mutex_name: Vec<u16> =
+                    format!("Global\\DanteSyncTrayMutex{}\0", instance_suffix(instance))
+                        .encode_utf16()
+                        .collect();
                 let handle = CreateMutexW(None, false, PCWSTR(mutex_name.as_ptr()));
 
                 match handle {
@@ -66,34 +96,6 @@ mod app {
         }
     }
 
-    // ========================================================================
-    // SYNC STATUS - Extended struct matching service
-    // ========================================================================
-
-    #[derive(Deserialize, Debug, Clone, Default)]
-    struct SyncStatus {
-        // Core fields
-        pub offset_ns: i64,
-        pub drift_ppm: f64,
-        #[serde(rename = "gm_uuid")]
-        pub _gm_uuid: Option<[u8; 6]>,
-        pub settled: bool,
-        #[serde(rename = "updated_ts")]
-        pub _updated_ts: u64,
-
-        // Extended fields for tray app
-        #[serde(default)]
-        pub is_locked: bool,
-        #[serde(default)]
-        pub smoothed_rate_ppm: f64,
-        #[serde(default)]
-        pub ntp_offset_us: i64,
-        #[serde(default)]
-        pub mode: String,
-        #[serde(default)]
-        pub ntp_failed: bool,
-    }
-
     // ========================================================================
     // GITHUB RELEASE - For version check
     // ========================================================================
@@ -146,6 +148,42 @@ mod app {
             .show();
     }
 
+    // ========================================================================
+    // IPC COMMANDS - Runtime controls without elevation
+    // ========================================================================
+
+    /// Send a command to the service over the status pipe.
+    ///
+    /// The service writes a status frame on every connection; it is drained
+    /// after writing the command so the service never blocks on a full pipe.
+    fn send_command(command: IpcCommand) -> std::io::Result<()> {
+        use std::io::{Read, Write};
+
+        let mut pipe = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(ipc_pipe_name(instance_name().as_deref()))?;
+        let bytes = serde_json::to_vec(&command)?;
+        pipe.write_all(&(bytes.len() as u32).to_le_bytes())?;
+        pipe.write_all(&bytes)?;
+
+        let mut len_buf = [0u8; 4];
+        pipe.read_exact(&mut len_buf)?;
+        let mut status = vec![0u8; u32::from_le_bytes(len_buf) as usize];
+        pipe.read_exact(&mut status)?;
+        Ok(())
+    }
+
+    /// Send a command off the UI thread; report failures as a notification
+    fn send_command_async(command: IpcCommand) {
+        std::thread::spawn(move || {
+            if let Err(e) = send_command(command) {
+                // ACCESS_DENIED: service predates the command channel
+                show_notification("DanteSync", &format!("Command failed: {}", e));
+            }
+        });
+    }
+
     // ========================================================================
     // VERSION CHECK - GitHub API
     // ========================================================================
@@ -165,6 +203,28 @@ mod app {
         Ok(release.tag_name)
     }
 
+    /// Toast categories, each switchable in `system.notifications`
+    #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+    enum NotifyCategory {
+        Ntp,
+        Ptp,
+        Service,
+        Interference,
+        Updates,
+    }
+
+    /// Notification settings from the service's config.json (defaults if
+    /// missing or unreadable). Read once at startup.
+    fn load_notification_config() -> NotificationConfig {
+        std::fs::read_to_string(r"C:\ProgramData\DanteSync\config.json")
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+            .and_then(|config| {
+                serde_json::from_value(config["system"]["notifications"].clone()).ok()
+            })
+            .unwrap_or_default()
+    }
+
     /// Track previous state for detecting transitions
     #[derive(Default)]
     struct NotificationState {
@@ -174,6 +234,35 @@ mod app {
         was_ptp_offline: bool,
         was_ntp_failed: bool,
         first_update: bool,
+        warned_schema: bool,
+        was_interference: bool,
+        config: NotificationConfig,
+        last_notified: HashMap<NotifyCategory, Instant>,
+    }
+
+    impl NotificationState {
+        /// Show a toast unless its category is disabled or already notified
+        /// within `min_interval_secs` (stops storms on a flapping network)
+        fn notify(&mut self, category: NotifyCategory, message: &str) {
+            let enabled = match category {
+                NotifyCategory::Ntp => self.config.ntp,
+                NotifyCategory::Ptp => self.config.ptp,
+                NotifyCategory::Service => self.config.service,
+                NotifyCategory::Interference => self.config.interference,
+                NotifyCategory::Updates => self.config.updates,
+            };
+            if !enabled {
+                return;
+            }
+            let min_interval = Duration::from_secs(self.config.min_interval_secs);
+            if let Some(last) = self.last_notified.get(&category) {
+                if last.elapsed() < min_interval {
+                    return;
+                }
+            }
+            self.last_notified.insert(category, Instant::now());
+            show_notification("DanteSync", message);
+        }
     }
 
     // ========================================================================
@@ -277,7 +366,8 @@ mod app {
 
     pub fn main() {
         // Single-instance check - exit silently if another instance is running
-        let _guard = match SingleInstanceGuard::try_acquire() {
+        let instance = instance_name();
+        let _guard = match SingleInstanceGuard::try_acquire(instance.as_deref()) {
             Some(guard) => guard,
             None => {
                 // Another instance is already running - exit silently
@@ -296,15 +386,24 @@ mod app {
 
         let status_i = MenuItem::new("Status: Connecting...", false, None);
         let mode_i = MenuItem::new("Mode: --", false, None);
+        let ptp_i = MenuItem::new("PTP: --", false, None);
 
         // Service control
         let restart_i = MenuItem::new("Restart Service", true, None);
         let start_stop_i = MenuItem::new("Stop Service", true, None);
 
+        // Runtime controls (IPC commands - no elevation)
+        let ntp_sync_i = MenuItem::new("Force NTP Sync", true, None);
+        let recalibrate_i = MenuItem::new("Recalibrate", true, None);
+        let reset_servo_i = MenuItem::new("Reset Servo", true, None);
+        let ntp_tracking_i = MenuItem::new("Disable NTP Tracking", true, None);
+        let pause_i = MenuItem::new("Pause Clock Corrections", true, None);
+
         // Utilities
         let log_i = MenuItem::new("Open Log File", true, None);
         let live_log_i = MenuItem::new("View Live Log", true, None);
         let config_i = MenuItem::new("Edit Configuration", true, None);
+        let capture_i = MenuItem::new("Save Packet Capture", true, None);
 
         // Upgrade - disabled until new version detected
         let upgrade_i = MenuItem::new("Check for Updates...", true, None);
@@ -314,15 +413,24 @@ mod app {
         let menu = Menu::new();
         menu.append(&status_i).unwrap();
         menu.append(&mode_i).unwrap();
+        menu.append(&ptp_i).unwrap();
         menu.append(&tray_icon::menu::PredefinedMenuItem::separator())
             .unwrap();
         menu.append(&restart_i).unwrap();
         menu.append(&start_stop_i).unwrap();
+        menu.append(&tray_icon::menu::PredefinedMenuItem::separator())
+            .unwrap();
+        menu.append(&ntp_sync_i).unwrap();
+        menu.append(&recalibrate_i).unwrap();
+        menu.append(&reset_servo_i).unwrap();
+        menu.append(&ntp_tracking_i).unwrap();
+        menu.append(&pause_i).unwrap();
         menu.append(&tray_icon::menu::PredefinedMenuItem::separator())
             .unwrap();
         menu.append(&log_i).unwrap();
         menu.append(&live_log_i).unwrap();
         menu.append(&config_i).unwrap();
+        menu.append(&capture_i).unwrap();
         menu.append(&tray_icon::menu::PredefinedMenuItem::separator())
             .unwrap();
         menu.append(&upgrade_i).unwrap();
@@ -348,6 +456,7 @@ mod app {
         ));
 
         // Spawn status poller thread
+        let pipe_name = ipc_pipe_name(instance.as_deref());
         std::thread::spawn(move || {
             let rt = tokio::runtime::Builder::new_current_thread()
                 .enable_all()
@@ -356,14 +465,13 @@ mod app {
 
             rt.block_on(async move {
                 loop {
-                    // Server pipe is outbound-only (Server -> Client).
-                    // We must open as read-only, otherwise we get ACCESS_DENIED.
-                    match ClientOptions::new()
-                        .write(false)
-                        .read(true)
-                        .open(r"\\.\pipe\dantesync")
-                    {
+                    // Status polling only writes an encoding request; commands
+                    // use a separate connection (see send_command).
+                    match ClientOptions::new().write(true).read(true).open(&pipe_name) {
                         Ok(mut client) => {
+                            // The service streams the status every second while
+                            // connected; the first frame is always JSON (see IpcEncoding)
+                            let mut negotiated = false;
                             loop {
                                 let mut len_buf = [0u8; 4];
                                 if client.read_exact(&mut len_buf).await.is_err() {
@@ -375,9 +483,20 @@ mod app {
                                     break;
                                 }
 
-                                match serde_json::from_slice::<SyncStatus>(&buf) {
+                                match IpcEncoding::decode_status(&buf) {
                                     Ok(status) => {
+                                        // Same schema on both sides: ask for the
+                                        // compact encoding (older services just close)
+                                        let request = !negotiated
+                                            && status.schema_version == STATUS_SCHEMA_VERSION;
                                         let _ = proxy.send_event(AppEvent::Update(status));
+                                        if request {
+                                            negotiated = true;
+                                            let frame = [1, 0, 0, 0, IpcEncoding::LATEST as u8];
+                                            if client.write_all(&frame).await.is_err() {
+                                                break;
+                                            }
+                                        }
                                     }
                                     Err(e) => {
                                         eprintln!(
@@ -388,7 +507,8 @@ mod app {
                                     }
                                 }
                             }
-                            // Connection closed by server (one-shot). Sleep before reconnecting to prevent UI freeze.
+                            // Connection closed (service restarting, or an older one-shot
+                            // service). Sleep before reconnecting to prevent UI freeze.
                             tokio::time::sleep(Duration::from_secs(1)).await;
                         }
                         Err(_) => {
@@ -453,7 +573,13 @@ mod app {
             was_ptp_offline: false,
             was_ntp_failed: false,
             first_update: true,
+            warned_schema: false,
+            was_interference: false,
+            config: load_notification_config(),
+            last_notified: HashMap::new(),
         });
+        // Last reported pause state, so the menu item sends Pause or Resume
+        let service_paused = Cell::new(false);
 
         event_loop.run(move |event, elwt| {
             elwt.set_control_flow(ControlFlow::Wait);
@@ -471,29 +597,30 @@ mod app {
                                 let is_nano = status.mode == "NANO";
                                 let is_ptp_offline = status.mode == "NTP-only";
 
-                                // Check for state changes (skip first update)
-                                if !state.first_update {
+                                // Check for state changes (skip first update, and
+                                // the transient right after an NTP clock step)
+                                if !state.first_update && !status.resettling {
                                     // NTP failure transitions (critical)
                                     if status.ntp_failed && !state.was_ntp_failed {
-                                        show_notification(
-                                            "DanteSync",
+                                        state.notify(
+                                            NotifyCategory::Ntp,
                                             "NTP server unreachable"
                                         );
                                     } else if !status.ntp_failed && state.was_ntp_failed {
-                                        show_notification(
-                                            "DanteSync",
+                                        state.notify(
+                                            NotifyCategory::Ntp,
                                             "NTP connection restored"
                                         );
                                     }
                                     // PTP offline transitions (highest priority)
                                     else if is_ptp_offline && !state.was_ptp_offline {
-                                        show_notification(
-                                            "DanteSync",
+                                        state.notify(
+                                            NotifyCategory::Ptp,
                                             "PTP offline - running NTP-only sync"
                                         );
                                     } else if !is_ptp_offline && state.was_ptp_offline {
-                                        show_notification(
-                                            "DanteSync",
+                                        state.notify(
+                                            NotifyCategory::Ptp,
                                             "PTP restored - resuming PTP sync"
                                         );
                                     }
@@ -502,19 +629,39 @@ mod app {
 
                                     // Service came online
                                     if !state.was_online {
-                                        show_notification(
-                                            "DanteSync",
+                                        state.notify(
+                                            NotifyCategory::Service,
                                             "Service connected"
                                         );
                                     }
                                 }
 
-                                state.was_locked = status.is_locked;
-                                state.was_nano = is_nano;
-                                state.was_ptp_offline = is_ptp_offline;
-                                state.was_ntp_failed = status.ntp_failed;
-                                state.was_online = true;
-                                state.first_update = false;
+                                // Another process fighting over the clock adjustment
+                                if status.external_clock_interference && !state.was_interference {
+                                    state.notify(
+                                        NotifyCategory::Interference,
+                                        "Clock adjustment reset by another process (W32Time?)"
+                                    );
+                                }
+                                state.was_interference = status.external_clock_interference;
+
+                                // Service speaks a newer status schema - fields may be missing
+                                if status.is_newer_schema() && !state.warned_schema {
+                                    state.notify(
+                                        NotifyCategory::Updates,
+                                        "Service is newer than this tray app - please update your tray app"
+                                    );
+                                    state.warned_schema = true;
+                                }
+
+                                if !status.resettling {
+                                    state.was_locked = status.is_locked;
+                                    state.was_nano = is_nano;
+                                    state.was_ptp_offline = is_ptp_offline;
+                                    state.was_ntp_failed = status.ntp_failed;
+                                    state.was_online = true;
+                                    state.first_update = false;
+                                }
                             }
 
                             // ================================================
@@ -522,8 +669,13 @@ mod app {
                             // ================================================
 
                             // Calculate pulse intensity from drift rate (0-1 range)
-                            // Higher drift rate = more visible ring
-                            let pulse_intensity = (status.smoothed_rate_ppm.abs() / 20.0).min(1.0) as f32;
+                            // Higher drift rate = more visible ring. Right after an
+                            // NTP step the rate is a transient: show no ring
+                            let pulse_intensity = if status.resettling {
+                                0.0
+                            } else {
+                                (status.smoothed_rate_ppm.abs() / 20.0).min(1.0) as f32
+                            };
 
                             // Check if update is available for badge
                             let has_update = update_available.load(Ordering::Relaxed);
@@ -535,7 +687,11 @@ mod app {
                                 generate_icon_full(255, 152, 0, 0.0, has_update)
                             } else if is_nano {
                                 // NANO mode: Cyan - ultra-precise sync
-                                let nano_pulse = (status.smoothed_rate_ppm.abs() / 5.0).min(1.0) as f32;
+                                let nano_pulse = if status.resettling {
+                                    0.0
+                                } else {
+                                    (status.smoothed_rate_ppm.abs() / 5.0).min(1.0) as f32
+                                };
                                 generate_icon_full(0, 188, 212, nano_pulse, has_update)
                             } else if status.is_locked {
                                 // Locked: Green with optional ring if there's drift
@@ -552,19 +708,88 @@ mod app {
                             // STATUS TEXT
                             // ================================================
 
-                            let mode_str = if status.mode.is_empty() {
-                                if status.is_locked { "LOCK" } else { "ACQ" }
+                            let mode_str = status.mode_str();
+
+                            // Drift rate display (rate of change, not absolute offset)
+                            let drift_str = if status.resettling {
+                                "re-settling after NTP step".to_string()
                             } else {
-                                &status.mode
+                                format!("{:+.1}us/s", status.display_rate())
                             };
 
-                            // Drift rate display (rate of change, not absolute offset)
-                            let drift_str = format!("{:+.1}us/s", status.smoothed_rate_ppm);
+                            // Lock duration for SLA visibility ("Locked for 2h13m")
+                            let lock_str = if status.is_locked {
+                                format!("Locked for {}", format_duration(status.locked_duration_secs))
+                            } else {
+                                "Not locked".to_string()
+                            };
 
-                            let tooltip = format!(
-                                "DanteSync v{}\nMode: {} | Drift: {}\nFreq Adj: {:+.1}ppm\nNTP Offset: {:+}us",
-                                version, mode_str, drift_str, status.drift_ppm, status.ntp_offset_us
+                            let mut tooltip = format!(
+                                "DanteSync v{}\n{}\n{} ({} unlocks)",
+                                version, status.summary(), lock_str, status.unlock_count
                             );
+                            if status.offset_jitter_ns > 0 {
+                                tooltip.push_str(&format!(
+                                    "\nOffset jitter: {:.1}us",
+                                    status.offset_jitter_ns as f64 / 1000.0
+                                ));
+                            }
+                            if let Some(secs) = status.time_to_lock_secs {
+                                tooltip.push_str(&format!("\nTime to lock: {}", format_duration(secs)));
+                            }
+                            if status.network_quality != NetworkQuality::Good {
+                                tooltip.push_str(&format!(
+                                    "\nNetwork: {} (check PTP QoS)",
+                                    status.network_quality.as_str()
+                                ));
+                            }
+                            if status.paused {
+                                tooltip.push_str("\nClock corrections PAUSED");
+                            }
+                            if status.leap_pending {
+                                tooltip.push_str("\nLeap second at UTC midnight");
+                            }
+                            if status.rejected_sync_pairs > 0 {
+                                tooltip.push_str(&format!(
+                                    "\nRejected sync pairs: {}",
+                                    status.rejected_sync_pairs
+                                ));
+                            }
+                            if status.external_clock_interference {
+                                tooltip.push_str("\nClock interference detected");
+                            }
+                            if status.gm_mismatch {
+                                tooltip.push_str("\nUNEXPECTED GRANDMASTER - not syncing");
+                            }
+                            if status.oscillator_fault {
+                                tooltip.push_str("\nOSCILLATOR FAULT - beyond max_healthy_ppm");
+                            }
+                            if status.master_epoch_changed {
+                                tooltip.push_str("\nGrandmaster restarted (epoch changed)");
+                            }
+                            if status.is_newer_schema() {
+                                tooltip.push_str("\nUpdate your tray app");
+                            }
+
+                            // Grandmaster and immediate sender differ behind a boundary clock
+                            let ptp_text = match (status.gm_uuid, status.sync_source_uuid) {
+                                (Some(gm), Some(src)) if gm != src => {
+                                    format!("GM: {} via {}", format_mac(&gm), format_mac(&src))
+                                }
+                                (Some(gm), _) => format!("GM: {}", format_mac(&gm)),
+                                (None, _) => "GM: --".to_string(),
+                            };
+                            // Vendor is only reported by schema 12+ services
+                            let ptp_text = match (status.gm_uuid, status.schema_version >= 12) {
+                                (Some(_), true) if status.master_is_dante => format!("{} (Dante)", ptp_text),
+                                (Some(_), true) => format!("{} (third-party)", ptp_text),
+                                _ => ptp_text,
+                            };
+                            let ptp_text = if status.observed_sync_interval_ms > 0 {
+                                format!("{} | Sync {}ms", ptp_text, status.observed_sync_interval_ms)
+                            } else {
+                                ptp_text
+                            };
 
                             let status_text = format!("{} | Drift: {}", mode_str, drift_str);
                             let mode_text = format!("Mode: {} | Adj: {:+.1}ppm", mode_str, status.drift_ppm);
@@ -575,17 +800,32 @@ mod app {
                             }
                             status_i.set_text(status_text);
                             mode_i.set_text(mode_text);
+                            ptp_i.set_text(ptp_text);
+                            ntp_tracking_i.set_text(if status.ntp_tracking {
+                                "Disable NTP Tracking"
+                            } else {
+                                "Enable NTP Tracking"
+                            });
+                            service_paused.set(status.paused);
+                            pause_i.set_text(if status.paused {
+                                "Resume Clock Corrections"
+                            } else {
+                                "Pause Clock Corrections"
+                            });
                             // Service is running - show Stop option
                             start_stop_i.set_text("Stop Service".to_string());
                             restart_i.set_enabled(true);
+                            for item in [&ntp_sync_i, &recalibrate_i, &reset_servo_i, &ntp_tracking_i, &pause_i] {
+                                item.set_enabled(true);
+                            }
                         }
                         AppEvent::Offline => {
                             // Check if we were online before
                             {
                                 let mut state = notification_state.borrow_mut();
                                 if state.was_online {
-                                    show_notification(
-                                        "DanteSync",
+                                    state.notify(
+                                        NotifyCategory::Service,
                                         "Service offline"
                                     );
                                 }
@@ -603,17 +843,21 @@ mod app {
                             }
                             status_i.set_text("Service Offline".to_string());
                             mode_i.set_text("--".to_string());
+                            ptp_i.set_text("PTP: --".to_string());
                             // Service is stopped - show Start option
                             start_stop_i.set_text("Start Service".to_string());
                             restart_i.set_enabled(false);
+                            for item in [&ntp_sync_i, &recalibrate_i, &reset_servo_i, &ntp_tracking_i, &pause_i] {
+                                item.set_enabled(false);
+                            }
                         }
                         AppEvent::NewVersionAvailable(new_version) => {
                             // Update menu item text to show available version
                             upgrade_i.set_text(format!("Upgrade to {}", new_version));
 
                             // Show notification
-                            show_notification(
-                                "DanteSync",
+                            notification_state.borrow_mut().notify(
+                                NotifyCategory::Updates,
                                 &format!("New version {} available", new_version)
                             );
                         }
@@ -644,6 +888,20 @@ mod app {
                                     .args(["-Command", "Start-Process powershell -Verb RunAs -ArgumentList '-Command','Start-Service dantesync'"])
                                     .spawn();
                             }
+                        } else if event.id == ntp_sync_i.id() {
+                            send_command_async(IpcCommand::ForceNtpSync);
+                        } else if event.id == recalibrate_i.id() {
+                            send_command_async(IpcCommand::Recalibrate);
+                        } else if event.id == reset_servo_i.id() {
+                            send_command_async(IpcCommand::ResetServo);
+                        } else if event.id == ntp_tracking_i.id() {
+                            send_command_async(IpcCommand::ToggleNtpTracking);
+                        } else if event.id == pause_i.id() {
+                            send_command_async(if service_paused.get() {
+                                IpcCommand::Resume
+                            } else {
+                                IpcCommand::Pause
+                            });
                         } else if event.id == log_i.id() {
                             let _ = std::process::Command::new("notepad.exe")
                                 .arg(r"C:\ProgramData\DanteSync\dantesync.log")
@@ -656,6 +914,13 @@ mod app {
                             let _ = std::process::Command::new("notepad.exe")
                                 .arg(r"C:\ProgramData\DanteSync\config.json")
                                 .spawn();
+                        } else if event.id == capture_i.id() {
+                            // Service polls for this file and dumps its packet ring
+                            // (requires system.capture.packets > 0 in config)
+                            let _ = std::fs::write(
+                                r"C:\ProgramData\DanteSync\capture.trigger",
+                                b"",
+                            );
                         } else if event.id == upgrade_i.id() {
                             // Run upgrade via PowerShell IRM (Invoke-RestMethod)
                             // This downloads and executes the install script from GitHub
@@ -0,0 +1,587 @@
+//! Ring-buffer packet capture for post-mortem debugging
+//!
+//! Keeps the last N raw PTP packets with their receive timestamps so a one-off
+//! glitch at a live event can be analyzed later. `CapturingNetwork` wraps any
+//! `PtpNetwork` and records every packet it returns; the ring is dumped to a
+//! JSON file on demand (SIGUSR1, tray menu, or unlock). `CaptureDumper`
+//! writes the dumps from a background thread so the sync loop only pays for
+//! copying the ring.
+//!
+//! File format (version 1):
+//! `{"version":1,"packets":[{"ts_ns":1700000000123456789,"data":"10020000..."}]}`
+//! where `ts_ns` is the receive time in ns since the Unix epoch and `data` is
+//! the hex-encoded UDP payload.
+//!
+//! `read_pcap` also loads classic pcap files (tcpdump/Wireshark) so field
+//! captures taken without DanteSync can be replayed too.
+
+use crate::error::{DanteSyncError, Result};
+use crate::traits::PtpNetwork;
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const CAPTURE_FORMAT_VERSION: u32 = 1;
+
+/// Minimum spacing of automatic dumps on unlock: a flapping lock must not
+/// fill the disk with near-identical captures
+pub const UNLOCK_DUMP_MIN_INTERVAL: Duration = Duration::from_secs(60);
+
+/// One received packet and its receive timestamp
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedPacket {
+    pub timestamp: SystemTime,
+    pub data: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CaptureRecord {
+    ts_ns: u64,
+    data: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CaptureFile {
+    version: u32,
+    packets: Vec<CaptureRecord>,
+}
+
+/// Fixed-capacity buffer of the most recent packets (capacity 0 = disabled)
+#[derive(Debug, Clone)]
+pub struct PacketRing {
+    capacity: usize,
+    packets: VecDeque<CapturedPacket>,
+}
+
+impl PacketRing {
+    pub fn new(capacity: usize) -> Self {
+        PacketRing {
+            capacity,
+            packets: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub fn push(&mut self, data: &[u8], timestamp: SystemTime) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.packets.len() >= self.capacity {
+            self.packets.pop_front();
+        }
+        self.packets.push_back(CapturedPacket {
+            timestamp,
+            data: data.to_vec(),
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.packets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.packets.is_empty()
+    }
+
+    /// Packets oldest first
+    pub fn packets(&self) -> impl Iterator<Item = &CapturedPacket> {
+        self.packets.iter()
+    }
+
+    /// Write the buffer to `path` as JSON (see module docs for the format)
+    pub fn write_json(&self, path: &Path) -> Result<()> {
+        let file = CaptureFile {
+            version: CAPTURE_FORMAT_VERSION,
+            packets: self
+                .packets
+                .iter()
+                .map(|p| CaptureRecord {
+                    ts_ns: p
+                        .timestamp
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_nanos() as u64,
+                    data: hex_encode(&p.data),
+                })
+                .collect(),
+        };
+        if let Some(dir) = path.parent() {
+            if !dir.as_os_str().is_empty() {
+                std::fs::create_dir_all(dir)?;
+            }
+        }
+        std::fs::write(path, serde_json::to_string(&file)?)?;
+        Ok(())
+    }
+}
+
+/// Read a capture file written by `PacketRing::write_json`
+pub fn read_capture(path: &Path) -> Result<Vec<CapturedPacket>> {
+    let file: CaptureFile = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    if file.version != CAPTURE_FORMAT_VERSION {
+        return Err(DanteSyncError::Parse(format!(
+            "Unsupported capture version {} (expected {})",
+            file.version, CAPTURE_FORMAT_VERSION
+        )));
+    }
+    file.packets
+        .into_iter()
+        .map(|r| {
+            Ok(CapturedPacket {
+                timestamp: UNIX_EPOCH + Duration::from_nanos(r.ts_ns),
+                data: hex_decode(&r.data)?,
+            })
+        })
+        .collect()
+}
+
+/// Read a DanteSync capture (`.json`) or, for any other extension, a pcap
+pub fn read_capture_file(path: &Path) -> Result<Vec<CapturedPacket>> {
+    let is_json = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("json"));
+    if is_json {
+        read_capture(path)
+    } else {
+        read_pcap(path)
+    }
+}
+
+/// Load PTP payloads (UDP 319/320) from a classic pcap file
+///
+/// Supports Ethernet (with optional 802.1Q tag), raw IPv4 and Linux cooked
+/// captures, µs or ns timestamps, either byte order. pcapng is not supported.
+pub fn read_pcap(path: &Path) -> Result<Vec<CapturedPacket>> {
+    parse_pcap(&std::fs::read(path)?)
+}
+
+const LINKTYPE_ETHERNET: u32 = 1;
+const LINKTYPE_RAW: u32 = 101;
+const LINKTYPE_LINUX_SLL: u32 = 113;
+const LINKTYPE_IPV4: u32 = 228;
+
+/// Parse an in-memory classic pcap file (see `read_pcap`)
+pub fn parse_pcap(bytes: &[u8]) -> Result<Vec<CapturedPacket>> {
+    if bytes.len() < 24 {
+        return Err(DanteSyncError::Parse(
+            "File too short for a pcap header".to_string(),
+        ));
+    }
+    let magic = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    let (big_endian, nanos) = match magic {
+        0xa1b2c3d4 => (false, false),
+        0xa1b23c4d => (false, true),
+        0xd4c3b2a1 => (true, false),
+        0x4d3cb2a1 => (true, true),
+        0x0a0d0d0a => {
+            return Err(DanteSyncError::Parse(
+                "pcapng is not supported; save as classic pcap".to_string(),
+            ))
+        }
+        _ => {
+            return Err(DanteSyncError::Parse(format!(
+                "Not a pcap file (magic {:08x})",
+                magic
+            )))
+        }
+    };
+    let read_u32 = |b: &[u8]| -> u32 {
+        let arr = [b[0], b[1], b[2], b[3]];
+        if big_endian {
+            u32::from_be_bytes(arr)
+        } else {
+            u32::from_le_bytes(arr)
+        }
+    };
+    let linktype = read_u32(&bytes[20..24]);
+
+    let mut packets = Vec::new();
+    let mut pos = 24;
+    while pos + 16 <= bytes.len() {
+        let ts_sec = read_u32(&bytes[pos..]) as u64;
+        let ts_frac = read_u32(&bytes[pos + 4..]) as u64;
+        let incl_len = read_u32(&bytes[pos + 8..]) as usize;
+        pos += 16;
+        if pos + incl_len > bytes.len() {
+            break; // Truncated final record
+        }
+        let frame = &bytes[pos..pos + incl_len];
+        pos += incl_len;
+
+        let ip = match linktype {
+            LINKTYPE_ETHERNET => ethernet_payload(frame),
+            LINKTYPE_RAW | LINKTYPE_IPV4 => Some(frame),
+            LINKTYPE_LINUX_SLL if frame.len() >= 16 && frame[14..16] == [0x08, 0x00] => {
+                Some(&frame[16..])
+            }
+            LINKTYPE_LINUX_SLL => None,
+            other => {
+                return Err(DanteSyncError::Parse(format!(
+                    "Unsupported pcap link type {}",
+                    other
+                )))
+            }
+        };
+        if let Some(payload) = ip.and_then(ptp_udp_payload) {
+            let frac = if nanos {
+                Duration::from_nanos(ts_frac)
+            } else {
+                Duration::from_micros(ts_frac)
+            };
+            packets.push(CapturedPacket {
+                timestamp: UNIX_EPOCH + Duration::from_secs(ts_sec) + frac,
+                data: payload.to_vec(),
+            });
+        }
+    }
+    Ok(packets)
+}
+
+/// IPv4 packet inside an Ethernet frame (skipping one 802.1Q tag)
+fn ethernet_payload(frame: &[u8]) -> Option<&[u8]> {
+    if frame.len() < 14 {
+        return None;
+    }
+    let (ethertype, offset) = match [frame[12], frame[13]] {
+        [0x81, 0x00] if frame.len() >= 18 => ([frame[16], frame[17]], 18),
+        et => (et, 14),
+    };
+    (ethertype == [0x08, 0x00]).then(|| &frame[offset..])
+}
+
+/// UDP payload of an IPv4 packet addressed to PTP port 319 or 320
+fn ptp_udp_payload(ip: &[u8]) -> Option<&[u8]> {
+    if ip.len() < 20 || ip[0] >> 4 != 4 || ip[9] != 17 {
+        return None;
+    }
+    let ihl = (ip[0] & 0x0F) as usize * 4;
+    let udp = ip.get(ihl..)?;
+    if udp.len() < 8 {
+        return None;
+    }
+    let dst_port = u16::from_be_bytes([udp[2], udp[3]]);
+    if dst_port != 319 && dst_port != 320 {
+        return None;
+    }
+    let udp_len = (u16::from_be_bytes([udp[4], udp[5]]) as usize).clamp(8, udp.len());
+    Some(&udp[8..udp_len])
+}
+
+fn hex_encode(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hex_decode(s: &str) -> Result<Vec<u8>> {
+    if !s.is_ascii() || s.len() % 2 != 0 {
+        return Err(DanteSyncError::Parse("Invalid hex string".to_string()));
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&s[i..i + 2], 16)
+                .map_err(|_| DanteSyncError::Parse(format!("Invalid hex at {}", i)))
+        })
+        .collect()
+}
+
+/// A ring snapshot queued for the writer thread
+struct DumpJob {
+    ring: PacketRing,
+    path: PathBuf,
+    reason: String,
+}
+
+/// Dumps the capture ring to `<dir>/dantesync-capture-<time>-<n>.json`
+///
+/// The caller's thread only copies the ring; serialising and writing happen
+/// on a background thread. Dropping the dumper waits for queued dumps.
+pub struct CaptureDumper {
+    ring: Arc<Mutex<PacketRing>>,
+    dir: PathBuf,
+    seq: u64,
+    unlock_interval: Duration,
+    last_unlock_dump: Option<Instant>,
+    tx: Option<Sender<DumpJob>>,
+    writer: Option<JoinHandle<()>>,
+}
+
+impl CaptureDumper {
+    pub fn new(ring: Arc<Mutex<PacketRing>>, dir: PathBuf) -> Self {
+        let (tx, rx) = mpsc::channel::<DumpJob>();
+        let writer = thread::Builder::new()
+            .name("capture".to_string())
+            .spawn(move || {
+                for job in rx {
+                    match job.ring.write_json(&job.path) {
+                        Ok(()) => info!(
+                            "[Capture] Wrote {} packets to {} ({})",
+                            job.ring.len(),
+                            job.path.display(),
+                            job.reason
+                        ),
+                        Err(e) => warn!("[Capture] Failed to write {}: {}", job.path.display(), e),
+                    }
+                }
+            })
+            .ok();
+        CaptureDumper {
+            ring,
+            dir,
+            seq: 0,
+            unlock_interval: UNLOCK_DUMP_MIN_INTERVAL,
+            last_unlock_dump: None,
+            tx: Some(tx),
+            writer,
+        }
+    }
+
+    /// Queue a dump of the current ring; returns the file it is written to
+    pub fn dump(&mut self, reason: &str) -> Option<PathBuf> {
+        let ring = self.ring.lock().ok()?.clone();
+        self.seq += 1;
+        // Millisecond time plus a sequence number: two dumps in the same
+        // second must not overwrite each other
+        let path = self.dir.join(format!(
+            "dantesync-capture-{}-{}.json",
+            chrono::Local::now().format("%Y%m%d-%H%M%S-%3f"),
+            self.seq
+        ));
+        let job = DumpJob {
+            ring,
+            path: path.clone(),
+            reason: reason.to_string(),
+        };
+        self.tx.as_ref()?.send(job).ok()?;
+        Some(path)
+    }
+
+    /// Dump after a LOCK -> UNLOCK transition, at most once per
+    /// `UNLOCK_DUMP_MIN_INTERVAL`
+    pub fn dump_on_unlock(&mut self) -> Option<PathBuf> {
+        if let Some(last) = self.last_unlock_dump {
+            if last.elapsed() < self.unlock_interval {
+                debug!(
+                    "[Capture] Unlock dump skipped: last one was {:?} ago",
+                    last.elapsed()
+                );
+                return None;
+            }
+        }
+        self.last_unlock_dump = Some(Instant::now());
+        self.dump("unlock")
+    }
+}
+
+impl Drop for CaptureDumper {
+    fn drop(&mut self) {
+        // Closing the channel ends the writer once the queue is written
+        self.tx = None;
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+/// `PtpNetwork` wrapper that records every received packet into a shared ring
+pub struct CapturingNetwork<N: PtpNetwork> {
+    inner: N,
+    ring: Arc<Mutex<PacketRing>>,
+}
+
+impl<N: PtpNetwork> CapturingNetwork<N> {
+    pub fn new(inner: N, ring: Arc<Mutex<PacketRing>>) -> Self {
+        CapturingNetwork { inner, ring }
+    }
+}
+
+impl<N: PtpNetwork> PtpNetwork for CapturingNetwork<N> {
+    fn recv_packet(&mut self) -> Result<Option<(Vec<u8>, usize, SystemTime)>> {
+        let packet = self.inner.recv_packet()?;
+        if let Some((buf, size, ts)) = &packet {
+            if let Ok(mut ring) = self.ring.lock() {
+                ring.push(&buf[..*size], *ts);
+            }
+        }
+        Ok(packet)
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        self.inner.reset()
+    }
+
+    fn timestamp_source(&self) -> (String, bool) {
+        self.inner.timestamp_source()
+    }
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::MockPtpNetwork;
+
+    #[test]
+    fn test_ring_keeps_last_n() {
+        let mut ring = PacketRing::new(3);
+        for i in 0..5u8 {
+            ring.push(&[i], UNIX_EPOCH + Duration::from_secs(i as u64));
+        }
+        let kept: Vec<u8> = ring.packets().map(|p| p.data[0]).collect();
+        assert_eq!(kept, vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn test_ring_disabled_with_zero_capacity() {
+        let mut ring = PacketRing::new(0);
+        ring.push(&[1, 2, 3], SystemTime::now());
+        assert!(ring.is_empty());
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("capture.json");
+
+        let mut ring = PacketRing::new(10);
+        let ts = UNIX_EPOCH + Duration::new(1_700_000_000, 123_456_789);
+        ring.push(&[0x10, 0x02, 0xab, 0xff], ts);
+        ring.push(&[0x00], ts + Duration::from_millis(125));
+        ring.write_json(&path).unwrap();
+
+        let packets = read_capture(&path).unwrap();
+        assert_eq!(packets.len(), 2);
+        assert_eq!(packets[0].data, vec![0x10, 0x02, 0xab, 0xff]);
+        assert_eq!(packets[0].timestamp, ts);
+        assert_eq!(packets[1].timestamp, ts + Duration::from_millis(125));
+    }
+
+    #[test]
+    fn test_dumper_writes_unique_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let ring = Arc::new(Mutex::new(PacketRing::new(10)));
+        ring.lock().unwrap().push(&[0x10, 0x02], SystemTime::now());
+
+        let mut dumper = CaptureDumper::new(ring.clone(), dir.path().to_path_buf());
+        // Back to back, well within one second
+        let first = dumper.dump("test").unwrap();
+        ring.lock().unwrap().push(&[0x00], SystemTime::now());
+        let second = dumper.dump("test").unwrap();
+        assert_ne!(first, second);
+        drop(dumper);
+
+        assert_eq!(read_capture(&first).unwrap().len(), 1);
+        assert_eq!(read_capture(&second).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_dumper_rate_limits_unlock_dumps() {
+        let dir = tempfile::tempdir().unwrap();
+        let ring = Arc::new(Mutex::new(PacketRing::new(10)));
+        let mut dumper = CaptureDumper::new(ring, dir.path().to_path_buf());
+
+        assert!(dumper.dump_on_unlock().is_some());
+        assert!(dumper.dump_on_unlock().is_none());
+        // Explicit requests are never rate limited
+        assert!(dumper.dump("SIGUSR1").is_some());
+
+        dumper.unlock_interval = Duration::ZERO;
+        assert!(dumper.dump_on_unlock().is_some());
+        drop(dumper);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 3);
+    }
+
+    #[test]
+    fn test_hex_decode_rejects_invalid() {
+        assert!(hex_decode("abc").is_err());
+        assert!(hex_decode("zz").is_err());
+        assert_eq!(hex_decode("00ff").unwrap(), vec![0x00, 0xff]);
+    }
+
+    /// Build a little-endian µs pcap with one Ethernet/IPv4/UDP frame per payload
+    fn build_pcap(frames: &[(u32, u32, u16, &[u8])]) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&0xa1b2c3d4u32.to_le_bytes());
+        out.extend_from_slice(&2u16.to_le_bytes());
+        out.extend_from_slice(&4u16.to_le_bytes());
+        out.extend_from_slice(&[0u8; 8]); // thiszone, sigfigs
+        out.extend_from_slice(&65535u32.to_le_bytes());
+        out.extend_from_slice(&LINKTYPE_ETHERNET.to_le_bytes());
+
+        for &(sec, usec, port, payload) in frames {
+            let mut frame = vec![0u8; 12];
+            frame.extend_from_slice(&[0x08, 0x00]);
+            let mut ip = vec![0x45, 0, 0, 0, 0, 0, 0, 0, 1, 17, 0, 0];
+            ip.extend_from_slice(&[10, 77, 8, 1, 224, 0, 1, 129]);
+            frame.extend_from_slice(&ip);
+            frame.extend_from_slice(&port.to_be_bytes()); // src port
+            frame.extend_from_slice(&port.to_be_bytes()); // dst port
+            frame.extend_from_slice(&((8 + payload.len()) as u16).to_be_bytes());
+            frame.extend_from_slice(&[0, 0]);
+            frame.extend_from_slice(payload);
+
+            out.extend_from_slice(&sec.to_le_bytes());
+            out.extend_from_slice(&usec.to_le_bytes());
+            out.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+            out.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+            out.extend_from_slice(&frame);
+        }
+        out
+    }
+
+    #[test]
+    fn test_parse_pcap_extracts_ptp_payloads() {
+        let pcap = build_pcap(&[
+            (1_700_000_000, 250_000, 319, &[0x10, 0x01]),
+            (1_700_000_000, 251_000, 320, &[0x10, 0x02, 0x03]),
+            (1_700_000_001, 0, 5004, &[0xff]), // Not PTP (media) - skipped
+        ]);
+        let packets = parse_pcap(&pcap).unwrap();
+        assert_eq!(packets.len(), 2);
+        assert_eq!(packets[0].data, vec![0x10, 0x01]);
+        assert_eq!(
+            packets[0].timestamp,
+            UNIX_EPOCH + Duration::new(1_700_000_000, 250_000_000)
+        );
+        assert_eq!(packets[1].data, vec![0x10, 0x02, 0x03]);
+    }
+
+    #[test]
+    fn test_parse_pcap_rejects_pcapng_and_garbage() {
+        assert!(parse_pcap(&[
+            0x0a, 0x0d, 0x0d, 0x0a, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0
+        ])
+        .is_err());
+        assert!(parse_pcap(b"hello").is_err());
+    }
+
+    #[test]
+    fn test_capturing_network_records_packets() {
+        let mut mock_net = MockPtpNetwork::new();
+        let mut calls = 0;
+        mock_net.expect_recv_packet().returning(move || {
+            calls += 1;
+            if calls == 1 {
+                Ok(Some((vec![1, 2, 3, 0, 0], 3, UNIX_EPOCH)))
+            } else {
+                Ok(None)
+            }
+        });
+
+        let ring = Arc::new(Mutex::new(PacketRing::new(10)));
+        let mut net = CapturingNetwork::new(mock_net, ring.clone());
+        assert!(net.recv_packet().unwrap().is_some());
+        assert!(net.recv_packet().unwrap().is_none());
+
+        let ring = ring.lock().unwrap();
+        assert_eq!(ring.len(), 1);
+        // Only the valid `size` bytes are recorded
+        assert_eq!(ring.packets().next().unwrap().data, vec![1, 2, 3]);
+    }
+}
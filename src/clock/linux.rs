@@ -1,11 +1,49 @@
 use super::SystemClock;
-use anyhow::{anyhow, Result};
+use crate::error::{DanteSyncError, Result};
 use libc::{self, adjtimex, settimeofday, timeval, timex, ADJ_FREQUENCY};
+use log::{debug, warn};
 use std::mem;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+/// How often the measured rate is logged (and the baseline restarted)
+const MEASURE_LOG_INTERVAL: Duration = Duration::from_secs(10);
 
 pub struct LinuxClock {
     original_freq: i64,
+    last_requested_ppm: f64,
+
+    // Measurement baseline (for diagnostics). CLOCK_MONOTONIC_RAW runs on the
+    // bare oscillator, CLOCK_REALTIME includes our adjtimex frequency.
+    baseline_raw_ns: i64,
+    baseline_realtime_ns: i64,
+    last_measurement_time: Instant,
+}
+
+/// Read a POSIX clock as nanoseconds
+fn clock_ns(clock: libc::clockid_t) -> i64 {
+    let mut ts: libc::timespec = unsafe { mem::zeroed() };
+    unsafe { libc::clock_gettime(clock, &mut ts) };
+    ts.tv_sec as i64 * 1_000_000_000 + ts.tv_nsec as i64
+}
+
+/// Rate of CLOCK_REALTIME relative to CLOCK_MONOTONIC_RAW in PPM
+fn observed_ppm(raw_elapsed_ns: i64, realtime_elapsed_ns: i64) -> f64 {
+    if raw_elapsed_ns <= 0 {
+        return 0.0;
+    }
+    (realtime_elapsed_ns - raw_elapsed_ns) as f64 / raw_elapsed_ns as f64 * 1_000_000.0
+}
+
+/// Fraction of the requested adjustment that actually shows up in the clock
+/// rate. With (almost) nothing requested, any rate under 10 PPM counts as 100%.
+fn effectiveness(requested_ppm: f64, observed_ppm: f64) -> f64 {
+    if requested_ppm.abs() > 0.1 {
+        observed_ppm / requested_ppm
+    } else if observed_ppm.abs() < 10.0 {
+        1.0
+    } else {
+        0.0
+    }
 }
 
 impl LinuxClock {
@@ -15,13 +53,67 @@ impl LinuxClock {
 
         let ret = unsafe { adjtimex(&mut tx) };
         if ret < 0 {
-            return Err(anyhow!("adjtimex failed (are you root?)"));
+            return Err(clock_error("adjtimex failed"));
         }
 
         Ok(LinuxClock {
             original_freq: tx.freq,
+            last_requested_ppm: 0.0,
+            baseline_raw_ns: clock_ns(libc::CLOCK_MONOTONIC_RAW),
+            baseline_realtime_ns: clock_ns(libc::CLOCK_REALTIME),
+            last_measurement_time: Instant::now(),
         })
     }
+
+    fn reset_measurement_baseline(&mut self) {
+        self.baseline_raw_ns = clock_ns(libc::CLOCK_MONOTONIC_RAW);
+        self.baseline_realtime_ns = clock_ns(libc::CLOCK_REALTIME);
+        self.last_measurement_time = Instant::now();
+    }
+
+    /// Compare CLOCK_REALTIME against CLOCK_MONOTONIC_RAW to confirm the
+    /// requested frequency is really applied (catches kernel clamping or
+    /// another daemon overriding adjtimex)
+    fn measure_and_log_effectiveness(&mut self) {
+        if self.last_measurement_time.elapsed() < MEASURE_LOG_INTERVAL {
+            return;
+        }
+
+        let raw_now = clock_ns(libc::CLOCK_MONOTONIC_RAW);
+        let realtime_now = clock_ns(libc::CLOCK_REALTIME);
+        let raw_elapsed = raw_now - self.baseline_raw_ns;
+        let observed = observed_ppm(raw_elapsed, realtime_now - self.baseline_realtime_ns);
+        let effectiveness = effectiveness(self.last_requested_ppm, observed);
+
+        debug!(
+            "[FreqMeasure] Elapsed: {:.1}s | Requested: {:+.1} PPM | Observed: {:+.1} PPM | Effectiveness: {:.0}%",
+            raw_elapsed as f64 / 1_000_000_000.0,
+            self.last_requested_ppm,
+            observed,
+            effectiveness * 100.0
+        );
+
+        if effectiveness.abs() < 0.3 && self.last_requested_ppm.abs() > 10.0 {
+            warn!(
+                "[FreqMeasure] LOW EFFECTIVENESS! Frequency adjustment may not be working (another time daemon adjusting the clock?)"
+            );
+        }
+
+        self.baseline_raw_ns = raw_now;
+        self.baseline_realtime_ns = realtime_now;
+        self.last_measurement_time = Instant::now();
+    }
+}
+
+/// Error for a failed clock call from its errno: EPERM means the service
+/// lacks root / CAP_SYS_TIME rather than a clock problem
+fn clock_error(what: &str) -> DanteSyncError {
+    let err = std::io::Error::last_os_error();
+    if err.kind() == std::io::ErrorKind::PermissionDenied {
+        DanteSyncError::PermissionDenied(format!("{} (are you root?): {}", what, err))
+    } else {
+        DanteSyncError::Clock(format!("{}: errno={}", what, err))
+    }
 }
 
 impl SystemClock for LinuxClock {
@@ -35,9 +127,23 @@ impl SystemClock for LinuxClock {
 
         let ret = unsafe { adjtimex(&mut tx) };
         if ret < 0 {
-            return Err(anyhow!("adjtimex failed to set frequency"));
+            return Err(clock_error("adjtimex failed to set frequency"));
         }
 
+        // adjtimex reports the frequency it kept; the kernel clamps at its
+        // tolerance (500 PPM)
+        if tx.freq != freq_val {
+            debug!(
+                "[FreqMeasure] Kernel clamped frequency: requested {:+.1} PPM, applied {:+.1} PPM",
+                ppm,
+                tx.freq as f64 / 65536.0
+            );
+        }
+        self.last_requested_ppm = ppm;
+
+        // Periodic effectiveness measurement
+        self.measure_and_log_effectiveness();
+
         Ok(())
     }
 
@@ -68,11 +174,11 @@ impl SystemClock for LinuxClock {
 
         let ret = unsafe { settimeofday(&tv, std::ptr::null()) };
         if ret < 0 {
-            return Err(anyhow!(
-                "settimeofday failed: errno={}",
-                std::io::Error::last_os_error()
-            ));
+            return Err(clock_error("settimeofday failed"));
         }
+
+        // Reset measurement baseline after step
+        self.reset_measurement_baseline();
         Ok(())
     }
 }
@@ -92,6 +198,29 @@ impl Drop for LinuxClock {
 
 #[cfg(test)]
 mod tests {
+    use super::{effectiveness, observed_ppm};
+
+    /// Test the REALTIME vs MONOTONIC_RAW rate math behind [FreqMeasure]
+    #[test]
+    fn test_observed_ppm_and_effectiveness() {
+        // 10s raw, realtime ran 1ms fast → +100 PPM
+        let ppm = observed_ppm(10_000_000_000, 10_001_000_000);
+        assert!((ppm - 100.0).abs() < 1e-9, "got {}", ppm);
+        // Slow clock → negative
+        let ppm = observed_ppm(10_000_000_000, 9_999_500_000);
+        assert!((ppm + 50.0).abs() < 1e-9, "got {}", ppm);
+        // Degenerate span
+        assert_eq!(observed_ppm(0, 5), 0.0);
+
+        // Fully applied, half applied (e.g. clamped), ignored
+        assert!((effectiveness(100.0, 100.0) - 1.0).abs() < 1e-9);
+        assert!((effectiveness(100.0, 50.0) - 0.5).abs() < 1e-9);
+        assert!(effectiveness(100.0, 0.5).abs() < 0.3);
+        // Nothing requested: small drift is fine, large drift means override
+        assert_eq!(effectiveness(0.0, 3.0), 1.0);
+        assert_eq!(effectiveness(0.0, 40.0), 0.0);
+    }
+
     /// Test PPM to freq_val conversion math
     /// The kernel uses freq_val = ppm * 65536 (16-bit fixed point)
     #[test]
@@ -128,7 +257,7 @@ mod tests {
         assert_eq!(freq_1ppm_direct, 65536);
 
         // -1ppm exactly
-        let freq_neg1ppm_direct = (-1.0_f64 * 65536.0) as i64;
+        let freq_neg1ppm_direct = (-65536.0_f64) as i64;
         assert_eq!(freq_neg1ppm_direct, -65536);
 
         // Verify the conversion formula is correct for boundary values
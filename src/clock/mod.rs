@@ -1,4 +1,4 @@
-use anyhow::Result;
+use crate::error::Result;
 
 #[cfg_attr(test, mockall::automock)]
 pub trait SystemClock {
@@ -13,6 +13,60 @@ pub trait SystemClock {
     fn step_clock(&mut self, offset: std::time::Duration, sign: i8) -> Result<()>;
 }
 
+/// Clock that can be switched to observe-only (`--observe`)
+///
+/// In observe mode the controller runs normally and reports what it would
+/// do, but the wrapped clock is never adjusted or stepped. Lets a second
+/// instance watch another network while the first disciplines the clock.
+pub struct ObservableClock<C> {
+    inner: C,
+    observe_only: bool,
+}
+
+impl<C: SystemClock> ObservableClock<C> {
+    pub fn new(inner: C, observe_only: bool) -> Self {
+        Self {
+            inner,
+            observe_only,
+        }
+    }
+
+    pub fn is_observe_only(&self) -> bool {
+        self.observe_only
+    }
+}
+
+impl<C: SystemClock> SystemClock for ObservableClock<C> {
+    fn adjust_frequency(&mut self, factor: f64) -> Result<()> {
+        if self.observe_only {
+            log::trace!("[Observe] Not applying frequency factor {:.9}", factor);
+            return Ok(());
+        }
+        self.inner.adjust_frequency(factor)
+    }
+
+    fn step_clock(&mut self, offset: std::time::Duration, sign: i8) -> Result<()> {
+        if self.observe_only {
+            log::info!(
+                "[Observe] Not stepping clock by {}{:?}",
+                if sign < 0 { "-" } else { "+" },
+                offset
+            );
+            return Ok(());
+        }
+        self.inner.step_clock(offset, sign)
+    }
+}
+
+/// Leap second announced for the end of the current UTC day
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LeapSecond {
+    /// 23:59:60 is inserted: the clock steps back 1s at midnight
+    Insert,
+    /// 23:59:59 is skipped: the clock steps forward 1s at midnight
+    Delete,
+}
+
 #[cfg(windows)]
 mod windows;
 #[cfg(windows)]
@@ -22,3 +76,33 @@ pub use self::windows::WindowsClock as PlatformClock;
 mod linux;
 #[cfg(unix)]
 pub use self::linux::LinuxClock as PlatformClock;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockall::predicate::eq;
+    use std::time::Duration;
+
+    #[test]
+    fn test_observable_clock_passes_through_or_observes() {
+        let mut inner = MockSystemClock::new();
+        inner
+            .expect_adjust_frequency()
+            .with(eq(1.00001))
+            .times(1)
+            .returning(|_| Ok(()));
+        inner.expect_step_clock().times(1).returning(|_, _| Ok(()));
+        let mut clock = ObservableClock::new(inner, false);
+        clock.adjust_frequency(1.00001).unwrap();
+        clock.step_clock(Duration::from_millis(5), 1).unwrap();
+
+        // Observe-only: the inner clock must not be touched at all
+        let mut inner = MockSystemClock::new();
+        inner.expect_adjust_frequency().times(0);
+        inner.expect_step_clock().times(0);
+        let mut clock = ObservableClock::new(inner, true);
+        assert!(clock.is_observe_only());
+        clock.adjust_frequency(1.00001).unwrap();
+        clock.step_clock(Duration::from_millis(5), -1).unwrap();
+    }
+}
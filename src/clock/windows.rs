@@ -4,9 +4,12 @@
 //! adjustment actually affects clock speed.
 
 use super::SystemClock;
-use anyhow::{anyhow, Result};
+use crate::error::{DanteSyncError, Result};
 use log::{debug, error, info, warn};
-use std::time::{Duration, Instant};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use windows::core::PCWSTR;
 use windows::Win32::Foundation::{
     CloseHandle, GetLastError, BOOL, ERROR_NOT_ALL_ASSIGNED, FILETIME, HANDLE, LUID, SYSTEMTIME,
@@ -23,6 +26,42 @@ use windows::Win32::System::SystemInformation::{
 use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
 use windows::Win32::System::Time::FileTimeToSystemTime;
 
+/// How often the monitor thread re-reads the adjustment
+const MONITOR_INTERVAL: Duration = Duration::from_secs(2);
+
+/// State shared with the monitor thread that catches external resets
+/// (e.g. W32Time restarting) between our own adjustment calls.
+struct AdjustmentMonitor {
+    expected_adj: AtomicU64,
+    stop: AtomicBool,
+    /// Unix seconds of the last detected interference (0 = never)
+    last_interference_secs: AtomicU64,
+}
+
+impl AdjustmentMonitor {
+    fn record_interference(&self) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(1);
+        self.last_interference_secs.store(now, Ordering::Relaxed);
+    }
+}
+
+/// Read-only view of the monitor for status reporting
+#[derive(Clone)]
+pub struct InterferenceHandle(Arc<AdjustmentMonitor>);
+
+impl InterferenceHandle {
+    /// When another process last changed or disabled our adjustment
+    pub fn last_detected(&self) -> Option<SystemTime> {
+        match self.0.last_interference_secs.load(Ordering::Relaxed) {
+            0 => None,
+            secs => Some(UNIX_EPOCH + Duration::from_secs(secs)),
+        }
+    }
+}
+
 pub struct WindowsClock {
     original_increment: u64,
     perf_frequency: i64,
@@ -36,6 +75,9 @@ pub struct WindowsClock {
     baseline_perf_counter: i64,
     baseline_filetime: u64,
     last_measurement_time: Instant,
+
+    monitor: Arc<AdjustmentMonitor>,
+    monitor_thread: Option<JoinHandle<()>>,
 }
 
 impl WindowsClock {
@@ -89,6 +131,13 @@ impl WindowsClock {
             (pc, ft_u64)
         };
 
+        let monitor = Arc::new(AdjustmentMonitor {
+            expected_adj: AtomicU64::new(inc),
+            stop: AtomicBool::new(false),
+            last_interference_secs: AtomicU64::new(0),
+        });
+        let monitor_thread = Self::spawn_monitor(monitor.clone());
+
         let clock = WindowsClock {
             original_increment: inc,
             perf_frequency: perf_freq,
@@ -98,6 +147,8 @@ impl WindowsClock {
             baseline_perf_counter: baseline_pc,
             baseline_filetime: baseline_ft,
             last_measurement_time: Instant::now(),
+            monitor,
+            monitor_thread,
         };
 
         // Check for interfering processes
@@ -108,6 +159,67 @@ impl WindowsClock {
         Ok(clock)
     }
 
+    /// Handle for reporting external interference in status
+    pub fn interference_handle(&self) -> InterferenceHandle {
+        InterferenceHandle(self.monitor.clone())
+    }
+
+    /// Poll the adjustment independently of `adjust_frequency` and reassert
+    /// our value if something else changed or disabled it. A mismatch must be
+    /// seen on two consecutive polls so an in-flight adjustment is not
+    /// mistaken for interference.
+    fn spawn_monitor(monitor: Arc<AdjustmentMonitor>) -> Option<JoinHandle<()>> {
+        let spawned = std::thread::Builder::new()
+            .name("clock-monitor".to_string())
+            .spawn(move || {
+                let mut strikes = 0;
+                while !monitor.stop.load(Ordering::Relaxed) {
+                    std::thread::park_timeout(MONITOR_INTERVAL);
+                    if monitor.stop.load(Ordering::Relaxed) {
+                        break;
+                    }
+
+                    let mut adj = 0u64;
+                    let mut inc = 0u64;
+                    let mut disabled = BOOL(0);
+                    if unsafe { GetSystemTimeAdjustmentPrecise(&mut adj, &mut inc, &mut disabled) }
+                        .is_err()
+                    {
+                        continue;
+                    }
+
+                    let expected = monitor.expected_adj.load(Ordering::Relaxed);
+                    if adj == expected && !disabled.as_bool() {
+                        strikes = 0;
+                        continue;
+                    }
+                    strikes += 1;
+                    if strikes < 2 {
+                        continue;
+                    }
+                    strikes = 0;
+
+                    warn!(
+                        "[Clock] Adjustment changed externally (actual={}{}, ours={}) - reasserting",
+                        adj,
+                        if disabled.as_bool() { ", DISABLED" } else { "" },
+                        expected
+                    );
+                    monitor.record_interference();
+                    if let Err(e) = unsafe { SetSystemTimeAdjustmentPrecise(expected, false) } {
+                        error!("[Clock] Failed to reassert adjustment: {}", e);
+                    }
+                }
+            });
+        match spawned {
+            Ok(handle) => Some(handle),
+            Err(e) => {
+                warn!("[Clock] Could not start adjustment monitor: {}", e);
+                None
+            }
+        }
+    }
+
     /// Check for processes that might interfere with time adjustment
     fn check_for_interference(&self) {
         info!("");
@@ -180,8 +292,9 @@ impl WindowsClock {
 
             if let Err(e) = GetLastError() {
                 if e.code() == ERROR_NOT_ALL_ASSIGNED.to_hresult() {
-                    return Err(anyhow!(
+                    return Err(DanteSyncError::PermissionDenied(
                         "Failed to adjust privilege: ERROR_NOT_ALL_ASSIGNED. Run as Administrator!"
+                            .to_string(),
                     ));
                 }
             }
@@ -275,6 +388,9 @@ impl SystemClock for WindowsClock {
             self.adjustment_count, ppm, self.last_adjustment, new_adj, delta_from_nominal
         );
 
+        // Publish before applying so the monitor never reasserts a stale value
+        self.monitor.expected_adj.store(new_adj, Ordering::Relaxed);
+
         unsafe {
             // Apply adjustment
             SetSystemTimeAdjustmentPrecise(new_adj, false)?;
@@ -299,6 +415,7 @@ impl SystemClock for WindowsClock {
                 }
                 if verify_disabled.as_bool() {
                     error!("[FreqAdj] TIME ADJUSTMENT DISABLED! Interference detected!");
+                    self.monitor.record_interference();
                     // Try to re-enable
                     let _ = SetSystemTimeAdjustmentPrecise(new_adj, false);
                 }
@@ -335,7 +452,9 @@ impl SystemClock for WindowsClock {
                 if u64_time > offset_100ns {
                     u64_time -= offset_100ns;
                 } else {
-                    return Err(anyhow!("Clock step would result in negative time"));
+                    return Err(DanteSyncError::Clock(
+                        "Clock step would result in negative time".to_string(),
+                    ));
                 }
             }
 
@@ -383,6 +502,13 @@ impl Drop for WindowsClock {
             self.adjustment_count
         );
 
+        // Stop the monitor first so it cannot reassert after the reset
+        self.monitor.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.monitor_thread.take() {
+            handle.thread().unpark();
+            let _ = handle.join();
+        }
+
         unsafe {
             match SetSystemTimeAdjustmentPrecise(self.original_increment, false) {
                 Ok(_) => info!("Clock reset to nominal successfully."),
@@ -1,9 +1,278 @@
+use crate::controller::{
+    DEFAULT_NTP_STEP_JITTER_K, NTP_STEP_THRESHOLD_US, PENDING_SYNC_MAX_AGE_SYNCS,
+    PROD_DEADBAND_SAMPLES_PER_S,
+};
+use crate::net::{InterfacePolicy, InterfaceSelector, SocketQos};
+use crate::ntp::{DEFAULT_QUERY_RETRIES, DEFAULT_QUERY_TIMEOUT_MS};
+use crate::spike_filter::MIN_MAD_FLOOR;
+use crate::status::parse_mac;
 use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SystemConfig {
     pub servo: ServoConfig,
     pub filters: FilterConfig,
+    #[serde(default)]
+    pub priority: PriorityConfig,
+    #[serde(default)]
+    pub logging: LogConfig,
+    /// Periodic NTP UTC stepping (false = PTP frequency matching only, never step)
+    pub ntp_tracking: bool,
+    /// Stop W32Time / disable timedatectl NTP at startup so they don't fight
+    /// our adjustments (false = the operator manages the time daemon)
+    pub manage_conflicting_services: bool,
+    /// Step the clock backward when it is ahead; false = slew it out by
+    /// running slow, so time never goes backward (takes longer)
+    pub allow_backward_step: bool,
+    /// How NTP tracking removes a UTC offset: step the clock, or slew it out
+    /// by running fast/slow so time never jumps
+    pub ntp_alignment: NtpAlignment,
+    /// Where UTC offsets come from (direct SNTP query or local chronyd)
+    pub ntp_source: NtpSourceKind,
+    /// Interface for NTP queries: unset = same as PTP, "any" = OS routing,
+    /// otherwise a name, IPv4 address, or CIDR subnet like `--interface`
+    pub ntp_interface: Option<String>,
+    /// PTP interfaces to try in order when `--interface` is not given (name,
+    /// IPv4 address or CIDR), before the wired-first auto-selection
+    pub interface_preference: Vec<String>,
+    /// Let auto-selection use wireless interfaces (false = never)
+    pub allow_wireless: bool,
+    /// SNTP (`ntp_source` "udp"): wait per request before retrying
+    pub ntp_query_timeout_ms: u64,
+    /// SNTP: extra requests per query before it counts as an NTP failure
+    pub ntp_query_retries: u32,
+    /// Step the clock when the NTP offset exceeds this many µs (floor)
+    pub ntp_step_threshold_us: u64,
+    /// Raise the step threshold to this × the stddev of recent NTP offsets,
+    /// so noisy NTP paths are not stepped on noise (0 = fixed threshold)
+    pub ntp_step_jitter_k: f64,
+    /// Linux: how PTP packets are read (UDP sockets or an AF_PACKET capture)
+    pub receive_backend: ReceiveBackend,
+    /// Path delay measurement: end-to-end (Dante/PTPv1) or peer-to-peer (802.1AS)
+    pub delay_mechanism: DelayMechanism,
+    /// Restart if the sync loop makes no progress for this long (0 = disabled)
+    pub watchdog_secs: u64,
+    /// Log a one-line summary this often even when nothing happens, as a
+    /// liveness trail (0 = disabled)
+    pub heartbeat_secs: u64,
+    /// Linux: delay systemd READY until the clock has settled, or at most
+    /// this long if PTP never appears (0 = ready as soon as the loop starts)
+    pub ready_timeout_secs: u64,
+    /// Windows: sync loop sleep between socket polls (µs). Lower = tighter
+    /// timestamps, more CPU
+    pub windows_poll_us: u64,
+    /// Drift rate (µs/s) below which the servo enters PROD and counts toward LOCK
+    pub lock_rate_us_s: f64,
+    /// Drift rate (µs/s) above which the servo drops back to ACQ (hysteresis)
+    pub unlock_rate_us_s: f64,
+    /// Derive the lock/unlock thresholds from the host's measured rate noise
+    /// instead of `lock_rate_us_s` / `unlock_rate_us_s`
+    pub auto_thresholds: bool,
+    /// Drift rate (µs/s) ignored as noise in PROD/LOCK (0 = correct every sample)
+    pub deadband_us_s: f64,
+    /// Audio sample rate of the plant (Hz). When set, the PROD/LOCK and NANO
+    /// dead-bands and the network quality thresholds are sized in audio
+    /// samples, replacing `deadband_us_s` (0 = µs values tuned for 96kHz)
+    pub target_sample_rate_hz: u32,
+    /// Largest change in applied frequency per servo update, in ppm (0 = unlimited)
+    pub max_ppm_step_per_sample: f64,
+    /// Stop integrating drift while the servo output is saturated (at the
+    /// frequency limit or held back by `max_ppm_step_per_sample`)
+    pub anti_windup: bool,
+    /// Fraction per locked sample by which the learned drift is pulled toward
+    /// the correction that held the rate steady (0 = off, 0.1 = fast)
+    pub integral_bleed: f64,
+    /// Frequency band (±ppm) a healthy oscillator stays within. The servo
+    /// never corrects beyond it and raises `oscillator_fault` when it would
+    /// need to (0 = off, full ±500ppm range)
+    pub max_healthy_ppm: f64,
+    /// Frequency correction (ppm) the servo starts from instead of 0, e.g. the
+    /// known drift of a characterized machine, so acquisition starts close to
+    /// locked. Applied at startup and by `ResetServo`
+    pub initial_freq_ppm: f64,
+    /// Only this grandmaster may be followed ("00:1D:C1:..."); Syncs from any
+    /// other are ignored and flagged as `gm_mismatch` (None = any master)
+    pub expected_gm_uuid: Option<String>,
+    /// Raise `master_epoch_changed` when the master-vs-system epoch offset
+    /// jumps by more than this (ms) beyond what drift can explain: the
+    /// grandmaster restarted or changed reference (0 = off)
+    pub epoch_change_alarm_ms: u64,
+    /// Treat a system time jump of more than this (ms) against the monotonic
+    /// clock as a step by another process or the VM host: samples spanning
+    /// it are discarded instead of read as drift (0 = off)
+    pub external_step_threshold_ms: u64,
+    /// Consecutive Syncs a new sync source / grandmaster must send before we
+    /// switch to it; stray packets from others are ignored (0 or 1 = immediate)
+    pub source_switch_syncs: u32,
+    /// Servo windows after a sync source handover during which the gains
+    /// ramp from 1/(N+1) back to normal (0 = full gains at once)
+    pub handover_blend_windows: u32,
+    /// Seconds after settling during which acquisition learns drift with
+    /// boosted gains, until the first lock (0 = disabled)
+    pub cold_start_secs: u64,
+    /// Servo samples averaged for the displayed drift rate (tray/status only;
+    /// the servo keeps its own EMA). 0 or 1 = show the servo's rate
+    pub display_rate_samples: usize,
+    /// Interval (seconds) for the rolling spike and NTP failure counts in
+    /// the status (`spikes_recent`, `ntp_failures_recent`)
+    pub counter_window_secs: u64,
+    #[serde(default)]
+    pub qos: QosConfig,
+    #[serde(default)]
+    pub capture: CaptureConfig,
+    /// Per-sample CSV log for offline analysis (rotates with `logging` limits)
+    pub csv_log_path: Option<String>,
+    /// Linux: stream one line per servo sample to this named pipe for live
+    /// plotting (created if missing; lines are dropped while nobody reads)
+    pub sample_fifo_path: Option<String>,
+    /// Send the status as a JSON UDP datagram here on every update (None = off)
+    pub telemetry_target: Option<SocketAddr>,
+    /// Windows: write key status values under HKLM\SOFTWARE\DanteSync\Status
+    /// every N seconds for monitoring tools (0 = off)
+    pub registry_status_secs: u64,
+    /// Tray toast notifications (read by dantesync-tray; the service ignores it)
+    #[serde(default)]
+    pub notifications: NotificationConfig,
+    /// Windows: who may read status / send commands on the tray pipe, as a
+    /// SID ("S-1-5-21-...") or SDDL alias ("AU" = Authenticated Users, "BU", "BA")
+    pub ipc_access_sid: String,
+}
+
+/// Ring-buffer packet capture for post-mortem debugging
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CaptureConfig {
+    pub packets: usize,       // Keep the last N raw PTP packets (0 = disabled)
+    pub dump_on_unlock: bool, // Write the buffer automatically on LOCK → UNLOCK
+    /// Directory for capture files (None = log directory / C:\ProgramData\DanteSync)
+    pub dir: Option<String>,
+}
+
+impl Default for CaptureConfig {
+    fn default() -> Self {
+        CaptureConfig {
+            packets: 0,
+            dump_on_unlock: true,
+            dir: None,
+        }
+    }
+}
+
+/// Tray toast notifications: per-category switches and a rate limit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    /// Minimum seconds between two toasts of the same category (0 = no limit)
+    pub min_interval_secs: u64,
+    pub ntp: bool,          // NTP server unreachable / restored
+    pub ptp: bool,          // PTP offline / restored
+    pub service: bool,      // Service connected / offline
+    pub interference: bool, // Clock adjustment reset by another process
+    pub updates: bool,      // New release, tray older than the service
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        NotificationConfig {
+            min_interval_secs: 60,
+            ntp: true,
+            ptp: true,
+            service: true,
+            interference: true,
+            updates: true,
+        }
+    }
+}
+
+/// DSCP and multicast TTL marking for PTP sockets
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QosConfig {
+    pub event_dscp: u8,     // Port 319 (Sync, Delay_Req). Default CS6 (48); EF is 46
+    pub general_dscp: u8,   // Port 320 (FollowUp). Default best effort (0)
+    pub multicast_ttl: u32, // IP_MULTICAST_TTL (1 = stay on the local subnet)
+}
+
+impl Default for QosConfig {
+    fn default() -> Self {
+        QosConfig {
+            event_dscp: 48,
+            general_dscp: 0,
+            multicast_ttl: 1,
+        }
+    }
+}
+
+impl QosConfig {
+    pub fn event(&self) -> SocketQos {
+        SocketQos {
+            dscp: self.event_dscp,
+            multicast_ttl: self.multicast_ttl,
+        }
+    }
+
+    pub fn general(&self) -> SocketQos {
+        SocketQos {
+            dscp: self.general_dscp,
+            multicast_ttl: self.multicast_ttl,
+        }
+    }
+}
+
+/// UTC offset source for NTP stepping
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NtpSourceKind {
+    /// Query `ntp_server` directly over UDP (SNTP)
+    #[default]
+    Udp,
+    /// Read the disciplined offset from a local chronyd (`chronyc -c tracking`)
+    #[serde(alias = "chrony_shm")]
+    Chrony,
+}
+
+/// How NTP tracking aligns the clock to UTC
+///
+/// Either way the PTP-learned frequency is kept: a slew only adds a
+/// temporary bias on top of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NtpAlignment {
+    /// Step the clock by the offset (immediate)
+    #[default]
+    Step,
+    /// Run the clock 200ppm fast or slow until the offset is gone
+    Slew,
+}
+
+/// Linux PTP receive path
+///
+/// Windows always captures with Npcap (falling back to Winsock); this picks
+/// the equivalent on Linux.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveBackend {
+    /// UDP sockets bound to 319/320 with SO_TIMESTAMPNS
+    #[default]
+    Udp,
+    /// Packet socket on the interface, like Npcap (needs CAP_NET_RAW)
+    AfPacket,
+}
+
+/// PTP path delay mechanism
+///
+/// The frequency servo does not need path delay (a constant delay cancels
+/// out of the rate), so this only selects which delay messages are expected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DelayMechanism {
+    /// End-to-end Delay_Req/Delay_Resp (PTPv1 / Dante)
+    #[default]
+    E2e,
+    /// Peer-to-peer Pdelay_Req/Pdelay_Resp (802.1AS / gPTP)
+    P2p,
 }
 
 /// Servo configuration - LEGACY FIELDS (not used by controller)
@@ -29,6 +298,130 @@ pub struct FilterConfig {
     pub min_delta_ns: i64,
     pub calibration_samples: usize, // Number of samples for timestamp calibration (0 = disabled)
     pub warmup_secs: f64,           // Warmup period in seconds (0.0 = disabled, for tests)
+    #[serde(default = "default_ntp_warmup_secs")]
+    pub ntp_warmup_secs: f64, // Startup NTP sampling window before the first step (0.0 = single sample)
+    #[serde(default, alias = "window_estimator")]
+    pub filter_type: FilterType, // Estimator applied to the sample window
+    #[serde(default = "default_trim_percent")]
+    pub trim_percent: f64, // trimmed_mean: drop this % from each end (0-50)
+    #[serde(default)]
+    pub sync_interval_hint_ms: u32, // Master Sync interval (0 = measure from arrivals)
+    #[serde(default = "default_spike_mad_floor")]
+    pub spike_mad_floor_us_s: f64, // Spike filter MAD floor; lower catches smaller spikes
+    #[serde(default = "default_max_pending_syncs")]
+    pub max_pending_syncs: u64, // Syncs awaiting a Follow_Up; older ones count as unmatched
+    #[serde(default)]
+    pub pending_sync_timeout_ms: u64, // Longest Sync → Follow_Up gap (0 = 2 Sync intervals, >= 250ms)
+}
+
+fn default_trim_percent() -> f64 {
+    25.0
+}
+
+fn default_ntp_warmup_secs() -> f64 {
+    5.0
+}
+
+fn default_spike_mad_floor() -> f64 {
+    MIN_MAD_FLOOR
+}
+
+fn default_max_pending_syncs() -> u64 {
+    PENDING_SYNC_MAX_AGE_SYNCS
+}
+
+/// Offset estimator for the sample window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterType {
+    /// Middle value - robust against outliers (default)
+    #[default]
+    Median,
+    /// Arithmetic mean - uses every sample, best on low-jitter hosts
+    Mean,
+    /// Mean after dropping `trim_percent` from each end - middle ground
+    TrimmedMean,
+    /// Smallest T2-T1 sample ("lucky packet"): queueing only ever adds delay,
+    /// so the least-delayed Sync is the cleanest on congested switches
+    MinDelay,
+}
+
+/// Scheduling class for the sync loop thread
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PriorityClass {
+    /// Linux: SCHED_FIFO at `rt_priority`. Windows: REALTIME_PRIORITY_CLASS
+    Realtime,
+    /// Linux: SCHED_OTHER with nice -10. Windows: HIGH_PRIORITY_CLASS
+    High,
+    /// Leave scheduler defaults untouched
+    Normal,
+}
+
+/// What to do when `class` cannot be applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RealtimePriorityMode {
+    /// Abort startup: running without the priority is pointless here
+    Require,
+    /// Warn and keep running at normal priority (default)
+    #[default]
+    Try,
+    /// Never touch the scheduler class (containers where it always fails)
+    Off,
+}
+
+/// Process priority and CPU pinning for the sync loop
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PriorityConfig {
+    pub class: PriorityClass,
+    pub rt_priority: i32, // SCHED_FIFO priority 1-99 (Linux realtime class only)
+    pub cpu_affinity: Option<usize>, // Pin sync thread to this core (None = no pinning)
+    pub realtime_priority: RealtimePriorityMode,
+}
+
+impl Default for PriorityConfig {
+    fn default() -> Self {
+        PriorityConfig {
+            class: PriorityClass::Realtime,
+            rt_priority: 50,
+            cpu_affinity: None,
+            realtime_priority: RealtimePriorityMode::Try,
+        }
+    }
+}
+
+/// Log file output and size-based rotation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogConfig {
+    /// Log file path. Windows service always logs to a file (default
+    /// `C:\ProgramData\DanteSync\dantesync.log`); on Linux None keeps
+    /// logging on stderr (journald).
+    pub file: Option<String>,
+    pub max_size_bytes: u64, // Rotate when the active file exceeds this (0 = never)
+    pub max_files: usize,    // Rotated files kept (dantesync.log.1 .. .N)
+    /// off, error, warn, info, debug, trace (overridden by -v / --log-level)
+    pub level: String,
+}
+
+impl LogConfig {
+    /// Parsed `level` (Info if invalid; `validate` reports it)
+    pub fn level_filter(&self) -> log::LevelFilter {
+        self.level.parse().unwrap_or(log::LevelFilter::Info)
+    }
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        LogConfig {
+            file: None,
+            max_size_bytes: 10_000_000,
+            max_files: 5,
+            level: "info".to_string(),
+        }
+    }
 }
 
 impl Default for SystemConfig {
@@ -70,7 +463,426 @@ impl Default for SystemConfig {
 
                 // Warmup period (same on both platforms)
                 warmup_secs: 3.0,
+                // Startup NTP step waits for a median instead of one sample
+                ntp_warmup_secs: default_ntp_warmup_secs(),
+
+                // Median is robust against Windows timestamp outliers
+                filter_type: FilterType::Median,
+                trim_percent: default_trim_percent(),
+                spike_mad_floor_us_s: default_spike_mad_floor(),
+
+                // Measure the master's Sync rate instead of assuming Dante's 125ms
+                sync_interval_hint_ms: 0,
+                max_pending_syncs: default_max_pending_syncs(),
+                pending_sync_timeout_ms: 0,
             },
+            priority: PriorityConfig::default(),
+            logging: LogConfig::default(),
+            ntp_tracking: true,
+            manage_conflicting_services: true,
+            allow_backward_step: true,
+            ntp_alignment: NtpAlignment::Step,
+            ntp_source: NtpSourceKind::Udp,
+            ntp_interface: None,
+            interface_preference: Vec::new(),
+            allow_wireless: true,
+            ntp_query_timeout_ms: DEFAULT_QUERY_TIMEOUT_MS,
+            ntp_query_retries: DEFAULT_QUERY_RETRIES,
+            ntp_step_threshold_us: NTP_STEP_THRESHOLD_US as u64,
+            ntp_step_jitter_k: DEFAULT_NTP_STEP_JITTER_K,
+            receive_backend: ReceiveBackend::Udp,
+            delay_mechanism: DelayMechanism::E2e,
+            watchdog_secs: 60,
+            heartbeat_secs: 60,
+            ready_timeout_secs: 60,
+            windows_poll_us: 50,
+            lock_rate_us_s: 5.0,
+            unlock_rate_us_s: 20.0,
+            auto_thresholds: false,
+            deadband_us_s: 0.3,
+            target_sample_rate_hz: 0,
+            // Off until its effect on time-to-lock is measured (e.g. 20 to
+            // ramp the up-to-200ppm ACQ jumps)
+            max_ppm_step_per_sample: 0.0,
+            anti_windup: true,
+            integral_bleed: 0.0,
+            max_healthy_ppm: 0.0,
+            initial_freq_ppm: 0.0,
+            expected_gm_uuid: None,
+            epoch_change_alarm_ms: 100,
+            external_step_threshold_ms: 20,
+            // 0.5s at Dante's 125ms Sync interval
+            source_switch_syncs: 4,
+            handover_blend_windows: 0,
+            cold_start_secs: 0,
+            display_rate_samples: 30,
+            counter_window_secs: 300,
+            qos: QosConfig::default(),
+            capture: CaptureConfig::default(),
+            csv_log_path: None,
+            sample_fifo_path: None,
+            telemetry_target: None,
+            registry_status_secs: 0,
+            notifications: NotificationConfig::default(),
+            ipc_access_sid: "AU".to_string(),
+        }
+    }
+}
+
+/// Settings only read at startup; everything else applies on a config reload
+const RESTART_REQUIRED: &[&str] = &[
+    "priority",
+    "logging",
+    "ntp_source",
+    "ntp_interface",
+    "interface_preference",
+    "allow_wireless",
+    "receive_backend",
+    "delay_mechanism",
+    "watchdog_secs",
+    "qos",
+    "capture",
+    "csv_log_path",
+    "sample_fifo_path",
+    "telemetry_target",
+    "registry_status_secs",
+    "ipc_access_sid",
+];
+
+impl SystemConfig {
+    /// PTP interface auto-selection rules; invalid entries are skipped
+    /// (`validate` reports them)
+    pub fn interface_policy(&self) -> InterfacePolicy {
+        InterfacePolicy {
+            preference: self
+                .interface_preference
+                .iter()
+                .filter_map(|iface| InterfaceSelector::parse(iface).ok())
+                .collect(),
+            allow_wireless: self.allow_wireless,
+        }
+    }
+
+    /// One audio sample period (µs) at `target_sample_rate_hz`, if set
+    pub fn sample_period_us(&self) -> Option<f64> {
+        (self.target_sample_rate_hz > 0).then(|| 1e6 / self.target_sample_rate_hz as f64)
+    }
+
+    /// PROD/LOCK dead-band (µs/s): `deadband_us_s`, or derived from the
+    /// audio sample period with `target_sample_rate_hz`
+    pub fn effective_deadband_us_s(&self) -> f64 {
+        self.sample_period_us()
+            .map_or(self.deadband_us_s, |period_us| {
+                PROD_DEADBAND_SAMPLES_PER_S * period_us
+            })
+    }
+
+    /// Sections that differ from `other` but only take effect after a restart
+    pub fn restart_required_changes(&self, other: &SystemConfig) -> Vec<&'static str> {
+        let (Ok(old), Ok(new)) = (serde_json::to_value(self), serde_json::to_value(other)) else {
+            return Vec::new();
+        };
+        RESTART_REQUIRED
+            .iter()
+            .copied()
+            .filter(|key| old.get(key) != new.get(key))
+            .collect()
+    }
+
+    /// Check value ranges, returning one message per invalid field
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+
+        for (name, value) in [
+            ("servo.kp", self.servo.kp),
+            ("servo.ki", self.servo.ki),
+            ("servo.max_freq_adj_ppm", self.servo.max_freq_adj_ppm),
+            ("servo.max_integral_ppm", self.servo.max_integral_ppm),
+        ] {
+            if !value.is_finite() || value < 0.0 {
+                errors.push(format!(
+                    "{} must be a finite value >= 0 (got {})",
+                    name, value
+                ));
+            }
+        }
+
+        if self.filters.sample_window_size == 0 {
+            errors.push("filters.sample_window_size must be > 0".to_string());
+        }
+        if self.filters.min_delta_ns < 0 {
+            errors.push(format!(
+                "filters.min_delta_ns must be >= 0 (got {})",
+                self.filters.min_delta_ns
+            ));
+        }
+        if !self.filters.warmup_secs.is_finite() || self.filters.warmup_secs < 0.0 {
+            errors.push(format!(
+                "filters.warmup_secs must be a finite value >= 0 (got {})",
+                self.filters.warmup_secs
+            ));
+        }
+
+        if !self.filters.ntp_warmup_secs.is_finite() || self.filters.ntp_warmup_secs < 0.0 {
+            errors.push(format!(
+                "filters.ntp_warmup_secs must be a finite value >= 0 (got {})",
+                self.filters.ntp_warmup_secs
+            ));
+        }
+
+        if self.filters.sync_interval_hint_ms > 10_000 {
+            errors.push(format!(
+                "filters.sync_interval_hint_ms must be <= 10000 (got {})",
+                self.filters.sync_interval_hint_ms
+            ));
+        }
+
+        if !(1..=1024).contains(&self.filters.max_pending_syncs) {
+            errors.push(format!(
+                "filters.max_pending_syncs must be in 1-1024 (got {})",
+                self.filters.max_pending_syncs
+            ));
+        }
+        if self.filters.pending_sync_timeout_ms > 10_000 {
+            errors.push(format!(
+                "filters.pending_sync_timeout_ms must be <= 10000 (got {})",
+                self.filters.pending_sync_timeout_ms
+            ));
+        }
+
+        if !(0.0..50.0).contains(&self.filters.trim_percent) {
+            errors.push(format!(
+                "filters.trim_percent must be in 0-50 (got {})",
+                self.filters.trim_percent
+            ));
+        }
+
+        if !self.filters.spike_mad_floor_us_s.is_finite()
+            || self.filters.spike_mad_floor_us_s <= 0.0
+        {
+            errors.push(format!(
+                "filters.spike_mad_floor_us_s must be a finite value > 0 (got {})",
+                self.filters.spike_mad_floor_us_s
+            ));
+        }
+
+        if self.priority.class == PriorityClass::Realtime
+            && !(1..=99).contains(&self.priority.rt_priority)
+        {
+            errors.push(format!(
+                "priority.rt_priority must be 1-99 (got {})",
+                self.priority.rt_priority
+            ));
+        }
+
+        if self.priority.realtime_priority == RealtimePriorityMode::Require
+            && self.priority.class == PriorityClass::Normal
+        {
+            errors.push(
+                "priority.realtime_priority \"require\" needs class \"realtime\" or \"high\""
+                    .to_string(),
+            );
+        }
+
+        // Spliced into the pipe's SDDL, so only accept a plain SID or alias
+        let sid = &self.ipc_access_sid;
+        let is_alias = sid.len() == 2 && sid.chars().all(|c| c.is_ascii_uppercase());
+        let is_sid = sid.starts_with("S-1-")
+            && sid[4..]
+                .split('-')
+                .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()));
+        if !is_alias && !is_sid {
+            errors.push(format!(
+                "ipc_access_sid must be a SID like S-1-5-32-545 or an SDDL alias like AU (got \"{}\")",
+                sid
+            ));
+        }
+
+        // 0 would spin a full core; above 1ms Linux's interval is the better choice
+        if !(1..=1000).contains(&self.windows_poll_us) {
+            errors.push(format!(
+                "windows_poll_us must be 1-1000 (got {})",
+                self.windows_poll_us
+            ));
+        }
+
+        if !(1..=10_000).contains(&self.ntp_query_timeout_ms) {
+            errors.push(format!(
+                "ntp_query_timeout_ms must be 1-10000 (got {})",
+                self.ntp_query_timeout_ms
+            ));
+        }
+        if self.ntp_query_retries > 10 {
+            errors.push(format!(
+                "ntp_query_retries must be <= 10 (got {})",
+                self.ntp_query_retries
+            ));
+        }
+        if self.ntp_step_threshold_us == 0 || self.ntp_step_threshold_us > 1_000_000 {
+            errors.push(format!(
+                "ntp_step_threshold_us must be 1-1000000 (got {})",
+                self.ntp_step_threshold_us
+            ));
+        }
+        if !(0.0..=20.0).contains(&self.ntp_step_jitter_k) {
+            errors.push(format!(
+                "ntp_step_jitter_k must be in 0-20 (got {})",
+                self.ntp_step_jitter_k
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&self.integral_bleed) {
+            errors.push(format!(
+                "integral_bleed must be in 0-1 (got {})",
+                self.integral_bleed
+            ));
+        }
+
+        if !(0.0..=500.0).contains(&self.max_healthy_ppm) {
+            errors.push(format!(
+                "max_healthy_ppm must be in 0-500 (got {})",
+                self.max_healthy_ppm
+            ));
+        }
+
+        let initial_limit = if self.max_healthy_ppm > 0.0 {
+            self.max_healthy_ppm
+        } else {
+            500.0
+        };
+        if !(-initial_limit..=initial_limit).contains(&self.initial_freq_ppm) {
+            errors.push(format!(
+                "initial_freq_ppm must be within ±{} (got {})",
+                initial_limit, self.initial_freq_ppm
+            ));
+        }
+
+        if self.counter_window_secs == 0 {
+            errors.push("counter_window_secs must be > 0".to_string());
+        }
+
+        // Status refreshes every 10s; shorter timeouts would trip while healthy
+        if self.watchdog_secs != 0 && self.watchdog_secs < 30 {
+            errors.push(format!(
+                "watchdog_secs must be 0 (disabled) or >= 30 (got {})",
+                self.watchdog_secs
+            ));
+        }
+
+        for (name, rate) in [
+            ("lock_rate_us_s", self.lock_rate_us_s),
+            ("unlock_rate_us_s", self.unlock_rate_us_s),
+        ] {
+            if !rate.is_finite() || rate <= 0.0 {
+                errors.push(format!(
+                    "{} must be a finite value > 0 (got {})",
+                    name, rate
+                ));
+            }
+        }
+        if self.lock_rate_us_s >= self.unlock_rate_us_s {
+            errors.push(format!(
+                "lock_rate_us_s ({}) must be below unlock_rate_us_s ({}) for hysteresis",
+                self.lock_rate_us_s, self.unlock_rate_us_s
+            ));
+        }
+
+        if !self.deadband_us_s.is_finite() || self.deadband_us_s < 0.0 {
+            errors.push(format!(
+                "deadband_us_s must be a finite value >= 0 (got {})",
+                self.deadband_us_s
+            ));
+        } else if self.effective_deadband_us_s() >= self.lock_rate_us_s {
+            let source = if self.sample_period_us().is_some() {
+                "dead-band from target_sample_rate_hz"
+            } else {
+                "deadband_us_s"
+            };
+            errors.push(format!(
+                "{} ({}) must be below lock_rate_us_s ({})",
+                source,
+                self.effective_deadband_us_s(),
+                self.lock_rate_us_s
+            ));
+        }
+
+        if self.target_sample_rate_hz != 0
+            && !(8_000..=768_000).contains(&self.target_sample_rate_hz)
+        {
+            errors.push(format!(
+                "target_sample_rate_hz must be 0 (off) or 8000-768000 (got {})",
+                self.target_sample_rate_hz
+            ));
+        }
+
+        if !self.max_ppm_step_per_sample.is_finite() || self.max_ppm_step_per_sample < 0.0 {
+            errors.push(format!(
+                "max_ppm_step_per_sample must be a finite value >= 0 (got {})",
+                self.max_ppm_step_per_sample
+            ));
+        }
+
+        for (name, dscp) in [
+            ("qos.event_dscp", self.qos.event_dscp),
+            ("qos.general_dscp", self.qos.general_dscp),
+        ] {
+            if dscp > 63 {
+                errors.push(format!("{} must be 0-63 (got {})", name, dscp));
+            }
+        }
+        if !(1..=255).contains(&self.qos.multicast_ttl) {
+            errors.push(format!(
+                "qos.multicast_ttl must be 1-255 (got {})",
+                self.qos.multicast_ttl
+            ));
+        }
+
+        if let Some(uuid) = &self.expected_gm_uuid {
+            if let Err(e) = parse_mac(uuid) {
+                errors.push(format!("expected_gm_uuid: {}", e));
+            }
+        }
+
+        if let Some(iface) = &self.ntp_interface {
+            if !iface.eq_ignore_ascii_case("any") {
+                if let Err(e) = InterfaceSelector::parse(iface) {
+                    errors.push(format!("ntp_interface: {}", e));
+                }
+            }
+        }
+
+        for (index, iface) in self.interface_preference.iter().enumerate() {
+            if let Err(e) = InterfaceSelector::parse(iface) {
+                errors.push(format!("interface_preference[{}]: {}", index, e));
+            }
+        }
+
+        if let Some(file) = &self.logging.file {
+            if file.trim().is_empty() {
+                errors.push("logging.file must not be empty (omit it instead)".to_string());
+            }
+        }
+        if self.logging.level.parse::<log::LevelFilter>().is_err() {
+            errors.push(format!(
+                "logging.level must be off/error/warn/info/debug/trace (got {:?})",
+                self.logging.level
+            ));
+        }
+        if let Some(path) = &self.csv_log_path {
+            if path.trim().is_empty() {
+                errors.push("csv_log_path must not be empty (omit it instead)".to_string());
+            }
+        }
+        if let Some(path) = &self.sample_fifo_path {
+            if path.trim().is_empty() {
+                errors.push("sample_fifo_path must not be empty (omit it instead)".to_string());
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
         }
     }
 }
@@ -101,6 +913,7 @@ mod tests {
         // Common values across platforms
         assert_eq!(config.filters.sample_window_size, 4);
         assert!((config.filters.warmup_secs - 3.0).abs() < f64::EPSILON);
+        assert!((config.filters.ntp_warmup_secs - 5.0).abs() < f64::EPSILON);
 
         // Platform-specific values
         #[cfg(windows)]
@@ -166,6 +979,609 @@ mod tests {
         assert!((config.filters.warmup_secs - 5.0).abs() < f64::EPSILON);
     }
 
+    #[test]
+    fn test_default_priority_config() {
+        let config = SystemConfig::default();
+        assert_eq!(config.priority.class, PriorityClass::Realtime);
+        assert_eq!(config.priority.rt_priority, 50);
+        assert!(config.priority.cpu_affinity.is_none());
+    }
+
+    #[test]
+    fn test_priority_config_optional_in_json() {
+        // Config files written before priority existed must still load
+        let json = r#"{
+            "servo": {"kp": 0.0005, "ki": 0.00005, "max_freq_adj_ppm": 500.0, "max_integral_ppm": 100.0},
+            "filters": {"sample_window_size": 4, "min_delta_ns": 0, "calibration_samples": 0, "warmup_secs": 3.0}
+        }"#;
+        let config: SystemConfig = serde_json::from_str(json).expect("parse failed");
+        assert_eq!(config.priority.class, PriorityClass::Realtime);
+        assert_eq!(config.priority.rt_priority, 50);
+        assert!((config.filters.ntp_warmup_secs - 5.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_priority_config_custom_values() {
+        let json = r#"{"class": "high", "cpu_affinity": 3}"#;
+        let priority: PriorityConfig = serde_json::from_str(json).expect("parse failed");
+        assert_eq!(priority.class, PriorityClass::High);
+        assert_eq!(priority.rt_priority, 50); // defaulted
+        assert_eq!(priority.cpu_affinity, Some(3));
+
+        let json = r#"{"class": "normal"}"#;
+        let priority: PriorityConfig = serde_json::from_str(json).expect("parse failed");
+        assert_eq!(priority.class, PriorityClass::Normal);
+    }
+
+    #[test]
+    fn test_realtime_priority_mode() {
+        let config = SystemConfig::default();
+        assert_eq!(config.priority.realtime_priority, RealtimePriorityMode::Try);
+
+        for (text, mode) in [
+            ("require", RealtimePriorityMode::Require),
+            ("try", RealtimePriorityMode::Try),
+            ("off", RealtimePriorityMode::Off),
+        ] {
+            let json = format!(r#"{{"realtime_priority": "{}"}}"#, text);
+            let priority: PriorityConfig = serde_json::from_str(&json).expect("parse failed");
+            assert_eq!(priority.realtime_priority, mode);
+        }
+
+        let mut config = SystemConfig::default();
+        config.priority.realtime_priority = RealtimePriorityMode::Require;
+        assert!(config.validate().is_ok());
+        config.priority.class = PriorityClass::Normal;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_log_config_defaults_and_partial_json() {
+        let config = SystemConfig::default();
+        assert!(config.logging.file.is_none());
+        assert_eq!(config.logging.max_size_bytes, 10_000_000);
+        assert_eq!(config.logging.max_files, 5);
+
+        let json = r#"{"file": "/var/log/dantesync/dantesync.log", "max_files": 2}"#;
+        let logging: LogConfig = serde_json::from_str(json).expect("parse failed");
+        assert_eq!(
+            logging.file.as_deref(),
+            Some("/var/log/dantesync/dantesync.log")
+        );
+        assert_eq!(logging.max_files, 2);
+        assert_eq!(logging.max_size_bytes, 10_000_000); // defaulted
+    }
+
+    #[test]
+    fn test_ntp_tracking_default_and_override() {
+        assert!(SystemConfig::default().ntp_tracking);
+
+        let config: SystemConfig = serde_json::from_str("{}").expect("parse failed");
+        assert!(config.ntp_tracking, "Missing field must keep tracking on");
+
+        let config: SystemConfig =
+            serde_json::from_str(r#"{"ntp_tracking": false}"#).expect("parse failed");
+        assert!(!config.ntp_tracking);
+    }
+
+    #[test]
+    fn test_manage_conflicting_services_default_and_override() {
+        let config: SystemConfig = serde_json::from_str("{}").expect("parse failed");
+        assert!(config.manage_conflicting_services);
+
+        let config: SystemConfig =
+            serde_json::from_str(r#"{"manage_conflicting_services": false}"#)
+                .expect("parse failed");
+        assert!(!config.manage_conflicting_services);
+    }
+
+    #[test]
+    fn test_ready_timeout_default_and_override() {
+        let config: SystemConfig = serde_json::from_str("{}").expect("parse failed");
+        assert_eq!(config.ready_timeout_secs, 60);
+
+        let config: SystemConfig =
+            serde_json::from_str(r#"{"ready_timeout_secs": 0}"#).expect("parse failed");
+        assert_eq!(config.ready_timeout_secs, 0);
+    }
+
+    #[test]
+    fn test_windows_poll_us_validation() {
+        let mut config = SystemConfig::default();
+        assert_eq!(config.windows_poll_us, 50);
+        config.windows_poll_us = 200;
+        assert!(config.validate().is_ok());
+        config.windows_poll_us = 0;
+        assert!(config.validate().is_err());
+        config.windows_poll_us = 5_000;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_ntp_query_policy_validation() {
+        let mut config = SystemConfig::default();
+        assert_eq!(config.ntp_query_timeout_ms, 1000);
+        assert_eq!(config.ntp_query_retries, 2);
+        config.ntp_query_retries = 0;
+        assert!(config.validate().is_ok());
+        config.ntp_query_timeout_ms = 0;
+        assert!(config.validate().is_err());
+        config.ntp_query_timeout_ms = 500;
+        config.ntp_query_retries = 50;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_ntp_step_threshold_validation() {
+        let mut config = SystemConfig::default();
+        assert_eq!(config.ntp_step_threshold_us, 500);
+        assert_eq!(config.ntp_step_jitter_k, 3.0);
+        config.ntp_step_jitter_k = 0.0;
+        assert!(config.validate().is_ok());
+        config.ntp_step_threshold_us = 0;
+        assert!(config.validate().is_err());
+        config.ntp_step_threshold_us = 2_000;
+        config.ntp_step_jitter_k = f64::NAN;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_max_healthy_ppm_validation() {
+        let mut config = SystemConfig::default();
+        assert_eq!(config.max_healthy_ppm, 0.0);
+        config.max_healthy_ppm = 50.0;
+        assert!(config.validate().is_ok());
+        config.max_healthy_ppm = -1.0;
+        assert!(config.validate().is_err());
+        config.max_healthy_ppm = 600.0;
+        assert!(config.validate().is_err());
+        config.max_healthy_ppm = f64::NAN;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_initial_freq_ppm_validation() {
+        let mut config = SystemConfig::default();
+        assert_eq!(config.initial_freq_ppm, 0.0);
+        config.initial_freq_ppm = -35.5;
+        assert!(config.validate().is_ok());
+        config.initial_freq_ppm = 501.0;
+        assert!(config.validate().is_err());
+        config.initial_freq_ppm = f64::NAN;
+        assert!(config.validate().is_err());
+        // Must lie within the healthy band when one is set
+        config.initial_freq_ppm = 60.0;
+        config.max_healthy_ppm = 50.0;
+        assert!(config.validate().is_err());
+        config.initial_freq_ppm = 40.0;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_external_step_threshold_optional_in_json() {
+        assert_eq!(SystemConfig::default().external_step_threshold_ms, 20);
+        let config: SystemConfig =
+            serde_json::from_str(r#"{"external_step_threshold_ms": 0}"#).expect("parse failed");
+        assert_eq!(config.external_step_threshold_ms, 0);
+    }
+
+    #[test]
+    fn test_registry_status_secs_restart_required() {
+        let old = SystemConfig::default();
+        assert_eq!(old.registry_status_secs, 0);
+        let new: SystemConfig =
+            serde_json::from_str(r#"{"registry_status_secs": 10}"#).expect("parse failed");
+        assert_eq!(new.registry_status_secs, 10);
+        assert_eq!(
+            old.restart_required_changes(&new),
+            vec!["registry_status_secs"]
+        );
+    }
+
+    #[test]
+    fn test_epoch_change_alarm_optional_in_json() {
+        assert_eq!(SystemConfig::default().epoch_change_alarm_ms, 100);
+        let config: SystemConfig =
+            serde_json::from_str(r#"{"epoch_change_alarm_ms": 0}"#).expect("parse failed");
+        assert_eq!(config.epoch_change_alarm_ms, 0);
+    }
+
+    #[test]
+    fn test_default_config_validates() {
+        assert!(SystemConfig::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_reports_each_bad_field() {
+        let mut config = SystemConfig::default();
+        config.filters.sample_window_size = 0;
+        config.servo.kp = f64::NAN;
+        config.filters.warmup_secs = -1.0;
+        config.priority.rt_priority = 150;
+
+        let errors = config.validate().unwrap_err();
+        assert_eq!(errors.len(), 4, "{:?}", errors);
+        assert!(errors.iter().any(|e| e.contains("sample_window_size")));
+        assert!(errors.iter().any(|e| e.contains("servo.kp")));
+        assert!(errors.iter().any(|e| e.contains("warmup_secs")));
+        assert!(errors.iter().any(|e| e.contains("rt_priority")));
+    }
+
+    #[test]
+    fn test_validate_rt_priority_only_for_realtime() {
+        let mut config = SystemConfig::default();
+        config.priority.class = PriorityClass::Normal;
+        config.priority.rt_priority = 0;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_ntp_alignment_parsing() {
+        assert_eq!(SystemConfig::default().ntp_alignment, NtpAlignment::Step);
+        let config: SystemConfig = serde_json::from_str(r#"{"ntp_alignment": "slew"}"#).unwrap();
+        assert_eq!(config.ntp_alignment, NtpAlignment::Slew);
+        assert!(serde_json::from_str::<SystemConfig>(r#"{"ntp_alignment": "smear"}"#).is_err());
+    }
+
+    #[test]
+    fn test_receive_backend_parsing() {
+        assert_eq!(SystemConfig::default().receive_backend, ReceiveBackend::Udp);
+        let config: SystemConfig =
+            serde_json::from_str(r#"{"receive_backend": "af_packet"}"#).unwrap();
+        assert_eq!(config.receive_backend, ReceiveBackend::AfPacket);
+        assert!(serde_json::from_str::<SystemConfig>(r#"{"receive_backend": "pcap"}"#).is_err());
+    }
+
+    #[test]
+    fn test_delay_mechanism_parsing() {
+        assert_eq!(SystemConfig::default().delay_mechanism, DelayMechanism::E2e);
+        let config: SystemConfig = serde_json::from_str(r#"{"delay_mechanism": "p2p"}"#).unwrap();
+        assert_eq!(config.delay_mechanism, DelayMechanism::P2p);
+        assert!(serde_json::from_str::<SystemConfig>(r#"{"delay_mechanism": "auto"}"#).is_err());
+    }
+
+    #[test]
+    fn test_ntp_source_parsing() {
+        assert_eq!(SystemConfig::default().ntp_source, NtpSourceKind::Udp);
+        for (json, expected) in [
+            (r#"{"ntp_source": "udp"}"#, NtpSourceKind::Udp),
+            (r#"{"ntp_source": "chrony"}"#, NtpSourceKind::Chrony),
+            (r#"{"ntp_source": "chrony_shm"}"#, NtpSourceKind::Chrony),
+        ] {
+            let config: SystemConfig = serde_json::from_str(json).expect("parse failed");
+            assert_eq!(config.ntp_source, expected, "{}", json);
+        }
+        assert!(serde_json::from_str::<SystemConfig>(r#"{"ntp_source": "gps"}"#).is_err());
+    }
+
+    #[test]
+    fn test_filter_type_parsing() {
+        let config = SystemConfig::default();
+        assert_eq!(config.filters.filter_type, FilterType::Median);
+        assert!((config.filters.trim_percent - 25.0).abs() < f64::EPSILON);
+
+        let json = r#"{"sample_window_size": 8, "min_delta_ns": 0, "calibration_samples": 0,
+                       "warmup_secs": 0.0, "filter_type": "trimmed_mean", "trim_percent": 10.0}"#;
+        let filters: FilterConfig = serde_json::from_str(json).expect("parse failed");
+        assert_eq!(filters.filter_type, FilterType::TrimmedMean);
+        assert!((filters.trim_percent - 10.0).abs() < f64::EPSILON);
+
+        // `window_estimator` is accepted as an alias
+        let json = r#"{"sample_window_size": 8, "min_delta_ns": 0, "calibration_samples": 0,
+                       "warmup_secs": 0.0, "window_estimator": "min_delay"}"#;
+        let aliased: FilterConfig = serde_json::from_str(json).expect("parse failed");
+        assert_eq!(aliased.filter_type, FilterType::MinDelay);
+
+        let mut config = SystemConfig::default();
+        config.filters.trim_percent = 50.0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_spike_mad_floor_config() {
+        let config = SystemConfig::default();
+        assert!((config.filters.spike_mad_floor_us_s - MIN_MAD_FLOOR).abs() < f64::EPSILON);
+
+        let json = r#"{"sample_window_size": 8, "min_delta_ns": 0, "calibration_samples": 0,
+                       "warmup_secs": 0.0, "spike_mad_floor_us_s": 0.02}"#;
+        let filters: FilterConfig = serde_json::from_str(json).expect("parse failed");
+        assert!((filters.spike_mad_floor_us_s - 0.02).abs() < f64::EPSILON);
+
+        let mut config = SystemConfig::default();
+        config.filters.spike_mad_floor_us_s = 0.0;
+        assert!(config.validate().is_err());
+        config.filters.spike_mad_floor_us_s = f64::NAN;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_schema_documents_every_field() {
+        fn check(value: &serde_json::Value, schema: &serde_json::Value, path: &str) {
+            let Some(fields) = value.as_object() else {
+                return;
+            };
+            for (key, child) in fields {
+                let child_path = format!("{}.{}", path, key);
+                let child_schema = schema
+                    .get("properties")
+                    .and_then(|p| p.get(key))
+                    .unwrap_or_else(|| panic!("{} missing from config.schema.json", child_path));
+                check(child, child_schema, &child_path);
+            }
+        }
+
+        let schema: serde_json::Value =
+            serde_json::from_str(include_str!("../config.schema.json")).unwrap();
+        let defaults = serde_json::to_value(SystemConfig::default()).unwrap();
+        check(&defaults, &schema["properties"]["system"], "system");
+    }
+
+    #[test]
+    fn test_max_ppm_step_validation() {
+        let mut config = SystemConfig::default();
+        assert_eq!(config.max_ppm_step_per_sample, 0.0);
+        assert!(config.validate().is_ok());
+        config.max_ppm_step_per_sample = 20.0;
+        assert!(config.validate().is_ok());
+        config.max_ppm_step_per_sample = -1.0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_sync_interval_hint_validation() {
+        let mut config = SystemConfig::default();
+        assert_eq!(config.filters.sync_interval_hint_ms, 0);
+        config.filters.sync_interval_hint_ms = 1000;
+        assert!(config.validate().is_ok());
+        config.filters.sync_interval_hint_ms = 60_000;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_pending_sync_limits_validation() {
+        let mut config = SystemConfig::default();
+        assert_eq!(config.filters.max_pending_syncs, 4);
+        assert_eq!(config.filters.pending_sync_timeout_ms, 0);
+        config.filters.max_pending_syncs = 16;
+        config.filters.pending_sync_timeout_ms = 500;
+        assert!(config.validate().is_ok());
+        config.filters.max_pending_syncs = 0;
+        assert!(config.validate().is_err());
+        config.filters.max_pending_syncs = 4;
+        config.filters.pending_sync_timeout_ms = 60_000;
+        assert!(config.validate().is_err());
+
+        // Older files without the keys get the defaults
+        let filters: FilterConfig = serde_json::from_str(
+            r#"{"sample_window_size": 4, "min_delta_ns": 0, "calibration_samples": 0, "warmup_secs": 0.0}"#,
+        )
+        .expect("parse failed");
+        assert_eq!(filters.max_pending_syncs, 4);
+        assert_eq!(filters.pending_sync_timeout_ms, 0);
+    }
+
+    #[test]
+    fn test_watchdog_secs_validation() {
+        let mut config = SystemConfig::default();
+        assert_eq!(config.watchdog_secs, 60);
+        config.watchdog_secs = 0;
+        assert!(config.validate().is_ok());
+        config.watchdog_secs = 10;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_log_level_parsing_and_validation() {
+        let mut config = SystemConfig::default();
+        assert_eq!(config.logging.level_filter(), log::LevelFilter::Info);
+
+        let logging: LogConfig = serde_json::from_str(r#"{"level": "DEBUG"}"#).unwrap();
+        assert_eq!(logging.level_filter(), log::LevelFilter::Debug);
+
+        config.logging.level = "chatty".to_string();
+        assert!(config.validate().is_err());
+        assert_eq!(config.logging.level_filter(), log::LevelFilter::Info);
+    }
+
+    #[test]
+    fn test_expected_gm_uuid_validation() {
+        let mut config = SystemConfig::default();
+        assert!(config.expected_gm_uuid.is_none());
+        config.expected_gm_uuid = Some("00:1D:C1:51:D0:D9".to_string());
+        assert!(config.validate().is_ok());
+        config.expected_gm_uuid = Some("00:1D:C1".to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_ntp_interface_validation() {
+        let mut config = SystemConfig::default();
+        assert!(config.ntp_interface.is_none());
+        for ok in ["any", "eth1", "10.0.0.5", "10.0.0.0/8"] {
+            config.ntp_interface = Some(ok.to_string());
+            assert!(config.validate().is_ok(), "{}", ok);
+        }
+        config.ntp_interface = Some("10.0.0.0/40".to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_interface_policy_config() {
+        let config = SystemConfig::default();
+        assert_eq!(config.interface_policy(), InterfacePolicy::default());
+
+        let json = r#"{"interface_preference": ["eth1", "10.77.8.0/24"], "allow_wireless": false}"#;
+        let config: SystemConfig = serde_json::from_str(json).unwrap();
+        assert!(config.validate().is_ok());
+        let policy = config.interface_policy();
+        assert!(!policy.allow_wireless);
+        assert_eq!(
+            policy.preference,
+            vec![
+                InterfaceSelector::Name("eth1".to_string()),
+                InterfaceSelector::parse("10.77.8.0/24").unwrap(),
+            ]
+        );
+
+        let config = SystemConfig {
+            interface_preference: vec!["eth1".to_string(), "10.0.0.0/40".to_string()],
+            ..Default::default()
+        };
+        let errors = config.validate().unwrap_err();
+        assert!(errors
+            .iter()
+            .any(|e| e.starts_with("interface_preference[1]")));
+    }
+
+    #[test]
+    fn test_qos_config_defaults_and_validation() {
+        let config = SystemConfig::default();
+        assert_eq!(config.qos.event().dscp, 48); // CS6
+        assert_eq!(config.qos.general().dscp, 0);
+        assert_eq!(config.qos.event().multicast_ttl, 1);
+
+        let mut config = SystemConfig::default();
+        config.qos.event_dscp = 64;
+        config.qos.multicast_ttl = 0;
+        assert_eq!(config.validate().unwrap_err().len(), 2);
+    }
+
+    #[test]
+    fn test_capture_config_defaults() {
+        let config = SystemConfig::default();
+        assert_eq!(config.capture.packets, 0, "Capture is opt-in");
+        assert!(config.capture.dump_on_unlock);
+
+        let capture: CaptureConfig =
+            serde_json::from_str(r#"{"packets": 2000}"#).expect("parse failed");
+        assert_eq!(capture.packets, 2000);
+        assert!(capture.dir.is_none());
+    }
+
+    #[test]
+    fn test_notification_config_defaults() {
+        let config = SystemConfig::default();
+        assert_eq!(config.notifications.min_interval_secs, 60);
+        assert!(config.notifications.ntp && config.notifications.updates);
+
+        // Silence PTP flapping, keep everything else
+        let notifications: NotificationConfig =
+            serde_json::from_str(r#"{"ptp": false, "min_interval_secs": 300}"#)
+                .expect("parse failed");
+        assert!(!notifications.ptp);
+        assert!(notifications.ntp);
+        assert_eq!(notifications.min_interval_secs, 300);
+    }
+
+    #[test]
+    fn test_lock_thresholds_validation() {
+        let mut config = SystemConfig::default();
+        assert!((config.lock_rate_us_s - 5.0).abs() < f64::EPSILON);
+        assert!((config.unlock_rate_us_s - 20.0).abs() < f64::EPSILON);
+
+        // Tighter thresholds for hardware-timestamped hosts
+        config.lock_rate_us_s = 2.0;
+        config.unlock_rate_us_s = 8.0;
+        assert!(config.validate().is_ok());
+
+        // No hysteresis
+        config.lock_rate_us_s = 10.0;
+        let errors = config.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.contains("hysteresis")));
+    }
+
+    #[test]
+    fn test_restart_required_changes() {
+        let old = SystemConfig::default();
+        let mut new = old.clone();
+        new.lock_rate_us_s = 3.0;
+        new.filters.sample_window_size = 8;
+        assert!(old.restart_required_changes(&new).is_empty());
+
+        new.qos.event_dscp = 46;
+        new.csv_log_path = Some("/tmp/samples.csv".to_string());
+        assert_eq!(
+            old.restart_required_changes(&new),
+            vec!["qos", "csv_log_path"]
+        );
+    }
+
+    #[test]
+    fn test_ipc_access_sid_validation() {
+        let mut config = SystemConfig::default();
+        assert_eq!(config.ipc_access_sid, "AU");
+        for ok in [
+            "BU",
+            "S-1-5-32-545",
+            "S-1-5-21-1004336348-1177238915-682003330-512",
+        ] {
+            config.ipc_access_sid = ok.to_string();
+            assert!(config.validate().is_ok(), "{} should be accepted", ok);
+        }
+        // Anything that could extend the DACL is rejected
+        for bad in ["", "au", "S-1-", "S-1-5-x", "AU)(A;;GA;;;WD"] {
+            config.ipc_access_sid = bad.to_string();
+            let errors = config.validate().unwrap_err();
+            assert!(
+                errors.iter().any(|e| e.contains("ipc_access_sid")),
+                "{}",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn test_target_sample_rate_sizes_deadband() {
+        let mut config = SystemConfig::default();
+        assert_eq!(config.target_sample_rate_hz, 0);
+        assert_eq!(config.sample_period_us(), None);
+        assert!((config.effective_deadband_us_s() - 0.3).abs() < f64::EPSILON);
+
+        // 48kHz: one sample is 20.8µs, twice the 96kHz dead-band
+        config.target_sample_rate_hz = 48_000;
+        assert!((config.sample_period_us().unwrap() - 20.833).abs() < 0.001);
+        assert!((config.effective_deadband_us_s() - 0.625).abs() < 1e-9);
+        assert!(config.validate().is_ok());
+
+        config.target_sample_rate_hz = 8_000;
+        config.lock_rate_us_s = 3.0;
+        let errors = config.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.contains("target_sample_rate_hz")));
+
+        config.lock_rate_us_s = 5.0;
+        config.target_sample_rate_hz = 1_000;
+        let errors = config.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.contains("8000-768000")));
+
+        let config: SystemConfig =
+            serde_json::from_str(r#"{"target_sample_rate_hz": 192000}"#).expect("parse failed");
+        assert!((config.effective_deadband_us_s() - 0.15625).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_deadband_validation() {
+        let mut config = SystemConfig::default();
+        assert!((config.deadband_us_s - 0.3).abs() < f64::EPSILON);
+        config.deadband_us_s = 0.0;
+        assert!(config.validate().is_ok());
+        config.deadband_us_s = -0.1;
+        assert!(config.validate().is_err());
+
+        // A dead-band as wide as the lock window would hide real drift
+        config.deadband_us_s = config.lock_rate_us_s;
+        let errors = config.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.contains("deadband_us_s")));
+    }
+
+    #[test]
+    fn test_partial_system_config_uses_defaults() {
+        // "system": {"logging": {...}} alone must not discard the whole section
+        let json = r#"{"logging": {"max_files": 1}}"#;
+        let config: SystemConfig = serde_json::from_str(json).expect("parse failed");
+        assert_eq!(config.logging.max_files, 1);
+        assert_eq!(config.filters.sample_window_size, 4);
+    }
+
     #[test]
     fn test_servo_config_clone() {
         let config = SystemConfig::default();
@@ -5,17 +5,29 @@
 //! 2. **Production Phase**: Precision maintenance using adaptive PI control with soft dead zones
 //!
 //! Key features:
-//! - Lucky packet filtering (minimum offset selection) for jitter immunity
+//! - Sample window estimator (median by default, or lucky packet / minimum
+//!   delay selection) for jitter immunity
 //! - Adaptive gain tuning based on oscillation detection
-//! - Soft dead zones tuned for 96kHz audio (1 sample = 10.4µs)
-
-use crate::clock::SystemClock;
-use crate::config::SystemConfig;
-use crate::ptp::{PtpV1Control, PtpV1FollowUpBody, PtpV1Header, PtpV1SyncMessageBody};
-use crate::spike_filter::{FilterMode, JitterEstimator, SpikeFilter};
-use crate::status::SyncStatus;
+//! - Soft dead zones tuned for 96kHz audio (1 sample = 10.4µs), or sized
+//!   from `target_sample_rate_hz` for other plants
+
+use crate::clock::{LeapSecond, SystemClock};
+use crate::config::{FilterType, NtpAlignment, SystemConfig};
+use crate::error::{DanteSyncError, Result};
+use crate::ptp::{
+    is_dante_master, parse_tlvs, PtpV1Control, PtpV1FollowUpBody, PtpV1Header,
+    PtpV1SyncMessageBody, PtpV2Header, PtpV2MessageType, PTP_V1_SYNC_LEN,
+};
+use crate::samplelog::{CsvSampleLog, SampleFifo, SampleRecord};
+use crate::sniff::format_clock_identity;
+use crate::spike_filter::{
+    FilterMode, JitterEstimator, NoiseFloorEstimator, SpikeFilter, WindowedCounter,
+};
+use crate::status::{
+    format_duration, format_mac, parse_mac, IpcCommand, NetworkQuality, SyncStatus,
+};
+use crate::telemetry::TelemetrySender;
 use crate::traits::{NtpSource, PtpNetwork};
-use anyhow::Result;
 use log::{debug, error, info, warn};
 use std::collections::{HashMap, VecDeque};
 use std::sync::{Arc, RwLock};
@@ -25,12 +37,139 @@ use std::time::{Duration, Instant, SystemTime};
 // HELPER FUNCTIONS
 // ============================================================================
 
-/// Format a 6-byte UUID/MAC as a readable string (e.g., "00:1D:C1:AB:CD:EF")
-fn format_mac(uuid: &[u8; 6]) -> String {
-    format!(
-        "{:02X}:{:02X}:{:02X}:{:02X}:{:02X}:{:02X}",
-        uuid[0], uuid[1], uuid[2], uuid[3], uuid[4], uuid[5]
-    )
+/// Project a (T2 - T1) offset from the Sync receive instant to FollowUp processing.
+///
+/// While the FollowUp is in flight the local clock keeps running with
+/// `freq_ppm` applied, so by the time the pair is processed the offset has
+/// moved by `elapsed_ns * freq_ppm`. At ACQ-level corrections (200ppm) and
+/// typical Sync→FollowUp gaps this is a few µs of systematic bias.
+fn project_offset_ns(offset_ns: i64, elapsed_ns: i64, freq_ppm: f64) -> i64 {
+    if !(0..=MAX_PROJECTION_NS).contains(&elapsed_ns) {
+        return offset_ns;
+    }
+    offset_ns + (elapsed_ns as f64 * freq_ppm * 1e-6).round() as i64
+}
+
+/// Move from `current_ppm` toward `target_ppm` by at most `max_step_ppm`
+/// (0 = no limit), so the OS never applies an abrupt frequency step.
+fn slew_limit_ppm(current_ppm: f64, target_ppm: f64, max_step_ppm: f64) -> f64 {
+    if max_step_ppm <= 0.0 {
+        return target_ppm;
+    }
+    current_ppm + (target_ppm - current_ppm).clamp(-max_step_ppm, max_step_ppm)
+}
+
+/// Offset noise of one sample window (ns, standard deviation)
+///
+/// Takes samples in arrival order and removes the least-squares line first,
+/// so steady drift across the window is not mistaken for timestamp jitter.
+/// Samples are ~evenly spaced (min_delta), so the index stands in for time.
+fn offset_jitter_ns(samples: &[i64]) -> f64 {
+    let n = samples.len();
+    if n < 3 {
+        return 0.0;
+    }
+    let base = samples[0];
+    let ys: Vec<f64> = samples.iter().map(|&s| (s - base) as f64).collect();
+    let mean_x = (n - 1) as f64 / 2.0;
+    let mean_y = ys.iter().sum::<f64>() / n as f64;
+    let (mut sxy, mut sxx) = (0.0, 0.0);
+    for (i, y) in ys.iter().enumerate() {
+        let dx = i as f64 - mean_x;
+        sxy += dx * (y - mean_y);
+        sxx += dx * dx;
+    }
+    let slope = sxy / sxx;
+    let sum_sq: f64 = ys
+        .iter()
+        .enumerate()
+        .map(|(i, y)| {
+            let residual = y - (mean_y + slope * (i as f64 - mean_x));
+            residual * residual
+        })
+        .sum();
+    // Two degrees of freedom go to the fitted line
+    (sum_sq / (n - 2) as f64).sqrt()
+}
+
+/// Robust calibration offset from a non-empty set of samples
+///
+/// Drops the `drop` samples furthest from the preliminary median (e.g. a
+/// congestion burst during calibration), then takes the median of the rest.
+/// Returns (offset, spread) with spread = max - min of the kept samples.
+fn calibration_offset_ns(samples: &[i64], drop: usize) -> (i64, i64) {
+    let mut kept = samples.to_vec();
+    kept.sort_unstable();
+    let preliminary = kept[kept.len() / 2];
+    kept.sort_by_key(|&s| (s - preliminary).unsigned_abs());
+    kept.truncate(samples.len().saturating_sub(drop).max(1));
+    kept.sort_unstable();
+    (kept[kept.len() / 2], kept[kept.len() - 1] - kept[0])
+}
+
+/// Count one more consecutive sighting of `uuid` as the replacement for the
+/// current sync source / grandmaster; true once it has been seen `needed`
+/// times in a row (the candidate is then cleared)
+fn confirm_switch(candidate: &mut Option<([u8; 6], u32)>, uuid: [u8; 6], needed: u32) -> bool {
+    let seen = match candidate {
+        Some((current, count)) if *current == uuid => {
+            *count += 1;
+            *count
+        }
+        _ => {
+            *candidate = Some((uuid, 1));
+            1
+        }
+    };
+    if seen >= needed {
+        *candidate = None;
+        true
+    } else {
+        false
+    }
+}
+
+/// Day of the month (1-31) for a day count since 1970-01-01 (proleptic Gregorian)
+fn day_of_month(days_since_epoch: i64) -> u32 {
+    // Howard Hinnant's civil_from_days
+    let z = days_since_epoch + 719_468;
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    (doy - (153 * mp + 2) / 5 + 1) as u32
+}
+
+/// UTC midnight (Unix seconds) at which an announced leap second takes
+/// effect: leaps only happen at the end of a month, and servers may announce
+/// them weeks early, so None unless tomorrow is the 1st
+fn leap_boundary_secs(now_secs: i64) -> Option<i64> {
+    let tomorrow = now_secs.div_euclid(86_400) + 1;
+    (day_of_month(tomorrow) == 1).then_some(tomorrow * 86_400)
+}
+
+/// Reduce a sorted, non-empty sample window to one offset estimate
+fn estimate_offset_ns(sorted: &[i64], filter_type: FilterType, trim_percent: f64) -> i64 {
+    let median = sorted[sorted.len() / 2];
+    let mean = |samples: &[i64]| -> i64 {
+        let sum: i128 = samples.iter().map(|&x| x as i128).sum();
+        (sum / samples.len() as i128) as i64
+    };
+
+    match filter_type {
+        FilterType::Median => median,
+        FilterType::Mean => mean(sorted),
+        // Offset is T2 - T1: the minimum is the sample with the least path delay
+        FilterType::MinDelay => sorted[0],
+        FilterType::TrimmedMean => {
+            let k = (sorted.len() as f64 * trim_percent / 100.0).floor() as usize;
+            if 2 * k >= sorted.len() {
+                median
+            } else {
+                mean(&sorted[k..sorted.len() - k])
+            }
+        }
+    }
 }
 
 // ============================================================================
@@ -39,6 +178,22 @@ fn format_mac(uuid: &[u8; 6]) -> String {
 
 // Safety limits
 const MAX_DELTA_NS: i64 = 2_000_000_000; // 2s - reject obviously invalid deltas
+const MAX_CONSECUTIVE_REJECTS: u32 = 3; // then accept the jump as the master's new timeline
+const MAX_PROJECTION_NS: i64 = 1_000_000_000; // 1s - don't project across implausible Sync→FollowUp gaps
+
+// PTP flood protection (a misbehaving device flooding the multicast group)
+const PTP_RATE_WINDOW: Duration = Duration::from_secs(1);
+const PTP_RATE_LIMIT_FACTOR: f64 = 20.0; // Flood = this many times the expected Sync + Follow_Up rate
+const PTP_RATE_LIMIT_FLOOR: u32 = 200; // Packets/s always allowed (other devices' Delay_Req share the group)
+const PTP_FLOOD_DRAIN_PER_ITERATION: usize = 32; // Excess packets discarded per loop pass, so the loop still yields
+
+// Network quality (offset jitter per sample window)
+const MARGINAL_JITTER_NS: f64 = 50_000.0; // 50µs - well above NIC timestamp noise
+const POOR_JITTER_NS: f64 = 200_000.0; // 200µs - PTP is queuing behind other traffic
+                                       // The same in audio samples, with `target_sample_rate_hz` (~5/~20 at 96kHz)
+const MARGINAL_JITTER_SAMPLES: f64 = 5.0;
+const POOR_JITTER_SAMPLES: f64 = 20.0;
+const NETWORK_QUALITY_WINDOWS: u32 = 10; // consecutive windows before the quality changes
 
 // ==========================================================================
 // SELF-TUNING SERVO ALGORITHM
@@ -73,6 +228,13 @@ const MAX_DELTA_NS: i64 = 2_000_000_000; // 2s - reject obviously invalid deltas
 // Acquisition phase (FAST convergence)
 const P_GAIN_ACQ: f64 = 0.8; // Aggressive P-term for quick lock
 const P_MAX_ACQ_PPM: f64 = 200.0; // Limit to prevent wild swings
+const I_GAIN_ACQ: f64 = 0.05;
+
+// Cold start (first `cold_start_secs` after settling, until the first lock):
+// the I-term learns large drifts ~4x faster. The boost fades linearly to the
+// ACQ values, so the hand-over is smooth; the learned baseline is kept.
+const COLD_START_I_GAIN: f64 = 0.25;
+const COLD_START_P_MAX_PPM: f64 = 400.0;
 
 // Production phase (gentle stability)
 const P_GAIN_PROD: f64 = 0.1; // Gentle P-term in production
@@ -89,27 +251,82 @@ const NANO_EXIT_RATE_US: f64 = 1.0; // Exit NANO if drift > 1.0 µs/s
 const NANO_SUSTAIN_COUNT: usize = 15; // 15 samples (~15s) to enter NANO
 const NANO_EXIT_COUNT: usize = 5; // 5 consecutive samples above threshold to exit (hysteresis)
 const NANO_DEADBAND_US: f64 = 0.1; // Ignore drift < 0.1 µs/s (noise floor)
+                                   // Dead-bands in audio samples per second, with `target_sample_rate_hz`
+                                   // (0.03 and 0.01 match the 0.3 / 0.1 µs/s defaults at 96kHz)
+pub const PROD_DEADBAND_SAMPLES_PER_S: f64 = 0.03;
+const NANO_DEADBAND_SAMPLES_PER_S: f64 = 0.01;
 
 // Max drift baseline limit
 const DRIFT_MAX_PPM: f64 = 500.0;
+// max_healthy_ppm: the fault clears once the needed correction is back
+// inside this fraction of the band, so noise at the edge does not flap it
+const OSCILLATOR_FAULT_CLEAR_FRACTION: f64 = 0.9;
+
+// Integral bleed-off (`integral_bleed`): EMA weight of the steady-state
+// correction, learned only from locked samples with a stable rate
+const STEADY_CORRECTION_ALPHA: f64 = 0.05;
 
 // Lock detection
 const LOCK_STABLE_COUNT: usize = 5;
+// auto_thresholds: lock at this many σ of the long-run rate noise, within
+// these bounds (µs/s, never below the deadband); unlock keeps the configured
+// unlock/lock ratio
+const AUTO_LOCK_SIGMAS: f64 = 3.0;
+const AUTO_LOCK_MIN_US_S: f64 = 1.0;
+const AUTO_LOCK_MAX_US_S: f64 = 20.0;
+// Re-log the derived thresholds when they move by more than this fraction
+const AUTO_LOCK_LOG_CHANGE: f64 = 0.25;
 
 // Lucky packet filter - minimum time between samples (config override available)
 const DEFAULT_MIN_T1_DELTA_NS: i64 = 100_000_000; // 100ms default (Dante sends ~125ms)
 
+// Sync rate adaptation - window/min_delta/alpha defaults are tuned for Dante's 8Hz
+const NOMINAL_SYNC_INTERVAL_MS: f64 = 125.0;
+// Pending Syncs: the 16-bit sequence id wraps (~2.3h at 8Hz), so only the
+// last few Syncs may be paired, and only with a Follow_Up that arrives soon
+pub const PENDING_SYNC_MAX_AGE_SYNCS: u64 = 4;
+// Follow_Ups that overtake their Sync (switch reordering) wait this many at most
+const MAX_ORPHAN_FOLLOWUPS: usize = 4;
+const PENDING_SYNC_TIMEOUT_INTERVALS: f64 = 2.0;
+const MIN_PENDING_SYNC_TIMEOUT: Duration = Duration::from_millis(250);
+// Unmatched Sync rate: EMA over roughly the last 100 Syncs
+const UNMATCHED_SYNC_ALPHA: f64 = 0.01;
+const MIN_DELTA_INTERVAL_FRACTION: f64 = 0.8; // Never demand more than 80% of the Sync interval
+const MAX_ADAPTED_WINDOW: usize = 64; // Cap window growth for fast (e.g. 32Hz+) masters
+
 // Periodic NTP UTC alignment (steps clock without changing frequency)
 const NTP_CHECK_INTERVAL_SECS: u64 = 30; // Check NTP every 30 seconds
 const NTP_SAMPLE_COUNT: usize = 5; // Samples needed for reliable median
-const NTP_STEP_THRESHOLD_US: i64 = 500; // Step if offset > 500µs (tighter UTC alignment)
+pub const NTP_STEP_THRESHOLD_US: i64 = 500; // Default step floor: offset > 500µs (tighter UTC alignment)
+pub const DEFAULT_NTP_STEP_JITTER_K: f64 = 3.0; // Noisy NTP paths: step only beyond 3σ of recent offsets
+const NTP_JITTER_MIN_SAMPLES: usize = 3; // Offsets needed before jitter raises the step threshold
+const NTP_RTT_SAMPLE_COUNT: usize = 20; // NTP answers whose minimum RTT is the path baseline
+const NTP_WARMUP_POLL_MS: u64 = 500; // Startup NTP sampling interval during ntp_warmup_secs
+const NTP_STEP_GRACE: Duration = Duration::from_secs(2); // Ignore PTP samples right after a step
+const EPOCH_CHANGE_ALARM_HOLD: Duration = Duration::from_secs(600); // master_epoch_changed stays raised this long
+
+// allow_backward_step = false / ntp_alignment = slew: remove an offset by
+// running slow (clock ahead) or fast (clock behind) instead of stepping.
+// Leaves room under the kernel's 500ppm limit for the servo's own correction.
+const SLEW_PPM: f64 = 200.0;
+// The slew bias moves by at most max_ppm_step_per_sample (SLEW_RAMP_PPM when
+// that is unlimited) this often: a slew exists to avoid abrupt changes, so
+// starting and ending one never switches the rate by SLEW_PPM at once
+const SLEW_RAMP_INTERVAL: Duration = Duration::from_secs(1);
+const SLEW_RAMP_PPM: f64 = 20.0;
 
 // PTP offline detection
 const PTP_TIMEOUT_SECS: u64 = 10; // Consider PTP offline after 10s without packets
+                                  // While PTP is offline, a quiet receive poll refreshes the status (and counts
+                                  // as progress) this often, so NTP-only operation is not mistaken for a stall
+const IDLE_PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
 
 // NTP failure detection
 const NTP_FAILURE_THRESHOLD: usize = 3; // Consider NTP failed after 3 consecutive failures
 
+// Network failure detection (NIC unplugged / device removed)
+const NETWORK_FAILURE_SECS: u64 = 5; // Request network rebuild after 5s of continuous recv errors
+
 // ============================================================================
 // DATA STRUCTURES
 // ============================================================================
@@ -129,22 +346,85 @@ where
 
     // PTP state
     pending_syncs: HashMap<u16, PendingSync>,
+    /// Follow_Ups that arrived before their Sync, by associated sequence id
+    orphan_followups: HashMap<u16, OrphanFollowUp>,
     prev_t1_ns: i64,
+    /// Sync pairs dropped for impossible master time jumps (cumulative)
+    rejected_sync_pairs: u64,
+    /// Syncs that never got their Follow_Up (since `counters_since`)
+    unmatched_syncs: u64,
+    /// Recent fraction of Syncs without a Follow_Up (EMA, 0-1)
+    unmatched_sync_ratio: f64,
+    /// PTPv2 Sync/Follow_Ups ignored for alternateMasterFlag / unicastFlag
+    /// (since `counters_since`)
+    flag_ignored_alternate_master: u64,
+    flag_ignored_unicast: u64,
+    consecutive_rejects: u32,
+    /// Receive time of the packet opening the current `PTP_RATE_WINDOW`, and
+    /// packets received in it. Measured on packet timestamps, not the loop's
+    /// clock, so a backlog read in one burst is not mistaken for a flood
+    ptp_rate_window: (SystemTime, u32),
+    /// Over the PTP rate limit (logged once per flood)
+    ptp_flooding: bool,
+    /// Packets discarded unparsed by the rate limit (cumulative)
+    ptp_flood_dropped: u64,
     prev_t2_ns: i64,
     current_gm_uuid: Option<[u8; 6]>,
+    /// Grandmaster identified as Dante (Audinate UUID or TLV); None until seen
+    master_is_dante: Option<bool>,
+    /// Current grandmaster is not `expected_gm_uuid` (its Syncs are ignored)
+    gm_mismatch: bool,
     /// The source UUID of the device sending Sync messages (may differ from grandmaster_clock_uuid)
     current_sync_source: Option<[u8; 6]>,
+    /// Sync source / grandmaster seen in place of the current one, with its
+    /// consecutive Sync count (see `source_switch_syncs`)
+    source_candidate: Option<([u8; 6], u32)>,
+    gm_candidate: Option<([u8; 6], u32)>,
+    /// Servo windows still run with reduced gains after a source handover
+    /// (`handover_blend_windows`)
+    handover_windows_left: u32,
+    /// Arrival time of the previous Sync (for observed interval)
+    last_sync_rx: Option<SystemTime>,
+    /// Smoothed inter-Sync arrival interval (ms, 0 = not yet measured)
+    sync_interval_ms: f64,
+    /// Syncs accepted so far; tags pending Syncs so reused ids can be aged out
+    sync_arrivals: u64,
+    /// Sample window size currently in use (grows for fast Sync rates)
+    adapted_window_size: usize,
 
     // Sample filtering
     sample_window: Vec<i64>,
+    /// Sync source of each `sample_window` entry
+    sample_sources: Vec<[u8; 6]>,
 
     // Metrics (for status display)
     last_phase_offset_ns: i64,
+    /// Detrended std-dev of the offsets in the last sample window
+    last_offset_jitter_ns: f64,
+    network_quality: NetworkQuality,
+    /// Consecutive windows whose jitter disagrees with `network_quality`
+    network_quality_streak: u32,
     last_adj_ppm: f64,
+    last_ntp_offset_us: i64,
+    /// Optional per-sample CSV log for offline analysis
+    sample_log: Option<CsvSampleLog>,
+    /// Live per-sample stream for plotting (`sample_fifo_path`)
+    sample_fifo: Option<SampleFifo>,
+    /// UDP status export (`telemetry_target`)
+    telemetry: Option<TelemetrySender>,
 
     // Epoch tracking
     initial_epoch_offset_ns: i64,
     epoch_aligned: bool,
+    /// Epoch offset (T2 - T1, ns) and T2 of the last settled pair
+    last_epoch: Option<(i64, i64)>,
+    /// When the epoch offset last jumped beyond `epoch_change_alarm_ms`
+    epoch_changed_at: Option<Instant>,
+    /// Size of that jump (ns)
+    epoch_shift_ns: i64,
+    /// System and monotonic time at the last loop pass, to spot steps by
+    /// other processes (None right after our own steps)
+    wall_clock_ref: Option<(SystemTime, Instant)>,
 
     // Settling state
     valid_count: usize,
@@ -161,6 +441,8 @@ where
 
     // Frequency control state
     applied_freq_ppm: f64,
+    /// The servo needs more than `max_healthy_ppm` (output clamped at the band)
+    oscillator_fault: bool,
 
     // Warmup tracking
     warmup_start: Instant,
@@ -173,10 +455,25 @@ where
     // ==========================================================================
     /// Learned drift baseline (auto-tuned from average correction when stable)
     drift_baseline_ppm: f64,
+    /// Correction that held the rate steady while locked (bleed-off target)
+    steady_correction_ppm: Option<f64>,
 
     /// Lock state - true when synchronized and stable
     is_locked: bool,
     lock_stable_count: usize,
+    /// When the current lock was acquired (None while unlocked)
+    locked_since: Option<Instant>,
+    /// Cumulative LOCK → UNLOCK transitions
+    unlock_count: u64,
+
+    /// Settle-time metric: starts at warmup completion (restarted by a servo
+    /// reset) and records how long the first LOCK / NANO took
+    settle_start: Option<Instant>,
+    time_to_lock: Option<Duration>,
+    /// Locked at least once since start (a servo reset does not clear it)
+    ever_locked: bool,
+    cold_start_active: bool,
+    time_to_nano: Option<Duration>,
 
     /// Production mode state (with hysteresis)
     in_production_mode: bool,
@@ -190,21 +487,61 @@ where
     last_offset_us: Option<f64>,
     last_offset_time: Option<Instant>,
     smoothed_rate_ppm: f64, // Exponential moving average of rate
+    /// Recent servo rates for the calmer display average (not used for control)
+    display_rates: VecDeque<f64>,
 
     // Periodic NTP UTC tracking state
     last_ntp_check: Instant,
     ntp_offset_samples: VecDeque<i64>, // in microseconds
+    /// Round-trip delays (µs) of recent NTP answers; the minimum is the
+    /// path's uncongested delay
+    ntp_rtt_samples: VecDeque<i64>,
     ntp_tracking_enabled: bool,
+    /// Clock corrections halted from the tray: samples are still measured
+    /// and reported, but the clock is neither slewed nor stepped
+    paused: bool,
     last_ntp_step: Option<Instant>, // Grace period after NTP stepping
+    /// Status reports `resettling` (cleared once the grace period ends)
+    resettling: bool,
+    /// Offset (µs) still to be slewed out instead of stepped: positive =
+    /// clock ahead (running slow), negative = clock behind (running fast)
+    slew_remaining_us: f64,
+    slew_updated: Instant,
+    /// Slew offset currently on the clock (ppm; negative = running fast),
+    /// ramping toward `slew_target_ppm`
+    slew_bias_ppm: f64,
+    slew_ramped: Option<Instant>,
+
+    // Leap seconds: announced by the PTP master (header flags) or NTP (LI)
+    ptp_leap: Option<LeapSecond>,
+    ntp_leap: Option<LeapSecond>,
+    /// Leap to apply at the given UTC midnight (Unix seconds)
+    leap_scheduled: Option<(LeapSecond, i64)>,
+    /// Boundary of the last applied leap, so it is not applied twice
+    last_leap_boundary: i64,
 
     // PTP offline detection
     last_ptp_packet: Instant,
     ptp_offline: bool,
     ptp_offline_logged: bool, // Prevent repeated logging
+    /// Advances when the loop does real work (see `progress`)
+    progress: u64,
+    idle_progress_at: Instant,
 
     // NTP failure tracking
     ntp_consecutive_failures: usize,
     ntp_failed: bool,
+    /// All failed NTP queries (rolling + since `counters_since`)
+    ntp_failure_counter: WindowedCounter,
+    /// Start of the spike/NTP failure counters (service start or ResetCounters)
+    counters_since: SystemTime,
+
+    // Network receive failure tracking
+    recv_error_since: Option<Instant>,
+    recv_error_count: u64,
+
+    /// Last `[Heartbeat]` log line (`heartbeat_secs`)
+    last_heartbeat: Instant,
 
     // ==========================================================================
     // ADAPTIVE SPIKE DETECTION
@@ -224,11 +561,24 @@ where
     // ==========================================================================
     /// Jitter estimator for adaptive EMA alpha
     jitter_estimator: JitterEstimator,
+    /// Long-run rate noise, for `auto_thresholds`
+    noise_floor: NoiseFloorEstimator,
+    /// Derived lock threshold last logged (0 = none yet)
+    logged_auto_lock_us_s: f64,
 }
 
 struct PendingSync {
     rx_time_sys: SystemTime,
     source_uuid: [u8; 6],
+    /// `sync_arrivals` when this Sync was received
+    arrival: u64,
+}
+
+struct OrphanFollowUp {
+    /// Precise origin timestamp (T1) carried by the Follow_Up
+    t1_ns: i64,
+    source_uuid: [u8; 6],
+    rx_time_sys: SystemTime,
 }
 
 // ============================================================================
@@ -251,6 +601,9 @@ where
         let window_size = config.filters.sample_window_size;
         let calibration_count = config.filters.calibration_samples;
         let calibration_complete = calibration_count == 0;
+        let counter_window = Duration::from_secs(config.counter_window_secs.max(1));
+        let spike_mad_floor = config.filters.spike_mad_floor_us_s;
+        let initial_freq_ppm = config.initial_freq_ppm;
 
         info!("=== PTP Controller Initialization ===");
         info!("Mode: AUTO-ADAPTIVE DIRECT DRIFT MEASUREMENT");
@@ -279,15 +632,46 @@ where
             ntp,
             config,
             pending_syncs: HashMap::new(),
+            orphan_followups: HashMap::new(),
             prev_t1_ns: 0,
+            rejected_sync_pairs: 0,
+            unmatched_syncs: 0,
+            flag_ignored_alternate_master: 0,
+            flag_ignored_unicast: 0,
+            unmatched_sync_ratio: 0.0,
+            ptp_rate_window: (SystemTime::UNIX_EPOCH, 0),
+            ptp_flooding: false,
+            ptp_flood_dropped: 0,
+            consecutive_rejects: 0,
             prev_t2_ns: 0,
             current_gm_uuid: None,
+            master_is_dante: None,
+            gm_mismatch: false,
             current_sync_source: None,
+            source_candidate: None,
+            gm_candidate: None,
+            handover_windows_left: 0,
+            last_sync_rx: None,
+            sync_interval_ms: 0.0,
+            sync_arrivals: 0,
+            adapted_window_size: window_size,
             sample_window: Vec::with_capacity(window_size),
+            sample_sources: Vec::with_capacity(window_size),
             last_phase_offset_ns: 0,
-            last_adj_ppm: 0.0,
+            last_offset_jitter_ns: 0.0,
+            network_quality: NetworkQuality::Good,
+            network_quality_streak: 0,
+            last_adj_ppm: initial_freq_ppm,
+            last_ntp_offset_us: 0,
+            sample_log: None,
+            sample_fifo: None,
+            telemetry: None,
             initial_epoch_offset_ns: 0,
             epoch_aligned: false,
+            last_epoch: None,
+            epoch_changed_at: None,
+            wall_clock_ref: None,
+            epoch_shift_ns: 0,
             valid_count: 0,
             clock_settled: false,
             settling_threshold: 1,
@@ -295,13 +679,22 @@ where
             calibration_samples: Vec::with_capacity(calibration_count),
             calibration_offset_ns: 0,
             calibration_complete,
-            applied_freq_ppm: 0.0,
+            applied_freq_ppm: initial_freq_ppm,
+            oscillator_fault: false,
             warmup_start: now,
             warmup_complete: false,
             // Self-tuning servo state
-            drift_baseline_ppm: 0.0,
+            drift_baseline_ppm: initial_freq_ppm,
+            steady_correction_ppm: None,
             is_locked: false,
             lock_stable_count: 0,
+            locked_since: None,
+            unlock_count: 0,
+            settle_start: None,
+            time_to_lock: None,
+            ever_locked: false,
+            cold_start_active: false,
+            time_to_nano: None,
             in_production_mode: false,
             in_nano_mode: false,
             nano_sustain_count: 0,
@@ -309,24 +702,51 @@ where
             last_offset_us: None,
             last_offset_time: None,
             smoothed_rate_ppm: 0.0,
+            display_rates: VecDeque::new(),
             // NTP UTC tracking - enabled on BOTH platforms
             // PTP (Dante) controls frequency only, NTP maintains UTC alignment
             // Dante provides device uptime, NOT UTC - so NTP is needed for real time
             last_ntp_check: now,
             ntp_offset_samples: VecDeque::with_capacity(NTP_SAMPLE_COUNT + 2),
+            ntp_rtt_samples: VecDeque::with_capacity(NTP_RTT_SAMPLE_COUNT),
             ntp_tracking_enabled: true, // Always enabled - NTP is the UTC time source
+            paused: false,
             last_ntp_step: None,
+            resettling: false,
+            slew_remaining_us: 0.0,
+            slew_updated: Instant::now(),
+            slew_bias_ppm: 0.0,
+            slew_ramped: None,
+            ptp_leap: None,
+            ntp_leap: None,
+            leap_scheduled: None,
+            last_leap_boundary: 0,
             // PTP offline detection
             last_ptp_packet: now,
             ptp_offline: false,
+            progress: 0,
+            idle_progress_at: now,
             ptp_offline_logged: false,
             // NTP failure tracking
             ntp_consecutive_failures: 0,
             ntp_failed: false,
+            ntp_failure_counter: WindowedCounter::new(counter_window),
+            counters_since: SystemTime::now(),
+            // Network receive failure tracking
+            recv_error_since: None,
+            recv_error_count: 0,
+            last_heartbeat: Instant::now(),
             // Adaptive spike detection
-            spike_filter: SpikeFilter::new(),
+            spike_filter: {
+                let mut filter = SpikeFilter::new();
+                filter.set_counter_window(counter_window);
+                filter.set_min_mad(spike_mad_floor);
+                filter
+            },
             // Adaptive jitter smoothing
             jitter_estimator: JitterEstimator::new(),
+            noise_floor: NoiseFloorEstimator::new(),
+            logged_auto_lock_us_s: 0.0,
         }
     }
 
@@ -338,29 +758,107 @@ where
         self.status_shared.clone()
     }
 
+    /// Seconds continuously locked (0 when not locked)
+    pub fn locked_duration_secs(&self) -> u64 {
+        self.locked_since
+            .map(|t| t.elapsed().as_secs())
+            .unwrap_or(0)
+    }
+
+    /// Put the `initial_freq_ppm` seed on the clock before the first packet
+    pub fn apply_initial_frequency(&mut self) {
+        if self.config.initial_freq_ppm == 0.0 {
+            return;
+        }
+        info!(
+            "Initial frequency: {:+.3}ppm (initial_freq_ppm)",
+            self.applied_freq_ppm
+        );
+        self.apply_frequency();
+    }
+
     pub fn run_ntp_sync(&mut self, skip: bool) {
         if skip {
             return;
         }
 
-        match self.ntp.get_offset() {
-            Ok((offset, sign)) => {
-                let sign_str = if sign > 0 { "+" } else { "-" };
-                info!("NTP Sync: Offset {}{:?}", sign_str, offset);
+        let Some((offset, sign)) = self.collect_startup_ntp_offset() else {
+            return;
+        };
+        let sign_str = if sign > 0 { "+" } else { "-" };
+        info!("NTP Sync: Offset {}{:?}", sign_str, offset);
 
-                if offset.as_millis() > 50 {
-                    info!("Stepping clock (NTP)...");
-                    if let Err(e) = self.clock.step_clock(offset, sign) {
-                        error!("Failed to step clock: {}", e);
-                    } else {
-                        info!("Clock stepped successfully.");
-                    }
-                } else {
-                    info!("Offset small, skipping step.");
+        if offset.as_millis() > 50 {
+            if self.slew_instead_of_step(offset, sign, "NTP", self.slew_ntp_alignment()) {
+                return;
+            }
+            info!("Stepping clock (NTP)...");
+            if let Err(e) = self.clock.step_clock(offset, sign) {
+                error!("Failed to step clock: {}", e);
+            } else {
+                info!("Clock stepped successfully.");
+            }
+        } else {
+            info!("Offset small, skipping step.");
+        }
+    }
+
+    /// Startup NTP offset: a single query, or the median of all samples
+    /// taken during `ntp_warmup_secs` so one delayed response right after
+    /// the NIC comes up cannot cause a bad step.
+    fn collect_startup_ntp_offset(&mut self) -> Option<(Duration, i8)> {
+        let warmup_secs = self.config.filters.ntp_warmup_secs;
+        if warmup_secs <= 0.0 {
+            return match self.ntp.get_offset() {
+                Ok(sample) => Some(sample),
+                Err(e) => {
+                    warn!("NTP Sync failed: {}", e);
+                    None
+                }
+            };
+        }
+
+        info!(
+            "[Warmup] Sampling NTP for {:.1}s before stepping",
+            warmup_secs
+        );
+        let deadline = Instant::now() + Duration::from_secs_f64(warmup_secs);
+        let mut samples_ns: Vec<i64> = Vec::new();
+        let mut last_error = None;
+        loop {
+            match self.ntp.get_offset() {
+                Ok((offset, sign)) => {
+                    let ns = offset.as_nanos().min(i64::MAX as u128) as i64;
+                    samples_ns.push(if sign < 0 { -ns } else { ns });
                 }
+                Err(e) => last_error = Some(e),
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            std::thread::sleep((deadline - now).min(Duration::from_millis(NTP_WARMUP_POLL_MS)));
+        }
+
+        if samples_ns.is_empty() {
+            match last_error {
+                Some(e) => warn!("NTP Sync failed: {}", e),
+                None => warn!("NTP Sync failed: no samples"),
             }
-            Err(e) => warn!("NTP Sync failed: {}", e),
+            return None;
         }
+
+        samples_ns.sort_unstable();
+        let median_ns = samples_ns[samples_ns.len() / 2];
+        info!(
+            "[Warmup] NTP median of {} samples (range {:+.1}ms .. {:+.1}ms)",
+            samples_ns.len(),
+            samples_ns[0] as f64 / 1e6,
+            samples_ns[samples_ns.len() - 1] as f64 / 1e6
+        );
+        let sign = if median_ns < 0 { -1 } else { 1 };
+        Some((Duration::from_nanos(median_ns.unsigned_abs()), sign))
     }
 
     /// Periodic NTP UTC alignment - steps clock to maintain UTC sync
@@ -400,15 +898,19 @@ where
             // PTP came back online
             self.ptp_offline = false;
             self.ptp_offline_logged = false;
+            // The outage gap is real, not a bad master timestamp
+            self.prev_t1_ns = 0;
+            self.prev_t2_ns = 0;
             info!("[PTP] Packets received - PTP sync resumed");
         }
     }
 
     pub fn check_ntp_utc_tracking(&mut self) {
-        // Run NTP sync when:
+        // Run NTP sync when tracking is enabled and:
         // 1. PTP is offline (NTP-only mode), OR
-        // 2. PTP is locked and tracking is enabled
-        let should_check = self.ptp_offline || (self.is_locked && self.ntp_tracking_enabled);
+        // 2. PTP is locked
+        // Disabled tracking (PTP-frequency-only deployments) never touches absolute time.
+        let should_check = self.ntp_tracking_enabled && (self.ptp_offline || self.is_locked);
         if !should_check {
             return;
         }
@@ -437,6 +939,12 @@ where
                 self.ntp_consecutive_failures = 0;
                 self.ntp_failed = false;
 
+                // Judged against the jitter seen so far, so a real jump in
+                // this sample does not raise its own threshold
+                let threshold_us = self.ntp_step_threshold_us();
+                let quality = self.ntp.quality();
+                let rtt_margin_us = quality.map_or(0, |q| self.ntp_rtt_margin_us(q.rtt));
+
                 // Add sample to buffer
                 self.ntp_offset_samples.push_back(offset_us);
                 if self.ntp_offset_samples.len() > NTP_SAMPLE_COUNT + 2 {
@@ -444,41 +952,65 @@ where
                 }
 
                 // Update shared status with NTP offset for tray app display
+                self.last_ntp_offset_us = offset_us;
                 if let Ok(mut status) = self.status_shared.write() {
                     status.ntp_offset_us = offset_us;
                     status.ntp_failed = false;
+                    if let Some(q) = quality {
+                        status.ntp_rtt_us = q.rtt.as_micros() as i64;
+                        status.ntp_stratum = q.stratum;
+                    }
                 }
 
                 // Log current offset
-                info!("[NTP] offset:{:+}us", offset_us);
+                if threshold_us > self.config.ntp_step_threshold_us as i64 {
+                    info!(
+                        "[NTP] offset:{:+}us (step threshold {}us from jitter)",
+                        offset_us, threshold_us
+                    );
+                } else {
+                    info!("[NTP] offset:{:+}us", offset_us);
+                }
+                if rtt_margin_us > 0 {
+                    info!(
+                        "[NTP] High-delay answer: step needs {}us more (half the RTT above the path minimum)",
+                        rtt_margin_us
+                    );
+                }
+
+                // Slewing: stop once NTP agrees. A clock-ahead slew (positive
+                // remaining) measures as a negative offset and vice versa
+                if self.slew_remaining_us != 0.0
+                    && -self.slew_remaining_us.signum() * (offset_us as f64) < threshold_us as f64
+                {
+                    self.finish_slew("NTP offset within threshold");
+                }
+
+                // Paused: the offset is reported but not acted on
+                if self.paused {
+                    return;
+                }
 
                 // Step clock if offset exceeds threshold
-                if offset_us.abs() > NTP_STEP_THRESHOLD_US {
+                if offset_us.abs() > threshold_us + rtt_margin_us {
                     let step_us = offset_us;
 
                     // Apply the step (sets time, does NOT change frequency)
                     let step_dur = Duration::from_micros(step_us.unsigned_abs());
                     let step_sign = if step_us > 0 { 1 } else { -1 };
 
+                    if self.slew_instead_of_step(
+                        step_dur,
+                        step_sign,
+                        "NTP",
+                        self.slew_ntp_alignment(),
+                    ) {
+                        return;
+                    }
                     if let Err(e) = self.clock.step_clock(step_dur, step_sign) {
                         warn!("[NTP] Step failed: {}", e);
                     } else {
-                        // Clear NTP samples after step to start fresh measurement
-                        self.ntp_offset_samples.clear();
-                        // Clear PTP sample window to discard post-step transient samples
-                        self.sample_window.clear();
-                        // Set grace period to skip PTP samples for 2s after step
-                        self.last_ntp_step = Some(Instant::now());
-                        // Reset drift tracking to avoid false spike from step
-                        self.last_offset_us = None;
-                        self.last_offset_time = None;
-                        // Reset prev timestamps so min_delta filter works correctly after grace period
-                        self.prev_t1_ns = 0;
-                        self.prev_t2_ns = 0;
-                        // Clear spike filter to prevent false positives from step transient
-                        self.spike_filter.clear();
-                        // NOTE: jitter_estimator is NOT cleared on NTP step because
-                        // jitter is a hardware property that persists across steps
+                        self.after_clock_step();
                         info!("[NTP] Stepped {:+}us", step_us);
                     }
                 }
@@ -486,6 +1018,7 @@ where
             Err(e) => {
                 // Track consecutive failures
                 self.ntp_consecutive_failures += 1;
+                self.ntp_failure_counter.record();
 
                 if self.ntp_consecutive_failures >= NTP_FAILURE_THRESHOLD && !self.ntp_failed {
                     self.ntp_failed = true;
@@ -508,195 +1041,1223 @@ where
         }
     }
 
-    /// Enable or disable periodic NTP UTC tracking
-    pub fn set_ntp_tracking(&mut self, enabled: bool) {
-        self.ntp_tracking_enabled = enabled;
-        info!(
-            "[NTP-UTC] Tracking {}",
-            if enabled { "enabled" } else { "disabled" }
-        );
+    /// Offset (µs) beyond which NTP tracking steps the clock
+    ///
+    /// `ntp_step_threshold_us`, raised to `ntp_step_jitter_k` × the standard
+    /// deviation of recent NTP offsets so a noisy NTP path does not step the
+    /// clock back and forth on noise.
+    fn ntp_step_threshold_us(&self) -> i64 {
+        let floor = self.config.ntp_step_threshold_us as i64;
+        let k = self.config.ntp_step_jitter_k;
+        let samples = &self.ntp_offset_samples;
+        if k <= 0.0 || samples.len() < NTP_JITTER_MIN_SAMPLES {
+            return floor;
+        }
+        let n = samples.len() as f64;
+        let mean = samples.iter().sum::<i64>() as f64 / n;
+        let variance = samples
+            .iter()
+            .map(|&s| (s as f64 - mean).powi(2))
+            .sum::<f64>()
+            / n;
+        floor.max((k * variance.sqrt()).round() as i64)
     }
 
-    pub fn log_status(&self) {
-        // Just update shared status for IPC - no redundant logging
-        self.update_shared_status();
+    /// Extra offset (µs) a high-delay NTP answer must show before it steps
+    ///
+    /// Half of the delay above the path's best recent RTT is queueing that
+    /// may sit on one direction only, so it bounds the asymmetry error of
+    /// this sample. Records `rtt` for later answers.
+    fn ntp_rtt_margin_us(&mut self, rtt: Duration) -> i64 {
+        let rtt_us = rtt.as_micros() as i64;
+        let margin = self
+            .ntp_rtt_samples
+            .iter()
+            .min()
+            .map_or(0, |&min| (rtt_us - min).max(0) / 2);
+        self.ntp_rtt_samples.push_back(rtt_us);
+        if self.ntp_rtt_samples.len() > NTP_RTT_SAMPLE_COUNT {
+            self.ntp_rtt_samples.pop_front();
+        }
+        margin
     }
 
-    pub fn process_loop_iteration(&mut self) -> Result<()> {
-        // Check PTP status first (handles timeout detection for NTP-only fallback)
-        self.check_ptp_status();
+    /// Discard state spanning a clock step so the servo does not see the
+    /// jump as drift
+    fn after_clock_step(&mut self) {
+        // Clear NTP samples after step to start fresh measurement
+        self.ntp_offset_samples.clear();
+        // Our own step moves T2 - T1; it is not a master epoch change
+        self.last_epoch = None;
+        // Nor is it an external step
+        self.wall_clock_ref = None;
+        // Clear PTP sample window to discard post-step transient samples
+        self.clear_sample_window();
+        // Set grace period to skip PTP samples for 2s after step
+        self.last_ntp_step = Some(Instant::now());
+        // Reset drift tracking to avoid false spike from step
+        self.last_offset_us = None;
+        self.last_offset_time = None;
+        // Reset prev timestamps so min_delta filter works correctly after grace period
+        self.prev_t1_ns = 0;
+        self.prev_t2_ns = 0;
+        // Clear spike filter to prevent false positives from step transient
+        self.spike_filter.clear();
+        // NOTE: jitter_estimator is NOT cleared on NTP step because
+        // jitter is a hardware property that persists across steps
+
+        // Tell the tray the coming jump is the step, not a sync problem
+        self.resettling = true;
+        self.update_shared_status();
+    }
 
-        let (buf, size, t2) = match self.network.recv_packet()? {
-            Some(res) => res,
-            None => {
-                // No packet, but still run NTP tracking if PTP is offline
-                if self.ptp_offline {
-                    self.check_ntp_utc_tracking();
-                }
-                return Ok(());
-            }
+    /// Detect a system time step made outside DanteSync (another time
+    /// service, VM host time sync) by comparing system and monotonic time
+    /// since the last loop pass, and discard state spanning it
+    fn check_external_step(&mut self) {
+        let now = (SystemTime::now(), Instant::now());
+        let Some((wall, mono)) = self.wall_clock_ref.replace(now) else {
+            return;
         };
-
-        // Packet received - update last_ptp_packet timestamp
-        self.last_ptp_packet = Instant::now();
-
-        if size < PtpV1Header::SIZE {
-            return Ok(());
+        let threshold_ms = self.config.external_step_threshold_ms;
+        if threshold_ms == 0 {
+            return;
         }
-
-        let header = match PtpV1Header::parse(&buf[..size]) {
-            Ok(h) => h,
-            Err(_) => return Ok(()),
+        let wall_ns = match now.0.duration_since(wall) {
+            Ok(d) => d.as_nanos() as i64,
+            Err(e) => -(e.duration().as_nanos() as i64),
         };
-
-        match header.message_type {
-            PtpV1Control::Sync => self.handle_sync_message(&header, &buf[..size], t2),
-            PtpV1Control::FollowUp => self.handle_followup_message(&header, &buf[..size]),
-            _ => {}
-        }
-
-        // Cleanup stale pending syncs
-        if self.pending_syncs.len() > 100 {
-            let now = SystemTime::now();
-            self.pending_syncs.retain(|_, v| {
-                now.duration_since(v.rx_time_sys).unwrap_or(Duration::ZERO) < Duration::from_secs(5)
-            });
+        let jump_ns = wall_ns - now.1.duration_since(mono).as_nanos() as i64;
+        if jump_ns.unsigned_abs() <= threshold_ms * 1_000_000 {
+            return;
         }
+        warn!(
+            "[Clock] System time stepped {:+.3}ms by another process (time service or VM host sync?) - discarding samples across it",
+            jump_ns as f64 / 1e6
+        );
+        self.after_clock_step();
+        self.wall_clock_ref = Some(now);
+    }
 
-        // Periodic NTP UTC tracking (every 30s in production mode)
-        self.check_ntp_utc_tracking();
-
-        Ok(())
+    /// Within `NTP_STEP_GRACE` of a clock step (servo and sampling paused)
+    fn in_step_grace(&self) -> bool {
+        self.last_ntp_step
+            .is_some_and(|step| step.elapsed() < NTP_STEP_GRACE)
     }
 
-    // ========================================================================
-    // PACKET HANDLING
-    // ========================================================================
+    /// `ntp_alignment` asks for NTP offsets to be slewed in both directions
+    fn slew_ntp_alignment(&self) -> bool {
+        self.config.ntp_alignment == NtpAlignment::Slew
+    }
 
-    fn handle_sync_message(&mut self, header: &PtpV1Header, buf: &[u8], t2: SystemTime) {
-        // Check if Sync source changed (different device sending PTP)
-        let source_uuid = header.source_uuid;
-        match self.current_sync_source {
-            Some(current) if current != source_uuid => {
-                warn!(
-                    ">>> SYNC SOURCE CHANGED: {} -> {} <<<",
-                    format_mac(&current),
-                    format_mac(&source_uuid)
-                );
-                self.current_sync_source = Some(source_uuid);
-                // Soft reset: clear stale data but KEEP current frequency
-                // Both Dante devices should have similar frequencies since they're
-                // synchronized to the same grandmaster time
-                self.pending_syncs.clear();
-                self.sample_window.clear();
-                self.prev_t1_ns = 0;
-                self.prev_t2_ns = 0;
-                // Keep: applied_freq_ppm, drift_baseline_ppm (learned values)
-                // Stay in production mode - let servo naturally adjust if needed
-                info!(
-                    "Soft reset: keeping freq={:.1}ppm, drift_baseline={:.1}ppm",
-                    self.applied_freq_ppm, self.drift_baseline_ppm
-                );
-            }
-            None => {
-                info!("Sync source: {}", format_mac(&source_uuid));
-                self.current_sync_source = Some(source_uuid);
-            }
-            _ => {}
+    /// Turn a step into a slew: always with `slew_always` (`ntp_alignment`),
+    /// otherwise only a backward step with `allow_backward_step` off.
+    /// Returns true if the caller must not step.
+    fn slew_instead_of_step(
+        &mut self,
+        offset: Duration,
+        sign: i8,
+        source: &str,
+        slew_always: bool,
+    ) -> bool {
+        if !slew_always && (sign > 0 || self.config.allow_backward_step) {
+            return false;
         }
-
-        // Limit pending_syncs size to prevent memory exhaustion from malformed packets
-        const MAX_PENDING_SYNCS: usize = 200;
-        if self.pending_syncs.len() >= MAX_PENDING_SYNCS {
-            // Clean up stale entries first
-            let now = SystemTime::now();
-            self.pending_syncs.retain(|_, v| {
-                now.duration_since(v.rx_time_sys).unwrap_or(Duration::ZERO) < Duration::from_secs(5)
-            });
-            // If still at capacity after cleanup, skip this sync
-            if self.pending_syncs.len() >= MAX_PENDING_SYNCS {
-                return;
-            }
+        let offset_us = offset.as_secs_f64() * 1e6;
+        // Stepping by -offset means the clock is ahead: run slow
+        let remaining_us = if sign > 0 { -offset_us } else { offset_us };
+        let already_slewing = self.slew_remaining_us != 0.0;
+        let direction_changed = self.slew_remaining_us.signum() != remaining_us.signum();
+        self.slew_remaining_us = remaining_us;
+        self.slew_updated = Instant::now();
+        if already_slewing && !direction_changed {
+            debug!(
+                "[Slew] {} remaining {:.3}ms (re-measured)",
+                source,
+                offset_us / 1000.0
+            );
+            return true;
         }
-
-        self.pending_syncs.insert(
-            header.sequence_id,
-            PendingSync {
-                rx_time_sys: t2,
-                source_uuid: header.source_uuid,
-            },
+        let (state, pace, instead_of) = if sign > 0 {
+            ("behind", "fast", "forward")
+        } else {
+            ("ahead", "slow", "backward")
+        };
+        warn!(
+            "[Slew] {}: clock {:.3}ms {} - running {:.0}ppm {} instead of stepping {} (~{})",
+            source,
+            offset_us / 1000.0,
+            state,
+            SLEW_PPM,
+            pace,
+            instead_of,
+            format_duration((offset_us / SLEW_PPM).ceil() as u64)
         );
+        self.ramp_slew_bias();
+        true
+    }
 
-        if let Ok(body) = PtpV1SyncMessageBody::parse(&buf[PtpV1Header::SIZE..]) {
-            let new_uuid = body.grandmaster_clock_uuid;
-            match self.current_gm_uuid {
-                Some(current) if current != new_uuid => {
-                    warn!(
-                        ">>> GRANDMASTER UUID CHANGED: {} -> {} <<<",
-                        format_mac(&current),
-                        format_mac(&new_uuid)
-                    );
-                    self.current_gm_uuid = Some(new_uuid);
-                    // Note: sync source change already did soft reset if needed
-                }
-                None => {
-                    info!("Grandmaster UUID: {}", format_mac(&new_uuid));
-                    self.current_gm_uuid = Some(new_uuid);
-                }
-                _ => {}
+    /// Count down the slew by the time spent running slow or fast, and
+    /// ramp the bias in or out
+    fn advance_slew(&mut self) {
+        if self.slew_remaining_us == 0.0 && self.slew_bias_ppm == 0.0 {
+            return;
+        }
+        let elapsed = self.slew_updated.elapsed().as_secs_f64();
+        self.slew_updated = Instant::now();
+        if self.slew_remaining_us != 0.0 {
+            let remaining_us = self.slew_remaining_us.abs() - self.slew_bias_ppm.abs() * elapsed;
+            if remaining_us <= 0.0 {
+                self.finish_slew("offset removed");
+            } else {
+                self.slew_remaining_us = remaining_us.copysign(self.slew_remaining_us);
             }
         }
+        self.ramp_slew_bias();
     }
 
-    fn handle_followup_message(&mut self, header: &PtpV1Header, buf: &[u8]) {
-        if let Ok(body) = PtpV1FollowUpBody::parse(&buf[PtpV1Header::SIZE..]) {
-            if let Some(sync_info) = self.pending_syncs.remove(&body.associated_sequence_id) {
-                if sync_info.source_uuid == header.source_uuid {
-                    self.process_sync_pair(
-                        body.precise_origin_timestamp.to_nanos(),
-                        sync_info.rx_time_sys,
-                    );
-                }
-            }
+    fn finish_slew(&mut self, reason: &str) {
+        self.slew_remaining_us = 0.0;
+        self.ramp_slew_bias();
+        if self.slew_bias_ppm == 0.0 {
+            info!("[Slew] Complete ({}) - normal frequency restored", reason);
+        } else {
+            info!(
+                "[Slew] Complete ({}) - ramping back to normal frequency",
+                reason
+            );
         }
     }
 
-    // ========================================================================
-    // SYNC PAIR PROCESSING - Main synchronization logic
-    // ========================================================================
+    /// Slew bias the clock should run at: ±SLEW_PPM while slewing, 0 once
+    /// the rest of the offset is what ramping the bias out still removes
+    fn slew_target_ppm(&self) -> f64 {
+        if self.slew_remaining_us == 0.0 || self.slew_remaining_us.abs() <= self.slew_ramp_out_us()
+        {
+            0.0
+        } else {
+            SLEW_PPM.copysign(self.slew_remaining_us)
+        }
+    }
 
-    fn process_sync_pair(&mut self, t1_ns: i64, t2_sys: SystemTime) {
-        let t2_ns = t2_sys
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_nanos() as i64;
+    /// Largest change of the slew bias per `SLEW_RAMP_INTERVAL` (ppm)
+    fn slew_ramp_step_ppm(&self) -> f64 {
+        let max_step = self.config.max_ppm_step_per_sample;
+        if max_step > 0.0 {
+            max_step
+        } else {
+            SLEW_RAMP_PPM
+        }
+    }
 
-        // Calculate display phase offset (modulo-based for readability)
-        let phase_offset_ns = self.calculate_phase_offset(t1_ns, t2_ns);
+    /// Offset (µs) removed while the current bias ramps back to 0
+    fn slew_ramp_out_us(&self) -> f64 {
+        let max_step = self.slew_ramp_step_ppm();
+        let bias = self.slew_bias_ppm.abs();
+        // The bias drops by max_step per interval; after the last step it is 0
+        let steps = (bias / max_step).ceil();
+        let held_ppm = (steps - 1.0).max(0.0) * bias - max_step * steps * (steps - 1.0) / 2.0;
+        held_ppm * SLEW_RAMP_INTERVAL.as_secs_f64()
+    }
 
-        // Handle calibration if needed
-        if self.process_calibration(phase_offset_ns) {
+    /// Move the slew bias on the clock toward `slew_target_ppm`, by at most
+    /// `slew_ramp_step_ppm` per `SLEW_RAMP_INTERVAL`. Paused corrections
+    /// leave it where it is.
+    fn ramp_slew_bias(&mut self) {
+        let target = self.slew_target_ppm();
+        if self.slew_bias_ppm == target || self.paused {
             return;
         }
-
-        // Apply calibration offset
-        let phase_offset_ns = phase_offset_ns - self.calibration_offset_ns;
-
-        // Handle warmup period
-        if !self.process_warmup() {
+        if self
+            .slew_ramped
+            .is_some_and(|t| t.elapsed() < SLEW_RAMP_INTERVAL)
+        {
             return;
         }
+        self.slew_ramped = Some(Instant::now());
+        self.slew_bias_ppm = slew_limit_ppm(self.slew_bias_ppm, target, self.slew_ramp_step_ppm());
+        // A deliberate rate change: not drift
+        self.reset_rate_tracking();
+        self.apply_frequency();
+    }
 
-        // Log delta sanity check
-        self.log_delta_sanity(t1_ns, t2_ns);
+    /// Frequency offset currently subtracted for slewing (ppm; negative =
+    /// running fast)
+    fn slew_ppm(&self) -> f64 {
+        self.slew_bias_ppm
+    }
 
-        // Process sync once settled
-        self.valid_count += 1;
-        if self.valid_count >= self.settling_threshold {
-            self.process_settled_sync(t1_ns, t2_ns, phase_offset_ns);
+    /// Re-apply the servo's frequency with the current slew offset
+    fn apply_frequency(&mut self) {
+        let factor = 1.0 + (self.applied_freq_ppm - self.slew_ppm()) / 1_000_000.0;
+        if let Err(e) = self.clock.adjust_frequency(factor) {
+            warn!("Clock adjustment failed: {}", e);
         }
+    }
 
-        self.prev_t1_ns = t1_ns;
+    /// Start the next rate measurement afresh when the clock rate changes
+    /// deliberately, so the transition is not read as drift
+    fn reset_rate_tracking(&mut self) {
+        self.clear_sample_window();
+        self.last_offset_us = None;
+        self.last_offset_time = None;
+    }
+
+    /// Leap second announced by the NTP source (polled by the caller after
+    /// each query, since `NtpSource` only reports offsets)
+    pub fn set_ntp_leap(&mut self, leap: Option<LeapSecond>) {
+        self.ntp_leap = leap;
+    }
+
+    /// Schedule an announced leap second for the coming UTC midnight and
+    /// step the clock by exactly 1s when it passes
+    fn check_leap_second(&mut self) {
+        let now_secs = SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64;
+
+        let announced = self.ptp_leap.or(self.ntp_leap);
+        match (announced, self.leap_scheduled) {
+            (Some(leap), None) => {
+                if let Some(boundary) = leap_boundary_secs(now_secs) {
+                    if boundary > self.last_leap_boundary {
+                        warn!(
+                            "[Leap] {:?} second announced - stepping at UTC midnight (in {})",
+                            leap,
+                            format_duration((boundary - now_secs) as u64)
+                        );
+                        self.leap_scheduled = Some((leap, boundary));
+                        self.update_shared_status();
+                    }
+                }
+            }
+            (None, Some((leap, _))) => {
+                info!("[Leap] {:?} second announcement withdrawn", leap);
+                self.leap_scheduled = None;
+                self.update_shared_status();
+            }
+            _ => {}
+        }
+
+        let Some((leap, boundary)) = self.leap_scheduled else {
+            return;
+        };
+        if now_secs < boundary {
+            return;
+        }
+        self.leap_scheduled = None;
+        self.last_leap_boundary = boundary;
+
+        if !self.ntp_tracking_enabled {
+            warn!("[Leap] NTP tracking disabled - leaving the clock unstepped");
+        } else if self.paused {
+            warn!("[Leap] Clock corrections paused - leaving the clock unstepped");
+        } else {
+            let sign = match leap {
+                LeapSecond::Insert => -1,
+                LeapSecond::Delete => 1,
+            };
+            if self.slew_instead_of_step(Duration::from_secs(1), sign, "Leap", false) {
+                self.update_shared_status();
+                return;
+            }
+            match self.clock.step_clock(Duration::from_secs(1), sign) {
+                Ok(()) => {
+                    self.after_clock_step();
+                    info!("[Leap] Applied {:?} second ({:+}s)", leap, sign);
+                }
+                Err(e) => warn!("[Leap] Step failed: {}", e),
+            }
+        }
+        self.update_shared_status();
+    }
+
+    /// PTP sync has been established (first usable Sync/Follow_Up pair)
+    pub fn is_settled(&self) -> bool {
+        self.clock_settled
+    }
+
+    /// Keep the last `display_rate_samples` servo rates for display
+    fn record_display_rate(&mut self, rate_ppm: f64) {
+        let window = self.config.display_rate_samples.max(1);
+        self.display_rates.push_back(rate_ppm);
+        while self.display_rates.len() > window {
+            self.display_rates.pop_front();
+        }
+    }
+
+    /// Moving average of the servo rate over the display window (us/s)
+    fn display_rate_ppm(&self) -> f64 {
+        if self.display_rates.is_empty() {
+            return self.smoothed_rate_ppm;
+        }
+        self.display_rates.iter().sum::<f64>() / self.display_rates.len() as f64
+    }
+
+    /// Work counter for liveness watchdogs: advances for every packet taken
+    /// from the network and, while PTP is offline, every
+    /// `IDLE_PROGRESS_INTERVAL` of clean receive polls. A loop whose
+    /// iterations return Ok without receiving anything (or only receive
+    /// errors) leaves it unchanged.
+    pub fn progress(&self) -> u64 {
+        self.progress
+    }
+
+    /// Number of LOCK → UNLOCK transitions since start
+    pub fn unlock_count(&self) -> u64 {
+        self.unlock_count
+    }
+
+    /// Time from warmup completion to the first LOCK (None until reached)
+    pub fn time_to_lock(&self) -> Option<Duration> {
+        self.time_to_lock
+    }
+
+    /// Time from warmup completion to the first NANO (None until reached)
+    pub fn time_to_nano(&self) -> Option<Duration> {
+        self.time_to_nano
+    }
+
+    /// True once receive errors have persisted for NETWORK_FAILURE_SECS
+    /// (e.g. NIC unplugged). The caller should rebuild the network and
+    /// hand it over via replace_network().
+    pub fn network_failed(&self) -> bool {
+        self.recv_error_since
+            .map(|t| t.elapsed() >= Duration::from_secs(NETWORK_FAILURE_SECS))
+            .unwrap_or(false)
+    }
+
+    /// Swap in a freshly opened network after interface loss.
+    ///
+    /// Soft reset: clears packet/sample state tied to the old network but keeps
+    /// the learned frequency (applied_freq_ppm, drift_baseline_ppm).
+    pub fn replace_network(&mut self, network: N) {
+        self.network = network;
+        self.recv_error_since = None;
+        self.recv_error_count = 0;
+        self.clear_sample_state();
+        info!(
+            "[Net] Network rebuilt: keeping freq={:.1}ppm, drift_baseline={:.1}ppm",
+            self.applied_freq_ppm, self.drift_baseline_ppm
+        );
+    }
+
+    /// Append one CSV row per servo sample to `log`
+    pub fn set_sample_log(&mut self, log: CsvSampleLog) {
+        self.sample_log = Some(log);
+    }
+
+    /// Stream one line per servo sample to `fifo` while a reader is attached
+    pub fn set_sample_fifo(&mut self, fifo: SampleFifo) {
+        self.sample_fifo = Some(fifo);
+    }
+
+    /// Send the status as a UDP datagram on every status update
+    pub fn set_telemetry(&mut self, telemetry: TelemetrySender) {
+        self.telemetry = Some(telemetry);
+    }
+
+    /// The packet source (e.g. to see how much of a replay is left)
+    pub fn network(&self) -> &N {
+        &self.network
+    }
+
+    /// Mutable access to the NTP source (e.g. to re-bind after a network rebuild)
+    pub fn ntp_source_mut(&mut self) -> &mut S {
+        &mut self.ntp
+    }
+
+    /// Enable or disable periodic NTP UTC tracking
+    ///
+    /// When disabled the clock is never stepped, not even in NTP-only mode
+    /// while PTP is offline.
+    pub fn set_ntp_tracking(&mut self, enabled: bool) {
+        self.ntp_tracking_enabled = enabled;
+        info!(
+            "[NTP-UTC] Tracking {}",
+            if enabled { "enabled" } else { "disabled" }
+        );
+        self.update_shared_status();
+    }
+
+    /// Halt or resume clock corrections without stopping the service
+    ///
+    /// While paused, PTP is still received and the drift measured for the
+    /// status, but the clock keeps its current frequency and is never
+    /// stepped. Resuming re-applies the learned frequency and restarts the
+    /// rate measurement, since the clock may have been moved meanwhile.
+    pub fn set_paused(&mut self, paused: bool) {
+        if paused == self.paused {
+            return;
+        }
+        self.paused = paused;
+        if paused {
+            // Abandon a slew in progress (its bias ramps out after resuming);
+            // NTP re-measures the offset
+            self.slew_remaining_us = 0.0;
+            warn!(
+                "[Pause] Clock corrections paused at {:.1}ppm - PTP still monitored",
+                self.applied_freq_ppm
+            );
+        } else {
+            self.after_clock_step();
+            self.apply_frequency();
+            info!(
+                "[Pause] Clock corrections resumed: freq={:.1}ppm (baseline {:.1}ppm)",
+                self.applied_freq_ppm, self.drift_baseline_ppm
+            );
+        }
+        self.update_shared_status();
+    }
+
+    /// Simulations: let `elapsed` of monotonic time pass for the
+    /// controller's timers (PTP timeout, NTP interval, servo dt, lock and
+    /// settle times) without sleeping
+    #[cfg(any(test, feature = "testing"))]
+    pub fn advance_time(&mut self, elapsed: Duration) {
+        let back = |t: Instant| t.checked_sub(elapsed).unwrap_or(t);
+        self.epoch_changed_at = self.epoch_changed_at.map(back);
+        self.warmup_start = back(self.warmup_start);
+        self.locked_since = self.locked_since.map(back);
+        self.settle_start = self.settle_start.map(back);
+        self.last_offset_time = self.last_offset_time.map(back);
+        self.last_ntp_check = back(self.last_ntp_check);
+        self.last_ntp_step = self.last_ntp_step.map(back);
+        self.slew_updated = back(self.slew_updated);
+        self.last_ptp_packet = back(self.last_ptp_packet);
+        self.recv_error_since = self.recv_error_since.map(back);
+        self.last_heartbeat = back(self.last_heartbeat);
+        // System time did not move with it; not an external step
+        self.wall_clock_ref = None;
+    }
+
+    pub fn config(&self) -> &SystemConfig {
+        &self.config
+    }
+
+    /// Swap in a reloaded config without dropping sync
+    ///
+    /// Filter, servo and lock settings are read live, so they apply from the
+    /// next sample; learned frequency and lock state are kept. NTP tracking
+    /// is left to the caller (it may be overridden on the command line).
+    pub fn apply_config(&mut self, config: SystemConfig) {
+        info!(
+            "[Config] Applied: window={} filter={:?} lock/unlock={}/{}us/s deadband={}us/s max_step={}ppm",
+            config.filters.sample_window_size,
+            config.filters.filter_type,
+            config.lock_rate_us_s,
+            config.unlock_rate_us_s,
+            config.effective_deadband_us_s(),
+            config.max_ppm_step_per_sample
+        );
+        let counter_window = Duration::from_secs(config.counter_window_secs.max(1));
+        self.spike_filter.set_counter_window(counter_window);
+        self.spike_filter
+            .set_min_mad(config.filters.spike_mad_floor_us_s);
+        self.ntp_failure_counter.set_window(counter_window);
+        self.config = config;
+    }
+
+    /// Apply a runtime command from the tray (see `IpcCommand`)
+    pub fn handle_command(&mut self, command: IpcCommand) {
+        info!("[IPC] Command: {:?}", command);
+        match command {
+            IpcCommand::ForceNtpSync => {
+                if !self.ntp_tracking_enabled {
+                    warn!("[IPC] NTP tracking is disabled - ignoring resync request");
+                    return;
+                }
+                // Make the next check due now; it still waits for lock (or
+                // NTP-only mode) so an unlocked servo isn't disturbed by a step
+                if let Some(due) =
+                    Instant::now().checked_sub(Duration::from_secs(NTP_CHECK_INTERVAL_SECS))
+                {
+                    self.last_ntp_check = due;
+                }
+                self.check_ntp_utc_tracking();
+            }
+            IpcCommand::Recalibrate => {
+                self.calibration_samples.clear();
+                self.calibration_offset_ns = 0;
+                self.calibration_complete = self.config.filters.calibration_samples == 0;
+                self.warmup_start = Instant::now();
+                self.warmup_complete = false;
+                self.clear_sample_state();
+                info!(
+                    "[IPC] Recalibrating: keeping freq={:.1}ppm",
+                    self.applied_freq_ppm
+                );
+            }
+            IpcCommand::ResetServo => {
+                self.clear_sample_state();
+                self.jitter_estimator.clear();
+                self.noise_floor.clear();
+                self.logged_auto_lock_us_s = 0.0;
+                let initial_freq_ppm = self.config.initial_freq_ppm;
+                self.drift_baseline_ppm = initial_freq_ppm;
+                self.steady_correction_ppm = None;
+                self.applied_freq_ppm = initial_freq_ppm;
+                self.last_adj_ppm = initial_freq_ppm;
+                self.smoothed_rate_ppm = 0.0;
+                self.display_rates.clear();
+                // Deliberate reset, not a sync loss: unlock_count is left alone
+                self.is_locked = false;
+                self.locked_since = None;
+                self.lock_stable_count = 0;
+                self.in_production_mode = false;
+                self.in_nano_mode = false;
+                self.nano_sustain_count = 0;
+                self.nano_exit_count = 0;
+                // Re-acquiring from scratch: measure the settle time again
+                self.settle_start = Some(Instant::now());
+                self.time_to_lock = None;
+                self.time_to_nano = None;
+                // A paused clock is left alone; resuming applies the seed
+                if !self.paused {
+                    let factor = 1.0 + initial_freq_ppm / 1_000_000.0;
+                    if let Err(e) = self.clock.adjust_frequency(factor) {
+                        warn!("Clock adjustment failed: {}", e);
+                    }
+                }
+                info!(
+                    "[IPC] Servo reset - re-acquiring from {:.1}ppm",
+                    initial_freq_ppm
+                );
+            }
+            IpcCommand::ToggleNtpTracking => {
+                self.set_ntp_tracking(!self.ntp_tracking_enabled);
+            }
+            IpcCommand::ResetCounters => {
+                let (_, spikes, _) = self.spike_filter.stats();
+                info!(
+                    "[IPC] Counters reset (were: {} spikes, {} NTP failures, {} unmatched Syncs, {} flag-ignored PTP packets)",
+                    spikes,
+                    self.ntp_failure_counter.total(),
+                    self.unmatched_syncs,
+                    self.flag_ignored_alternate_master + self.flag_ignored_unicast
+                );
+                self.spike_filter.reset_counters();
+                self.ntp_failure_counter.reset();
+                self.unmatched_syncs = 0;
+                self.flag_ignored_alternate_master = 0;
+                self.flag_ignored_unicast = 0;
+                self.counters_since = SystemTime::now();
+            }
+            IpcCommand::Pause => self.set_paused(true),
+            IpcCommand::Resume => self.set_paused(false),
+        }
+        self.update_shared_status();
+    }
+
+    /// Drop in-flight samples so the servo restarts its rate measurement
+    fn clear_sample_state(&mut self) {
+        self.pending_syncs.clear();
+        self.orphan_followups.clear();
+        self.clear_sample_window();
+        self.prev_t1_ns = 0;
+        self.prev_t2_ns = 0;
+        self.last_offset_us = None;
+        self.last_offset_time = None;
+        self.spike_filter.clear();
+    }
+
+    pub fn log_status(&mut self) {
+        // Update shared status for IPC; lock duration is the only periodic log line
+        if self.is_locked {
+            debug!(
+                "[PTP] Locked for {}",
+                format_duration(self.locked_duration_secs())
+            );
+        }
+        self.update_shared_status();
+        if let Some(line) = self.heartbeat() {
+            info!("{}", line);
+        }
+    }
+
+    /// One-line summary once every `heartbeat_secs`, so a quiet log still
+    /// shows the service was alive
+    fn heartbeat(&mut self) -> Option<String> {
+        let interval = self.config.heartbeat_secs;
+        if interval == 0 || self.last_heartbeat.elapsed() < Duration::from_secs(interval) {
+            return None;
+        }
+        self.last_heartbeat = Instant::now();
+
+        let status = self.status_shared.read().ok()?;
+        let lock = if status.is_locked {
+            format!("locked {}", format_duration(status.locked_duration_secs))
+        } else {
+            "not locked".to_string()
+        };
+        Some(format!("[Heartbeat] {} | {}", status.summary(), lock))
+    }
+
+    pub fn process_loop_iteration(&mut self) -> Result<()> {
+        self.check_external_step();
+        // Check PTP status first (handles timeout detection for NTP-only fallback)
+        self.check_ptp_status();
+        self.check_leap_second();
+        self.advance_slew();
+        if self.resettling && !self.in_step_grace() {
+            self.resettling = false;
+            self.update_shared_status();
+        }
+
+        let received = match self.network.recv_packet() {
+            Ok(res) => {
+                if self.recv_error_since.take().is_some() {
+                    info!(
+                        "[Net] Receive recovered after {} errors",
+                        self.recv_error_count
+                    );
+                }
+                self.recv_error_count = 0;
+                res
+            }
+            Err(e) => {
+                // Record the error streak; network_failed() tells the caller
+                // when to rebuild the network. Log once per streak, not per poll.
+                self.recv_error_count += 1;
+                if self.recv_error_since.is_none() {
+                    self.recv_error_since = Some(Instant::now());
+                    warn!("[Net] Receive error: {}", e);
+                }
+                None
+            }
+        };
+
+        let (buf, size, t2) = match received {
+            // Never trust the reported length beyond the actual buffer
+            Some((buf, size, t2)) => {
+                self.progress += 1;
+                let size = size.min(buf.len());
+                (buf, size, t2)
+            }
+            None => {
+                // No packet, but still run NTP tracking if PTP is offline
+                if self.ptp_offline {
+                    if self.recv_error_count == 0
+                        && self.idle_progress_at.elapsed() >= IDLE_PROGRESS_INTERVAL
+                    {
+                        self.idle_progress_at = Instant::now();
+                        self.progress += 1;
+                        self.update_shared_status();
+                    }
+                    self.check_ntp_utc_tracking();
+                }
+                return Ok(());
+            }
+        };
+
+        if !self.admit_ptp_packet(t2) {
+            self.drain_ptp_flood();
+            return Ok(());
+        }
+
+        // Packet received - update last_ptp_packet timestamp
+        self.last_ptp_packet = Instant::now();
+
+        if size < PtpV1Header::SIZE {
+            return Ok(());
+        }
+
+        if self.ignore_by_flag(&buf[..size]) {
+            return Ok(());
+        }
+
+        let header = match PtpV1Header::parse(&buf[..size]) {
+            Ok(h) => h,
+            Err(_) => return Ok(()),
+        };
+
+        match header.message_type {
+            PtpV1Control::Sync => self.handle_sync_message(&header, &buf[..size], t2),
+            PtpV1Control::FollowUp => self.handle_followup_message(&header, &buf[..size], t2),
+            _ => {}
+        }
+
+        // Periodic NTP UTC tracking (every 30s in production mode)
+        self.check_ntp_utc_tracking();
+
+        Ok(())
+    }
+
+    /// True for a PTPv2 Sync/Follow_Up flagged alternateMaster or unicast:
+    /// we follow the multicast grandmaster, so such a source must not be
+    /// mistaken for it. The first packet of each kind is logged.
+    fn ignore_by_flag(&mut self, packet: &[u8]) -> bool {
+        let header = match PtpV2Header::parse(packet) {
+            Ok(header) => header,
+            Err(_) => return false,
+        };
+        if !matches!(
+            header.message_type,
+            PtpV2MessageType::Sync | PtpV2MessageType::FollowUp
+        ) {
+            return false;
+        }
+        let flag = match header.non_authoritative_flag() {
+            Some(flag) => flag,
+            None => return false,
+        };
+        let counter = if flag == "unicast" {
+            &mut self.flag_ignored_unicast
+        } else {
+            &mut self.flag_ignored_alternate_master
+        };
+        if *counter == 0 {
+            info!(
+                "[PTP] Ignoring {}-flagged PTPv2 traffic from {} (not the multicast timing source)",
+                flag,
+                format_clock_identity(&header.source_port_identity.clock_identity)
+            );
+        }
+        *counter += 1;
+        true
+    }
+
+    /// Packets per second beyond which PTP traffic is treated as a flood
+    fn ptp_rate_limit(&self) -> u32 {
+        let interval_ms = self.effective_sync_interval_ms();
+        let interval_ms = if interval_ms > 0.0 {
+            interval_ms
+        } else {
+            NOMINAL_SYNC_INTERVAL_MS
+        };
+        // Sync + Follow_Up per interval
+        let expected_per_sec = 2.0 * 1000.0 / interval_ms;
+        ((expected_per_sec * PTP_RATE_LIMIT_FACTOR) as u32).max(PTP_RATE_LIMIT_FLOOR)
+    }
+
+    /// True once `rx` lies outside the current rate window (a packet
+    /// timestamped before it means the clock was stepped back)
+    fn ptp_rate_window_over(&self, rx: SystemTime) -> bool {
+        rx.duration_since(self.ptp_rate_window.0)
+            .map_or(true, |elapsed| elapsed >= PTP_RATE_WINDOW)
+    }
+
+    /// Count a packet received at `rx` against the rate limit; false =
+    /// discard it unparsed
+    fn admit_ptp_packet(&mut self, rx: SystemTime) -> bool {
+        let limit = self.ptp_rate_limit();
+        let count = self.ptp_rate_window.1;
+        if self.ptp_rate_window_over(rx) {
+            if self.ptp_flooding && count <= limit {
+                self.ptp_flooding = false;
+                info!(
+                    "[Net] PTP packet rate back to normal ({} packets discarded in total)",
+                    self.ptp_flood_dropped
+                );
+            }
+            self.ptp_rate_window = (rx, 0);
+        }
+
+        self.ptp_rate_window.1 += 1;
+        if self.ptp_rate_window.1 <= limit {
+            return true;
+        }
+        if !self.ptp_flooding {
+            self.ptp_flooding = true;
+            warn!(
+                "[Net] PTP flood: more than {} packets/s on the PTP group - discarding the excess",
+                limit
+            );
+            self.update_shared_status();
+        }
+        self.ptp_flood_dropped += 1;
+        false
+    }
+
+    /// Over the limit: discard a bounded batch of queued packets so the
+    /// socket does not back up, without parsing them or holding the loop
+    fn drain_ptp_flood(&mut self) {
+        for _ in 0..PTP_FLOOD_DRAIN_PER_ITERATION {
+            match self.network.recv_packet() {
+                Ok(Some((_, _, rx))) => {
+                    self.ptp_flood_dropped += 1;
+                    // Past the window: let the next pass judge the new one
+                    if self.ptp_rate_window_over(rx) {
+                        self.ptp_rate_window = (rx, 1);
+                        break;
+                    }
+                    self.ptp_rate_window.1 += 1;
+                }
+                _ => break,
+            }
+        }
+    }
+
+    // ========================================================================
+    // PACKET HANDLING
+    // ========================================================================
+
+    fn handle_sync_message(&mut self, header: &PtpV1Header, buf: &[u8], t2: SystemTime) {
+        // Check if Sync source changed (different device sending PTP)
+        let source_uuid = header.source_uuid;
+        let needed = self.config.source_switch_syncs;
+        let mut source_switched = false;
+        match self.current_sync_source {
+            Some(current) if current != source_uuid => {
+                // Stray or flapping source: keep following the current one
+                if !confirm_switch(&mut self.source_candidate, source_uuid, needed) {
+                    debug!(
+                        "Ignoring Sync from {} (current source {})",
+                        format_mac(&source_uuid),
+                        format_mac(&current)
+                    );
+                    return;
+                }
+                source_switched = true;
+                warn!(
+                    ">>> SYNC SOURCE CHANGED: {} -> {} <<<",
+                    format_mac(&current),
+                    format_mac(&source_uuid)
+                );
+                self.current_sync_source = Some(source_uuid);
+                self.last_sync_rx = None;
+                // Soft reset: clear stale data but KEEP current frequency
+                // Both Dante devices should have similar frequencies since they're
+                // synchronized to the same grandmaster time
+                self.pending_syncs.clear();
+                self.orphan_followups.clear();
+                self.clear_sample_window();
+                self.prev_t1_ns = 0;
+                self.prev_t2_ns = 0;
+                // Keep: applied_freq_ppm, drift_baseline_ppm (learned values)
+                // Stay in production mode - let servo naturally adjust if needed
+                info!(
+                    "Soft reset: keeping freq={:.1}ppm, drift_baseline={:.1}ppm",
+                    self.applied_freq_ppm, self.drift_baseline_ppm
+                );
+                // The new source's offset baseline may differ: trust it gradually
+                self.handover_windows_left = self.config.handover_blend_windows;
+                if self.handover_windows_left > 0 {
+                    info!(
+                        "[Handover] Easing servo gains in over {} windows",
+                        self.handover_windows_left
+                    );
+                }
+            }
+            None => {
+                info!("Sync source: {}", format_mac(&source_uuid));
+                self.current_sync_source = Some(source_uuid);
+            }
+            _ => self.source_candidate = None,
+        }
+        self.ptp_leap = header.leap_second();
+
+        if let Ok(body) = PtpV1SyncMessageBody::parse(PtpV1Header::body(buf)) {
+            let new_uuid = body.grandmaster_clock_uuid;
+            match self.current_gm_uuid {
+                Some(current) if current != new_uuid => {
+                    // A new source may bring its own GM; otherwise debounce
+                    if !source_switched && !confirm_switch(&mut self.gm_candidate, new_uuid, needed)
+                    {
+                        debug!(
+                            "Ignoring Sync with grandmaster {} (current {})",
+                            format_mac(&new_uuid),
+                            format_mac(&current)
+                        );
+                        return;
+                    }
+                    warn!(
+                        ">>> GRANDMASTER UUID CHANGED: {} -> {} <<<",
+                        format_mac(&current),
+                        format_mac(&new_uuid)
+                    );
+                    self.current_gm_uuid = Some(new_uuid);
+                    self.gm_candidate = None;
+                    // Note: sync source change already did soft reset if needed
+                }
+                None => {
+                    info!("Grandmaster UUID: {}", format_mac(&new_uuid));
+                    self.current_gm_uuid = Some(new_uuid);
+                }
+                _ => self.gm_candidate = None,
+            }
+            if !self.check_expected_gm(new_uuid) {
+                return;
+            }
+
+            let tlvs = parse_tlvs(buf, PTP_V1_SYNC_LEN);
+            let is_dante = is_dante_master(&new_uuid, &tlvs);
+            if self.master_is_dante != Some(is_dante) {
+                if is_dante {
+                    info!("Grandmaster is a Dante device");
+                } else {
+                    info!("Grandmaster is not a Dante device (third-party PTP master)");
+                }
+                for org in tlvs.iter().filter_map(|tlv| tlv.organization()) {
+                    debug!(
+                        "Grandmaster TLV: OUI {:02X}{:02X}{:02X} subtype {:02X}{:02X}{:02X} ({} bytes)",
+                        org.oui[0],
+                        org.oui[1],
+                        org.oui[2],
+                        org.subtype[0],
+                        org.subtype[1],
+                        org.subtype[2],
+                        org.data.len()
+                    );
+                }
+                self.master_is_dante = Some(is_dante);
+                self.update_shared_status();
+            }
+        }
+
+        self.track_sync_interval(t2);
+
+        // Keep only the last few Syncs: a Follow_Up for anything older is
+        // lost, and after a wrap its sequence id could match an unrelated Sync.
+        // This also bounds the map against floods of malformed packets.
+        self.sync_arrivals += 1;
+        let arrival = self.sync_arrivals;
+        let max_age = self.config.filters.max_pending_syncs;
+        let pending = self.pending_syncs.len();
+        self.pending_syncs
+            .retain(|_, v| arrival - v.arrival < max_age);
+        for _ in self.pending_syncs.len()..pending {
+            self.record_sync_outcome(false);
+        }
+
+        // Its Follow_Up overtook it: pair now instead of waiting
+        if let Some(orphan) = self.orphan_followups.remove(&header.sequence_id) {
+            let gap = t2
+                .duration_since(orphan.rx_time_sys)
+                .unwrap_or(Duration::ZERO);
+            if gap <= self.pending_sync_timeout() && orphan.source_uuid == header.source_uuid {
+                debug!(
+                    "Pairing Sync seq {} with its earlier Follow_Up ({}ms reordered)",
+                    header.sequence_id,
+                    gap.as_millis()
+                );
+                self.pending_syncs.remove(&header.sequence_id);
+                self.record_sync_outcome(true);
+                self.process_sync_pair(orphan.t1_ns, header.source_uuid, t2, t2);
+                return;
+            }
+        }
+
+        // A reused sequence id replaces the stale entry
+        self.pending_syncs.insert(
+            header.sequence_id,
+            PendingSync {
+                rx_time_sys: t2,
+                source_uuid: header.source_uuid,
+                arrival,
+            },
+        );
+    }
+
+    /// Compare the grandmaster against `expected_gm_uuid`; false = ignore its Sync
+    ///
+    /// A mismatch drops lock and raises `gm_mismatch`: the clock keeps its
+    /// learned frequency instead of following a rogue master.
+    fn check_expected_gm(&mut self, gm_uuid: [u8; 6]) -> bool {
+        let expected = self
+            .config
+            .expected_gm_uuid
+            .as_deref()
+            .and_then(|uuid| parse_mac(uuid).ok());
+        let mismatch = expected.is_some_and(|expected| expected != gm_uuid);
+        if mismatch != self.gm_mismatch {
+            self.gm_mismatch = mismatch;
+            if let (true, Some(expected)) = (mismatch, expected) {
+                error!(
+                    ">>> GRANDMASTER MISMATCH: {} is not the expected {} - ignoring its Syncs <<<",
+                    format_mac(&gm_uuid),
+                    format_mac(&expected)
+                );
+                // Samples already queued came from the wrong master
+                self.pending_syncs.clear();
+                self.orphan_followups.clear();
+                self.clear_sample_window();
+                self.prev_t1_ns = 0;
+                self.prev_t2_ns = 0;
+                self.lock_stable_count = 0;
+                self.in_nano_mode = false;
+                if self.is_locked {
+                    self.is_locked = false;
+                    self.unlock_count += 1;
+                    self.locked_since = None;
+                    warn!(
+                        "[PTP] === UNLOCKED === Grandmaster mismatch (unlocks: {})",
+                        self.unlock_count
+                    );
+                }
+            } else {
+                info!(
+                    "[PTP] Grandmaster {} accepted - following it",
+                    format_mac(&gm_uuid)
+                );
+            }
+            self.update_shared_status();
+        }
+        !mismatch
+    }
+
+    /// Count a pending Sync as paired with its Follow_Up, or given up on
+    fn record_sync_outcome(&mut self, matched: bool) {
+        let unmatched = if matched { 0.0 } else { 1.0 };
+        self.unmatched_sync_ratio += UNMATCHED_SYNC_ALPHA * (unmatched - self.unmatched_sync_ratio);
+        if !matched {
+            self.unmatched_syncs += 1;
+        }
+    }
+
+    /// Longest Sync → Follow_Up gap accepted as a pair
+    /// (`filters.pending_sync_timeout_ms`, else two Sync intervals)
+    fn pending_sync_timeout(&self) -> Duration {
+        if self.config.filters.pending_sync_timeout_ms > 0 {
+            return Duration::from_millis(self.config.filters.pending_sync_timeout_ms);
+        }
+        let interval_ms = match self.effective_sync_interval_ms() {
+            ms if ms > 0.0 => ms,
+            _ => NOMINAL_SYNC_INTERVAL_MS,
+        };
+        Duration::from_secs_f64(interval_ms * PENDING_SYNC_TIMEOUT_INTERVALS / 1000.0)
+            .max(MIN_PENDING_SYNC_TIMEOUT)
+    }
+
+    /// Update the smoothed Sync interval from inter-arrival deltas.
+    /// Gaps (PTP outage, missed packets) are ignored rather than averaged in.
+    fn track_sync_interval(&mut self, t2: SystemTime) {
+        const MAX_SYNC_INTERVAL_MS: f64 = 10_000.0;
+        const SYNC_INTERVAL_ALPHA: f64 = 0.1;
+
+        if let Some(prev) = self.last_sync_rx {
+            let delta_ms = t2
+                .duration_since(prev)
+                .map(|d| d.as_secs_f64() * 1000.0)
+                .unwrap_or(0.0);
+            if delta_ms > 0.0 && delta_ms <= MAX_SYNC_INTERVAL_MS {
+                self.sync_interval_ms = if self.sync_interval_ms == 0.0 {
+                    delta_ms
+                } else {
+                    self.sync_interval_ms + SYNC_INTERVAL_ALPHA * (delta_ms - self.sync_interval_ms)
+                };
+            }
+        }
+        self.last_sync_rx = Some(t2);
+    }
+
+    fn handle_followup_message(&mut self, header: &PtpV1Header, buf: &[u8], rx_time: SystemTime) {
+        if let Ok(body) = PtpV1FollowUpBody::parse(PtpV1Header::body(buf)) {
+            if let Some(sync_info) = self.pending_syncs.remove(&body.associated_sequence_id) {
+                // Follow_Up "before" its Sync (clock stepped) counts as no gap
+                let gap = rx_time
+                    .duration_since(sync_info.rx_time_sys)
+                    .unwrap_or(Duration::ZERO);
+                if gap > self.pending_sync_timeout() {
+                    debug!(
+                        "Dropping Follow_Up seq {}: {}ms after its Sync",
+                        body.associated_sequence_id,
+                        gap.as_millis()
+                    );
+                    self.record_sync_outcome(false);
+                    return;
+                }
+                self.record_sync_outcome(sync_info.source_uuid == header.source_uuid);
+                if sync_info.source_uuid == header.source_uuid {
+                    self.process_sync_pair(
+                        body.precise_origin_timestamp.to_nanos(),
+                        sync_info.source_uuid,
+                        sync_info.rx_time_sys,
+                        rx_time,
+                    );
+                }
+            } else {
+                self.hold_orphan_followup(header, &body, rx_time);
+            }
+        }
+    }
+
+    /// Keep a Follow_Up whose Sync has not arrived yet, for pairing in
+    /// `handle_sync_message`; true orphans expire after `pending_sync_timeout`
+    fn hold_orphan_followup(
+        &mut self,
+        header: &PtpV1Header,
+        body: &PtpV1FollowUpBody,
+        rx_time: SystemTime,
+    ) {
+        let timeout = self.pending_sync_timeout();
+        self.orphan_followups.retain(|_, orphan| {
+            rx_time
+                .duration_since(orphan.rx_time_sys)
+                .is_ok_and(|age| age <= timeout)
+        });
+        if self.orphan_followups.len() >= MAX_ORPHAN_FOLLOWUPS {
+            debug!(
+                "Dropping Follow_Up seq {}: no Sync and {} already waiting",
+                body.associated_sequence_id, MAX_ORPHAN_FOLLOWUPS
+            );
+            return;
+        }
+        self.orphan_followups.insert(
+            body.associated_sequence_id,
+            OrphanFollowUp {
+                t1_ns: body.precise_origin_timestamp.to_nanos(),
+                source_uuid: header.source_uuid,
+                rx_time_sys: rx_time,
+            },
+        );
+    }
+
+    // ========================================================================
+    // SYNC PAIR PROCESSING - Main synchronization logic
+    // ========================================================================
+
+    /// Feed one Sync / Follow_Up pair from `source_uuid` to the servo
+    fn process_sync_pair(
+        &mut self,
+        t1_ns: i64,
+        source_uuid: [u8; 6],
+        t2_sys: SystemTime,
+        followup_sys: SystemTime,
+    ) {
+        let t2_ns = t2_sys
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos() as i64;
+
+        if self.reject_master_jump(t1_ns) {
+            return;
+        }
+
+        // Calculate display phase offset (modulo-based for readability)
+        let phase_offset_ns = self.calculate_phase_offset(t1_ns, t2_ns);
+
+        // Account for clock rate between Sync receive and FollowUp processing
+        let elapsed_ns = followup_sys
+            .duration_since(t2_sys)
+            .map(|d| d.as_nanos() as i64)
+            .unwrap_or(-1);
+        let phase_offset_ns = project_offset_ns(phase_offset_ns, elapsed_ns, self.applied_freq_ppm);
+
+        // Handle calibration if needed
+        if self.process_calibration(phase_offset_ns) {
+            return;
+        }
+
+        // Apply calibration offset
+        let phase_offset_ns = phase_offset_ns - self.calibration_offset_ns;
+
+        // Handle warmup period
+        if !self.process_warmup() {
+            return;
+        }
+
+        // Log delta sanity check
+        self.log_delta_sanity(t1_ns, t2_ns);
+
+        // Process sync once settled
+        self.valid_count += 1;
+        if self.valid_count >= self.settling_threshold {
+            self.process_settled_sync(t1_ns, t2_ns, phase_offset_ns, source_uuid);
+        }
+
+        self.prev_t1_ns = t1_ns;
         self.prev_t2_ns = t2_ns;
     }
 
@@ -725,16 +2286,19 @@ where
             return false;
         }
 
+        // Collect ~10% extra (at least one) and drop that many outliers
+        let extra = (count + 9) / 10;
         self.calibration_samples.push(phase_offset_ns);
-        if self.calibration_samples.len() >= count {
-            let mut sorted = self.calibration_samples.clone();
-            sorted.sort();
-            self.calibration_offset_ns = sorted[sorted.len() / 2];
+        if self.calibration_samples.len() >= count + extra {
+            let (offset_ns, spread_ns) = calibration_offset_ns(&self.calibration_samples, extra);
+            self.calibration_offset_ns = offset_ns;
             self.calibration_complete = true;
             info!(
-                "Calibration complete: offset={:.3}ms ({} samples)",
+                "Calibration complete: offset={:.3}ms spread={:.1}us ({} samples, {} outliers dropped)",
                 self.calibration_offset_ns as f64 / 1_000_000.0,
-                count
+                spread_ns as f64 / 1_000.0,
+                count,
+                extra
             );
         }
         true
@@ -748,6 +2312,9 @@ where
         let warmup_secs = self.config.filters.warmup_secs;
         if warmup_secs <= 0.0 || self.warmup_start.elapsed().as_secs_f64() >= warmup_secs {
             self.warmup_complete = true;
+            if self.settle_start.is_none() {
+                self.settle_start = Some(Instant::now());
+            }
             if warmup_secs > 0.0 {
                 info!("[Warmup] Complete after {:.1}s", warmup_secs);
             }
@@ -757,6 +2324,45 @@ where
         }
     }
 
+    /// Reject a pair whose master time runs backward or leaps ahead of the
+    /// previous pair (buggy Follow_Up). Repeated jumps in a row are taken as
+    /// a real timeline change (master reboot) and accepted.
+    fn reject_master_jump(&mut self, t1_ns: i64) -> bool {
+        if self.prev_t1_ns == 0 {
+            return false;
+        }
+        let delta_master = t1_ns - self.prev_t1_ns;
+        if delta_master > 0 && delta_master <= MAX_DELTA_NS {
+            self.consecutive_rejects = 0;
+            return false;
+        }
+
+        self.consecutive_rejects += 1;
+        if self.consecutive_rejects >= MAX_CONSECUTIVE_REJECTS {
+            warn!(
+                "[Sanity] Master time jumped {:+.3}s on {} pairs in a row - accepting new timeline",
+                delta_master as f64 / 1e9,
+                self.consecutive_rejects
+            );
+            self.consecutive_rejects = 0;
+            self.clear_sample_window();
+            self.prev_t1_ns = 0;
+            self.prev_t2_ns = 0;
+            return false;
+        }
+
+        self.rejected_sync_pairs += 1;
+        warn!(
+            "[Sanity] Rejected sync pair: master time delta {:+.3}s (total rejected: {})",
+            delta_master as f64 / 1e9,
+            self.rejected_sync_pairs
+        );
+        if let Ok(mut status) = self.status_shared.write() {
+            status.rejected_sync_pairs = self.rejected_sync_pairs;
+        }
+        true
+    }
+
     fn log_delta_sanity(&self, t1_ns: i64, t2_ns: i64) {
         if self.prev_t1_ns > 0 && self.prev_t2_ns > 0 {
             let delta_master = t1_ns - self.prev_t1_ns;
@@ -776,46 +2382,152 @@ where
         }
     }
 
-    fn process_settled_sync(&mut self, t1_ns: i64, t2_ns: i64, phase_offset_ns: i64) {
+    fn process_settled_sync(
+        &mut self,
+        t1_ns: i64,
+        t2_ns: i64,
+        phase_offset_ns: i64,
+        source_uuid: [u8; 6],
+    ) {
         if !self.clock_settled {
             self.clock_settled = true;
             self.initial_epoch_offset_ns = t2_ns - t1_ns;
             self.epoch_aligned = true;
             info!("Sync established.");
         }
+        self.check_epoch_offset(t1_ns, t2_ns);
 
         // Collect sample if enough time has passed
         if self.should_add_sample(t1_ns) {
             self.sample_window.push(phase_offset_ns);
+            self.sample_sources.push(source_uuid);
         }
 
         // Process window when full - pass master time for drift calculation
-        if self.sample_window.len() >= self.config.filters.sample_window_size {
+        let window_size = self.effective_window_size();
+        if window_size != self.adapted_window_size {
+            info!(
+                "[Filter] Sync interval {:.1}ms: sample window {} -> {}",
+                self.effective_sync_interval_ms(),
+                self.adapted_window_size,
+                window_size
+            );
+            self.adapted_window_size = window_size;
+        }
+        if self.sample_window.len() >= window_size {
             self.process_sample_window(t1_ns);
         }
     }
 
-    // NOTE: PTP stepping removed - Dante provides device uptime, not UTC.
-    // NTP handles all time stepping via check_ntp_utc_tracking().
+    /// Flag a master epoch change: the T2 - T1 offset moving further between
+    /// two settled pairs than the clock can drift in that time. Dante T1 is
+    /// device uptime, so a grandmaster restart or a new grandmaster shows up
+    /// here as a jump of seconds or more.
+    fn check_epoch_offset(&mut self, t1_ns: i64, t2_ns: i64) {
+        let epoch_ns = t2_ns - t1_ns;
+        let previous = self.last_epoch.replace((epoch_ns, t2_ns));
+        let alarm_ns = self.config.epoch_change_alarm_ms as i64 * 1_000_000;
+        let Some((prev_epoch_ns, prev_t2_ns)) = previous else {
+            return;
+        };
+        if alarm_ns == 0 {
+            return;
+        }
 
-    fn should_add_sample(&self, t1_ns: i64) -> bool {
-        // Skip samples during 2s grace period after NTP step (prevents transient from corrupting servo)
-        if let Some(step_time) = self.last_ntp_step {
-            if step_time.elapsed() < Duration::from_secs(2) {
-                debug!("[NTP-Grace] Skipping sample during post-step grace period");
-                return false;
-            }
+        let shift_ns = epoch_ns - prev_epoch_ns;
+        let drift_allowance_ns =
+            ((t2_ns - prev_t2_ns).abs() as f64 * (DRIFT_MAX_PPM + SLEW_PPM) / 1e6) as i64;
+        if shift_ns.abs() <= alarm_ns + drift_allowance_ns {
+            return;
         }
-        if self.prev_t1_ns == 0 {
-            return true;
+
+        warn!(
+            "[Epoch] Master epoch changed by {:+.3}s - grandmaster restarted or changed reference (not drift)",
+            shift_ns as f64 / 1e9
+        );
+        self.initial_epoch_offset_ns = epoch_ns;
+        self.epoch_changed_at = Some(Instant::now());
+        self.epoch_shift_ns = shift_ns;
+        // The window straddles two timelines
+        self.reset_rate_tracking();
+        self.update_shared_status();
+    }
+
+    // ========================================================================
+    // SYNC RATE ADAPTATION
+    // ========================================================================
+    //
+    // The filter defaults assume Dante's 125ms Sync interval. Other PTPv1
+    // masters may send at 1Hz or 32Hz+, so min_delta, the window size and
+    // the rate EMA are rescaled from the measured (or hinted) interval:
+    // - Fast masters: min_delta shrinks so samples aren't all rejected, and
+    //   the window grows so each servo update still spans ~0.5s
+    // - Slow masters: the window is kept, and alpha is raised so the EMA
+    //   keeps the same time constant in seconds despite fewer updates
+    //
+    // ========================================================================
+
+    /// Sync interval used for adaptation (config hint, else measured; 0 = unknown)
+    fn effective_sync_interval_ms(&self) -> f64 {
+        if self.config.filters.sync_interval_hint_ms > 0 {
+            self.config.filters.sync_interval_hint_ms as f64
+        } else {
+            self.sync_interval_ms
         }
+    }
+
+    fn effective_min_delta_ns(&self) -> i64 {
         // Use config value if > 0, otherwise default (Dante sends packets every ~125ms)
         let min_delta = if self.config.filters.min_delta_ns > 0 {
             self.config.filters.min_delta_ns
         } else {
             DEFAULT_MIN_T1_DELTA_NS
         };
-        (t1_ns - self.prev_t1_ns).abs() >= min_delta
+        let interval_ms = self.effective_sync_interval_ms();
+        if interval_ms > 0.0 {
+            let interval_cap_ns = (interval_ms * MIN_DELTA_INTERVAL_FRACTION * 1_000_000.0) as i64;
+            min_delta.min(interval_cap_ns)
+        } else {
+            min_delta
+        }
+    }
+
+    fn effective_window_size(&self) -> usize {
+        let configured = self.config.filters.sample_window_size;
+        let interval_ms = self.effective_sync_interval_ms();
+        if interval_ms > 0.0 && interval_ms < NOMINAL_SYNC_INTERVAL_MS {
+            let scaled = (configured as f64 * NOMINAL_SYNC_INTERVAL_MS / interval_ms).round();
+            (scaled as usize).clamp(configured, MAX_ADAPTED_WINDOW.max(configured))
+        } else {
+            configured
+        }
+    }
+
+    /// Rescale an EMA alpha tuned for 125ms Syncs to the actual interval.
+    /// N updates at alpha a decay like (1-a)^N, so an update covering k times
+    /// the nominal time uses 1-(1-a)^k to keep the same time constant.
+    fn interval_scaled_alpha(&self, alpha: f64) -> f64 {
+        let ratio = self.effective_sync_interval_ms() / NOMINAL_SYNC_INTERVAL_MS;
+        if ratio > 1.0 {
+            1.0 - (1.0 - alpha).powf(ratio)
+        } else {
+            alpha
+        }
+    }
+
+    // NOTE: PTP stepping removed - Dante provides device uptime, not UTC.
+    // NTP handles all time stepping via check_ntp_utc_tracking().
+
+    fn should_add_sample(&self, t1_ns: i64) -> bool {
+        // Skip samples during 2s grace period after NTP step (prevents transient from corrupting servo)
+        if self.in_step_grace() {
+            debug!("[NTP-Grace] Skipping sample during post-step grace period");
+            return false;
+        }
+        if self.prev_t1_ns == 0 {
+            return true;
+        }
+        (t1_ns - self.prev_t1_ns).abs() >= self.effective_min_delta_ns()
     }
 
     // ========================================================================
@@ -831,28 +2543,132 @@ where
     // ========================================================================
 
     fn process_sample_window(&mut self, _master_time_ns: i64) {
+        // A window straddling a sync source handover would blend two
+        // masters' offsets into one median
+        if let Some(first) = self.sample_sources.first().copied() {
+            let foreign = self.sample_sources.iter().filter(|&&s| s != first).count();
+            if foreign > 0 {
+                warn!(
+                    "[Filter] Discarding window: {} of {} samples from another sync source than {} (handover)",
+                    foreign,
+                    self.sample_sources.len(),
+                    format_mac(&first)
+                );
+                self.clear_sample_window();
+                return;
+            }
+        }
+
         let mut sorted = self.sample_window.clone();
         sorted.sort();
 
-        let median = sorted[sorted.len() / 2];
-
-        // UNIFIED: Use median for both platforms (robust against outliers)
-        let offset_ns = median;
+        // Median by default (robust against outliers); mean / trimmed mean
+        // use more of the window on low-jitter hosts
+        let filter_type = self.config.filters.filter_type;
+        let offset_ns = estimate_offset_ns(&sorted, filter_type, self.config.filters.trim_percent);
         let offset_us = offset_ns as f64 / 1000.0;
+        let jitter_ns = offset_jitter_ns(&self.sample_window);
 
         debug!(
-            "[Filter] min={:.1}us max={:.1}us median={:.1}us",
+            "[Filter] min={:.1}us max={:.1}us {:?}={:.1}us jitter={:.2}us",
             sorted.first().map(|&x| x as f64 / 1000.0).unwrap_or(0.0),
             sorted.last().map(|&x| x as f64 / 1000.0).unwrap_or(0.0),
-            offset_us
+            filter_type,
+            offset_us,
+            jitter_ns / 1000.0
         );
 
         self.last_phase_offset_ns = offset_ns;
+        self.last_offset_jitter_ns = jitter_ns;
+        self.update_network_quality(jitter_ns);
 
         // Apply self-tuning servo
         self.apply_self_tuning_servo(offset_us);
 
+        self.clear_sample_window();
+    }
+
+    /// Lock / unlock drift-rate thresholds (µs/s) in effect
+    ///
+    /// The configured `lock_rate_us_s` / `unlock_rate_us_s`, or with
+    /// `auto_thresholds` (once the noise floor is known) `AUTO_LOCK_SIGMAS` ×
+    /// the long-run rate noise, so a quiet host locks tighter than a noisy VM.
+    fn lock_thresholds(&self) -> (f64, f64) {
+        let (lock, unlock) = (self.config.lock_rate_us_s, self.config.unlock_rate_us_s);
+        if !self.config.auto_thresholds {
+            return (lock, unlock);
+        }
+        match self.noise_floor.stddev() {
+            Some(sigma) => {
+                let floor = AUTO_LOCK_MIN_US_S.max(self.config.effective_deadband_us_s());
+                let auto = (AUTO_LOCK_SIGMAS * sigma).clamp(floor, AUTO_LOCK_MAX_US_S.max(floor));
+                (auto, auto * unlock / lock)
+            }
+            None => (lock, unlock),
+        }
+    }
+
+    fn log_auto_thresholds(&mut self, lock_rate: f64, unlock_rate: f64) {
+        if !self.config.auto_thresholds || self.noise_floor.stddev().is_none() {
+            return;
+        }
+        let logged = self.logged_auto_lock_us_s;
+        if logged > 0.0 && (lock_rate - logged).abs() <= logged * AUTO_LOCK_LOG_CHANGE {
+            return;
+        }
+        info!(
+            "[Servo] Auto thresholds: lock <{:.2}us/s unlock >{:.2}us/s (rate noise {:.2}us/s)",
+            lock_rate,
+            unlock_rate,
+            self.noise_floor.stddev().unwrap_or(0.0)
+        );
+        self.logged_auto_lock_us_s = lock_rate;
+    }
+
+    fn clear_sample_window(&mut self) {
         self.sample_window.clear();
+        self.sample_sources.clear();
+    }
+
+    /// Largest frequency correction (ppm) the servo may apply:
+    /// `max_healthy_ppm` when set, otherwise the full `DRIFT_MAX_PPM` range
+    fn output_limit_ppm(&self) -> f64 {
+        let band = self.config.max_healthy_ppm;
+        if band > 0.0 {
+            band.min(DRIFT_MAX_PPM)
+        } else {
+            DRIFT_MAX_PPM
+        }
+    }
+
+    /// Raise or clear `oscillator_fault` from the correction the servo wants
+    /// (before clamping). A healthy oscillator never needs more than
+    /// `max_healthy_ppm`; needing more means the hardware, not the servo, is
+    /// at fault.
+    fn check_oscillator_band(&mut self, needed_ppm: f64) {
+        let band = self.config.max_healthy_ppm;
+        if band <= 0.0 {
+            if self.oscillator_fault {
+                info!("[Servo] max_healthy_ppm disabled - clearing oscillator fault");
+                self.oscillator_fault = false;
+            }
+            return;
+        }
+        if !self.oscillator_fault && needed_ppm.abs() > band {
+            error!(
+                "[Servo] OSCILLATOR FAULT: correction of {:+.1}ppm needed, beyond max_healthy_ppm {:.1} - clamped at the band edge, check the clock hardware",
+                needed_ppm, band
+            );
+            self.oscillator_fault = true;
+        } else if self.oscillator_fault
+            && needed_ppm.abs() <= band * OSCILLATOR_FAULT_CLEAR_FRACTION
+        {
+            info!(
+                "[Servo] Oscillator fault cleared: correction back to {:+.1}ppm (max_healthy_ppm {:.1})",
+                needed_ppm, band
+            );
+            self.oscillator_fault = false;
+        }
     }
 
     /// Self-tuning servo algorithm
@@ -877,11 +2693,9 @@ where
         // NTP handles UTC alignment separately. PTP only matches frequency.
 
         // Skip correction during post-step grace period
-        if let Some(step_time) = self.last_ntp_step {
-            if step_time.elapsed() < Duration::from_secs(2) {
-                debug!("[Servo] In grace period, skipping correction");
-                return;
-            }
+        if self.in_step_grace() {
+            debug!("[Servo] In grace period, skipping correction");
+            return;
         }
 
         // Track offset for rate calculation
@@ -897,8 +2711,8 @@ where
             if dt_secs > 0.1 {
                 // Need meaningful time delta
                 let delta_offset = offset_us - prev_offset;
-                // Convert: us/s = ppm
-                (delta_offset / dt_secs).clamp(-500.0, 500.0)
+                // Convert: us/s = ppm. A deliberate slew is not drift.
+                (delta_offset / dt_secs + self.slew_ppm()).clamp(-500.0, 500.0)
             } else {
                 self.smoothed_rate_ppm // Keep previous
             }
@@ -933,10 +2747,15 @@ where
         // Log when spike is detected and rejected
         if filter_result.is_spike {
             info!(
-                "[Spike] REJECTED {:+.1}us/s (dev={:.1}, thresh={:.1}, median={:.1})",
+                "[Spike] REJECTED {:+.2}us/s (dev={:.2}, thresh={:.2}{}, median={:.2})",
                 raw_rate_ppm,
                 filter_result.deviation,
                 filter_result.threshold,
+                if filter_result.mad_floored {
+                    " from MAD floor"
+                } else {
+                    ""
+                },
                 filter_result.median
             );
         }
@@ -954,10 +2773,19 @@ where
         // Use adaptive alpha from jitter estimator:
         // - Low-jitter systems (strih.lan): α=0.3 for responsive tracking
         // - High-jitter systems (stream.lan): α=0.1 for heavy smoothing
-        let adaptive_alpha = self.jitter_estimator.add_sample(filtered_rate_ppm);
+        // Slow (non-Dante) Sync rates raise α so the time constant stays the same
+        let jitter_alpha = self.jitter_estimator.add_sample(filtered_rate_ppm);
+        let adaptive_alpha = self.interval_scaled_alpha(jitter_alpha);
         self.smoothed_rate_ppm =
             self.smoothed_rate_ppm * (1.0 - adaptive_alpha) + filtered_rate_ppm * adaptive_alpha;
         let rate_ppm = self.smoothed_rate_ppm;
+        self.record_display_rate(rate_ppm);
+
+        // Paused: report the drift but leave lock state and the clock alone
+        if self.paused {
+            self.update_shared_status();
+            return;
+        }
 
         // Log jitter statistics periodically (every 50 samples when adjusted)
         if self.jitter_estimator.sample_count() > 0
@@ -975,6 +2803,13 @@ where
         // THREE-PHASE CONTROL: ACQ → PROD → NANO based on rate stability
         let abs_rate = rate_ppm.abs();
 
+        // Noise floor from everything but acquisition (large, trending rates)
+        if abs_rate < self.config.unlock_rate_us_s {
+            self.noise_floor.add_sample(rate_ppm);
+        }
+        let (lock_rate, unlock_rate) = self.lock_thresholds();
+        self.log_auto_thresholds(lock_rate, unlock_rate);
+
         // NANO mode transitions (from LOCK state only)
         if self.is_locked {
             if abs_rate < NANO_ENTER_RATE_US {
@@ -994,6 +2829,14 @@ where
                         "[PTP] === NANO MODE === Ultra-precise servo engaged (after {} samples)",
                         NANO_SUSTAIN_COUNT
                     );
+                    if self.time_to_nano.is_none() {
+                        let elapsed = self.settle_elapsed();
+                        self.time_to_nano = Some(elapsed);
+                        info!(
+                            "[PTP] Reached NANO after {}",
+                            format_duration(elapsed.as_secs())
+                        );
+                    }
                 }
             } else if abs_rate > NANO_EXIT_RATE_US {
                 // Above exit threshold - count towards exit (hysteresis)
@@ -1032,12 +2875,12 @@ where
             self.nano_exit_count = 0;
         }
 
-        // ACQ/PROD transitions
-        if abs_rate < 5.0 {
-            // Rate stable within 5µs/s
+        // ACQ/PROD transitions (hysteresis: lock_rate < unlock_rate)
+        if abs_rate < lock_rate {
+            // Rate stable (default within 5µs/s)
             self.in_production_mode = true;
-        } else if abs_rate > 20.0 {
-            // Rate unstable above 20µs/s
+        } else if abs_rate > unlock_rate {
+            // Rate unstable (default above 20µs/s)
             self.in_production_mode = false;
         }
 
@@ -1047,12 +2890,27 @@ where
         } else if self.in_production_mode {
             (P_GAIN_PROD, P_MAX_PROD_PPM, 0.05, "PROD")
         } else {
-            (P_GAIN_ACQ, P_MAX_ACQ_PPM, 0.05, "ACQ")
+            let boost = self.cold_start_boost();
+            (
+                P_GAIN_ACQ,
+                P_MAX_ACQ_PPM + (COLD_START_P_MAX_PPM - P_MAX_ACQ_PPM) * boost,
+                I_GAIN_ACQ + (COLD_START_I_GAIN - I_GAIN_ACQ) * boost,
+                "ACQ",
+            )
         };
+        let weight = self.handover_weight();
+        let (p_gain, i_gain) = (p_gain * weight, i_gain * weight);
 
         // P-term: responds to rate of change (not absolute offset!)
-        // NANO mode: apply deadband - don't correct tiny rates (noise)
-        let effective_rate = if self.in_nano_mode && abs_rate < NANO_DEADBAND_US {
+        // NANO/PROD/LOCK: apply deadband - don't correct tiny rates (noise)
+        let deadband = if self.in_nano_mode {
+            self.nano_deadband_us_s()
+        } else if self.in_production_mode || self.is_locked {
+            self.config.effective_deadband_us_s()
+        } else {
+            0.0
+        };
+        let effective_rate = if abs_rate < deadband {
             0.0 // Within deadband, no correction needed
         } else {
             rate_ppm
@@ -1064,23 +2922,64 @@ where
         // I-term: Integrate rate error to learn true drift
         // Uses mode-appropriate gain
         let i_term = -effective_rate * i_gain;
-        self.drift_baseline_ppm =
-            (self.drift_baseline_ppm + i_term).clamp(-DRIFT_MAX_PPM, DRIFT_MAX_PPM);
+
+        // Output limit: the kernel's range, or the healthy band for plants
+        // with known-good oscillators
+        let max_ppm = self.output_limit_ppm();
+
+        // Anti-windup: while the output is pinned (at ±max_ppm or by the
+        // slew limit) the rate error persists however much we integrate, and the
+        // excess would overshoot once conditions normalize
+        let unclamped_output = self.drift_baseline_ppm + i_term + p_term;
+        self.check_oscillator_band(unclamped_output);
+        let achievable_output = slew_limit_ppm(
+            self.applied_freq_ppm,
+            unclamped_output.clamp(-max_ppm, max_ppm),
+            self.config.max_ppm_step_per_sample,
+        );
+        if self.config.anti_windup && i_term * (unclamped_output - achievable_output) > 0.0 {
+            debug!(
+                "[Servo] Output saturated at {:+.1}ppm - holding integral at {:+.1}ppm",
+                achievable_output, self.drift_baseline_ppm
+            );
+        } else {
+            self.drift_baseline_ppm = (self.drift_baseline_ppm + i_term).clamp(-max_ppm, max_ppm);
+        }
 
         // Total correction = drift baseline + P-term
-        let total_correction =
-            (self.drift_baseline_ppm + p_term).clamp(-DRIFT_MAX_PPM, DRIFT_MAX_PPM);
+        let target_correction = (self.drift_baseline_ppm + p_term).clamp(-max_ppm, max_ppm);
+
+        // Ramp toward the target: dependent devices feel frequency steps
+        let total_correction = slew_limit_ppm(
+            self.applied_freq_ppm,
+            target_correction,
+            self.config.max_ppm_step_per_sample,
+        );
+        if total_correction != target_correction {
+            debug!(
+                "[Servo] Slew limited: target {:+.1}ppm, applying {:+.1}ppm",
+                target_correction, total_correction
+            );
+        }
 
         // Lock state: based on rate stability, not absolute offset
-        let rate_stable = abs_rate < 5.0; // Within 5ppm
+        let rate_stable = abs_rate < lock_rate;
         if rate_stable {
             self.lock_stable_count += 1;
             if self.lock_stable_count >= LOCK_STABLE_COUNT && !self.is_locked {
                 self.is_locked = true;
+                self.ever_locked = true;
+                self.cold_start_active = false;
+                self.locked_since = Some(Instant::now());
                 info!(
                     "[PTP] === LOCKED === Adj:{:+.1}ppm",
                     self.drift_baseline_ppm
                 );
+                if self.time_to_lock.is_none() {
+                    let elapsed = self.settle_elapsed();
+                    self.time_to_lock = Some(elapsed);
+                    info!("[PTP] Locked after {}", format_duration(elapsed.as_secs()));
+                }
             }
         } else {
             if self.lock_stable_count > 0 {
@@ -1088,14 +2987,34 @@ where
             }
             if self.lock_stable_count == 0 && self.is_locked {
                 self.is_locked = false;
-                info!("[PTP] === UNLOCKED === Drift:{:+.1}us/s", rate_ppm);
+                self.unlock_count += 1;
+                info!(
+                    "[PTP] === UNLOCKED === Drift:{:+.1}us/s (was locked {}, unlocks: {})",
+                    rate_ppm,
+                    format_duration(self.locked_duration_secs()),
+                    self.unlock_count
+                );
+                self.locked_since = None;
+            }
+        }
+
+        // Bleed-off: while locked, pull the integral toward the correction that
+        // actually held the rate steady, unwinding what a transient left behind
+        if self.is_locked {
+            if rate_stable {
+                let steady = self.steady_correction_ppm.get_or_insert(total_correction);
+                *steady += STEADY_CORRECTION_ALPHA * (total_correction - *steady);
+            }
+            if let Some(steady) = self.steady_correction_ppm {
+                self.drift_baseline_ppm +=
+                    self.config.integral_bleed * (steady - self.drift_baseline_ppm);
             }
         }
 
         // Apply correction
         self.last_adj_ppm = total_correction;
         self.applied_freq_ppm = total_correction;
-        let factor = 1.0 + (total_correction / 1_000_000.0);
+        let factor = 1.0 + ((total_correction - self.slew_ppm()) / 1_000_000.0);
 
         let status = if self.in_nano_mode {
             "NANO"
@@ -1115,29 +3034,179 @@ where
             );
         } else {
             info!(
-                "[PTP] {:4}  Drift:{:+6.1}us/s  Adj:{:+6.1}ppm",
-                status, rate_ppm, total_correction
+                "[PTP] {:4}  Drift:{:+6.1}us/s  Adj:{:+6.1}ppm",
+                status, rate_ppm, total_correction
+            );
+        }
+
+        if let Err(e) = self.clock.adjust_frequency(factor) {
+            warn!("Clock adjustment failed: {}", e);
+        }
+
+        if self.sample_log.is_some() || self.sample_fifo.is_some() {
+            let record = SampleRecord {
+                timestamp: SystemTime::now(),
+                offset_ns: self.last_phase_offset_ns,
+                raw_rate: raw_rate_ppm,
+                filtered_rate: filtered_rate_ppm,
+                smoothed_rate: rate_ppm,
+                applied_ppm: total_correction,
+                mode: status,
+                is_spike: filter_result.is_spike,
+                ntp_offset_us: self.last_ntp_offset_us,
+            };
+            if let Some(log) = self.sample_log.as_mut() {
+                if let Err(e) = log.record(&record) {
+                    warn!("[CSV] Sample log write failed: {} - disabling", e);
+                    self.sample_log = None;
+                }
+            }
+            if let Some(fifo) = self.sample_fifo.as_mut() {
+                // Dropped lines are expected while no plotter is attached
+                fifo.send(&record);
+            }
+        }
+
+        self.update_shared_status();
+    }
+
+    // ========================================================================
+    // UTILITY METHODS
+    // ========================================================================
+
+    /// Marginal and poor offset jitter (ns): fixed, or in audio samples
+    /// with `target_sample_rate_hz`
+    fn jitter_thresholds_ns(&self) -> (f64, f64) {
+        match self.config.sample_period_us() {
+            Some(period_us) => (
+                MARGINAL_JITTER_SAMPLES * period_us * 1000.0,
+                POOR_JITTER_SAMPLES * period_us * 1000.0,
+            ),
+            None => (MARGINAL_JITTER_NS, POOR_JITTER_NS),
+        }
+    }
+
+    /// NANO dead-band (µs/s): fixed, or in audio samples with
+    /// `target_sample_rate_hz`
+    fn nano_deadband_us_s(&self) -> f64 {
+        self.config
+            .sample_period_us()
+            .map_or(NANO_DEADBAND_US, |period_us| {
+                NANO_DEADBAND_SAMPLES_PER_S * period_us
+            })
+    }
+
+    /// Time since the settle-time metric started (zero before warmup completes)
+    /// Grade the PTP path from offset jitter; only a level that persists for
+    /// NETWORK_QUALITY_WINDOWS windows is adopted, so one burst doesn't flap it
+    fn update_network_quality(&mut self, jitter_ns: f64) {
+        let (marginal_ns, poor_ns) = self.jitter_thresholds_ns();
+        let level = if jitter_ns >= poor_ns {
+            NetworkQuality::Poor
+        } else if jitter_ns >= marginal_ns {
+            NetworkQuality::Marginal
+        } else {
+            NetworkQuality::Good
+        };
+        if level == self.network_quality {
+            self.network_quality_streak = 0;
+            return;
+        }
+        self.network_quality_streak += 1;
+        if self.network_quality_streak < NETWORK_QUALITY_WINDOWS {
+            return;
+        }
+
+        self.network_quality_streak = 0;
+        let previous = std::mem::replace(&mut self.network_quality, level);
+        if level > previous {
+            warn!(
+                "[Network] PTP offset jitter {:.1}us - network quality {}. \
+                 The network may not be prioritizing PTP: check switch QoS \
+                 (Dante marks PTP as DSCP 56 / CS7, it needs the strict-priority queue)",
+                jitter_ns / 1000.0,
+                level.as_str()
+            );
+        } else {
+            info!(
+                "[Network] PTP offset jitter {:.1}us - network quality {}",
+                jitter_ns / 1000.0,
+                level.as_str()
+            );
+        }
+    }
+
+    /// Cold-start weight: 1 at settle, fading to 0 at `cold_start_secs`.
+    /// Always 0 once the servo has locked.
+    fn cold_start_boost(&mut self) -> f64 {
+        let secs = self.config.cold_start_secs;
+        let boost = if secs == 0 || self.ever_locked {
+            0.0
+        } else {
+            (1.0 - self.settle_elapsed().as_secs_f64() / secs as f64).max(0.0)
+        };
+        if boost > 0.0 && !self.cold_start_active {
+            self.cold_start_active = true;
+            info!(
+                "[Servo] Cold start: boosted acquisition for up to {}s",
+                secs
             );
+        } else if boost == 0.0 && self.cold_start_active {
+            self.cold_start_active = false;
+            info!("[Servo] Cold start over - normal acquisition gains");
         }
+        boost
+    }
 
-        if let Err(e) = self.clock.adjust_frequency(factor) {
-            warn!("Clock adjustment failed: {}", e);
+    /// Gain weight after a sync source handover: 1/(N+1) on the first servo
+    /// window, rising by 1/(N+1) per window to 1 after
+    /// `handover_blend_windows` (N). Always 1 outside a handover.
+    fn handover_weight(&mut self) -> f64 {
+        let windows = self.config.handover_blend_windows;
+        let left = self.handover_windows_left.min(windows);
+        if left == 0 {
+            self.handover_windows_left = 0;
+            return 1.0;
         }
-
-        self.update_shared_status();
+        self.handover_windows_left = left - 1;
+        if self.handover_windows_left == 0 {
+            info!("[Handover] New sync source trusted - normal servo gains");
+        }
+        (windows - left + 1) as f64 / (windows + 1) as f64
     }
 
-    // ========================================================================
-    // UTILITY METHODS
-    // ========================================================================
+    fn settle_elapsed(&self) -> Duration {
+        self.settle_start
+            .map(|start| start.elapsed())
+            .unwrap_or_default()
+    }
 
     fn update_shared_status(&self) {
         if let Ok(mut status) = self.status_shared.write() {
             // Core fields
             status.offset_ns = self.last_phase_offset_ns;
+            status.raw_offset_ns = self.last_phase_offset_ns + self.calibration_offset_ns;
+            status.offset_jitter_ns = self.last_offset_jitter_ns.round() as u64;
             status.drift_ppm = self.last_adj_ppm;
+            status.drift_baseline_ppm = self.drift_baseline_ppm;
             status.gm_uuid = self.current_gm_uuid;
-            status.settled = self.clock_settled;
+            status.master_is_dante = self.master_is_dante.unwrap_or(false);
+            status.gm_mismatch = self.gm_mismatch;
+            status.ptp_flood_dropped = self.ptp_flood_dropped;
+            status.unmatched_sync_rate = self.unmatched_sync_ratio * 100.0;
+            status.unmatched_syncs_total = self.unmatched_syncs;
+            status.flag_ignored_alternate_master = self.flag_ignored_alternate_master;
+            status.flag_ignored_unicast = self.flag_ignored_unicast;
+            status.master_epoch_changed = self
+                .epoch_changed_at
+                .is_some_and(|at| at.elapsed() < EPOCH_CHANGE_ALARM_HOLD);
+            status.epoch_shift_ns = self.epoch_shift_ns;
+            status.sync_source_uuid = self.current_sync_source;
+            status.observed_sync_interval_ms = self.sync_interval_ms.round() as u32;
+            status.settled = self.clock_settled && !self.ptp_offline;
+            status.resettling = self.resettling;
+            status.lock_threshold_us_s = self.lock_thresholds().0;
+            status.oscillator_fault = self.oscillator_fault;
             status.updated_ts = SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap_or_default()
@@ -1146,7 +3215,37 @@ where
             // Extended fields for tray app
             status.is_locked = self.is_locked;
             status.smoothed_rate_ppm = self.smoothed_rate_ppm;
-            status.mode = if self.in_nano_mode {
+            status.display_rate_ppm = self.display_rate_ppm();
+            status.locked_duration_secs = self.locked_duration_secs();
+            status.unlock_count = self.unlock_count;
+            status.rejected_sync_pairs = self.rejected_sync_pairs;
+            let (_, spikes_total, _) = self.spike_filter.stats();
+            status.spikes_total = spikes_total;
+            status.spikes_recent = self.spike_filter.recent_spikes();
+            let (_, _, spike_threshold) = self.spike_filter.last_stats();
+            status.spike_threshold_us_s = spike_threshold;
+            status.spike_threshold_floored = self.spike_filter.last_mad_floored();
+            status.ntp_failures_total = self.ntp_failure_counter.total();
+            status.ntp_failures_recent = self.ntp_failure_counter.count();
+            status.counter_window_secs = self.ntp_failure_counter.window().as_secs();
+            status.counters_since_ts = self
+                .counters_since
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            status.network_quality = self.network_quality;
+            status.ntp_tracking = self.ntp_tracking_enabled;
+            status.ntp_step_threshold_us = self.ntp_step_threshold_us();
+            status.leap_pending = self.leap_scheduled.is_some();
+            status.time_to_lock_secs = self.time_to_lock.map(|d| d.as_secs());
+            status.time_to_nano_secs = self.time_to_nano.map(|d| d.as_secs());
+            status.paused = self.paused;
+            status.mode = if self.paused {
+                "PAUSED".to_string()
+            } else if self.ptp_offline {
+                // An NTP step while PTP is away must not report LOCK
+                "NTP-only".to_string()
+            } else if self.in_nano_mode {
                 "NANO".to_string()
             } else if self.is_locked {
                 "LOCK".to_string()
@@ -1157,6 +3256,136 @@ where
             };
             // NTP offset is updated separately via check_ntp_utc_tracking()
         }
+        if let Some(telemetry) = &self.telemetry {
+            if let Ok(status) = self.status_shared.read() {
+                telemetry.send(&status);
+            }
+        }
+    }
+}
+
+// ============================================================================
+// BUILDER
+// ============================================================================
+
+/// Builder for embedding the controller in another program
+///
+/// Supply your own `SystemClock` to discipline something other than the OS
+/// clock (e.g. an application media clock). Config and shared status are
+/// optional and default to `SystemConfig::default()` and a fresh status.
+///
+/// ```no_run
+/// # use dantesync::{NtpSource, PtpControllerBuilder, PtpNetwork, SystemClock};
+/// # fn run<N: PtpNetwork, S: NtpSource>(network: N, ntp: S) -> dantesync::error::Result<()> {
+/// struct MediaClock {
+///     rate: f64,
+/// }
+///
+/// impl SystemClock for MediaClock {
+///     fn adjust_frequency(&mut self, factor: f64) -> dantesync::error::Result<()> {
+///         self.rate = factor;
+///         Ok(())
+///     }
+///     fn step_clock(
+///         &mut self,
+///         _offset: std::time::Duration,
+///         _sign: i8,
+///     ) -> dantesync::error::Result<()> {
+///         Ok(())
+///     }
+/// }
+///
+/// let mut controller = PtpControllerBuilder::new()
+///     .clock(MediaClock { rate: 1.0 })
+///     .network(network)
+///     .ntp(ntp)
+///     .build()?;
+/// loop {
+///     controller.process_loop_iteration()?;
+/// }
+/// # }
+/// ```
+pub struct PtpControllerBuilder<C, N, S> {
+    clock: Option<C>,
+    network: Option<N>,
+    ntp: Option<S>,
+    config: SystemConfig,
+    status: Option<Arc<RwLock<SyncStatus>>>,
+}
+
+impl<C, N, S> Default for PtpControllerBuilder<C, N, S> {
+    fn default() -> Self {
+        PtpControllerBuilder {
+            clock: None,
+            network: None,
+            ntp: None,
+            config: SystemConfig::default(),
+            status: None,
+        }
+    }
+}
+
+impl<C, N, S> PtpControllerBuilder<C, N, S>
+where
+    C: SystemClock,
+    N: PtpNetwork,
+    S: NtpSource,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Clock that receives frequency adjustments and NTP steps (required)
+    pub fn clock(mut self, clock: C) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Source of timestamped PTP packets (required)
+    pub fn network(mut self, network: N) -> Self {
+        self.network = Some(network);
+        self
+    }
+
+    /// UTC offset source for NTP tracking (required; disable tracking via
+    /// `SystemConfig::ntp_tracking` if the clock has no UTC meaning)
+    pub fn ntp(mut self, ntp: S) -> Self {
+        self.ntp = Some(ntp);
+        self
+    }
+
+    pub fn config(mut self, config: SystemConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Share an existing status handle (e.g. one your UI already reads)
+    pub fn status(mut self, status: Arc<RwLock<SyncStatus>>) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Fails if a required dependency was not set
+    pub fn build(self) -> Result<PtpController<C, N, S>> {
+        let clock = self
+            .clock
+            .ok_or_else(|| DanteSyncError::Config("PtpControllerBuilder: clock not set".into()))?;
+        let network = self.network.ok_or_else(|| {
+            DanteSyncError::Config("PtpControllerBuilder: network not set".into())
+        })?;
+        let ntp = self.ntp.ok_or_else(|| {
+            DanteSyncError::Config("PtpControllerBuilder: ntp source not set".into())
+        })?;
+        let status = self
+            .status
+            .unwrap_or_else(|| Arc::new(RwLock::new(SyncStatus::default())));
+
+        let ntp_tracking = self.config.ntp_tracking;
+        let mut controller = PtpController::new(clock, network, ntp, status, self.config);
+        if !ntp_tracking {
+            controller.set_ntp_tracking(false);
+        }
+        Ok(controller)
     }
 }
 
@@ -1168,8 +3397,33 @@ where
 mod tests {
     use super::*;
     use crate::clock::MockSystemClock;
+    use crate::ntp::NtpQuality;
     use crate::traits::{MockNtpSource, MockPtpNetwork};
     use mockall::predicate::*;
+    use std::sync::Mutex;
+
+    /// PTPv1 Sync from `source` announcing grandmaster `gm`
+    fn make_v1_sync(seq: u16, source: [u8; 6], gm: [u8; 6]) -> Vec<u8> {
+        let mut buf = vec![0u8; 60];
+        buf[0] = 0x10;
+        buf[22..28].copy_from_slice(&source);
+        buf[30..32].copy_from_slice(&seq.to_be_bytes());
+        buf[49..55].copy_from_slice(&gm);
+        buf
+    }
+
+    /// PTPv1 Follow_Up from `source` for Sync `seq`, with preciseOriginTimestamp `t1_ns`
+    fn make_v1_followup(seq: u16, source: [u8; 6], t1_ns: u64) -> Vec<u8> {
+        let mut buf = vec![0u8; 60];
+        buf[0] = 0x10;
+        buf[22..28].copy_from_slice(&source);
+        buf[30..32].copy_from_slice(&seq.to_be_bytes());
+        buf[32] = 0x02;
+        buf[42..44].copy_from_slice(&seq.to_be_bytes());
+        buf[44..48].copy_from_slice(&((t1_ns / 1_000_000_000) as u32).to_be_bytes());
+        buf[48..52].copy_from_slice(&((t1_ns % 1_000_000_000) as u32).to_be_bytes());
+        buf
+    }
 
     #[test]
     fn test_ntp_sync_trigger() {
@@ -1190,20 +3444,266 @@ mod tests {
             .returning(|_, _| Ok(()));
 
         let status = Arc::new(RwLock::new(SyncStatus::default()));
-        let mut controller = PtpController::new(
-            mock_clock,
-            mock_net,
-            mock_ntp,
-            status,
-            SystemConfig::default(),
-        );
+        let mut config = SystemConfig::default();
+        config.filters.ntp_warmup_secs = 0.0; // Single-sample startup step
+        let mut controller = PtpController::new(mock_clock, mock_net, mock_ntp, status, config);
+        controller.run_ntp_sync(false);
+    }
+
+    /// Run a slew of `offset` (NTP sign `sign`) to completion, one simulated
+    /// second per loop pass. Returns every rate put on the clock (ppm),
+    /// starting from `base_ppm`, and the seconds the slew took.
+    fn slew_rates(
+        mut config: SystemConfig,
+        base_ppm: f64,
+        offset: Duration,
+        sign: i8,
+    ) -> (Vec<f64>, u32) {
+        let mut mock_clock = MockSystemClock::new();
+        let mock_net = MockPtpNetwork::new();
+        let mut mock_ntp = MockNtpSource::new();
+
+        mock_ntp
+            .expect_get_offset()
+            .times(1)
+            .returning(move || Ok((offset, sign)));
+        mock_clock.expect_step_clock().times(0);
+        let rates = Arc::new(Mutex::new(vec![base_ppm]));
+        let recorded = rates.clone();
+        mock_clock.expect_adjust_frequency().returning(move |f| {
+            recorded.lock().unwrap().push((f - 1.0) * 1e6);
+            Ok(())
+        });
+
+        let status = Arc::new(RwLock::new(SyncStatus::default()));
+        config.filters.ntp_warmup_secs = 0.0;
+        let mut controller = PtpController::new(mock_clock, mock_net, mock_ntp, status, config);
+        controller.applied_freq_ppm = base_ppm;
+        controller.drift_baseline_ppm = base_ppm;
         controller.run_ntp_sync(false);
+        let offset_us = offset.as_secs_f64() * 1e6;
+        assert!((controller.slew_remaining_us.abs() - offset_us).abs() < 1.0);
+        // Clock ahead (negative NTP offset) is slewed out by running slow
+        assert_eq!(controller.slew_remaining_us.signum(), -f64::from(sign));
+
+        let mut secs = 0;
+        while controller.slew_remaining_us != 0.0 || controller.slew_bias_ppm != 0.0 {
+            assert!(secs < 10_000, "slew never finished");
+            controller.slew_updated -= Duration::from_secs(1);
+            if let Some(ramped) = controller.slew_ramped.as_mut() {
+                *ramped -= SLEW_RAMP_INTERVAL;
+            }
+            controller.advance_slew();
+            secs += 1;
+        }
+        // The learned frequency stays underneath the slew bias
+        assert_eq!(controller.drift_baseline_ppm, base_ppm);
+        let rates = rates.lock().unwrap().clone();
+        (rates, secs)
+    }
+
+    /// Largest change between consecutive rates (ppm)
+    fn largest_rate_step(rates: &[f64]) -> f64 {
+        rates
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).abs())
+            .fold(0.0, f64::max)
     }
 
     #[test]
-    fn test_ptp_locking_flow() {
-        use byteorder::{BigEndian, WriteBytesExt};
+    fn test_backward_offset_slewed_when_backward_step_disallowed() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let config = SystemConfig {
+            allow_backward_step: false,
+            ..SystemConfig::default()
+        };
+        let (rates, secs) = slew_rates(config, 0.0, Duration::from_millis(100), -1);
+
+        // Runs the full 200ppm slow, then returns to the learned rate
+        assert!(rates.iter().any(|&r| (r + SLEW_PPM).abs() < 1e-6));
+        assert!(rates.last().unwrap().abs() < 1e-6);
+        // 100ms at 200ppm takes 500s, plus a little for the partial bias
+        // while ramping in and out
+        assert!((500..=520).contains(&secs), "slew took {}s", secs);
+        // Unlimited max_ppm_step_per_sample: the bias still ramps
+        assert!(largest_rate_step(&rates) <= SLEW_RAMP_PPM + 1e-6);
+    }
+
+    #[test]
+    fn test_backward_slew_ramps_within_step_limit() {
+        let config = SystemConfig {
+            allow_backward_step: false,
+            max_ppm_step_per_sample: 10.0,
+            ..SystemConfig::default()
+        };
+        let (rates, _) = slew_rates(config, 0.0, Duration::from_millis(100), -1);
+
+        assert!(rates.iter().any(|&r| (r + SLEW_PPM).abs() < 1e-6));
+        assert!(rates.last().unwrap().abs() < 1e-6);
+        let step = largest_rate_step(&rates);
+        assert!(step <= 10.0 + 1e-6, "rate stepped by {:.1}ppm", step);
+    }
+
+    #[test]
+    fn test_ntp_alignment_slew_runs_fast_when_behind() {
+        let config = SystemConfig {
+            ntp_alignment: NtpAlignment::Slew,
+            ..SystemConfig::default()
+        };
+        let (rates, secs) = slew_rates(config, 15.0, Duration::from_millis(100), 1);
+
+        // 200ppm fast on top of the learned +15ppm, then back to it
+        assert!(rates.iter().any(|&r| (r - (15.0 + SLEW_PPM)).abs() < 1e-6));
+        assert!((rates.last().unwrap() - 15.0).abs() < 1e-6);
+        assert!((500..=520).contains(&secs), "slew took {}s", secs);
+        // Starting and ending NTP alignment never steps the rate by 200ppm
+        assert!(largest_rate_step(&rates) <= SLEW_RAMP_PPM + 1e-6);
+    }
+
+    #[test]
+    fn test_ntp_warmup_steps_on_median() {
+        let _ = env_logger::builder().is_test(true).try_init();
+        let mut mock_clock = MockSystemClock::new();
+        let mock_net = MockPtpNetwork::new();
+        let mut mock_ntp = MockNtpSource::new();
+
+        // First response right after boot is badly delayed; median ignores it
+        let mut responses = vec![
+            Ok((Duration::from_millis(900), 1)),
+            Ok((Duration::from_millis(100), 1)),
+            Ok((Duration::from_millis(110), 1)),
+        ]
+        .into_iter();
+        mock_ntp
+            .expect_get_offset()
+            .times(3)
+            .returning(move || responses.next().unwrap());
+
+        mock_clock
+            .expect_step_clock()
+            .with(eq(Duration::from_millis(110)), eq(1))
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let status = Arc::new(RwLock::new(SyncStatus::default()));
+        let mut config = SystemConfig::default();
+        config.filters.ntp_warmup_secs = 1.0; // Samples at 0, 0.5, 1.0s
+        let mut controller = PtpController::new(mock_clock, mock_net, mock_ntp, status, config);
+        controller.run_ntp_sync(false);
+    }
+
+    #[test]
+    fn test_ptp_flood_discarded_beyond_rate_limit() {
+        let (mut controller, status) = create_nano_test_controller();
+        controller
+            .network
+            .expect_recv_packet()
+            .returning(|| Ok(Some((vec![0u8; 10], 10, SystemTime::now()))));
+        let limit = controller.ptp_rate_limit();
+        assert_eq!(limit, 320); // 20 x 16 packets/s at Dante's 125ms
+
+        // The first `limit` packets of the window are handled one per pass
+        for _ in 0..limit {
+            controller.process_loop_iteration().unwrap();
+        }
+        assert!(!controller.ptp_flooding);
+        assert_eq!(controller.ptp_flood_dropped, 0);
+
+        // Beyond it, each pass discards one plus a bounded drain
+        controller.process_loop_iteration().unwrap();
+        assert!(controller.ptp_flooding);
+        assert_eq!(
+            controller.ptp_flood_dropped,
+            1 + PTP_FLOOD_DRAIN_PER_ITERATION as u64
+        );
+        controller.update_shared_status();
+        assert_eq!(
+            status.read().unwrap().ptp_flood_dropped,
+            controller.ptp_flood_dropped
+        );
+
+        // A quiet window ends the flood
+        controller.ptp_rate_window = (SystemTime::now() - PTP_RATE_WINDOW, 10);
+        controller.process_loop_iteration().unwrap();
+        assert!(!controller.ptp_flooding);
+        assert_eq!(controller.ptp_rate_window.1, 1);
+    }
+
+    #[test]
+    fn test_normal_rate_backlog_never_discarded() {
+        let (mut controller, _) = create_nano_test_controller();
+        // Dante's Sync + Follow_Up every 125ms, read far faster than real time
+        // (a backlog after a stall, or a simulation)
+        let mut rx = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        controller.network.expect_recv_packet().returning(move || {
+            rx += Duration::from_micros(62_500);
+            Ok(Some((vec![0u8; 10], 10, rx)))
+        });
+
+        for _ in 0..5_000 {
+            controller.process_loop_iteration().unwrap();
+        }
+        assert!(!controller.ptp_flooding);
+        assert_eq!(controller.ptp_flood_dropped, 0);
+    }
+
+    #[test]
+    fn test_external_step_clears_samples() {
+        let (mut controller, status) = create_locked_controller();
+        controller
+            .network
+            .expect_recv_packet()
+            .returning(|| Ok(None));
+        controller.last_offset_us = Some(5.0);
+
+        // Normal passes: system and monotonic time advance together
+        controller.process_loop_iteration().unwrap();
+        controller.process_loop_iteration().unwrap();
+        assert_eq!(controller.sample_window.len(), 2);
+        assert!(controller.last_ntp_step.is_none());
+
+        // Another process stepped the clock 1s back since the last pass
+        let (wall, mono) = controller.wall_clock_ref.unwrap();
+        controller.wall_clock_ref = Some((wall + Duration::from_secs(1), mono));
+        controller.process_loop_iteration().unwrap();
+        assert!(controller.sample_window.is_empty());
+        assert!(controller.last_offset_us.is_none());
+        assert!(controller.in_step_grace());
+        assert!(status.read().unwrap().resettling);
+        assert!(controller.is_locked, "an external step is not an unlock");
+
+        // Off: ignored
+        controller.config.external_step_threshold_ms = 0;
+        controller.last_offset_us = Some(5.0);
+        let (wall, mono) = controller.wall_clock_ref.unwrap();
+        controller.wall_clock_ref = Some((wall + Duration::from_secs(1), mono));
+        controller.process_loop_iteration().unwrap();
+        assert_eq!(controller.last_offset_us, Some(5.0));
+    }
+
+    #[test]
+    fn test_step_reports_resettling_until_grace_ends() {
+        let (mut controller, status) = create_locked_controller();
+        controller
+            .network
+            .expect_recv_packet()
+            .returning(|| Ok(None));
+        assert!(!status.read().unwrap().resettling);
+
+        controller.after_clock_step();
+        assert!(status.read().unwrap().resettling);
+        // Still within the grace period
+        controller.process_loop_iteration().unwrap();
+        assert!(status.read().unwrap().resettling);
+        assert!(status.read().unwrap().is_locked, "a step is not an unlock");
+
+        controller.last_ntp_step = Instant::now().checked_sub(NTP_STEP_GRACE);
+        controller.process_loop_iteration().unwrap();
+        assert!(!status.read().unwrap().resettling);
+    }
 
+    #[test]
+    fn test_ptp_locking_flow() {
         let _ = env_logger::builder().is_test(true).try_init();
         let mut mock_clock = MockSystemClock::new();
         let mut mock_net = MockPtpNetwork::new();
@@ -1211,40 +3711,12 @@ mod tests {
 
         let gm_uuid = [0x01, 0x02, 0x03, 0x04, 0x05, 0x06];
 
-        let make_sync = move |seq: u16| -> Vec<u8> {
-            let mut buf = vec![0u8; 60];
-            buf[0] = 0x10;
-            buf[32] = 0x00;
-            buf[22..28].copy_from_slice(&gm_uuid);
-            let mut w = &mut buf[30..32];
-            w.write_u16::<BigEndian>(seq).unwrap();
-            buf[49..55].copy_from_slice(&gm_uuid);
-            buf
-        };
-
-        let make_followup = move |seq: u16, t1_ns: u64| -> Vec<u8> {
-            let mut buf = vec![0u8; 60];
-            buf[0] = 0x10;
-            buf[32] = 0x02;
-            buf[22..28].copy_from_slice(&gm_uuid);
-            let mut w = &mut buf[30..32];
-            w.write_u16::<BigEndian>(seq).unwrap();
-            let mut w = &mut buf[42..44];
-            w.write_u16::<BigEndian>(seq).unwrap();
-            let mut w = &mut buf[44..52];
-            let s = (t1_ns / 1_000_000_000) as u32;
-            let n = (t1_ns % 1_000_000_000) as u32;
-            w.write_u32::<BigEndian>(s).unwrap();
-            w.write_u32::<BigEndian>(n).unwrap();
-            buf
-        };
-
         for i in 0..8 {
             let t1 = 1_000_000_000 + i as u64 * 1_000_000_000;
             let t2 = SystemTime::UNIX_EPOCH + Duration::from_nanos(t1 + 1000);
 
-            let sync_pkt = make_sync(i as u16);
-            let follow_pkt = make_followup(i as u16, t1);
+            let sync_pkt = make_v1_sync(i as u16, gm_uuid, gm_uuid);
+            let follow_pkt = make_v1_followup(i as u16, gm_uuid, t1);
 
             mock_net
                 .expect_recv_packet()
@@ -1257,25 +3729,514 @@ mod tests {
                 .returning(move || Ok(Some((follow_pkt.clone(), 60, t2))));
         }
 
-        mock_net.expect_recv_packet().returning(|| Ok(None));
-        mock_clock
+        mock_net.expect_recv_packet().returning(|| Ok(None));
+        mock_clock
+            .expect_adjust_frequency()
+            .times(2)
+            .returning(|_| Ok(()));
+
+        let status = Arc::new(RwLock::new(SyncStatus::default()));
+        let mut config = SystemConfig::default();
+        config.filters.sample_window_size = 4;
+        config.filters.calibration_samples = 0;
+        config.filters.warmup_secs = 0.0;
+
+        let mut controller = PtpController::new(mock_clock, mock_net, mock_ntp, status, config);
+
+        for _ in 0..16 {
+            let _ = controller.process_loop_iteration();
+        }
+
+        assert!(controller.get_status_shared().read().unwrap().settled);
+    }
+
+    #[test]
+    fn test_sync_source_and_interval_in_status() {
+        let (mut controller, status) = create_nano_test_controller();
+        let sender = [0x00, 0x1D, 0xC1, 0x51, 0xD0, 0xD9];
+        let gm = [0x00, 0x1D, 0xC1, 0x00, 0x00, 0x01];
+
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        for i in 0..20u64 {
+            let buf = make_v1_sync(i as u16, sender, gm);
+            let header = PtpV1Header::parse(&buf).unwrap();
+            // 250ms Sync interval, plus one 30s outage that must be ignored
+            let t2 = start + Duration::from_millis(250 * i + if i >= 10 { 30_000 } else { 0 });
+            controller.handle_sync_message(&header, &buf, t2);
+        }
+        controller.update_shared_status();
+
+        let s = status.read().unwrap();
+        assert_eq!(s.gm_uuid, Some(gm));
+        assert_eq!(s.sync_source_uuid, Some(sender));
+        assert_eq!(s.observed_sync_interval_ms, 250);
+    }
+
+    #[test]
+    fn test_source_and_gm_switch_debounced() {
+        let (mut controller, _) = create_nano_test_controller();
+        let needed = controller.config.source_switch_syncs as u16;
+        let source_a = [0x00, 0x1D, 0xC1, 0x51, 0xD0, 0xD9];
+        let source_b = [0x00, 0x1D, 0xC1, 0x1A, 0x44, 0x30];
+        let gm_1 = [0x00, 0x1D, 0xC1, 0x00, 0x00, 0x01];
+        let gm_2 = [0x00, 0x1D, 0xC1, 0x00, 0x00, 0x02];
+        let now = SystemTime::now();
+        let mut seq = 0u16;
+        let mut send = |controller: &mut PtpController<_, _, _>, source: [u8; 6], gm: [u8; 6]| {
+            let buf = make_v1_sync(seq, source, gm);
+            let header = PtpV1Header::parse(&buf).unwrap();
+            controller.handle_sync_message(&header, &buf, now);
+            seq += 1;
+            controller.pending_syncs.contains_key(&(seq - 1))
+        };
+
+        assert!(send(&mut controller, source_a, gm_1));
+
+        // A single stray packet from another source or GM is ignored
+        assert!(!send(&mut controller, source_b, gm_2));
+        assert!(send(&mut controller, source_a, gm_1));
+        assert!(!send(&mut controller, source_a, gm_2));
+        assert!(send(&mut controller, source_a, gm_1));
+        assert_eq!(controller.current_sync_source, Some(source_a));
+        assert_eq!(controller.current_gm_uuid, Some(gm_1));
+
+        // Interrupted runs start over
+        for _ in 0..needed - 1 {
+            assert!(!send(&mut controller, source_b, gm_2));
+        }
+        assert!(send(&mut controller, source_a, gm_1));
+        assert_eq!(controller.current_sync_source, Some(source_a));
+
+        // A source that persists is followed, together with its GM
+        for _ in 0..needed - 1 {
+            assert!(!send(&mut controller, source_b, gm_2));
+        }
+        assert!(send(&mut controller, source_b, gm_2));
+        assert_eq!(controller.current_sync_source, Some(source_b));
+        assert_eq!(controller.current_gm_uuid, Some(gm_2));
+
+        // 0 switches immediately
+        controller.config.source_switch_syncs = 0;
+        assert!(send(&mut controller, source_a, gm_1));
+        assert_eq!(controller.current_sync_source, Some(source_a));
+        assert_eq!(controller.current_gm_uuid, Some(gm_1));
+    }
+
+    #[test]
+    fn test_unexpected_grandmaster_ignored_and_flagged() {
+        let (mut controller, status) = create_locked_controller();
+        let source = [0x00, 0x1D, 0xC1, 0x51, 0xD0, 0xD9];
+        let expected = [0x00, 0x1D, 0xC1, 0x00, 0x00, 0x01];
+        let rogue = [0x00, 0x11, 0x22, 0x33, 0x44, 0x55];
+        controller.config.expected_gm_uuid = Some(format_mac(&expected));
+        controller.config.source_switch_syncs = 0;
+        controller.current_gm_uuid = Some(expected);
+        let now = SystemTime::now();
+        let mut seq = 100u16;
+        let mut send = |controller: &mut PtpController<_, _, _>, gm: [u8; 6]| {
+            let buf = make_v1_sync(seq, source, gm);
+            let header = PtpV1Header::parse(&buf).unwrap();
+            controller.handle_sync_message(&header, &buf, now);
+            seq += 1;
+            controller.pending_syncs.contains_key(&(seq - 1))
+        };
+
+        assert!(send(&mut controller, expected));
+        assert!(!status.read().unwrap().gm_mismatch);
+
+        // A rogue master takes over: no samples, lock dropped, alarm raised
+        assert!(!send(&mut controller, rogue));
+        assert!(!send(&mut controller, rogue));
+        assert!(!controller.is_locked);
+        assert_eq!(controller.unlock_count, 1);
+        {
+            let s = status.read().unwrap();
+            assert!(s.gm_mismatch);
+            assert_eq!(s.gm_uuid, Some(rogue));
+        }
+
+        // The expected master returns and is followed again
+        assert!(send(&mut controller, expected));
+        assert!(!status.read().unwrap().gm_mismatch);
+
+        // Unset = any master
+        controller.config.expected_gm_uuid = None;
+        assert!(send(&mut controller, rogue));
+    }
+
+    #[test]
+    fn test_master_epoch_change_alarm() {
+        let (mut controller, status) = create_nano_test_controller();
+        let sec = 1_000_000_000i64;
+        let t2 = 1_700_000_000 * sec;
+        let uptime = 3_600 * sec;
+
+        // Drift between pairs (even at the servo limit) is not an epoch change
+        controller.check_epoch_offset(uptime, t2);
+        controller.check_epoch_offset(uptime + sec, t2 + sec + 400_000);
+        assert!(controller.epoch_changed_at.is_none());
+
+        // Grandmaster rebooted: its uptime restarts near zero
+        controller.check_epoch_offset(5 * sec, t2 + 2 * sec);
+        assert!(controller.epoch_changed_at.is_some());
+        assert_eq!(controller.initial_epoch_offset_ns, t2 + 2 * sec - 5 * sec);
+        {
+            let s = status.read().unwrap();
+            assert!(s.master_epoch_changed);
+            assert_eq!(s.epoch_shift_ns, 3_600 * sec - 3 * sec - 400_000);
+        }
+
+        // Our own clock step moves T2 without a master change
+        controller.epoch_changed_at = None;
+        controller.after_clock_step();
+        controller.check_epoch_offset(6 * sec, t2 + 60 * sec);
+        assert!(controller.epoch_changed_at.is_none());
+
+        // Disabled
+        controller.config.epoch_change_alarm_ms = 0;
+        controller.check_epoch_offset(uptime, t2 + 61 * sec);
+        assert!(controller.epoch_changed_at.is_none());
+    }
+
+    #[test]
+    fn test_impossible_master_jumps_rejected() {
+        let (mut controller, status) = create_nano_test_controller();
+        let now = SystemTime::now();
+        let sec = 1_000_000_000i64;
+        controller.prev_t1_ns = 10 * sec;
+        controller.prev_t2_ns = 1;
+
+        // Follow_Up time runs backward: dropped before the sample window
+        controller.process_sync_pair(9 * sec, [0; 6], now, now);
+        assert_eq!(controller.rejected_sync_pairs, 1);
+        assert_eq!(controller.prev_t1_ns, 10 * sec);
+        assert!(controller.sample_window.is_empty());
+
+        // The next sane pair is accepted
+        controller.process_sync_pair(10 * sec + 125_000_000, [0; 6], now, now);
+        assert_eq!(controller.rejected_sync_pairs, 1);
+        assert_eq!(controller.prev_t1_ns, 10 * sec + 125_000_000);
+
+        // A master that keeps reporting the new timeline (reboot) is followed
+        // after MAX_CONSECUTIVE_REJECTS pairs
+        let year = 365 * 86_400 * sec;
+        for i in 0..MAX_CONSECUTIVE_REJECTS as i64 {
+            controller.process_sync_pair(year + i * 125_000_000, [0; 6], now, now);
+        }
+        assert_eq!(
+            controller.rejected_sync_pairs,
+            1 + MAX_CONSECUTIVE_REJECTS as u64 - 1
+        );
+        assert_eq!(
+            controller.prev_t1_ns,
+            year + (MAX_CONSECUTIVE_REJECTS as i64 - 1) * 125_000_000
+        );
+        assert_eq!(
+            status.read().unwrap().rejected_sync_pairs,
+            controller.rejected_sync_pairs
+        );
+    }
+
+    #[test]
+    fn test_sync_rate_adaptation() {
+        let (mut controller, _) = create_nano_test_controller();
+        controller.config.filters.min_delta_ns = 0;
+        assert_eq!(controller.effective_min_delta_ns(), DEFAULT_MIN_T1_DELTA_NS);
+        assert_eq!(controller.effective_window_size(), 4);
+
+        // 32Hz master: 31.25ms Syncs would all fall under the 100ms default
+        controller.sync_interval_ms = 31.25;
+        controller.prev_t1_ns = 1_000_000_000;
+        assert_eq!(controller.effective_min_delta_ns(), 25_000_000);
+        assert!(controller.should_add_sample(1_031_250_000));
+        assert_eq!(controller.effective_window_size(), 16);
+        assert!((controller.interval_scaled_alpha(0.3) - 0.3).abs() < 1e-12);
+
+        // 1Hz master: window and min_delta unchanged, alpha keeps its time constant
+        controller.sync_interval_ms = 1000.0;
+        assert_eq!(controller.effective_min_delta_ns(), DEFAULT_MIN_T1_DELTA_NS);
+        assert_eq!(controller.effective_window_size(), 4);
+        let alpha = controller.interval_scaled_alpha(0.1);
+        assert!((alpha - (1.0 - 0.9f64.powi(8))).abs() < 1e-12);
+
+        // Config hint overrides the measurement
+        controller.config.filters.sync_interval_hint_ms = 125;
+        assert!((controller.interval_scaled_alpha(0.1) - 0.1).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_one_hz_sync_stream_locks() {
+        let (mut controller, status) = create_nano_test_controller();
+        controller
+            .clock
+            .expect_adjust_frequency()
+            .returning(|_| Ok(()));
+        let gm = [0x00, 0x1D, 0xC1, 0x00, 0x00, 0x01];
+
+        // 1Hz Syncs with a steady 1µs offset and ±200ns of jitter
+        let jitter_ns = [0u64, 200, 0, 400];
+        for i in 0..40u64 {
+            let seq = i as u16;
+            let t1_ns = (1_000 + i) * 1_000_000_000;
+            let t2 = SystemTime::UNIX_EPOCH
+                + Duration::from_nanos(t1_ns + 1_000 + jitter_ns[(i % 4) as usize]);
+
+            let sync = make_v1_sync(seq, gm, gm);
+            let header = PtpV1Header::parse(&sync).unwrap();
+            controller.handle_sync_message(&header, &sync, t2);
+
+            let followup = make_v1_followup(seq, gm, t1_ns);
+            let header = PtpV1Header::parse(&followup).unwrap();
+            controller.handle_followup_message(&header, &followup, t2);
+
+            // Servo measures dt with Instant - pace each window past its 0.1s floor
+            if i % 4 == 3 {
+                std::thread::sleep(Duration::from_millis(150));
+            }
+        }
+
+        assert!((controller.sync_interval_ms - 1000.0).abs() < 1.0);
+        assert!(controller.is_locked, "1Hz stream should still lock");
+        assert!(status.read().unwrap().is_locked);
+    }
+
+    #[test]
+    fn test_pending_syncs_survive_sequence_wraparound() {
+        let (mut controller, _) = create_nano_test_controller();
+        controller
+            .clock
+            .expect_adjust_frequency()
+            .returning(|_| Ok(()));
+        let gm = [0x00, 0x1D, 0xC1, 0x00, 0x00, 0x01];
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let mut t1_secs = 1_000u32;
+
+        let send_sync = |controller: &mut PtpController<_, _, _>, seq: u16, t2: SystemTime| {
+            let sync = make_v1_sync(seq, gm, gm);
+            let header = PtpV1Header::parse(&sync).unwrap();
+            controller.handle_sync_message(&header, &sync, t2);
+        };
+        // Returns whether the Follow_Up was paired (prev_t1_ns advanced)
+        let mut send_followup =
+            |controller: &mut PtpController<_, _, _>, seq: u16, rx: SystemTime| {
+                t1_secs += 1;
+                let followup = make_v1_followup(seq, gm, t1_secs as u64 * 1_000_000_000);
+                let header = PtpV1Header::parse(&followup).unwrap();
+                controller.handle_followup_message(&header, &followup, rx);
+                controller.prev_t1_ns == t1_secs as i64 * 1_000_000_000
+            };
+
+        // Pairs across the 65535 -> 0 wrap
+        for (i, seq) in [65_534u16, 65_535, 0, 1].into_iter().enumerate() {
+            let t2 = start + Duration::from_millis(125 * i as u64);
+            send_sync(&mut controller, seq, t2);
+            assert!(send_followup(&mut controller, seq, t2), "seq {}", seq);
+        }
+
+        // A Follow_Up for a Sync several Syncs back is not paired
+        let t2 = start + Duration::from_secs(1);
+        send_sync(&mut controller, 7, t2);
+        for seq in 8..8 + PENDING_SYNC_MAX_AGE_SYNCS as u16 {
+            send_sync(&mut controller, seq, t2);
+        }
+        assert!(controller.pending_syncs.len() <= PENDING_SYNC_MAX_AGE_SYNCS as usize);
+        assert!(!send_followup(&mut controller, 7, t2));
+
+        // A stale Follow_Up arriving long after a reused id's Sync is dropped
+        send_sync(&mut controller, 2, t2);
+        assert!(!send_followup(
+            &mut controller,
+            2,
+            t2 + Duration::from_secs(10)
+        ));
+        assert!(!controller.pending_syncs.contains_key(&2));
+    }
+
+    #[test]
+    fn test_unmatched_syncs_counted() {
+        let (mut controller, status) = create_nano_test_controller();
+        controller
+            .clock
+            .expect_adjust_frequency()
+            .returning(|_| Ok(()));
+        controller.config.filters.max_pending_syncs = 2;
+        controller.config.filters.pending_sync_timeout_ms = 1_000;
+        let gm = [0x00, 0x1D, 0xC1, 0x00, 0x00, 0x01];
+        let t2 = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let mut t1_secs = 1_000u32;
+
+        let send_sync = |controller: &mut PtpController<_, _, _>, seq: u16| {
+            let sync = make_v1_sync(seq, gm, gm);
+            let header = PtpV1Header::parse(&sync).unwrap();
+            controller.handle_sync_message(&header, &sync, t2);
+        };
+        let mut send_followup =
+            |controller: &mut PtpController<_, _, _>, seq: u16, rx: SystemTime| {
+                t1_secs += 1;
+                let followup = make_v1_followup(seq, gm, t1_secs as u64 * 1_000_000_000);
+                let header = PtpV1Header::parse(&followup).unwrap();
+                controller.handle_followup_message(&header, &followup, rx);
+            };
+
+        for seq in 0..4 {
+            send_sync(&mut controller, seq);
+            send_followup(&mut controller, seq, t2);
+        }
+        assert_eq!(controller.unmatched_syncs, 0);
+        assert_eq!(controller.unmatched_sync_ratio, 0.0);
+
+        // Follow_Ups lost: beyond max_pending_syncs each Sync pushes one out
+        for seq in 10..15 {
+            send_sync(&mut controller, seq);
+        }
+        assert_eq!(controller.unmatched_syncs, 3);
+        assert_eq!(controller.pending_syncs.len(), 2);
+
+        // A Follow_Up later than pending_sync_timeout_ms leaves its Sync unmatched
+        send_followup(&mut controller, 14, t2 + Duration::from_secs(2));
+        assert_eq!(controller.unmatched_syncs, 4);
+
+        controller.update_shared_status();
+        {
+            let s = status.read().unwrap();
+            assert_eq!(s.unmatched_syncs_total, 4);
+            assert!(s.unmatched_sync_rate > 0.0 && s.unmatched_sync_rate < 100.0);
+        }
+
+        // The total restarts with the other counters; the rate is rolling
+        controller.handle_command(IpcCommand::ResetCounters);
+        let s = status.read().unwrap();
+        assert_eq!(s.unmatched_syncs_total, 0);
+        assert!(s.unmatched_sync_rate > 0.0);
+    }
+
+    #[test]
+    fn test_flagged_v2_sync_ignored() {
+        let (mut controller, status) = create_nano_test_controller();
+        let v2_packet = |msg_type: u8, flags: u8| {
+            let mut packet = vec![0u8; 44];
+            packet[0] = msg_type;
+            packet[1] = 0x02;
+            packet[6] = flags;
+            packet[20..28].copy_from_slice(&[0x00, 0x1D, 0xC1, 0xFF, 0xFE, 0x12, 0x34, 0x56]);
+            packet
+        };
+
+        // Authoritative traffic and PTPv1 go on to the normal path
+        assert!(!controller.ignore_by_flag(&v2_packet(0x0, 0x02)));
+        let mut v1 = vec![0u8; 60];
+        v1[1] = 0x01;
+        v1[6] = 0x01;
+        assert!(!controller.ignore_by_flag(&v1));
+        // Only Sync/Follow_Up are filtered (Announce 0xB)
+        assert!(!controller.ignore_by_flag(&v2_packet(0xB, 0x01)));
+
+        assert!(controller.ignore_by_flag(&v2_packet(0x0, 0x03)));
+        assert!(controller.ignore_by_flag(&v2_packet(0x8, 0x03)));
+        assert!(controller.ignore_by_flag(&v2_packet(0x0, 0x06)));
+        assert_eq!(controller.flag_ignored_alternate_master, 2);
+        assert_eq!(controller.flag_ignored_unicast, 1);
+
+        controller.update_shared_status();
+        {
+            let s = status.read().unwrap();
+            assert_eq!(s.flag_ignored_alternate_master, 2);
+            assert_eq!(s.flag_ignored_unicast, 1);
+        }
+
+        controller.handle_command(IpcCommand::ResetCounters);
+        let s = status.read().unwrap();
+        assert_eq!(s.flag_ignored_alternate_master, 0);
+        assert_eq!(s.flag_ignored_unicast, 0);
+    }
+
+    #[test]
+    fn test_followup_before_sync_is_paired() {
+        let (mut controller, _) = create_nano_test_controller();
+        controller
+            .clock
             .expect_adjust_frequency()
-            .times(2)
             .returning(|_| Ok(()));
+        let gm = [0x00, 0x1D, 0xC1, 0x00, 0x00, 0x01];
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
 
-        let status = Arc::new(RwLock::new(SyncStatus::default()));
-        let mut config = SystemConfig::default();
-        config.filters.sample_window_size = 4;
-        config.filters.calibration_samples = 0;
-        config.filters.warmup_secs = 0.0;
+        let send_sync = |controller: &mut PtpController<_, _, _>, seq: u16, t2: SystemTime| {
+            let sync = make_v1_sync(seq, gm, gm);
+            let header = PtpV1Header::parse(&sync).unwrap();
+            controller.handle_sync_message(&header, &sync, t2);
+        };
+        let send_followup =
+            |controller: &mut PtpController<_, _, _>, seq: u16, t1_secs: u32, rx: SystemTime| {
+                let followup = make_v1_followup(seq, gm, t1_secs as u64 * 1_000_000_000);
+                let header = PtpV1Header::parse(&followup).unwrap();
+                controller.handle_followup_message(&header, &followup, rx);
+            };
 
-        let mut controller = PtpController::new(mock_clock, mock_net, mock_ntp, status, config);
+        // Follow_Up overtakes its Sync by a few ms: paired when the Sync lands
+        send_followup(&mut controller, 10, 2_000, start);
+        assert_eq!(controller.prev_t1_ns, 0);
+        send_sync(&mut controller, 10, start + Duration::from_millis(3));
+        assert_eq!(controller.prev_t1_ns, 2_000 * 1_000_000_000);
+        assert!(controller.orphan_followups.is_empty());
+        assert!(!controller.pending_syncs.contains_key(&10));
+
+        // In-order pairs still work afterwards
+        let t2 = start + Duration::from_millis(125);
+        send_sync(&mut controller, 11, t2);
+        send_followup(&mut controller, 11, 2_001, t2);
+        assert_eq!(controller.prev_t1_ns, 2_001 * 1_000_000_000);
+
+        // A true orphan is not paired with a Sync arriving much later
+        send_followup(&mut controller, 12, 2_002, t2);
+        send_sync(&mut controller, 12, t2 + Duration::from_secs(5));
+        assert_eq!(controller.prev_t1_ns, 2_001 * 1_000_000_000);
+        assert!(controller.pending_syncs.contains_key(&12));
+
+        // Orphans are bounded
+        for seq in 100..100 + 2 * MAX_ORPHAN_FOLLOWUPS as u16 {
+            send_followup(&mut controller, seq, 3_000, t2 + Duration::from_secs(5));
+        }
+        assert_eq!(controller.orphan_followups.len(), MAX_ORPHAN_FOLLOWUPS);
+    }
 
-        for _ in 0..16 {
-            let _ = controller.process_loop_iteration();
+    #[test]
+    fn test_malformed_packets_do_not_panic() {
+        let mock_clock = MockSystemClock::new();
+        let mut mock_net = MockPtpNetwork::new();
+        let mock_ntp = MockNtpSource::new();
+
+        // Truncated Sync/FollowUp headers, and a reported size larger than the buffer
+        let mut packets = Vec::new();
+        for control in [0u8, 2] {
+            for len in [0usize, 1, 35, 36, 37, 50] {
+                let mut buf = vec![0u8; len.max(33)];
+                buf[0] = 0x10;
+                buf[32] = control;
+                buf.truncate(len);
+                packets.push((buf, len));
+            }
+            let mut buf = vec![0u8; 40];
+            buf[32] = control;
+            packets.push((buf, 1500));
         }
+        let mut packets = packets.into_iter();
+        mock_net.expect_recv_packet().returning(move || {
+            Ok(packets
+                .next()
+                .map(|(buf, size)| (buf, size, SystemTime::now())))
+        });
 
-        assert!(controller.get_status_shared().read().unwrap().settled);
+        let status = Arc::new(RwLock::new(SyncStatus::default()));
+        let mut controller = PtpController::new(
+            mock_clock,
+            mock_net,
+            mock_ntp,
+            status,
+            SystemConfig::default(),
+        );
+        for _ in 0..20 {
+            controller.process_loop_iteration().unwrap();
+        }
     }
 
     // ========================================================================
@@ -1302,6 +4263,48 @@ mod tests {
         (controller, status)
     }
 
+    #[test]
+    fn test_progress_needs_packets_or_offline_polls() {
+        let (mut controller, _) = create_nano_test_controller();
+        controller
+            .network
+            .expect_recv_packet()
+            .returning(|| Ok(None));
+
+        // Ok passes that receive nothing while PTP counts as online: stalled
+        for _ in 0..100 {
+            assert!(controller.process_loop_iteration().is_ok());
+        }
+        assert_eq!(controller.progress(), 0);
+
+        // PTP offline: a quiet network is NTP-only operation, not a stall
+        controller.last_ptp_packet = Instant::now() - Duration::from_secs(PTP_TIMEOUT_SECS + 1);
+        controller.idle_progress_at = Instant::now() - IDLE_PROGRESS_INTERVAL;
+        controller.process_loop_iteration().unwrap();
+        assert_eq!(controller.progress(), 1);
+        // ...counted once per IDLE_PROGRESS_INTERVAL, not per pass
+        controller.process_loop_iteration().unwrap();
+        assert_eq!(controller.progress(), 1);
+    }
+
+    #[test]
+    fn test_progress_not_advanced_by_receive_errors() {
+        let (mut controller, _) = create_nano_test_controller();
+        controller.network.expect_recv_packet().returning(|| {
+            Err(DanteSyncError::Io(std::io::Error::new(
+                std::io::ErrorKind::BrokenPipe,
+                "stuck handle",
+            )))
+        });
+        controller.last_ptp_packet = Instant::now() - Duration::from_secs(PTP_TIMEOUT_SECS + 1);
+        controller.idle_progress_at = Instant::now() - IDLE_PROGRESS_INTERVAL;
+
+        for _ in 0..10 {
+            assert!(controller.process_loop_iteration().is_ok());
+        }
+        assert_eq!(controller.progress(), 0);
+    }
+
     #[test]
     fn test_nano_mode_requires_lock_first() {
         let (controller, _) = create_nano_test_controller();
@@ -1499,6 +4502,104 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_locked_duration_tracking() {
+        let (mut controller, status) = create_nano_test_controller();
+        assert_eq!(controller.locked_duration_secs(), 0);
+
+        // Simulate lock acquired 2 hours ago
+        controller.is_locked = true;
+        controller.locked_since = Some(Instant::now() - Duration::from_secs(7200));
+        controller.unlock_count = 3;
+        controller.update_shared_status();
+
+        let s = status.read().unwrap();
+        assert!(s.locked_duration_secs >= 7200);
+        assert_eq!(s.unlock_count, 3);
+    }
+
+    #[test]
+    fn test_unlock_increments_count_and_clears_locked_since() {
+        let (mut controller, _) = create_nano_test_controller();
+        controller.is_locked = true;
+        controller.lock_stable_count = 1;
+        controller.locked_since = Some(Instant::now());
+        controller.last_offset_us = Some(0.0);
+        controller.last_offset_time = Some(Instant::now() - Duration::from_secs(1));
+        controller.smoothed_rate_ppm = 100.0;
+        controller
+            .clock
+            .expect_adjust_frequency()
+            .returning(|_| Ok(()));
+
+        // Large drift: rate unstable → lock_stable_count drops to 0 → unlock
+        controller.apply_self_tuning_servo(100.0);
+
+        assert!(!controller.is_locked);
+        assert!(controller.locked_since.is_none());
+        assert_eq!(controller.unlock_count, 1);
+        assert_eq!(controller.locked_duration_secs(), 0);
+    }
+
+    #[test]
+    fn test_time_to_lock_recorded_on_first_lock_only() {
+        let (mut controller, status) = create_nano_test_controller();
+        controller
+            .clock
+            .expect_adjust_frequency()
+            .returning(|_| Ok(()));
+        assert!(controller.process_warmup());
+        assert!(controller.settle_start.is_some());
+        assert_eq!(controller.time_to_lock(), None);
+
+        // Warmup finished 47s ago; one more stable sample completes the lock
+        let lock_once = |c: &mut PtpController<_, _, _>| {
+            c.lock_stable_count = LOCK_STABLE_COUNT - 1;
+            c.last_offset_us = Some(0.0);
+            c.last_offset_time = Some(Instant::now() - Duration::from_secs(1));
+            c.apply_self_tuning_servo(0.0);
+            assert!(c.is_locked);
+        };
+        controller.settle_start = Some(Instant::now() - Duration::from_secs(47));
+        lock_once(&mut controller);
+        assert_eq!(controller.time_to_lock().map(|d| d.as_secs()), Some(47));
+        assert_eq!(status.read().unwrap().time_to_lock_secs, Some(47));
+        assert_eq!(status.read().unwrap().time_to_nano_secs, None);
+
+        // A later relock keeps the first settle time
+        controller.is_locked = false;
+        controller.settle_start = Some(Instant::now() - Duration::from_secs(300));
+        lock_once(&mut controller);
+        assert_eq!(controller.time_to_lock().map(|d| d.as_secs()), Some(47));
+
+        // A servo reset starts the measurement over
+        controller.handle_command(IpcCommand::ResetServo);
+        assert_eq!(controller.time_to_lock(), None);
+        assert_eq!(status.read().unwrap().time_to_lock_secs, None);
+    }
+
+    #[test]
+    fn test_configurable_lock_threshold() {
+        // Drift of ~3µs/s: stable with the default 5µs/s window, not with 2µs/s
+        let run = |lock_rate: f64, unlock_rate: f64| -> bool {
+            let (mut controller, _) = create_nano_test_controller();
+            controller.config.lock_rate_us_s = lock_rate;
+            controller.config.unlock_rate_us_s = unlock_rate;
+            controller.last_offset_us = Some(0.0);
+            controller.last_offset_time = Some(Instant::now() - Duration::from_secs(1));
+            controller.smoothed_rate_ppm = 3.0;
+            controller
+                .clock
+                .expect_adjust_frequency()
+                .returning(|_| Ok(()));
+            controller.apply_self_tuning_servo(3.0);
+            controller.in_production_mode && controller.lock_stable_count == 1
+        };
+
+        assert!(run(5.0, 20.0), "3µs/s should count as stable at 5µs/s");
+        assert!(!run(2.0, 8.0), "3µs/s should not count as stable at 2µs/s");
+    }
+
     #[test]
     fn test_nano_deadband_constant() {
         // Verify deadband is configured correctly
@@ -1547,6 +4648,7 @@ mod tests {
             PendingSync {
                 rx_time_sys: SystemTime::now(),
                 source_uuid: [0x00, 0x1D, 0xC1, 0x51, 0xD0, 0xD9],
+                arrival: 0,
             },
         );
         controller.sample_window.push(1000);
@@ -1668,41 +4770,529 @@ mod tests {
         controller.prev_t2_ns = 0;
         // Key: is_locked and in_production_mode are NOT reset
 
-        assert!(
-            controller.is_locked,
-            "Soft reset should NOT change lock state"
+        assert!(
+            controller.is_locked,
+            "Soft reset should NOT change lock state"
+        );
+        assert!(
+            controller.in_production_mode,
+            "Soft reset should NOT change production mode"
+        );
+    }
+
+    #[test]
+    fn test_sync_source_change_in_nano_mode_stays_nano() {
+        let (mut controller, _) = create_locked_controller();
+
+        // Put in NANO mode
+        controller.in_nano_mode = true;
+        controller.nano_sustain_count = NANO_SUSTAIN_COUNT;
+
+        // Simulate soft reset
+        let new_source = [0x00, 0x1D, 0xC1, 0x1A, 0x44, 0x30];
+        controller.current_sync_source = Some(new_source);
+        controller.pending_syncs.clear();
+        controller.sample_window.clear();
+        controller.prev_t1_ns = 0;
+        controller.prev_t2_ns = 0;
+        // Soft reset does NOT touch nano mode state
+
+        assert!(
+            controller.in_nano_mode,
+            "Soft reset should NOT exit NANO mode"
+        );
+        assert_eq!(
+            controller.nano_sustain_count, NANO_SUSTAIN_COUNT,
+            "Soft reset should NOT reset nano_sustain_count"
+        );
+    }
+
+    /// Locked at 35ppm, hand over to a new sync source whose offset baseline
+    /// settles over the first two windows (50µs/s apparent drift). Returns
+    /// the largest frequency excursion from 35ppm in the first four windows.
+    fn run_source_handover(blend_windows: u32) -> f64 {
+        let (mut controller, _) = create_locked_controller();
+        controller.drift_baseline_ppm = 35.0;
+        controller.config.source_switch_syncs = 0;
+        controller.config.handover_blend_windows = blend_windows;
+        controller
+            .clock
+            .expect_adjust_frequency()
+            .returning(|_| Ok(()));
+
+        let buf = make_v1_sync(
+            0,
+            [0x00, 0x1D, 0xC1, 0x1A, 0x44, 0x30],
+            [0x00, 0x00, 0x00, 0x00, 0x01, 0x00],
+        );
+        let header = PtpV1Header::parse(&buf).unwrap();
+        controller.handle_sync_message(&header, &buf, SystemTime::now());
+        assert_eq!(
+            controller.handover_windows_left, blend_windows,
+            "handover should arm the blend"
+        );
+
+        let mut excursion = 0.0f64;
+        for offset_us in [0.0, 50.0, 100.0, 100.0] {
+            controller.last_offset_time = Some(Instant::now() - Duration::from_secs(1));
+            controller.apply_self_tuning_servo(offset_us);
+            excursion = excursion.max((controller.applied_freq_ppm - 35.0).abs());
+        }
+        assert_eq!(
+            controller.handover_windows_left,
+            blend_windows.saturating_sub(4)
+        );
+        excursion
+    }
+
+    #[test]
+    fn test_handover_blend_limits_frequency_excursion() {
+        let abrupt = run_source_handover(0);
+        let blended = run_source_handover(10);
+        assert!(
+            abrupt > 1.0,
+            "expected the new baseline to move the frequency, got {:.2}ppm",
+            abrupt
+        );
+        assert!(
+            blended < abrupt * 0.5,
+            "blended handover moved the frequency {:.2}ppm (abrupt {:.2}ppm)",
+            blended,
+            abrupt
+        );
+    }
+
+    #[test]
+    fn test_handover_weight_ramps_to_full_gain() {
+        let (mut controller, _) = create_nano_test_controller();
+        assert_eq!(controller.handover_weight(), 1.0);
+
+        controller.config.handover_blend_windows = 3;
+        controller.handover_windows_left = 3;
+        let weights: Vec<f64> = (0..5).map(|_| controller.handover_weight()).collect();
+        assert_eq!(weights, [0.25, 0.5, 0.75, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_project_offset_200ppm() {
+        // Sync received with a 50µs offset, FollowUp processed 10ms later at 200ppm
+        let naive_ns = 50_000;
+        let projected_ns = project_offset_ns(naive_ns, 10_000_000, 200.0);
+        assert_eq!(
+            projected_ns - naive_ns,
+            2_000,
+            "10ms at 200ppm should move the offset by 2µs"
+        );
+
+        // Negative correction projects the other way
+        assert_eq!(project_offset_ns(naive_ns, 10_000_000, -200.0), 48_000);
+
+        // No correction or no elapsed time: naive offset unchanged
+        assert_eq!(project_offset_ns(naive_ns, 10_000_000, 0.0), naive_ns);
+        assert_eq!(project_offset_ns(naive_ns, 0, 200.0), naive_ns);
+    }
+
+    #[test]
+    fn test_offset_jitter_ignores_drift() {
+        assert_eq!(offset_jitter_ns(&[5_000, 7_000]), 0.0);
+        // Pure drift across the window is not jitter
+        assert!(offset_jitter_ns(&[0, 5_000, 10_000, 15_000]) < 1e-6);
+
+        // ±500ns alternating noise: same result with or without a ramp under it
+        let noisy = offset_jitter_ns(&[0, 1_000, 0, 1_000]);
+        assert!((noisy - 632.456).abs() < 0.01, "got {}", noisy);
+        let drifting = offset_jitter_ns(&[100_000, 106_000, 110_000, 116_000]);
+        assert!((drifting - noisy).abs() < 1e-6, "got {}", drifting);
+    }
+
+    #[test]
+    fn test_offset_jitter_published_in_status() {
+        let (mut controller, status) = create_nano_test_controller();
+        controller
+            .clock
+            .expect_adjust_frequency()
+            .returning(|_| Ok(()));
+        controller.sample_window = vec![0, 1_000, 0, 1_000];
+        controller.process_sample_window(0);
+        assert_eq!(status.read().unwrap().offset_jitter_ns, 632);
+    }
+
+    #[test]
+    fn test_auto_thresholds_follow_rate_noise() {
+        let feed = |controller: &mut PtpController<_, _, _>, amplitude: f64| {
+            controller.noise_floor.clear();
+            for i in 0..2_000 {
+                let sign = if i % 2 == 0 { 1.0 } else { -1.0 };
+                controller.noise_floor.add_sample(0.2 + sign * amplitude);
+            }
+        };
+        let (mut controller, status) = create_nano_test_controller();
+        assert_eq!(controller.lock_thresholds(), (5.0, 20.0));
+
+        // Off: the measured noise is ignored
+        feed(&mut controller, 0.5);
+        assert_eq!(controller.lock_thresholds(), (5.0, 20.0));
+
+        // Quiet host: 3σ, unlock keeping the 4× hysteresis
+        controller.config.auto_thresholds = true;
+        let (lock, unlock) = controller.lock_thresholds();
+        assert!((lock - 1.5).abs() < 0.05, "lock {:.3}", lock);
+        assert!((unlock - 4.0 * lock).abs() < 1e-9);
+        controller.update_shared_status();
+        assert!((status.read().unwrap().lock_threshold_us_s - lock).abs() < 1e-9);
+
+        // Noisy VM locks looser, within bounds
+        feed(&mut controller, 4.0);
+        let (lock, _) = controller.lock_thresholds();
+        assert!((lock - 12.0).abs() < 0.5, "lock {:.3}", lock);
+        feed(&mut controller, 0.01);
+        assert_eq!(controller.lock_thresholds().0, AUTO_LOCK_MIN_US_S);
+        feed(&mut controller, 50.0);
+        assert_eq!(controller.lock_thresholds().0, AUTO_LOCK_MAX_US_S);
+
+        // Until the noise floor is known the configured values apply
+        controller.noise_floor.clear();
+        assert_eq!(controller.lock_thresholds(), (5.0, 20.0));
+    }
+
+    #[test]
+    fn test_window_with_mixed_sources_discarded() {
+        let (mut controller, status) = create_nano_test_controller();
+        let old = [0x00, 0x1D, 0xC1, 0x11, 0x11, 0x11];
+        let new = [0x00, 0x1D, 0xC1, 0x22, 0x22, 0x22];
+
+        // Straddles a handover: dropped without reaching the servo
+        controller.sample_window = vec![0, 1_000, 50_000, 51_000];
+        controller.sample_sources = vec![old, old, new, new];
+        controller.process_sample_window(0);
+        assert!(controller.sample_window.is_empty());
+        assert!(controller.sample_sources.is_empty());
+        assert_eq!(status.read().unwrap().offset_jitter_ns, 0);
+
+        // One source: processed as usual
+        controller
+            .clock
+            .expect_adjust_frequency()
+            .returning(|_| Ok(()));
+        controller.sample_window = vec![0, 1_000, 0, 1_000];
+        controller.sample_sources = vec![new; 4];
+        controller.process_sample_window(0);
+        assert_eq!(status.read().unwrap().offset_jitter_ns, 632);
+        assert!(controller.sample_sources.is_empty());
+    }
+
+    #[test]
+    fn test_network_quality_needs_sustained_jitter() {
+        let (mut controller, status) = create_nano_test_controller();
+
+        // A short burst doesn't change the grade
+        for _ in 0..NETWORK_QUALITY_WINDOWS - 1 {
+            controller.update_network_quality(500_000.0);
+        }
+        controller.update_network_quality(1_000.0);
+        assert_eq!(controller.network_quality, NetworkQuality::Good);
+
+        for _ in 0..NETWORK_QUALITY_WINDOWS {
+            controller.update_network_quality(500_000.0);
+        }
+        assert_eq!(controller.network_quality, NetworkQuality::Poor);
+
+        // Mixed marginal/poor windows settle on the latest level
+        for _ in 0..NETWORK_QUALITY_WINDOWS {
+            controller.update_network_quality(80_000.0);
+        }
+        assert_eq!(controller.network_quality, NetworkQuality::Marginal);
+
+        controller.update_shared_status();
+        assert_eq!(
+            status.read().unwrap().network_quality,
+            NetworkQuality::Marginal
+        );
+
+        for _ in 0..NETWORK_QUALITY_WINDOWS {
+            controller.update_network_quality(2_000.0);
+        }
+        assert_eq!(controller.network_quality, NetworkQuality::Good);
+    }
+
+    #[test]
+    fn test_target_sample_rate_sizes_thresholds() {
+        let (mut controller, _) = create_nano_test_controller();
+        assert_eq!(
+            controller.jitter_thresholds_ns(),
+            (MARGINAL_JITTER_NS, POOR_JITTER_NS)
+        );
+        assert_eq!(controller.nano_deadband_us_s(), NANO_DEADBAND_US);
+
+        // 48kHz: one sample is 20.8µs, so 80µs of jitter is still good
+        controller.config.target_sample_rate_hz = 48_000;
+        let (marginal, poor) = controller.jitter_thresholds_ns();
+        assert!((marginal - 104_166.7).abs() < 1.0);
+        assert!((poor - 416_666.7).abs() < 1.0);
+        assert!((controller.nano_deadband_us_s() - 0.2083).abs() < 1e-3);
+        for _ in 0..NETWORK_QUALITY_WINDOWS {
+            controller.update_network_quality(80_000.0);
+        }
+        assert_eq!(controller.network_quality, NetworkQuality::Good);
+
+        // 192kHz: a quarter of that
+        controller.config.target_sample_rate_hz = 192_000;
+        for _ in 0..NETWORK_QUALITY_WINDOWS {
+            controller.update_network_quality(80_000.0);
+        }
+        assert_eq!(controller.network_quality, NetworkQuality::Marginal);
+    }
+
+    #[test]
+    fn test_slew_limit_ppm() {
+        assert_eq!(slew_limit_ppm(0.0, 200.0, 20.0), 20.0);
+        assert_eq!(slew_limit_ppm(50.0, -150.0, 20.0), 30.0);
+        assert_eq!(slew_limit_ppm(50.0, 55.0, 20.0), 55.0);
+        // 0 disables the limit
+        assert_eq!(slew_limit_ppm(0.0, 200.0, 0.0), 200.0);
+    }
+
+    #[test]
+    fn test_servo_output_is_slew_limited() {
+        let (mut controller, _) = create_nano_test_controller();
+        controller.config.max_ppm_step_per_sample = 10.0;
+        controller
+            .clock
+            .expect_adjust_frequency()
+            .returning(|_| Ok(()));
+
+        // Large drift would make ACQ jump by up to 200ppm at once
+        controller.last_offset_us = Some(0.0);
+        controller.last_offset_time = Some(Instant::now() - Duration::from_secs(1));
+        controller.smoothed_rate_ppm = 300.0;
+        controller.apply_self_tuning_servo(300.0);
+
+        assert!((controller.applied_freq_ppm + 10.0).abs() < 1e-9);
+    }
+
+    /// Closed loop: the clock needs +20ppm, a thermal excursion needs +120ppm
+    /// for `transient` samples. Returns (peak integral, peak drift rate once the
+    /// excursion is over, final applied ppm).
+    fn run_thermal_transient(
+        max_step: f64,
+        anti_windup: bool,
+        integral_bleed: f64,
+    ) -> (f64, f64, f64) {
+        let (mut controller, _) = create_nano_test_controller();
+        controller.config.max_ppm_step_per_sample = max_step;
+        controller.config.anti_windup = anti_windup;
+        controller.config.integral_bleed = integral_bleed;
+        controller.drift_baseline_ppm = 20.0;
+        controller.applied_freq_ppm = 20.0;
+        controller
+            .clock
+            .expect_adjust_frequency()
+            .returning(|_| Ok(()));
+
+        let transient = 120..180;
+        let mut offset_us = 0.0;
+        let (mut peak_integral, mut peak_rate_after) = (0.0f64, 0.0f64);
+        for i in 0..480 {
+            let needed_ppm = if transient.contains(&i) { 120.0 } else { 20.0 };
+            // Offset grows by the uncorrected drift each second
+            offset_us += controller.applied_freq_ppm - needed_ppm;
+            controller.last_offset_time = Some(Instant::now() - Duration::from_secs(1));
+            controller.apply_self_tuning_servo(offset_us);
+
+            peak_integral = peak_integral.max(controller.drift_baseline_ppm);
+            if i >= transient.end {
+                peak_rate_after = peak_rate_after.max(controller.applied_freq_ppm - needed_ppm);
+            }
+        }
+        (peak_integral, peak_rate_after, controller.applied_freq_ppm)
+    }
+
+    #[test]
+    fn test_anti_windup_limits_overshoot_after_transient() {
+        // Slow actuator (2ppm/sample): the output ramps for ~50 samples
+        let (wound_integral, wound_overshoot, _) = run_thermal_transient(2.0, false, 0.0);
+        let (integral, overshoot, final_ppm) = run_thermal_transient(2.0, true, 0.0);
+
+        assert!(
+            wound_integral > 100.0,
+            "expected windup without anti-windup, integral peaked at {:.1}ppm",
+            wound_integral
+        );
+        assert!(
+            integral < 60.0,
+            "integral wound up to {:.1}ppm despite anti-windup",
+            integral
+        );
+        assert!(
+            overshoot < 70.0 && overshoot < wound_overshoot,
+            "far-side overshoot {:.1}us/s (without anti-windup {:.1}us/s)",
+            overshoot,
+            wound_overshoot
+        );
+        assert!(
+            (final_ppm - 20.0).abs() < 1.0,
+            "settled at {:.2}ppm",
+            final_ppm
+        );
+    }
+
+    #[test]
+    fn test_integral_bleed_unwinds_after_transient() {
+        // Default slew limit: the output tracks, but the lagging rate estimate
+        // keeps integrating after the excursion ends
+        let (integral, _, _) = run_thermal_transient(20.0, true, 0.0);
+        let (bled_integral, _, final_ppm) = run_thermal_transient(20.0, true, 0.1);
+
+        assert!(integral > 90.0, "integral peaked at {:.1}ppm", integral);
+        assert!(
+            bled_integral < 60.0,
+            "bleed-off left the integral at {:.1}ppm",
+            bled_integral
+        );
+        assert!(
+            (final_ppm - 20.0).abs() < 1.0,
+            "settled at {:.2}ppm",
+            final_ppm
+        );
+    }
+
+    #[test]
+    fn test_max_healthy_ppm_clamps_and_raises_oscillator_fault() {
+        let (mut controller, status) = create_nano_test_controller();
+        controller.config.max_healthy_ppm = 50.0;
+        controller.drift_baseline_ppm = 20.0;
+        controller.applied_freq_ppm = 20.0;
+        controller
+            .clock
+            .expect_adjust_frequency()
+            .returning(|_| Ok(()));
+
+        // The oscillator drifts to needing 80ppm, then recovers to 30ppm
+        let mut offset_us = 0.0;
+        let mut faulted = false;
+        for i in 0..400 {
+            let needed_ppm = if (60..200).contains(&i) { 80.0 } else { 30.0 };
+            offset_us += controller.applied_freq_ppm - needed_ppm;
+            controller.last_offset_time = Some(Instant::now() - Duration::from_secs(1));
+            controller.apply_self_tuning_servo(offset_us);
+
+            assert!(
+                controller.applied_freq_ppm.abs() <= 50.0 + 1e-9,
+                "applied {:.1}ppm beyond the healthy band",
+                controller.applied_freq_ppm
+            );
+            if i == 199 {
+                faulted = controller.oscillator_fault;
+                controller.update_shared_status();
+                assert!(status.read().unwrap().oscillator_fault);
+            }
+        }
+
+        assert!(faulted, "80ppm needed but no oscillator fault raised");
+        assert!(!controller.oscillator_fault, "fault did not clear at 30ppm");
+        assert!(
+            (controller.applied_freq_ppm - 30.0).abs() < 1.0,
+            "settled at {:.2}ppm",
+            controller.applied_freq_ppm
+        );
+        controller.update_shared_status();
+        assert!(!status.read().unwrap().oscillator_fault);
+    }
+
+    #[test]
+    fn test_lock_deadband_suppresses_micro_corrections() {
+        // Stable at +10ppm with 0.2µs/s of residual noise
+        let run = |deadband: f64| -> f64 {
+            let (mut controller, _) = create_nano_test_controller();
+            controller.config.deadband_us_s = deadband;
+            controller.in_production_mode = true;
+            controller.drift_baseline_ppm = 10.0;
+            controller.applied_freq_ppm = 10.0;
+            controller.last_offset_us = Some(0.0);
+            controller.last_offset_time = Some(Instant::now() - Duration::from_secs(1));
+            controller.smoothed_rate_ppm = 0.2;
+            controller
+                .clock
+                .expect_adjust_frequency()
+                .returning(|_| Ok(()));
+            controller.apply_self_tuning_servo(0.2);
+            controller.applied_freq_ppm
+        };
+
+        assert_eq!(
+            run(0.3),
+            10.0,
+            "noise inside the dead-band must not move Adj"
         );
         assert!(
-            controller.in_production_mode,
-            "Soft reset should NOT change production mode"
+            run(0.0) < 10.0,
+            "without a dead-band the servo still nudges"
         );
     }
 
     #[test]
-    fn test_sync_source_change_in_nano_mode_stays_nano() {
-        let (mut controller, _) = create_locked_controller();
+    fn test_project_offset_ignores_implausible_gaps() {
+        // FollowUp "before" Sync (clock stepped) or seconds later: don't project
+        assert_eq!(project_offset_ns(50_000, -1, 200.0), 50_000);
+        assert_eq!(project_offset_ns(50_000, 5_000_000_000, 200.0), 50_000);
+    }
 
-        // Put in NANO mode
-        controller.in_nano_mode = true;
-        controller.nano_sustain_count = NANO_SUSTAIN_COUNT;
+    #[test]
+    fn test_estimate_offset_each_filter_type() {
+        // Nine samples around 100µs with one large outlier
+        let mut samples: Vec<i64> = vec![
+            96_000, 97_000, 98_000, 99_000, 100_000, 101_000, 102_000, 103_000, 1_000_000,
+        ];
+        samples.sort();
 
-        // Simulate soft reset
-        let new_source = [0x00, 0x1D, 0xC1, 0x1A, 0x44, 0x30];
-        controller.current_sync_source = Some(new_source);
-        controller.pending_syncs.clear();
-        controller.sample_window.clear();
-        controller.prev_t1_ns = 0;
-        controller.prev_t2_ns = 0;
-        // Soft reset does NOT touch nano mode state
+        assert_eq!(
+            estimate_offset_ns(&samples, FilterType::Median, 25.0),
+            100_000
+        );
+        // Mean is pulled toward the outlier: (796_000 + 1_000_000) / 9
+        assert_eq!(
+            estimate_offset_ns(&samples, FilterType::Mean, 25.0),
+            199_555
+        );
+        // 25% of 9 → drop 2 from each end → mean of 98..102µs
+        assert_eq!(
+            estimate_offset_ns(&samples, FilterType::TrimmedMean, 25.0),
+            100_000
+        );
+        // 10% of 9 → drop 0 → same as mean
+        assert_eq!(
+            estimate_offset_ns(&samples, FilterType::TrimmedMean, 10.0),
+            199_555
+        );
+        // Least-delayed packet
+        assert_eq!(
+            estimate_offset_ns(&samples, FilterType::MinDelay, 25.0),
+            96_000
+        );
+    }
 
-        assert!(
-            controller.in_nano_mode,
-            "Soft reset should NOT exit NANO mode"
+    #[test]
+    fn test_estimate_offset_trimmed_mean_small_window() {
+        // 40% of 3 → drop 1 from each end → middle sample
+        let samples = vec![10_000, 20_000, 90_000];
+        assert_eq!(
+            estimate_offset_ns(&samples, FilterType::TrimmedMean, 40.0),
+            20_000
         );
+        // Trimming would leave nothing: falls back to median
+        let samples = vec![10_000, 20_000];
         assert_eq!(
-            controller.nano_sustain_count, NANO_SUSTAIN_COUNT,
-            "Soft reset should NOT reset nano_sustain_count"
+            estimate_offset_ns(&samples, FilterType::TrimmedMean, 50.0),
+            20_000
         );
+        let samples = vec![42];
+        for ft in [
+            FilterType::Median,
+            FilterType::Mean,
+            FilterType::TrimmedMean,
+        ] {
+            assert_eq!(estimate_offset_ns(&samples, ft, 25.0), 42);
+        }
     }
 
     #[test]
@@ -1832,6 +5422,28 @@ mod tests {
         assert_eq!(status_guard.mode, "NTP-only", "Mode should be NTP-only");
     }
 
+    #[test]
+    fn test_ntp_only_mode_survives_status_update() {
+        let (mut controller, status) = create_locked_controller();
+
+        controller.advance_time(Duration::from_secs(PTP_TIMEOUT_SECS + 1));
+        controller.check_ptp_status();
+        assert!(controller.ptp_offline);
+
+        // An NTP step in NTP-only mode refreshes the status
+        controller.after_clock_step();
+        {
+            let status = status.read().unwrap();
+            assert_eq!(status.mode, "NTP-only");
+            assert!(!status.settled);
+        }
+
+        controller.last_ptp_packet = Instant::now();
+        controller.check_ptp_status();
+        controller.update_shared_status();
+        assert_eq!(status.read().unwrap().mode, "LOCK");
+    }
+
     #[test]
     fn test_ptp_online_recovery() {
         let (mut controller, _) = create_nano_test_controller();
@@ -1877,7 +5489,7 @@ mod tests {
         let (controller, _) = create_nano_test_controller();
 
         // The check_ntp_utc_tracking function has this logic:
-        // let should_check = self.ptp_offline || (self.is_locked && self.ntp_tracking_enabled);
+        // let should_check = self.ntp_tracking_enabled && (self.ptp_offline || self.is_locked);
 
         // When PTP is offline, NTP tracking should run regardless of lock state
         // This is validated by the modified check_ntp_utc_tracking condition
@@ -1887,6 +5499,608 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_ntp_tracking_disabled_never_queries_ntp() {
+        let mut mock_ntp = MockNtpSource::new();
+        mock_ntp.expect_get_offset().times(0);
+        let mut mock_clock = MockSystemClock::new();
+        mock_clock.expect_step_clock().times(0);
+        let status = Arc::new(RwLock::new(SyncStatus::default()));
+        let mut controller = PtpController::new(
+            mock_clock,
+            MockPtpNetwork::new(),
+            mock_ntp,
+            status,
+            SystemConfig::default(),
+        );
+        controller.set_ntp_tracking(false);
+
+        // Due for a check in both NTP-only (offline) and locked states
+        controller.last_ntp_check =
+            Instant::now() - Duration::from_secs(NTP_CHECK_INTERVAL_SECS + 1);
+        controller.ptp_offline = true;
+        controller.check_ntp_utc_tracking();
+        controller.ptp_offline = false;
+        controller.is_locked = true;
+        controller.check_ntp_utc_tracking();
+    }
+
+    /// NTP-only controller whose next tracking check returns `offset_us`
+    fn ntp_step_controller(
+        offset_us: u64,
+        expected_steps: usize,
+        jitter_k: f64,
+    ) -> PtpController<MockSystemClock, MockPtpNetwork, MockNtpSource> {
+        ntp_step_controller_with_quality(offset_us, expected_steps, jitter_k, None)
+    }
+
+    fn ntp_step_controller_with_quality(
+        offset_us: u64,
+        expected_steps: usize,
+        jitter_k: f64,
+        quality: Option<NtpQuality>,
+    ) -> PtpController<MockSystemClock, MockPtpNetwork, MockNtpSource> {
+        let mut mock_ntp = MockNtpSource::new();
+        mock_ntp
+            .expect_get_offset()
+            .returning(move || Ok((Duration::from_micros(offset_us), 1)));
+        mock_ntp.expect_quality().returning(move || quality);
+        let mut mock_clock = MockSystemClock::new();
+        mock_clock
+            .expect_step_clock()
+            .times(expected_steps)
+            .returning(|_, _| Ok(()));
+        let config = SystemConfig {
+            ntp_step_jitter_k: jitter_k,
+            ..Default::default()
+        };
+        let status = Arc::new(RwLock::new(SyncStatus::default()));
+        let mut controller =
+            PtpController::new(mock_clock, MockPtpNetwork::new(), mock_ntp, status, config);
+        controller.ptp_offline = true;
+        controller.last_ntp_check =
+            Instant::now() - Duration::from_secs(NTP_CHECK_INTERVAL_SECS + 1);
+        controller
+    }
+
+    #[test]
+    fn test_ntp_step_threshold_scales_with_jitter() {
+        // ~1.4ms of NTP jitter: 1.8ms is noise, not a reason to step
+        let noisy = [1500, -1500, 1200, -1400, 1600];
+        let mut controller = ntp_step_controller(1800, 0, DEFAULT_NTP_STEP_JITTER_K);
+        controller.ntp_offset_samples.extend(noisy);
+        let threshold = controller.ntp_step_threshold_us();
+        assert!(threshold > 4000, "threshold {}", threshold);
+        controller.update_shared_status();
+        assert_eq!(
+            controller
+                .status_shared
+                .read()
+                .unwrap()
+                .ntp_step_threshold_us,
+            threshold
+        );
+        controller.check_ntp_utc_tracking();
+
+        // Fixed threshold (k = 0): the same offset is stepped
+        let mut controller = ntp_step_controller(1800, 1, 0.0);
+        controller.ntp_offset_samples.extend(noisy);
+        assert_eq!(controller.ntp_step_threshold_us(), NTP_STEP_THRESHOLD_US);
+        controller.check_ntp_utc_tracking();
+
+        // Clean path: jitter is far below the floor, which stays in force
+        let mut controller = ntp_step_controller(600, 1, DEFAULT_NTP_STEP_JITTER_K);
+        controller.ntp_offset_samples.extend([40, -30, 20, -50, 10]);
+        assert_eq!(controller.ntp_step_threshold_us(), NTP_STEP_THRESHOLD_US);
+        controller.check_ntp_utc_tracking();
+    }
+
+    #[test]
+    fn test_ntp_rtt_reported_and_high_delay_not_stepped() {
+        let quality = NtpQuality {
+            rtt: Duration::from_micros(2_400),
+            stratum: 2,
+        };
+        // Path baseline 400us: 1ms of extra delay may be 1ms of asymmetry,
+        // so an 800us offset is not stepped
+        let mut controller = ntp_step_controller_with_quality(800, 0, 0.0, Some(quality));
+        controller.ntp_rtt_samples.extend([400, 450, 420]);
+        controller.check_ntp_utc_tracking();
+        {
+            let status = controller.status_shared.read().unwrap();
+            assert_eq!(status.ntp_rtt_us, 2_400);
+            assert_eq!(status.ntp_stratum, 2);
+        }
+        assert_eq!(controller.ntp_rtt_samples.back(), Some(&2_400));
+
+        // The same offset at the baseline delay is stepped
+        let quality = NtpQuality {
+            rtt: Duration::from_micros(400),
+            stratum: 2,
+        };
+        let mut controller = ntp_step_controller_with_quality(800, 1, 0.0, Some(quality));
+        controller.ntp_rtt_samples.extend([400, 450, 420]);
+        controller.check_ntp_utc_tracking();
+    }
+
+    // ========================================================================
+    // NETWORK FAILURE / REBUILD TESTS
+    // ========================================================================
+
+    #[test]
+    fn test_recv_error_recorded_not_propagated() {
+        let (mut controller, _) = create_nano_test_controller();
+        controller
+            .network
+            .expect_recv_packet()
+            .times(3)
+            .returning(|| Err(DanteSyncError::Interface("device removed".to_string())));
+
+        for _ in 0..3 {
+            assert!(controller.process_loop_iteration().is_ok());
+        }
+        assert_eq!(controller.recv_error_count, 3);
+        assert!(controller.recv_error_since.is_some());
+        // Streak just started - not yet a sustained failure
+        assert!(!controller.network_failed());
+    }
+
+    #[test]
+    fn test_network_failed_after_sustained_errors() {
+        let (mut controller, _) = create_nano_test_controller();
+        controller.recv_error_since =
+            Some(Instant::now() - Duration::from_secs(NETWORK_FAILURE_SECS + 1));
+        assert!(controller.network_failed());
+    }
+
+    #[test]
+    fn test_recv_success_clears_error_streak() {
+        let (mut controller, _) = create_nano_test_controller();
+        controller.recv_error_since = Some(Instant::now());
+        controller.recv_error_count = 10;
+        controller
+            .network
+            .expect_recv_packet()
+            .returning(|| Ok(None));
+
+        controller.process_loop_iteration().unwrap();
+        assert!(controller.recv_error_since.is_none());
+        assert_eq!(controller.recv_error_count, 0);
+    }
+
+    #[test]
+    fn test_replace_network_preserves_learned_frequency() {
+        let (mut controller, _) = create_locked_controller();
+        controller.recv_error_since =
+            Some(Instant::now() - Duration::from_secs(NETWORK_FAILURE_SECS + 1));
+        controller.prev_t1_ns = 123;
+
+        controller.replace_network(MockPtpNetwork::new());
+
+        assert!(!controller.network_failed());
+        assert!(controller.pending_syncs.is_empty());
+        assert!(controller.sample_window.is_empty());
+        assert_eq!(controller.prev_t1_ns, 0);
+        assert!((controller.applied_freq_ppm - 35.0).abs() < 0.01);
+        assert!((controller.drift_baseline_ppm - 33.5).abs() < 0.01);
+        assert!(controller.is_locked);
+    }
+
+    #[test]
+    fn test_apply_config_keeps_lock_and_frequency() {
+        let (mut controller, _) = create_locked_controller();
+        let mut config = controller.config().clone();
+        config.lock_rate_us_s = 2.0;
+        config.unlock_rate_us_s = 8.0;
+
+        controller.apply_config(config);
+
+        assert!((controller.config.lock_rate_us_s - 2.0).abs() < f64::EPSILON);
+        assert!(controller.is_locked);
+        assert!((controller.applied_freq_ppm - 35.0).abs() < 0.01);
+    }
+
+    // ========================================================================
+    // BUILDER TESTS
+    // ========================================================================
+
+    #[test]
+    fn test_builder_requires_dependencies() {
+        let result = PtpControllerBuilder::<MockSystemClock, MockPtpNetwork, MockNtpSource>::new()
+            .network(MockPtpNetwork::new())
+            .ntp(MockNtpSource::new())
+            .build();
+        let err = result.err().expect("missing clock must fail");
+        assert!(err.to_string().contains("clock not set"));
+    }
+
+    #[test]
+    fn test_builder_uses_supplied_config_and_status() {
+        let status = Arc::new(RwLock::new(SyncStatus::default()));
+        let mut config = SystemConfig {
+            ntp_tracking: false,
+            ..Default::default()
+        };
+        config.filters.sample_window_size = 8;
+
+        let controller = PtpControllerBuilder::new()
+            .clock(MockSystemClock::new())
+            .network(MockPtpNetwork::new())
+            .ntp(MockNtpSource::new())
+            .config(config)
+            .status(status.clone())
+            .build()
+            .unwrap();
+
+        assert!(Arc::ptr_eq(&controller.get_status_shared(), &status));
+        assert_eq!(controller.config.filters.sample_window_size, 8);
+        assert!(!controller.ntp_tracking_enabled);
+        assert!(!status.read().unwrap().ntp_tracking);
+    }
+
+    // ========================================================================
+    // IPC COMMAND TESTS
+    // ========================================================================
+
+    #[test]
+    fn test_ipc_reset_servo_clears_learned_frequency() {
+        let (mut controller, status) = create_locked_controller();
+        controller
+            .clock
+            .expect_adjust_frequency()
+            .with(eq(1.0))
+            .times(1)
+            .returning(|_| Ok(()));
+
+        controller.handle_command(IpcCommand::ResetServo);
+
+        assert!(!controller.is_locked);
+        assert!(!controller.in_production_mode);
+        assert_eq!(controller.applied_freq_ppm, 0.0);
+        assert_eq!(controller.drift_baseline_ppm, 0.0);
+        assert!(controller.pending_syncs.is_empty());
+        assert_eq!(controller.unlock_count(), 0);
+        assert_eq!(status.read().unwrap().mode, "ACQ");
+    }
+
+    #[test]
+    fn test_initial_freq_seeds_servo() {
+        let mut clock = MockSystemClock::new();
+        clock
+            .expect_adjust_frequency()
+            .withf(|factor| (factor - (1.0 + 35.0e-6)).abs() < 1e-12)
+            .times(2)
+            .returning(|_| Ok(()));
+        let config = SystemConfig {
+            initial_freq_ppm: 35.0,
+            ..Default::default()
+        };
+        let status = Arc::new(RwLock::new(SyncStatus::default()));
+        let mut controller = PtpController::new(
+            clock,
+            MockPtpNetwork::new(),
+            MockNtpSource::new(),
+            status,
+            config,
+        );
+        assert_eq!(controller.applied_freq_ppm, 35.0);
+        assert_eq!(controller.drift_baseline_ppm, 35.0);
+
+        controller.apply_initial_frequency();
+
+        // A servo reset re-acquires from the seed, not from 0ppm
+        controller.drift_baseline_ppm = 37.0;
+        controller.applied_freq_ppm = 38.0;
+        controller.handle_command(IpcCommand::ResetServo);
+        assert_eq!(controller.applied_freq_ppm, 35.0);
+        assert_eq!(controller.drift_baseline_ppm, 35.0);
+    }
+
+    #[test]
+    fn test_calibration_rejects_outlier() {
+        let (mut controller, _) = create_nano_test_controller();
+        controller.config.filters.calibration_samples = 3;
+        controller.calibration_complete = false;
+
+        // 3 samples + 1 extra; the congestion burst is the one dropped
+        for offset in [100_000, 110_000, 5_000_000] {
+            assert!(controller.process_calibration(offset));
+            assert!(!controller.calibration_complete);
+        }
+        assert!(controller.process_calibration(105_000));
+        assert!(controller.calibration_complete);
+        assert_eq!(controller.calibration_offset_ns, 105_000);
+
+        assert_eq!(
+            calibration_offset_ns(&[100, 110, 5_000_000, 105], 1),
+            (105, 10)
+        );
+        assert_eq!(calibration_offset_ns(&[7], 1), (7, 0));
+    }
+
+    #[test]
+    fn test_raw_offset_includes_calibration() {
+        let (mut controller, status) = create_nano_test_controller();
+        controller.calibration_offset_ns = 5_000;
+        controller.last_phase_offset_ns = 100;
+        controller.update_shared_status();
+
+        let s = status.read().unwrap();
+        assert_eq!(s.offset_ns, 100);
+        assert_eq!(s.raw_offset_ns, 5_100);
+    }
+
+    #[test]
+    fn test_leap_boundary_only_at_month_end() {
+        // 2016-12-31 12:00 UTC: leap at 2017-01-01 00:00
+        assert_eq!(leap_boundary_secs(1_483_185_600), Some(1_483_228_800));
+        // 2016-12-30 12:00 UTC: announced early, not today
+        assert_eq!(leap_boundary_secs(1_483_099_200), None);
+        // 2016-02-29 (leap year) rolls into March
+        assert_eq!(leap_boundary_secs(1_456_747_200), Some(1_456_790_400));
+    }
+
+    #[test]
+    fn test_scheduled_leap_steps_clock_once() {
+        let (mut controller, status) = create_nano_test_controller();
+        controller
+            .clock
+            .expect_step_clock()
+            .with(eq(Duration::from_secs(1)), eq(-1))
+            .times(1)
+            .returning(|_, _| Ok(()));
+        controller.set_ntp_leap(Some(LeapSecond::Insert));
+        controller.leap_scheduled = Some((LeapSecond::Insert, 1_000));
+        controller.last_offset_us = Some(10.0);
+
+        controller.check_leap_second();
+
+        assert!(controller.leap_scheduled.is_none());
+        assert_eq!(controller.last_leap_boundary, 1_000);
+        assert!(controller.last_offset_us.is_none());
+        assert!(controller.last_ntp_step.is_some());
+        assert!(!status.read().unwrap().leap_pending);
+
+        // Still announced, but the boundary has been applied already
+        controller.check_leap_second();
+    }
+
+    #[test]
+    fn test_display_rate_averages_over_window() {
+        let (mut controller, status) = create_nano_test_controller();
+        controller.config.display_rate_samples = 4;
+
+        for rate in [10.0, 2.0, 4.0, 6.0, 8.0] {
+            controller.smoothed_rate_ppm = rate;
+            controller.record_display_rate(rate);
+        }
+        controller.update_shared_status();
+
+        let s = status.read().unwrap();
+        assert!((s.smoothed_rate_ppm - 8.0).abs() < 1e-9);
+        // Oldest sample (10.0) has left the 4-sample window
+        assert!((s.display_rate_ppm - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_master_vendor_detection() {
+        let (mut controller, status) = create_nano_test_controller();
+        let master = [0xEC, 0x46, 0x70, 0x00, 0x00, 0x01];
+        let mut sync = make_v1_sync(0, master, master);
+        let header = PtpV1Header::parse(&sync).unwrap();
+        controller.handle_sync_message(&header, &sync, SystemTime::now());
+        assert_eq!(controller.master_is_dante, Some(false));
+        assert!(!status.read().unwrap().master_is_dante);
+
+        // Same master, now with an Audinate organization TLV after the body
+        sync.resize(PTP_V1_SYNC_LEN, 0);
+        sync.extend_from_slice(&[0x00, 0x03, 0x00, 0x06, 0x00, 0x1D, 0xC1, 0x00, 0x00, 0x01]);
+        sync[30..32].copy_from_slice(&1u16.to_be_bytes());
+        let header = PtpV1Header::parse(&sync).unwrap();
+        controller.handle_sync_message(&header, &sync, SystemTime::now());
+        assert_eq!(controller.master_is_dante, Some(true));
+        assert!(status.read().unwrap().master_is_dante);
+    }
+
+    #[test]
+    fn test_cold_start_boost_fades_and_stops_after_lock() {
+        let (mut controller, _) = create_nano_test_controller();
+        assert_eq!(controller.cold_start_boost(), 0.0, "disabled by default");
+
+        controller.config.cold_start_secs = 100;
+        controller.settle_start = Some(Instant::now());
+        assert!(controller.cold_start_boost() > 0.99);
+
+        controller.settle_start = Some(Instant::now() - Duration::from_secs(75));
+        let boost = controller.cold_start_boost();
+        assert!((boost - 0.25).abs() < 0.01, "boost {}", boost);
+
+        controller.settle_start = Some(Instant::now() - Duration::from_secs(150));
+        assert_eq!(controller.cold_start_boost(), 0.0);
+        assert!(!controller.cold_start_active);
+
+        // Never again once locked, even after a servo reset
+        controller
+            .clock
+            .expect_adjust_frequency()
+            .returning(|_| Ok(()));
+        controller.ever_locked = true;
+        controller.handle_command(IpcCommand::ResetServo);
+        assert_eq!(controller.cold_start_boost(), 0.0);
+    }
+
+    #[test]
+    fn test_ipc_recalibrate_keeps_frequency() {
+        let (mut controller, _) = create_locked_controller();
+        controller.config.filters.calibration_samples = 10;
+        controller.calibration_complete = true;
+        controller.calibration_offset_ns = 5_000;
+
+        controller.handle_command(IpcCommand::Recalibrate);
+
+        assert!(!controller.calibration_complete);
+        assert_eq!(controller.calibration_offset_ns, 0);
+        assert!(!controller.warmup_complete);
+        assert!(controller.sample_window.is_empty());
+        assert!((controller.applied_freq_ppm - 35.0).abs() < 0.01);
+        assert!(controller.is_locked);
+    }
+
+    #[test]
+    fn test_ipc_toggle_ntp_tracking_and_force_sync() {
+        let (mut controller, status) = create_locked_controller();
+        controller
+            .ntp
+            .expect_get_offset()
+            .times(1)
+            .returning(|| Ok((Duration::from_micros(10), 1)));
+        controller.ntp.expect_quality().returning(|| None);
+
+        // Locked: a forced sync queries NTP immediately, not after 30s
+        controller.handle_command(IpcCommand::ForceNtpSync);
+        assert_eq!(status.read().unwrap().ntp_offset_us, 10);
+
+        controller.handle_command(IpcCommand::ToggleNtpTracking);
+        assert!(!status.read().unwrap().ntp_tracking);
+        // Disabled: no further NTP query (times(1) above)
+        controller.handle_command(IpcCommand::ForceNtpSync);
+
+        controller.handle_command(IpcCommand::ToggleNtpTracking);
+        assert!(status.read().unwrap().ntp_tracking);
+    }
+
+    #[test]
+    fn test_ipc_reset_counters() {
+        let (mut controller, status) = create_locked_controller();
+        controller
+            .ntp
+            .expect_get_offset()
+            .times(2)
+            .returning(|| Err(DanteSyncError::NtpTimeout("timeout".to_string())));
+        for _ in 0..2 {
+            controller.handle_command(IpcCommand::ForceNtpSync);
+        }
+        for _ in 0..15 {
+            controller.spike_filter.filter(0.0, FilterMode::Lock);
+        }
+        controller.spike_filter.filter(100.0, FilterMode::Lock);
+        controller.update_shared_status();
+        {
+            let s = status.read().unwrap();
+            assert_eq!((s.ntp_failures_total, s.ntp_failures_recent), (2, 2));
+            assert_eq!((s.spikes_total, s.spikes_recent), (1, 1));
+            assert_eq!(s.counter_window_secs, 300);
+        }
+
+        controller.handle_command(IpcCommand::ResetCounters);
+
+        let s = status.read().unwrap();
+        assert_eq!((s.ntp_failures_total, s.ntp_failures_recent), (0, 0));
+        assert_eq!((s.spikes_total, s.spikes_recent), (0, 0));
+        assert!(s.counters_since_ts > 0);
+    }
+
+    #[test]
+    fn test_ipc_pause_halts_corrections_and_resume_restores_frequency() {
+        let (mut controller, status) = create_locked_controller();
+        // No clock expectations: any step or frequency change while paused panics
+        controller
+            .ntp
+            .expect_get_offset()
+            .returning(|| Ok((Duration::from_millis(50), 1)));
+        controller.ntp.expect_quality().returning(|| None);
+
+        controller.handle_command(IpcCommand::Pause);
+        {
+            let s = status.read().unwrap();
+            assert!(s.paused);
+            assert_eq!(s.mode, "PAUSED");
+        }
+
+        // NTP offset is still reported, but not stepped
+        controller.handle_command(IpcCommand::ForceNtpSync);
+        assert_eq!(status.read().unwrap().ntp_offset_us, 50_000);
+
+        // PTP samples are still measured, but the servo does not act
+        for offset_us in [0.0, 80.0, 160.0] {
+            controller.apply_self_tuning_servo(offset_us);
+        }
+        controller.handle_command(IpcCommand::ResetServo);
+        assert!(status.read().unwrap().paused);
+
+        // Resume re-applies the (reset) learned frequency once
+        controller
+            .clock
+            .expect_adjust_frequency()
+            .times(1)
+            .withf(|factor| (*factor - 1.0).abs() < 1e-9)
+            .returning(|_| Ok(()));
+        controller.handle_command(IpcCommand::Resume);
+        let s = status.read().unwrap();
+        assert!(!s.paused);
+        assert_ne!(s.mode, "PAUSED");
+        assert!(s.resettling);
+    }
+
+    #[test]
+    fn test_ipc_pause_keeps_learned_frequency() {
+        let (mut controller, _) = create_locked_controller();
+        controller.handle_command(IpcCommand::Pause);
+        controller.apply_self_tuning_servo(0.0);
+        assert!(controller.is_locked);
+
+        controller
+            .clock
+            .expect_adjust_frequency()
+            .times(1)
+            .withf(|factor| (*factor - (1.0 + 35.0 / 1_000_000.0)).abs() < 1e-9)
+            .returning(|_| Ok(()));
+        controller.handle_command(IpcCommand::Resume);
+        assert!((controller.drift_baseline_ppm - 33.5).abs() < 0.01);
+        assert!((controller.applied_freq_ppm - 35.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_status_update_sends_telemetry() {
+        let receiver = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        receiver
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .unwrap();
+        let (mut controller, _status) = create_locked_controller();
+        controller.set_telemetry(TelemetrySender::new(receiver.local_addr().unwrap()).unwrap());
+
+        controller.update_shared_status();
+
+        let mut buf = [0u8; 65536];
+        let len = receiver.recv(&mut buf).unwrap();
+        let sent: SyncStatus = serde_json::from_slice(&buf[..len]).unwrap();
+        assert!(sent.is_locked);
+        assert_eq!(sent.mode, "LOCK");
+    }
+
+    #[test]
+    fn test_heartbeat_logged_once_per_interval() {
+        let (mut controller, _status) = create_locked_controller();
+        controller.config.heartbeat_secs = 60;
+        controller.update_shared_status();
+
+        // Just started: not due yet
+        assert_eq!(controller.heartbeat(), None);
+
+        controller.last_heartbeat = Instant::now() - Duration::from_secs(61);
+        let line = controller.heartbeat().expect("heartbeat due");
+        assert!(line.starts_with("[Heartbeat] LOCK | off:"), "{}", line);
+        assert!(line.contains(" drift:"), "{}", line);
+        assert!(line.contains(" ntp:"), "{}", line);
+        assert!(line.contains(" | locked "), "{}", line);
+        // Logged: quiet until the next interval
+        assert_eq!(controller.heartbeat(), None);
+
+        controller.config.heartbeat_secs = 0;
+        controller.last_heartbeat = Instant::now() - Duration::from_secs(3600);
+        assert_eq!(controller.heartbeat(), None);
+    }
+
     #[test]
     fn test_ptp_offline_within_timeout_stays_online() {
         let (mut controller, _) = create_nano_test_controller();
@@ -0,0 +1,127 @@
+//! Crate error type
+//!
+//! The library returns `DanteSyncError` so embedders can tell failure
+//! classes apart: a missing privilege needs the operator, a timeout is worth
+//! retrying. The binaries wrap it in `anyhow` for reporting. Messages are
+//! kept as the library has always logged them; the variant carries the
+//! class.
+
+use std::io;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum DanteSyncError {
+    /// No usable network interface, or the requested one does not exist
+    #[error("{0}")]
+    Interface(String),
+    /// The OS refused the operation (not root / Administrator, missing
+    /// CAP_SYS_TIME or CAP_NET_RAW)
+    #[error("{0}")]
+    PermissionDenied(String),
+    /// The NTP server (or chronyd) did not answer in time
+    #[error("{0}")]
+    NtpTimeout(String),
+    /// Any other NTP failure: bad response, unsynchronised chrony
+    #[error("{0}")]
+    Ntp(String),
+    /// Malformed packet, capture file or textual value
+    #[error("{0}")]
+    Parse(String),
+    /// Frequency or step adjustment rejected by the clock
+    #[error("{0}")]
+    Clock(String),
+    /// Invalid configuration or incomplete builder
+    #[error("{0}")]
+    Config(String),
+    /// Socket, file or device I/O
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+pub type Result<T, E = DanteSyncError> = std::result::Result<T, E>;
+
+impl DanteSyncError {
+    /// Wrap an I/O error with what was being attempted, keeping its kind
+    pub fn io(what: impl std::fmt::Display, err: io::Error) -> Self {
+        if err.kind() == io::ErrorKind::PermissionDenied {
+            DanteSyncError::PermissionDenied(format!("{}: {}", what, err))
+        } else {
+            DanteSyncError::Io(io::Error::new(err.kind(), format!("{}: {}", what, err)))
+        }
+    }
+
+    /// Needs elevated privileges (rather than a retry or a config change)
+    pub fn is_permission_denied(&self) -> bool {
+        match self {
+            DanteSyncError::PermissionDenied(_) => true,
+            DanteSyncError::Io(e) => e.kind() == io::ErrorKind::PermissionDenied,
+            _ => false,
+        }
+    }
+
+    /// Transient: the same call may succeed later (timeouts, would-block,
+    /// interrupted I/O)
+    pub fn is_transient(&self) -> bool {
+        match self {
+            DanteSyncError::NtpTimeout(_) => true,
+            DanteSyncError::Io(e) => matches!(
+                e.kind(),
+                io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock | io::ErrorKind::Interrupted
+            ),
+            _ => false,
+        }
+    }
+}
+
+/// Malformed JSON (capture files, saved state)
+impl From<serde_json::Error> for DanteSyncError {
+    fn from(err: serde_json::Error) -> Self {
+        DanteSyncError::Parse(err.to_string())
+    }
+}
+
+#[cfg(unix)]
+impl From<nix::errno::Errno> for DanteSyncError {
+    fn from(errno: nix::errno::Errno) -> Self {
+        DanteSyncError::Io(errno.into())
+    }
+}
+
+/// Win32 errors keep their code; ERROR_ACCESS_DENIED maps to
+/// `is_permission_denied`
+#[cfg(windows)]
+impl From<windows::core::Error> for DanteSyncError {
+    fn from(err: windows::core::Error) -> Self {
+        DanteSyncError::Io(err.into())
+    }
+}
+
+#[cfg(windows)]
+impl From<pcap::Error> for DanteSyncError {
+    fn from(err: pcap::Error) -> Self {
+        DanteSyncError::Io(io::Error::new(io::ErrorKind::Other, err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_classes() {
+        let denied =
+            DanteSyncError::io("adjtimex", io::Error::from(io::ErrorKind::PermissionDenied));
+        assert!(denied.is_permission_denied());
+        assert!(matches!(denied, DanteSyncError::PermissionDenied(_)));
+        assert!(denied.to_string().starts_with("adjtimex: "));
+
+        let timeout = DanteSyncError::NtpTimeout("timeout".to_string());
+        assert!(timeout.is_transient());
+        assert!(!timeout.is_permission_denied());
+        assert_eq!(timeout.to_string(), "timeout");
+
+        let io_err: DanteSyncError = io::Error::from(io::ErrorKind::WouldBlock).into();
+        assert!(io_err.is_transient());
+        assert!(!DanteSyncError::Parse("bad".to_string()).is_transient());
+    }
+}
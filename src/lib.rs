@@ -1,15 +1,39 @@
+pub mod capture;
 pub mod clock;
 pub mod config;
 pub mod controller;
+pub mod error;
+pub mod logfile;
 pub mod net;
 pub mod ntp;
 pub mod ptp;
+pub mod registry;
+pub mod samplelog;
+pub mod selftest;
+pub mod simulate;
+pub mod sniff;
 pub mod spike_filter;
 pub mod status;
+pub mod telemetry;
 pub mod traits;
 
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
+#[cfg(unix)]
+pub mod net_packet;
+
 #[cfg(windows)]
 pub mod net_pcap;
 
 #[cfg(windows)]
 pub mod net_winsock;
+
+// Embedding API: implement these traits to drive the controller from your own
+// clock, packet source or UTC reference
+pub use clock::SystemClock;
+pub use config::SystemConfig;
+pub use controller::{PtpController, PtpControllerBuilder};
+pub use error::DanteSyncError;
+pub use status::SyncStatus;
+pub use traits::{NtpSource, PtpNetwork};
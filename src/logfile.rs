@@ -0,0 +1,292 @@
+//! Size-based rotating log file
+//!
+//! Long-running services must not fill the disk. The writer tracks bytes
+//! written and rotates at runtime once the active file exceeds `max_bytes`:
+//! `dantesync.log` → `dantesync.log.1` → ... → `dantesync.log.N` (oldest
+//! deleted). With `max_files = 0` the active file is simply truncated.
+//!
+//! External rotation (logrotate) is supported too: after the file has been
+//! moved away, `RotatingFileWriter::reopen_all()` makes every writer reopen
+//! its path on the next write.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Bumped by `reopen_all()`; writers compare it with the value they opened at
+static REOPEN_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+pub struct RotatingFileWriter {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    file: File,
+    written: u64,
+    /// Written at the top of every new file (e.g. a CSV column header)
+    header: Option<Vec<u8>>,
+    generation: u64,
+}
+
+impl RotatingFileWriter {
+    /// Open (append) the log file. An existing oversized file is rotated
+    /// on the first write.
+    pub fn open(path: impl AsRef<Path>, max_bytes: u64, max_files: usize) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(dir) = path.parent() {
+            if !dir.as_os_str().is_empty() {
+                fs::create_dir_all(dir)?;
+            }
+        }
+        let file = Self::open_append(&path)?;
+        let written = file.metadata().map(|m| m.len()).unwrap_or(0);
+        Ok(RotatingFileWriter {
+            path,
+            max_bytes,
+            max_files,
+            file,
+            written,
+            header: None,
+            generation: REOPEN_GENERATION.load(Ordering::SeqCst),
+        })
+    }
+
+    /// Ask every writer to reopen its file before the next write
+    /// (e.g. on SIGHUP after logrotate moved the files)
+    pub fn reopen_all() {
+        REOPEN_GENERATION.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Start every file with `header`: now if the file is empty, and after
+    /// each rotation.
+    pub fn with_header(mut self, header: impl Into<Vec<u8>>) -> io::Result<Self> {
+        let header = header.into();
+        if self.written == 0 {
+            self.file.write_all(&header)?;
+            self.written = header.len() as u64;
+        }
+        self.header = Some(header);
+        Ok(self)
+    }
+
+    /// Path of the n-th rotated file (`dantesync.log.1`, `dantesync.log.2`, ...)
+    pub fn rotated_path(path: &Path, n: usize) -> PathBuf {
+        let mut name = path.as_os_str().to_os_string();
+        name.push(format!(".{}", n));
+        PathBuf::from(name)
+    }
+
+    fn open_append(path: &Path) -> io::Result<File> {
+        OpenOptions::new().create(true).append(true).open(path)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+
+        if self.max_files == 0 {
+            self.file = OpenOptions::new()
+                .create(true)
+                .write(true)
+                .truncate(true)
+                .open(&self.path)?;
+            self.written = 0;
+            return self.write_header();
+        }
+
+        // Shift log.(N-1) → log.N, ..., log → log.1 (oldest falls off)
+        let _ = fs::remove_file(Self::rotated_path(&self.path, self.max_files));
+        for n in (1..self.max_files).rev() {
+            let from = Self::rotated_path(&self.path, n);
+            if from.exists() {
+                fs::rename(&from, Self::rotated_path(&self.path, n + 1))?;
+            }
+        }
+        fs::rename(&self.path, Self::rotated_path(&self.path, 1))?;
+
+        self.file = Self::open_append(&self.path)?;
+        self.written = 0;
+        self.write_header()
+    }
+
+    fn reopen(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        self.file = Self::open_append(&self.path)?;
+        self.written = self.file.metadata().map(|m| m.len()).unwrap_or(0);
+        if self.written == 0 {
+            self.write_header()?;
+        }
+        Ok(())
+    }
+
+    fn write_header(&mut self) -> io::Result<()> {
+        if let Some(header) = &self.header {
+            self.file.write_all(header)?;
+            self.written = header.len() as u64;
+        }
+        Ok(())
+    }
+}
+
+impl Write for RotatingFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let generation = REOPEN_GENERATION.load(Ordering::SeqCst);
+        if generation != self.generation {
+            self.generation = generation;
+            if let Err(e) = self.reopen() {
+                eprintln!("Log reopen failed for {:?}: {}", self.path, e);
+            }
+        }
+        let header_len = self.header.as_ref().map_or(0, |h| h.len() as u64);
+        if self.max_bytes > 0
+            && self.written > header_len
+            && self.written + buf.len() as u64 > self.max_bytes
+        {
+            // Rotation failure must not stop logging - keep appending
+            if let Err(e) = self.rotate() {
+                eprintln!("Log rotation failed for {:?}: {}", self.path, e);
+            }
+        }
+        let n = self.file.write(buf)?;
+        self.written += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotated_path() {
+        let p = Path::new("/var/log/dantesync/dantesync.log");
+        assert_eq!(
+            RotatingFileWriter::rotated_path(p, 2),
+            PathBuf::from("/var/log/dantesync/dantesync.log.2")
+        );
+    }
+
+    #[test]
+    fn test_rotation_keeps_max_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dantesync.log");
+        let mut w = RotatingFileWriter::open(&path, 100, 2).unwrap();
+
+        // 5 writes of 60 bytes each: every write after the first rotates
+        for i in 0..5 {
+            let line = format!("{:059}\n", i);
+            w.write_all(line.as_bytes()).unwrap();
+        }
+        w.flush().unwrap();
+
+        let active = fs::read_to_string(&path).unwrap();
+        let rot1 = fs::read_to_string(RotatingFileWriter::rotated_path(&path, 1)).unwrap();
+        let rot2 = fs::read_to_string(RotatingFileWriter::rotated_path(&path, 2)).unwrap();
+        assert!(active.trim_end().ends_with('4'));
+        assert!(rot1.trim_end().ends_with('3'));
+        assert!(rot2.trim_end().ends_with('2'));
+        assert!(!RotatingFileWriter::rotated_path(&path, 3).exists());
+    }
+
+    #[test]
+    fn test_rotation_zero_files_truncates() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dantesync.log");
+        let mut w = RotatingFileWriter::open(&path, 10, 0).unwrap();
+
+        w.write_all(b"first line\n").unwrap();
+        w.write_all(b"second\n").unwrap();
+        w.flush().unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second\n");
+        assert!(!RotatingFileWriter::rotated_path(&path, 1).exists());
+    }
+
+    #[test]
+    fn test_existing_oversized_file_rotated_on_first_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dantesync.log");
+        fs::write(&path, vec![b'x'; 500]).unwrap();
+
+        let mut w = RotatingFileWriter::open(&path, 100, 3).unwrap();
+        w.write_all(b"fresh\n").unwrap();
+        w.flush().unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "fresh\n");
+        assert_eq!(
+            fs::metadata(RotatingFileWriter::rotated_path(&path, 1))
+                .unwrap()
+                .len(),
+            500
+        );
+    }
+
+    #[test]
+    fn test_zero_max_bytes_disables_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dantesync.log");
+        let mut w = RotatingFileWriter::open(&path, 0, 3).unwrap();
+        for _ in 0..10 {
+            w.write_all(b"0123456789\n").unwrap();
+        }
+        w.flush().unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().len(), 110);
+        assert!(!RotatingFileWriter::rotated_path(&path, 1).exists());
+    }
+
+    #[test]
+    fn test_header_written_to_every_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("samples.csv");
+        let mut w = RotatingFileWriter::open(&path, 30, 1)
+            .unwrap()
+            .with_header("a,b\n")
+            .unwrap();
+        w.write_all(b"1,2222222222222\n").unwrap();
+        w.write_all(b"3,4444444444444\n").unwrap();
+        w.flush().unwrap();
+        drop(w);
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "a,b\n3,4444444444444\n");
+        assert_eq!(
+            fs::read_to_string(RotatingFileWriter::rotated_path(&path, 1)).unwrap(),
+            "a,b\n1,2222222222222\n"
+        );
+
+        // Reopening a non-empty file does not repeat the header
+        let w = RotatingFileWriter::open(&path, 0, 1)
+            .unwrap()
+            .with_header("a,b\n")
+            .unwrap();
+        drop(w);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "a,b\n3,4444444444444\n");
+    }
+
+    #[test]
+    fn test_reopen_after_external_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("samples.csv");
+        let moved = dir.path().join("samples.csv.1");
+        let mut w = RotatingFileWriter::open(&path, 0, 1)
+            .unwrap()
+            .with_header("a,b\n")
+            .unwrap();
+        w.write_all(b"1,2\n").unwrap();
+
+        // logrotate moves the file, then signals us
+        fs::rename(&path, &moved).unwrap();
+        RotatingFileWriter::reopen_all();
+        w.write_all(b"3,4\n").unwrap();
+        w.flush().unwrap();
+
+        assert_eq!(fs::read_to_string(&moved).unwrap(), "a,b\n1,2\n");
+        assert_eq!(fs::read_to_string(&path).unwrap(), "a,b\n3,4\n");
+    }
+}
@@ -1,15 +1,16 @@
-use anyhow::Result;
-use clap::Parser;
+use anyhow::{anyhow, Result};
+use clap::{ArgAction, Parser, Subcommand};
 use log::{error, info, warn};
 use std::fs::File;
+use std::net::Ipv4Addr;
+use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex, RwLock};
 use std::thread;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
-#[cfg(unix)]
-use anyhow::anyhow;
 #[cfg(unix)]
 use nix::fcntl::{flock, FlockArg};
 #[cfg(unix)]
@@ -18,14 +19,13 @@ use std::io::ErrorKind;
 use std::net::UdpSocket;
 #[cfg(unix)]
 use std::os::unix::io::AsRawFd;
-#[cfg(unix)]
-use std::time::SystemTime;
 
 #[cfg(windows)]
 use windows::Win32::Media::timeBeginPeriod;
 #[cfg(windows)]
 use windows::Win32::System::Threading::{
-    GetCurrentProcess, SetPriorityClass, HIGH_PRIORITY_CLASS, REALTIME_PRIORITY_CLASS,
+    GetCurrentProcess, GetCurrentThread, SetPriorityClass, SetThreadAffinityMask,
+    HIGH_PRIORITY_CLASS, REALTIME_PRIORITY_CLASS,
 };
 
 #[cfg(windows)]
@@ -47,7 +47,7 @@ use windows::Win32::System::Pipes::{CreateNamedPipeW, NAMED_PIPE_MODE};
 
 // Constants for Pipe (Manual definition to avoid import issues)
 #[cfg(windows)]
-const PIPE_ACCESS_OUTBOUND: u32 = 0x00000002;
+const PIPE_ACCESS_DUPLEX: u32 = 0x00000003;
 #[cfg(windows)]
 const FILE_FLAG_OVERLAPPED: u32 = 0x40000000;
 #[cfg(windows)]
@@ -74,18 +74,29 @@ use windows_service::{
 };
 
 // Use library crate modules
-#[cfg(windows)]
-use dantesync::net_pcap;
+use dantesync::capture::{read_capture_file, CaptureDumper, CapturingNetwork, PacketRing};
+use dantesync::logfile::RotatingFileWriter;
+use dantesync::samplelog::{CsvSampleLog, SampleFifo};
+use dantesync::telemetry::TelemetrySender;
+use dantesync::{
+    clock, config, controller, error, net, ntp, selftest, simulate, sniff, status, traits,
+};
 #[cfg(unix)]
-use dantesync::ptp;
-use dantesync::{clock, config, controller, net, ntp, status, traits};
+use dantesync::{net_packet, ptp};
+#[cfg(windows)]
+use dantesync::{net_pcap, net_winsock};
 
-use config::SystemConfig;
+use config::{
+    DelayMechanism, NtpSourceKind, PriorityClass, PriorityConfig, QosConfig, RealtimePriorityMode,
+    ReceiveBackend, SystemConfig,
+};
 use controller::PtpController;
 use serde::{Deserialize, Serialize};
-use status::SyncStatus;
+use status::{
+    instance_suffix, IpcCommand, IpcEncoding, SyncStatus, MAX_IPC_COMMAND_BYTES,
+    MAX_IPC_STATUS_BYTES, STATUS_SCHEMA_VERSION,
+};
 use traits::NtpSource;
-#[cfg(unix)]
 use traits::PtpNetwork;
 
 /// Simplified configuration - only NTP server needs to be managed
@@ -108,51 +119,289 @@ impl Default for Config {
     }
 }
 
-fn load_config() -> Config {
+/// Config file location: `--config` / `DANTESYNC_CONFIG`, else the platform default
+fn config_path(args: &Args) -> PathBuf {
     #[cfg(windows)]
-    let path = r"C:\ProgramData\DanteSync\config.json";
+    let default = r"C:\ProgramData\DanteSync\config.json";
     #[cfg(not(windows))]
-    let path = "/etc/dantesync/config.json";
+    let default = "/etc/dantesync/config.json";
 
-    if let Ok(content) = std::fs::read_to_string(path) {
-        if let Ok(cfg) = serde_json::from_str::<Config>(&content) {
-            return cfg;
+    args.config
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(default))
+}
+
+/// Load config.json, returning the config plus any problems found
+///
+/// Problems are returned (not logged) because logging is not initialized yet.
+/// The default file is only written when none exists - a file that fails to
+/// parse is left untouched so the operator's settings are not lost.
+fn load_config(path: &Path) -> (Config, Vec<String>) {
+    let display = path.display();
+    let mut problems = Vec::new();
+
+    match std::fs::read_to_string(path) {
+        Ok(content) => match serde_json::from_str::<Config>(&content) {
+            Ok(mut cfg) => {
+                if cfg.ntp_server.trim().is_empty() {
+                    problems.push(format!("{}: ntp_server must not be empty", display));
+                    cfg.ntp_server = Config::default().ntp_server;
+                }
+                if let Err(errors) = cfg.system.validate() {
+                    for e in errors {
+                        problems.push(format!("{}: system.{}", display, e));
+                    }
+                    problems.push("Using default system settings".to_string());
+                    cfg.system = SystemConfig::default();
+                }
+                return (cfg, problems);
+            }
+            Err(e) => {
+                // serde_json errors include "at line X column Y"
+                problems.push(format!("{}: {}", display, e));
+                problems.push("Using defaults; config file left unchanged".to_string());
+                return (Config::default(), problems);
+            }
+        },
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            problems.push(format!("{}: cannot read: {}", display, e));
+            return (Config::default(), problems);
         }
+        Err(_) => {}
     }
 
-    // Create simple config with only ntp_server (system defaults auto-apply)
-    let cfg = Config::default();
+    // No config file yet: create simple config with only ntp_server
+    // (system defaults auto-apply)
     let simple_config = r#"{
   "ntp_server": "10.77.8.2"
 }"#;
     let _ = std::fs::write(path, simple_config);
-    cfg
+    (Config::default(), problems)
 }
 
 #[derive(Parser, Debug, Clone)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    #[arg(short, long)]
+    /// Interface name, IPv4 address, or CIDR subnet (e.g. 10.77.8.0/24)
+    #[arg(short, long, env = "DANTESYNC_INTERFACE")]
     interface: Option<String>,
 
-    #[arg(long, default_value = "10.77.8.2")]
+    /// NTP server [default: ntp_server from config.json]
+    #[arg(long = "ntp-server", env = "DANTESYNC_NTP_SERVER")]
+    ntp_server_override: Option<String>,
+
+    /// Config file [default: /etc/dantesync/config.json, C:\ProgramData\DanteSync\config.json]
+    #[arg(long, env = "DANTESYNC_CONFIG")]
+    config: Option<PathBuf>,
+
+    /// Effective NTP server, resolved by `Args::apply_config`
+    #[arg(skip)]
     ntp_server: String,
 
+    /// ntp_server came from config.json (not --ntp-server), so reloads may change it
+    #[arg(skip)]
+    ntp_server_from_config: bool,
+
+    /// Skip the one-time NTP step at startup (periodic tracking still runs)
     #[arg(long, default_value_t = false)]
     skip_ntp: bool,
 
+    /// Never step the clock from NTP (PTP frequency matching only).
+    /// Implies --skip-ntp. Same as "ntp_tracking": false in config.
+    #[arg(long, default_value_t = false)]
+    no_ntp_tracking: bool,
+
     #[arg(long, default_value_t = false)]
     service: bool,
+
+    /// More log detail: -v = debug, -vv = trace (overrides logging.level)
+    #[arg(short, long, action = ArgAction::Count)]
+    verbose: u8,
+
+    /// Log level: off, error, warn, info, debug, trace (overrides -v)
+    #[arg(long, value_parser = parse_log_level)]
+    log_level: Option<log::LevelFilter>,
+
+    /// Print the full config (config.json merged with all defaults) as JSON and exit.
+    /// `dantesync --dump-config > config.json` gives an editable template;
+    /// field descriptions are in config.schema.json.
+    #[arg(long, default_value_t = false)]
+    dump_config: bool,
+
+    /// Print the settings actually in effect (defaults, config.json,
+    /// environment and command line merged as the service would) as JSON and exit
+    #[arg(long, default_value_t = false)]
+    print_effective_config: bool,
+
+    /// Run as a named instance with its own lock file and IPC pipe/socket, so
+    /// several can run side by side (e.g. one per Dante network). Only one
+    /// instance may discipline the clock; start the others with --observe
+    #[arg(
+        long,
+        global = true,
+        env = "DANTESYNC_INSTANCE",
+        value_parser = parse_instance_name
+    )]
+    instance_name: Option<String>,
+
+    /// Sync and report as usual, but never adjust or step the system clock
+    #[arg(long, default_value_t = false)]
+    observe: bool,
+
+    /// Run without root/admin against an in-memory clock and a synthetic
+    /// Dante master, logging what would be done to the real clock
+    #[arg(long, default_value_t = false)]
+    simulate: bool,
+
+    /// With --simulate: replay this capture (.json dump or pcap) instead of
+    /// the synthetic master
+    #[arg(long, value_name = "FILE", requires = "simulate")]
+    simulate_capture: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<Commands>,
+}
+
+impl Args {
+    /// Resolve settings with precedence CLI > environment > config.json > default
+    /// (clap already applies CLI > environment)
+    fn apply_config(&mut self, config: &Config) {
+        match &self.ntp_server_override {
+            Some(server) => {
+                self.ntp_server = server.clone();
+                self.ntp_server_from_config = false;
+            }
+            None => {
+                self.ntp_server = config.ntp_server.clone();
+                self.ntp_server_from_config = true;
+            }
+        }
+    }
+}
+
+/// Settings in effect after merging defaults, config.json, environment and
+/// command line (`--print-effective-config`)
+#[derive(Serialize)]
+struct EffectiveConfig<'a> {
+    config_file: PathBuf,
+    ntp_server: &'a str,
+    /// --interface (None = auto-select by `interface_preference`)
+    interface: Option<&'a str>,
+    instance_name: Option<&'a str>,
+    observe: bool,
+    /// No NTP step at startup
+    skip_ntp: bool,
+    system: SystemConfig,
+}
+
+impl<'a> EffectiveConfig<'a> {
+    /// Apply the overrides `run_sync_loop` applies to `config` (after
+    /// `Args::apply_config`)
+    fn new(args: &'a Args, config: &Config) -> Self {
+        let mut system = config.system.clone();
+        system.logging.level = effective_log_level(args, &system.logging)
+            .to_string()
+            .to_lowercase();
+        #[cfg(windows)]
+        if args.service && system.logging.file.is_none() {
+            system.logging.file = Some(SERVICE_LOG_FILE.to_string());
+        }
+        system.ntp_tracking &= !(args.no_ntp_tracking || args.observe);
+
+        EffectiveConfig {
+            config_file: config_path(args),
+            ntp_server: &args.ntp_server,
+            interface: args.interface.as_deref(),
+            instance_name: args.instance_name.as_deref(),
+            observe: args.observe,
+            skip_ntp: args.skip_ntp || args.observe || !system.ntp_tracking,
+            system,
+        }
+    }
+}
+
+fn parse_instance_name(value: &str) -> std::result::Result<String, String> {
+    status::validate_instance_name(value).map(|()| value.to_string())
+}
+
+fn parse_log_level(value: &str) -> std::result::Result<log::LevelFilter, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid log level {:?}", value))
+}
+
+/// Effective log level: --log-level, then -v/-vv, then `logging.level` in config
+fn effective_log_level(args: &Args, log_cfg: &config::LogConfig) -> log::LevelFilter {
+    match (args.log_level, args.verbose) {
+        (Some(level), _) => level,
+        (None, 0) => log_cfg.level_filter(),
+        (None, 1) => log::LevelFilter::Debug,
+        (None, _) => log::LevelFilter::Trace,
+    }
+}
+
+#[derive(Subcommand, Debug, Clone)]
+enum Commands {
+    /// Receive PTP packets and validate timestamping precision (pass/fail)
+    Selftest {
+        /// Number of Sync/FollowUp pairs to measure
+        #[arg(long, default_value_t = 100)]
+        packets: usize,
+
+        /// Give up after this many seconds
+        #[arg(long, default_value_t = 60)]
+        timeout_secs: u64,
+    },
+    /// Print decoded PTP packets live without touching the clock
+    Sniff {
+        /// Stop after this many Sync/Follow_Up/Announce packets (0 = until Ctrl+C)
+        #[arg(long, default_value_t = 0)]
+        packets: u64,
+    },
+    /// Show candidate network interfaces for --interface
+    ListInterfaces,
+    /// Probe the running service (exit 0 = healthy, 1 = unhealthy, 2 = unreachable)
+    Healthcheck {
+        /// Also require the servo to be locked
+        #[arg(long)]
+        require_lock: bool,
+
+        /// Status older than this counts as a stalled sync loop
+        #[arg(long, default_value_t = 30)]
+        max_age_secs: u64,
+    },
+    /// Print the running service's learned servo model as JSON (fleet collection)
+    ServoModel,
 }
 
 // Concrete Implementations for Traits
-struct RealNtpSource {
-    client: ntp::NtpClient,
+enum RealNtpSource {
+    Udp(ntp::NtpClient),
+    Chrony(ntp::ChronyClient),
 }
 
 impl NtpSource for RealNtpSource {
-    fn get_offset(&self) -> Result<(Duration, i8)> {
-        self.client.get_offset()
+    fn get_offset(&self) -> error::Result<(Duration, i8)> {
+        match self {
+            RealNtpSource::Udp(client) => client.get_offset(),
+            RealNtpSource::Chrony(client) => client.get_offset(),
+        }
+    }
+
+    fn quality(&self) -> Option<ntp::NtpQuality> {
+        match self {
+            RealNtpSource::Udp(client) => client.quality(),
+            RealNtpSource::Chrony(client) => client.quality(),
+        }
+    }
+}
+
+impl RealNtpSource {
+    fn leap_second(&self) -> Option<clock::LeapSecond> {
+        match self {
+            RealNtpSource::Udp(client) => client.leap_second(),
+            RealNtpSource::Chrony(client) => client.leap_second(),
+        }
     }
 }
 
@@ -165,7 +414,7 @@ struct RealPtpNetwork {
 
 #[cfg(unix)]
 impl PtpNetwork for RealPtpNetwork {
-    fn recv_packet(&mut self) -> Result<Option<(Vec<u8>, usize, SystemTime)>> {
+    fn recv_packet(&mut self) -> error::Result<Option<(Vec<u8>, usize, SystemTime)>> {
         let mut buf = [0u8; 2048];
 
         // Check Event Socket first
@@ -189,7 +438,7 @@ impl PtpNetwork for RealPtpNetwork {
         Ok(None)
     }
 
-    fn reset(&mut self) -> Result<()> {
+    fn reset(&mut self) -> error::Result<()> {
         // Drain buffers to prevent processing old packets after a clock step
         let mut buf = [0u8; 2048];
         loop {
@@ -210,10 +459,80 @@ impl PtpNetwork for RealPtpNetwork {
     }
 }
 
+// Linux reads PTP from UDP sockets by default; `receive_backend: "af_packet"`
+// captures it from the interface instead, like Npcap on Windows.
+#[cfg(unix)]
+enum UnixPtpNetwork {
+    Udp(RealPtpNetwork),
+    Packet(net_packet::PacketPtpNetwork),
+}
+
+#[cfg(unix)]
+impl PtpNetwork for UnixPtpNetwork {
+    fn recv_packet(&mut self) -> error::Result<Option<(Vec<u8>, usize, SystemTime)>> {
+        match self {
+            UnixPtpNetwork::Udp(net) => net.recv_packet(),
+            UnixPtpNetwork::Packet(net) => net.recv_packet(),
+        }
+    }
+
+    fn reset(&mut self) -> error::Result<()> {
+        match self {
+            UnixPtpNetwork::Udp(net) => net.reset(),
+            UnixPtpNetwork::Packet(net) => net.reset(),
+        }
+    }
+
+    fn timestamp_source(&self) -> (String, bool) {
+        match self {
+            UnixPtpNetwork::Udp(net) => net.timestamp_source(),
+            UnixPtpNetwork::Packet(net) => net.timestamp_source(),
+        }
+    }
+}
+
 // Windows uses Npcap for precise packet timestamps with HostHighPrec mode
-// See net_pcap::NpcapPtpNetwork - uses KeQuerySystemTimePrecise() for synchronized timestamps
+// See net_pcap::NpcapPtpNetwork - uses KeQuerySystemTimePrecise() for synchronized timestamps.
+// Without Npcap it degrades to Winsock sockets (SO_TIMESTAMP where available).
+#[cfg(windows)]
+enum WindowsPtpNetwork {
+    Npcap(net_pcap::NpcapPtpNetwork),
+    Winsock(net_winsock::WinsockPtpNetwork),
+}
+
+#[cfg(windows)]
+impl PtpNetwork for WindowsPtpNetwork {
+    fn recv_packet(&mut self) -> error::Result<Option<(Vec<u8>, usize, std::time::SystemTime)>> {
+        match self {
+            WindowsPtpNetwork::Npcap(net) => net.recv_packet(),
+            WindowsPtpNetwork::Winsock(net) => net.recv_packet(),
+        }
+    }
+
+    fn reset(&mut self) -> error::Result<()> {
+        match self {
+            WindowsPtpNetwork::Npcap(net) => net.reset(),
+            WindowsPtpNetwork::Winsock(net) => net.reset(),
+        }
+    }
+
+    fn timestamp_source(&self) -> (String, bool) {
+        match self {
+            WindowsPtpNetwork::Npcap(net) => net.timestamp_source(),
+            WindowsPtpNetwork::Winsock(net) => net.timestamp_source(),
+        }
+    }
+}
+
+fn stop_conflicting_services(manage: bool, ntp_source: NtpSourceKind) {
+    if !manage {
+        #[cfg(windows)]
+        warn!("manage_conflicting_services is off - W32Time left untouched; make sure it does not adjust the clock");
+        #[cfg(unix)]
+        warn!("manage_conflicting_services is off - system NTP (timedatectl) left untouched; make sure it does not adjust the clock");
+        return;
+    }
 
-fn stop_conflicting_services() {
     #[cfg(windows)]
     {
         info!("Attempting to stop W32Time service...");
@@ -222,13 +541,25 @@ fn stop_conflicting_services() {
                 if out.status.success() {
                     info!("W32Time stopped successfully.");
                 } else {
-                    // Ignore errors if already stopped
+                    // Usually "service is not started" - nothing to stop
+                    info!(
+                        "W32Time not stopped: {}",
+                        String::from_utf8_lossy(&out.stderr).trim()
+                    );
                 }
             }
             Err(e) => warn!("Failed to execute 'net stop w32time': {}", e),
         }
     }
 
+    // chronyd is our UTC source here: `set-ntp false` would stop it. It must
+    // run with -x so it does not discipline the clock alongside the servo.
+    #[cfg(unix)]
+    if ntp_source == NtpSourceKind::Chrony {
+        info!("ntp_source is chrony - leaving chronyd running; it must run with -x (no clock control)");
+        return;
+    }
+
     #[cfg(unix)]
     {
         info!("Ensuring system NTP is disabled (timedatectl set-ntp false)...");
@@ -236,43 +567,111 @@ fn stop_conflicting_services() {
             .args(["set-ntp", "false"])
             .output()
         {
-            Ok(_) => info!("NTP service disabled via timedatectl."),
+            Ok(out) if out.status.success() => info!("NTP service disabled via timedatectl."),
+            Ok(out) => warn!(
+                "timedatectl set-ntp false failed (ignoring): {}",
+                String::from_utf8_lossy(&out.stderr).trim()
+            ),
             Err(e) => warn!("Failed to disable NTP via timedatectl (ignoring): {}", e),
         }
     }
 }
 
-fn enable_realtime_priority() {
+/// Apply `priority`. Errors only when `realtime_priority` is "require" and
+/// the scheduler class could not be set.
+fn enable_realtime_priority(cfg: &PriorityConfig) -> Result<()> {
+    let mut failure: Option<String> = None;
+    if cfg.realtime_priority == RealtimePriorityMode::Off {
+        info!("Priority: scheduler class left unchanged (realtime_priority off).");
+    }
     #[cfg(unix)]
     {
         unsafe {
-            let policy = libc::SCHED_FIFO;
-            let param = libc::sched_param { sched_priority: 50 };
+            match cfg.class {
+                _ if cfg.realtime_priority == RealtimePriorityMode::Off => {}
+                PriorityClass::Realtime => {
+                    let prio = cfg.rt_priority.clamp(1, 99);
+                    let param = libc::sched_param {
+                        sched_priority: prio,
+                    };
 
-            if libc::sched_setscheduler(0, policy, &param) == 0 {
-                info!("Realtime priority (SCHED_FIFO, 50) enabled successfully.");
-            } else {
-                let err = std::io::Error::last_os_error();
-                warn!(
-                    "Failed to set realtime priority: {}. Latency might suffer.",
-                    err
-                );
+                    if libc::sched_setscheduler(0, libc::SCHED_FIFO, &param) == 0 {
+                        info!(
+                            "Realtime priority (SCHED_FIFO, {}) enabled successfully.",
+                            prio
+                        );
+                    } else {
+                        let err = std::io::Error::last_os_error();
+                        failure = Some(format!("Failed to set realtime priority: {}", err));
+                    }
+                }
+                PriorityClass::High => {
+                    if libc::setpriority(libc::PRIO_PROCESS, 0, -10) == 0 {
+                        info!("High priority (nice -10) enabled successfully.");
+                    } else {
+                        let err = std::io::Error::last_os_error();
+                        failure = Some(format!("Failed to set high priority: {}", err));
+                    }
+                }
+                PriorityClass::Normal => info!("Priority: normal (scheduler defaults)."),
+            }
+
+            if let Some(cpu) = cfg.cpu_affinity {
+                if cpu >= libc::CPU_SETSIZE as usize {
+                    warn!("CPU affinity {} out of range, not pinning.", cpu);
+                } else {
+                    let mut set: libc::cpu_set_t = std::mem::zeroed();
+                    libc::CPU_SET(cpu, &mut set);
+                    if libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) == 0
+                    {
+                        info!("Sync thread pinned to CPU {}.", cpu);
+                    } else {
+                        let err = std::io::Error::last_os_error();
+                        warn!("Failed to pin sync thread to CPU {}: {}", cpu, err);
+                    }
+                }
             }
         }
     }
     #[cfg(windows)]
     {
         unsafe {
-            if SetPriorityClass(GetCurrentProcess(), REALTIME_PRIORITY_CLASS).is_ok() {
-                info!("Windows Realtime Priority enabled.");
-            } else {
-                warn!("Failed to set Windows Realtime Priority. Trying High...");
-                if SetPriorityClass(GetCurrentProcess(), HIGH_PRIORITY_CLASS).is_ok() {
-                    info!("Windows High Priority enabled.");
+            match cfg.class {
+                _ if cfg.realtime_priority == RealtimePriorityMode::Off => {}
+                PriorityClass::Realtime => {
+                    if SetPriorityClass(GetCurrentProcess(), REALTIME_PRIORITY_CLASS).is_ok() {
+                        info!("Windows Realtime Priority enabled.");
+                    } else if cfg.realtime_priority == RealtimePriorityMode::Require {
+                        failure = Some("Failed to set Windows Realtime Priority".to_string());
+                    } else {
+                        warn!("Failed to set Windows Realtime Priority. Trying High...");
+                        if SetPriorityClass(GetCurrentProcess(), HIGH_PRIORITY_CLASS).is_ok() {
+                            info!("Windows High Priority enabled.");
+                        } else {
+                            failure = Some("Failed to set Windows priority".to_string());
+                        }
+                    }
+                }
+                PriorityClass::High => {
+                    if SetPriorityClass(GetCurrentProcess(), HIGH_PRIORITY_CLASS).is_ok() {
+                        info!("Windows High Priority enabled.");
+                    } else {
+                        failure = Some("Failed to set Windows priority".to_string());
+                    }
+                }
+                PriorityClass::Normal => info!("Priority: normal (scheduler defaults)."),
+            }
+
+            if let Some(cpu) = cfg.cpu_affinity {
+                if cpu >= usize::BITS as usize {
+                    warn!("CPU affinity {} out of range, not pinning.", cpu);
+                } else if SetThreadAffinityMask(GetCurrentThread(), 1usize << cpu) != 0 {
+                    info!("Sync thread pinned to CPU {}.", cpu);
                 } else {
-                    warn!("Failed to set Windows priority.");
+                    warn!("Failed to pin sync thread to CPU {}.", cpu);
                 }
             }
+
             if timeBeginPeriod(1) == 0 {
                 info!("Windows High-Res Timer (1ms) enabled.");
             } else {
@@ -280,13 +679,25 @@ fn enable_realtime_priority() {
             }
         }
     }
+
+    match failure {
+        Some(msg) if cfg.realtime_priority == RealtimePriorityMode::Require => Err(anyhow!(
+            "{} (priority.realtime_priority is \"require\")",
+            msg
+        )),
+        Some(msg) => {
+            warn!("{}. Latency might suffer.", msg);
+            Ok(())
+        }
+        None => Ok(()),
+    }
 }
 
-fn acquire_singleton_lock() -> Result<File> {
+fn acquire_singleton_lock(instance: Option<&str>) -> Result<File> {
     #[cfg(unix)]
     {
-        let lock_path = "/var/run/dantesync.lock";
-        let file = File::create(lock_path)
+        let lock_path = format!("/var/run/dantesync{}.lock", instance_suffix(instance));
+        let file = File::create(&lock_path)
             .map_err(|e| anyhow!("Failed to create lock file {}: {}", lock_path, e))?;
 
         match flock(file.as_raw_fd(), FlockArg::LockExclusiveNonblock) {
@@ -304,14 +715,141 @@ fn acquire_singleton_lock() -> Result<File> {
         // But File::create opens/truncates.
         // We want shared read, exclusive write?
         // Simple create is fine for now if we hold the handle.
-        let file = File::create("dantesync.lock")?;
+        let file = File::create(format!("dantesync{}.lock", instance_suffix(instance)))?;
         Ok(file)
     }
 }
 
-// --- IPC Server (Windows) ---
+// --- IPC Server ---
+/// How long a client may take to send its command after the status frame
+#[cfg(unix)]
+const IPC_COMMAND_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Pipe creation retry delay: doubles per failure up to the cap
+#[cfg(windows)]
+const IPC_RETRY_MIN: Duration = Duration::from_secs(1);
+#[cfg(windows)]
+const IPC_RETRY_MAX: Duration = Duration::from_secs(30);
+/// Consecutive failures before the pipe is reported as broken (logged once)
+#[cfg(windows)]
+const IPC_FAILURES_BEFORE_ERROR: u32 = 5;
+
+/// Status frame written to a connected pipe client while it stays connected
+#[cfg(windows)]
+const IPC_STREAM_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Write one status frame: raw JSON until the client negotiated an
+/// encoding (see `IpcEncoding`)
+#[cfg(windows)]
+async fn write_status_frame<W: tokio::io::AsyncWrite + Unpin>(
+    writer: &mut W,
+    status: &RwLock<SyncStatus>,
+    encoding: Option<IpcEncoding>,
+) -> std::io::Result<()> {
+    use std::io::{Error, ErrorKind};
+    use tokio::io::AsyncWriteExt;
+
+    // Handle poisoned lock gracefully instead of panicking
+    let s = match status.read() {
+        Ok(guard) => guard.clone(),
+        Err(e) => {
+            error!("Status lock poisoned: {}. Skipping IPC write.", e);
+            return Ok(());
+        }
+    };
+    let bytes = match encoding {
+        None => serde_json::to_vec(&s).map_err(|e| Error::new(ErrorKind::InvalidData, e))?,
+        Some(encoding) => encoding
+            .encode_status(&s)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))?,
+    };
+    writer
+        .write_all(&(bytes.len() as u32).to_le_bytes())
+        .await?;
+    writer.write_all(&bytes).await
+}
+
+/// Serve one pipe client: stream the status every `IPC_STREAM_INTERVAL`
+/// until it disconnects (one-shot clients read a frame and close). Frames
+/// from the client are an encoding request, answered at once and used for
+/// later frames, or a command.
+#[cfg(windows)]
+async fn serve_ipc_client(
+    server: NamedPipeServer,
+    status: Arc<RwLock<SyncStatus>>,
+    commands: Sender<IpcCommand>,
+) {
+    use tokio::io::AsyncReadExt;
+
+    let (mut reader, mut writer) = tokio::io::split(server);
+    // Client frames are read on their own task: a partial read must not be
+    // cancelled by the stream timer
+    let (frames_tx, mut frames_rx) = tokio::sync::mpsc::channel::<Vec<u8>>(4);
+    let read_task = tokio::spawn(async move {
+        loop {
+            let mut len_buf = [0u8; 4];
+            if reader.read_exact(&mut len_buf).await.is_err() {
+                return;
+            }
+            let len = u32::from_le_bytes(len_buf) as usize;
+            if len > MAX_IPC_COMMAND_BYTES {
+                warn!("[IPC] Command frame too large ({} bytes)", len);
+                return;
+            }
+            let mut buf = vec![0u8; len];
+            if reader.read_exact(&mut buf).await.is_err() || frames_tx.send(buf).await.is_err() {
+                return;
+            }
+        }
+    });
+
+    let mut encoding = None;
+    let mut reading = true;
+    let mut ticker = tokio::time::interval(IPC_STREAM_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                if write_status_frame(&mut writer, &status, encoding).await.is_err() {
+                    break; // client gone
+                }
+            }
+            frame = frames_rx.recv(), if reading => match frame {
+                Some(buf) if buf.len() == 1 => {
+                    encoding = Some(IpcEncoding::negotiate(buf[0]));
+                    if write_status_frame(&mut writer, &status, encoding).await.is_err() {
+                        break;
+                    }
+                }
+                Some(buf) => match serde_json::from_slice::<IpcCommand>(&buf) {
+                    Ok(cmd) => {
+                        let _ = commands.send(cmd);
+                    }
+                    Err(e) => warn!("[IPC] Ignoring unknown command: {}", e),
+                },
+                // Read side closed; the next write notices a disconnect
+                None => reading = false,
+            },
+        }
+    }
+    read_task.abort();
+}
+
 #[cfg(windows)]
-fn start_ipc_server(status: Arc<RwLock<SyncStatus>>) {
+fn start_ipc_server(
+    status: Arc<RwLock<SyncStatus>>,
+    commands: Sender<IpcCommand>,
+    access_sid: &str,
+    instance: Option<&str>,
+) {
+    let pipe_name = status::ipc_pipe_name(instance);
+    // SDDL: Grant full control to SYSTEM (service), read/write to `access_sid` (tray)
+    // GA = Generic All (for SYSTEM to write), GR/GW = Generic Read/Write
+    // (users read status and send IpcCommands - no elevation needed)
+    // SY = SYSTEM, default AU = Authenticated Users (system.ipc_access_sid)
+    // MUST include SYSTEM explicitly since custom DACL overrides defaults
+    let sddl = format!("D:(A;;GA;;;SY)(A;;GRGW;;;{})", access_sid);
+    info!("[IPC] Pipe access: SYSTEM + {}", access_sid);
+
     thread::spawn(move || {
         let rt = tokio::runtime::Builder::new_current_thread()
             .enable_all()
@@ -319,22 +857,19 @@ fn start_ipc_server(status: Arc<RwLock<SyncStatus>>) {
             .expect("Failed to build tokio runtime for IPC");
 
         rt.block_on(async move {
-            use tokio::io::AsyncWriteExt;
-
             // Pre-allocate UTF-16 strings outside loop for performance
-            let pipe_name_wide: Vec<u16> = r"\\.\pipe\dantesync"
+            let pipe_name_wide: Vec<u16> = pipe_name
                 .encode_utf16()
                 .chain(std::iter::once(0))
                 .collect();
-            // SDDL: Grant full control to SYSTEM (service), read to Authenticated Users (tray)
-            // GA = Generic All (for SYSTEM to write), GR = Generic Read (for users to read)
-            // SY = SYSTEM, AU = Authenticated Users
-            // MUST include SYSTEM explicitly since custom DACL overrides defaults
-            let sddl_wide: Vec<u16> = "D:(A;;GA;;;SY)(A;;GR;;;AU)"
+            let sddl_wide: Vec<u16> = sddl
                 .encode_utf16()
                 .chain(std::iter::once(0))
                 .collect();
 
+            let mut retry_delay = IPC_RETRY_MIN;
+            let mut failures = 0u32;
+
             // Named pipe server loop
             loop {
                 // Create pipe manually with Security Descriptor to allow Users to connect to Service
@@ -346,194 +881,1284 @@ fn start_ipc_server(status: Arc<RwLock<SyncStatus>>) {
                     bInheritHandle: false.into(),
                 };
 
-                let handle = unsafe {
-                    if ConvertStringSecurityDescriptorToSecurityDescriptorW(
+                let created: Result<NamedPipeServer, String> = unsafe {
+                    match ConvertStringSecurityDescriptorToSecurityDescriptorW(
                         PCWSTR(sddl_wide.as_ptr()),
                         SDDL_REVISION_1,
                         &mut sd,
                         None,
-                    )
-                    .is_ok()
-                    {
-                        sa.lpSecurityDescriptor = sd.0;
-
-                        let h = CreateNamedPipeW(
-                            PCWSTR(pipe_name_wide.as_ptr()),
-                            FILE_FLAGS_AND_ATTRIBUTES(PIPE_ACCESS_OUTBOUND | FILE_FLAG_OVERLAPPED),
-                            NAMED_PIPE_MODE(0), // Byte mode (0) for Tokio compatibility
-                            PIPE_UNLIMITED_INSTANCES,
-                            1024,
-                            1024,
-                            0,
-                            Some(&sa),
-                        );
-
-                        let _ = LocalFree(std::mem::transmute(sd));
-                        h
-                    } else {
-                        // Fallback if SDDL fails (shouldn't happen)
-                        windows::Win32::Foundation::INVALID_HANDLE_VALUE
+                    ) {
+                        Ok(()) => {
+                            sa.lpSecurityDescriptor = sd.0;
+
+                            let h = CreateNamedPipeW(
+                                PCWSTR(pipe_name_wide.as_ptr()),
+                                FILE_FLAGS_AND_ATTRIBUTES(PIPE_ACCESS_DUPLEX | FILE_FLAG_OVERLAPPED),
+                                NAMED_PIPE_MODE(0), // Byte mode (0) for Tokio compatibility
+                                PIPE_UNLIMITED_INSTANCES,
+                                1024,
+                                1024,
+                                0,
+                                Some(&sa),
+                            );
+                            // GetLastError before LocalFree can overwrite it
+                            let create_err = std::io::Error::last_os_error();
+
+                            let _ = LocalFree(std::mem::transmute(sd));
+                            if h == windows::Win32::Foundation::INVALID_HANDLE_VALUE {
+                                Err(format!("CreateNamedPipeW failed: {}", create_err))
+                            } else {
+                                // Wrap in Tokio
+                                NamedPipeServer::from_raw_handle(h.0 as *mut std::ffi::c_void)
+                                    .map_err(|e| format!("cannot wrap pipe handle: {}", e))
+                            }
+                        }
+                        Err(e) => Err(format!("SDDL conversion failed: {}", e)),
                     }
                 };
 
-                if handle == windows::Win32::Foundation::INVALID_HANDLE_VALUE {
-                    error!("Failed to create named pipe with SDDL. Retrying...");
-                    tokio::time::sleep(Duration::from_secs(1)).await;
-                    continue;
-                }
-
-                // Wrap in Tokio
-                let mut server = unsafe {
-                    match NamedPipeServer::from_raw_handle(handle.0 as *mut std::ffi::c_void) {
-                        Ok(s) => s,
-                        Err(e) => {
-                            error!("Failed to wrap named pipe handle: {}", e);
-                            tokio::time::sleep(Duration::from_secs(1)).await;
-                            continue;
+                let server = match created {
+                    Ok(server) => {
+                        if failures >= IPC_FAILURES_BEFORE_ERROR {
+                            info!("[IPC] Named pipe recovered after {} failed attempts", failures);
+                        }
+                        failures = 0;
+                        retry_delay = IPC_RETRY_MIN;
+                        server
+                    }
+                    Err(e) => {
+                        failures += 1;
+                        if failures == IPC_FAILURES_BEFORE_ERROR {
+                            error!(
+                                "[IPC] Cannot create named pipe {}: {}. The tray app cannot connect \
+                                 (name collision or security policy?); retrying with backoff up to {}s",
+                                pipe_name,
+                                e,
+                                IPC_RETRY_MAX.as_secs()
+                            );
+                        } else if failures < IPC_FAILURES_BEFORE_ERROR {
+                            warn!("[IPC] {} - retrying in {}s", e, retry_delay.as_secs());
+                        } else {
+                            log::debug!("[IPC] {} (attempt {})", e, failures);
                         }
+                        tokio::time::sleep(retry_delay).await;
+                        retry_delay = (retry_delay * 2).min(IPC_RETRY_MAX);
+                        continue;
                     }
                 };
 
                 if server.connect().await.is_ok() {
-                    // Handle poisoned lock gracefully instead of panicking
-                    let s = match status.read() {
-                        Ok(guard) => guard.clone(),
-                        Err(e) => {
-                            error!("Status lock poisoned: {}. Skipping IPC write.", e);
-                            continue;
-                        }
-                    };
-                    if let Ok(bytes) = serde_json::to_vec(&s) {
-                        let len = (bytes.len() as u32).to_le_bytes();
-                        let _ = server.write_all(&len).await;
-                        let _ = server.write_all(&bytes).await;
-                    }
+                    // Serve in the background so the next instance is listening
+                    // while this client is connected (any number at once)
+                    tokio::spawn(serve_ipc_client(server, status.clone(), commands.clone()));
                 }
             }
         });
     });
 }
 
-#[cfg(not(windows))]
-fn start_ipc_server(_status: Arc<RwLock<SyncStatus>>) {
-    // No-op on Linux for now (or implement Unix Domain Socket)
-}
-
-// --- Sync Loop ---
-fn run_sync_loop(args: Args, running: Arc<AtomicBool>, system_config: SystemConfig) -> Result<()> {
-    // Notify systemd (Linux) that we are starting
-    #[cfg(unix)]
-    {
-        let _ = sd_notify::notify(
-            false,
-            &[sd_notify::NotifyState::Status(
-                format!("v{} | Starting...", env!("CARGO_PKG_VERSION")).as_str(),
-            )],
+#[cfg(unix)]
+fn start_ipc_server(
+    status: Arc<RwLock<SyncStatus>>,
+    commands: Sender<IpcCommand>,
+    _access_sid: &str,
+    instance: Option<&str>,
+) {
+    use std::os::unix::fs::PermissionsExt;
+    use std::os::unix::net::UnixListener;
+
+    // Callers hold the instance lock, so a leftover socket is from a dead
+    // instance. Still never unlink one that something is serving.
+    let socket_path = status::ipc_socket_path(instance);
+    if std::os::unix::net::UnixStream::connect(&socket_path).is_ok() {
+        warn!(
+            "[IPC] {} is served by another process - healthcheck unavailable",
+            socket_path
         );
+        return;
     }
-
-    // Initialize Shared Status
-    let status_shared = Arc::new(RwLock::new(SyncStatus::default()));
-
-    // Start IPC Server immediately (so Tray App can connect even if network is down)
-    start_ipc_server(status_shared.clone());
-
-    stop_conflicting_services();
-    enable_realtime_priority();
-
-    let sys_clock = match clock::PlatformClock::new() {
-        Ok(c) => c,
+    let _ = std::fs::remove_file(&socket_path);
+    let listener = match UnixListener::bind(&socket_path) {
+        Ok(listener) => listener,
         Err(e) => {
-            error!("Failed to initialize system clock adjustment: {}", e);
-            return Err(e);
+            warn!(
+                "[IPC] Cannot create {}: {} - healthcheck unavailable",
+                socket_path, e
+            );
+            return;
         }
     };
-    info!("System clock control initialized.");
-
-    // Network Interface Selection (Retry Loop)
-    let (iface_name, iface_ip) = loop {
-        match net::get_default_interface() {
-            Ok(res) => break res,
-            Err(e) => {
-                if !running.load(Ordering::SeqCst) {
-                    return Ok(());
+    // root and its group: status and runtime commands
+    if let Err(e) = std::fs::set_permissions(&socket_path, std::fs::Permissions::from_mode(0o660)) {
+        warn!("[IPC] Cannot restrict {}: {}", socket_path, e);
+    }
+    info!("[IPC] Status socket: {}", socket_path);
+
+    let _ = thread::Builder::new()
+        .name("ipc".to_string())
+        .spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        let status = status.clone();
+                        let commands = commands.clone();
+                        thread::spawn(move || serve_ipc_socket_client(stream, &status, &commands));
+                    }
+                    Err(e) => log::debug!("[IPC] Accept failed: {}", e),
                 }
-                warn!("Waiting for network interface... ({})", e);
-                thread::sleep(Duration::from_secs(5));
             }
-        }
-    };
-
-    // Platform-specific network setup
-    #[cfg(unix)]
-    let network = {
-        // Create sockets to join multicast groups (IGMP) with kernel timestamping
-        let sock_event = net::create_multicast_socket(ptp::PTP_EVENT_PORT, iface_ip)?;
-        let sock_general = net::create_multicast_socket(ptp::PTP_GENERAL_PORT, iface_ip)?;
-        info!(
-            "Joined Multicast Groups on {} ({}) - Kernel timestamping",
-            iface_name, iface_ip
-        );
+        });
+}
 
-        RealPtpNetwork {
-            sock_event,
-            sock_general,
+/// Unix socket version of `serve_ipc_client`
+#[cfg(unix)]
+fn serve_ipc_socket_client(
+    mut stream: std::os::unix::net::UnixStream,
+    status: &RwLock<SyncStatus>,
+    commands: &Sender<IpcCommand>,
+) {
+    use std::io::{Read, Write};
+
+    let s = match status.read() {
+        Ok(guard) => guard.clone(),
+        Err(e) => {
+            error!("Status lock poisoned: {}. Skipping IPC write.", e);
+            return;
         }
     };
+    if let Ok(bytes) = serde_json::to_vec(&s) {
+        let len = (bytes.len() as u32).to_le_bytes();
+        if stream.write_all(&len).is_err() || stream.write_all(&bytes).is_err() {
+            return;
+        }
+    }
 
-    #[cfg(windows)]
-    let network = {
-        // Use Npcap with HostHighPrec timestamps (KeQuerySystemTimePrecise)
-        // This provides driver-level timestamps that are both precise AND synced with system time
-        match net_pcap::NpcapPtpNetwork::new(&iface_name) {
-            Ok(npcap_net) => {
-                info!(
-                    "Using Npcap HostHighPrec timestamps on {} ({})",
-                    iface_name, iface_ip
-                );
-                npcap_net
+    let _ = stream.set_read_timeout(Some(IPC_COMMAND_TIMEOUT));
+    loop {
+        let mut len_buf = [0u8; 4];
+        if stream.read_exact(&mut len_buf).is_err() {
+            return; // status-only client
+        }
+        let len = u32::from_le_bytes(len_buf) as usize;
+        if len > MAX_IPC_COMMAND_BYTES {
+            warn!("[IPC] Command frame too large ({} bytes)", len);
+            return;
+        }
+        let mut buf = vec![0u8; len];
+        if stream.read_exact(&mut buf).is_err() {
+            return;
+        }
+        // Encoding request: the status again, in the negotiated encoding
+        if len == 1 {
+            let Ok(frame) = IpcEncoding::negotiate(buf[0]).encode_status(&s) else {
+                return;
+            };
+            let len = (frame.len() as u32).to_le_bytes();
+            if stream.write_all(&len).is_err() || stream.write_all(&frame).is_err() {
+                return;
             }
-            Err(e) => {
-                error!(
-                    "Failed to initialize Npcap: {}. Npcap is required on Windows.",
-                    e
-                );
-                return Err(e);
+            continue;
+        }
+        match serde_json::from_slice::<IpcCommand>(&buf) {
+            Ok(cmd) => {
+                let _ = commands.send(cmd);
             }
+            Err(e) => warn!("[IPC] Ignoring unknown command: {}", e),
         }
-    };
-
-    let ntp_source = RealNtpSource {
-        client: ntp::NtpClient::new(&args.ntp_server),
-    };
-
-    let mut controller =
-        PtpController::new(sys_clock, network, ntp_source, status_shared, system_config);
-
-    if !args.skip_ntp {
-        info!("Using NTP Server: {}", args.ntp_server);
+        return;
     }
-    controller.run_ntp_sync(args.skip_ntp);
+}
 
-    info!("Starting PTP Loop...");
+// --- Healthcheck ---
+/// Read one status frame from the running service (pipe / Unix socket)
+///
+/// Prefers the binary encoding when the service runs the same status
+/// schema; the initial JSON frame is used if that fails.
+fn read_service_status(instance: Option<&str>) -> std::io::Result<SyncStatus> {
+    use std::io::{Error, ErrorKind, Read, Write};
 
-    // Notify systemd we are ready and loop is running
+    #[cfg(windows)]
+    let mut stream = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(status::ipc_pipe_name(instance))?;
     #[cfg(unix)]
-    {
-        let _ = sd_notify::notify(
-            false,
-            &[
-                sd_notify::NotifyState::Ready,
-                sd_notify::NotifyState::Status(
-                    format!("v{} | PTP Loop Running", env!("CARGO_PKG_VERSION")).as_str(),
-                ),
-            ],
-        );
+    let mut stream = {
+        let stream = std::os::unix::net::UnixStream::connect(status::ipc_socket_path(instance))?;
+        stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+        stream
+    };
+
+    fn read_frame(stream: &mut impl Read) -> std::io::Result<Vec<u8>> {
+        let mut len_buf = [0u8; 4];
+        stream.read_exact(&mut len_buf)?;
+        let len = u32::from_le_bytes(len_buf) as usize;
+        if len > MAX_IPC_STATUS_BYTES {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("status frame too large ({} bytes)", len),
+            ));
+        }
+        let mut buf = vec![0u8; len];
+        stream.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+    let json = read_frame(&mut stream)?;
+    let status: SyncStatus =
+        serde_json::from_slice(&json).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+    if status.schema_version != STATUS_SCHEMA_VERSION {
+        return Ok(status);
     }
 
-    let mut last_log = Instant::now();
+    let request = [1, 0, 0, 0, IpcEncoding::LATEST as u8];
+    let negotiated = stream
+        .write_all(&request)
+        .and_then(|()| read_frame(&mut stream))
+        .ok()
+        .and_then(|frame| IpcEncoding::decode_status(&frame).ok());
+    Ok(negotiated.unwrap_or(status))
+}
+
+/// `dantesync healthcheck`: read-only probe for container liveness/readiness.
+/// Prints one line and returns the process exit code.
+fn run_healthcheck(instance: Option<&str>, require_lock: bool, max_age_secs: u64) -> i32 {
+    let status = match read_service_status(instance) {
+        Ok(status) => status,
+        Err(e) => {
+            eprintln!("UNREACHABLE: cannot read service status: {}", e);
+            return 2;
+        }
+    };
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let problems = status.health_problems(now, max_age_secs, require_lock);
+    if problems.is_empty() {
+        println!("OK: {}", status.summary());
+        0
+    } else {
+        eprintln!("UNHEALTHY: {}", problems.join("; "));
+        1
+    }
+}
+
+/// Print the learned servo model of the running service (exit 2 = unreachable)
+fn run_servo_model(instance: Option<&str>) -> i32 {
+    let status = match read_service_status(instance) {
+        Ok(status) => status,
+        Err(e) => {
+            eprintln!("UNREACHABLE: cannot read service status: {}", e);
+            return 2;
+        }
+    };
+    let model = status::ServoModel::from_status(&status, &machine_hostname(), instance);
+    match serde_json::to_string(&model) {
+        Ok(json) => {
+            println!("{}", json);
+            0
+        }
+        Err(e) => {
+            eprintln!("Cannot serialize servo model: {}", e);
+            1
+        }
+    }
+}
+
+/// Host name identifying this machine in collected records
+fn machine_hostname() -> String {
+    #[cfg(windows)]
+    let name = std::env::var("COMPUTERNAME").ok();
+    #[cfg(unix)]
+    let name = nix::unistd::gethostname()
+        .ok()
+        .and_then(|name| name.into_string().ok());
+    name.unwrap_or_else(|| "unknown".to_string())
+}
+
+// --- PTP Network Setup ---
+#[cfg(unix)]
+type PlatformNetwork = UnixPtpNetwork;
+#[cfg(windows)]
+type PlatformNetwork = WindowsPtpNetwork;
+
+#[cfg(unix)]
+fn open_ptp_network(
+    iface_name: &str,
+    iface_ip: Ipv4Addr,
+    qos: &QosConfig,
+    backend: ReceiveBackend,
+) -> Result<PlatformNetwork> {
+    if backend == ReceiveBackend::AfPacket {
+        match net_packet::PacketPtpNetwork::new(iface_name, iface_ip) {
+            Ok(packet_net) => {
+                info!("Using AF_PACKET capture on {} ({})", iface_name, iface_ip);
+                return Ok(UnixPtpNetwork::Packet(packet_net));
+            }
+            // Usually missing CAP_NET_RAW: keep syncing on the UDP path
+            Err(e) => warn!(
+                "Failed to initialize AF_PACKET capture: {:#}. Falling back to UDP sockets",
+                e
+            ),
+        }
+    }
+
+    // Create sockets to join multicast groups (IGMP) with kernel timestamping
+    let sock_event =
+        net::create_multicast_socket(ptp::PTP_EVENT_PORT, iface_name, iface_ip, qos.event())?;
+    let sock_general =
+        net::create_multicast_socket(ptp::PTP_GENERAL_PORT, iface_name, iface_ip, qos.general())?;
+    info!(
+        "Joined Multicast Groups on {} ({}) - Kernel timestamping",
+        iface_name, iface_ip
+    );
+
+    Ok(UnixPtpNetwork::Udp(RealPtpNetwork {
+        sock_event,
+        sock_general,
+    }))
+}
+
+#[cfg(windows)]
+fn open_ptp_network(
+    iface_name: &str,
+    iface_ip: Ipv4Addr,
+    qos: &QosConfig,
+    _backend: ReceiveBackend,
+) -> Result<PlatformNetwork> {
+    // Use Npcap with HostHighPrec timestamps (KeQuerySystemTimePrecise)
+    // This provides driver-level timestamps that are both precise AND synced with system time
+    let npcap_err =
+        match net_pcap::NpcapPtpNetwork::new(iface_name, iface_ip, qos.event(), qos.general()) {
+            Ok(npcap_net) => {
+                info!(
+                    "Using Npcap HostHighPrec timestamps on {} ({})",
+                    iface_name, iface_ip
+                );
+                return Ok(WindowsPtpNetwork::Npcap(npcap_net));
+            }
+            Err(e) => e,
+        };
+
+    // No Npcap (e.g. blocked by policy): keep NTP-disciplined time running on
+    // plain sockets rather than taking the service down
+    warn!(
+        "Failed to initialize Npcap: {}. Falling back to Winsock - PTP timestamp precision is degraded (install Npcap for best results)",
+        npcap_err
+    );
+    match net_winsock::WinsockPtpNetwork::new(iface_ip) {
+        Ok(winsock_net) => {
+            let (source, precise) = winsock_net.timestamp_source();
+            info!(
+                "Using {} timestamps on {} ({}){}",
+                source,
+                iface_name,
+                iface_ip,
+                if precise {
+                    ""
+                } else {
+                    " - expect higher jitter"
+                }
+            );
+            Ok(WindowsPtpNetwork::Winsock(winsock_net))
+        }
+        Err(e) => {
+            error!(
+                "Failed to initialize Winsock fallback: {}. No PTP network available (Npcap: {})",
+                e, npcap_err
+            );
+            Err(e.into())
+        }
+    }
+}
+
+/// Source address for NTP queries
+///
+/// Unset follows the PTP interface so NTP takes the same path as PTP on
+/// multi-homed hosts; "any" leaves routing to the OS; anything else is an
+/// interface selector for hosts where NTP deliberately lives elsewhere.
+fn resolve_ntp_bind_ip(setting: Option<&str>, ptp_ip: Ipv4Addr) -> Option<Ipv4Addr> {
+    let value = match setting {
+        None => {
+            info!("NTP bound to PTP interface ({})", ptp_ip);
+            return Some(ptp_ip);
+        }
+        Some(v) if v.eq_ignore_ascii_case("any") => {
+            info!("NTP uses OS routing (ntp_interface = any)");
+            return None;
+        }
+        Some(v) => v,
+    };
+    match net::InterfaceSelector::parse(value)
+        .and_then(|sel| net::get_default_interface(Some(&sel)))
+    {
+        Ok((name, ip)) => {
+            info!("NTP bound to {} ({})", name, ip);
+            Some(ip)
+        }
+        Err(e) => {
+            warn!(
+                "ntp_interface {:?} not usable ({}) - using OS routing",
+                value, e
+            );
+            None
+        }
+    }
+}
+
+/// How long external clock interference stays flagged in status
+#[cfg(windows)]
+const CLOCK_INTERFERENCE_WINDOW: Duration = Duration::from_secs(600);
+
+/// Retry interval while waiting for a usable interface or rebuilding the network
+const NETWORK_RETRY_SECS: u64 = 5;
+
+/// Pick the PTP interface and log why it was chosen
+fn select_ptp_interface(
+    selector: Option<&net::InterfaceSelector>,
+    policy: &net::InterfacePolicy,
+) -> Result<(String, Ipv4Addr)> {
+    let (name, ip, reason) = net::choose_interface(selector, policy)?;
+    info!("PTP interface: {} ({}) - {}", name, ip, reason);
+    Ok((name, ip))
+}
+
+/// Block until an interface matching `selector` is available (None on shutdown)
+fn wait_for_interface(
+    selector: Option<&net::InterfaceSelector>,
+    policy: &net::InterfacePolicy,
+    running: &AtomicBool,
+) -> Option<(String, Ipv4Addr)> {
+    loop {
+        match select_ptp_interface(selector, policy) {
+            Ok(res) => return Some(res),
+            Err(e) => {
+                if !running.load(Ordering::SeqCst) {
+                    return None;
+                }
+                warn!("Waiting for network interface... ({})", e);
+                thread::sleep(Duration::from_secs(NETWORK_RETRY_SECS));
+            }
+        }
+    }
+}
+
+// --- Watchdog ---
+/// Grace for the sync loop to stop after a stall before the process exits hard
+const WATCHDOG_EXIT_GRACE: Duration = Duration::from_secs(10);
+
+/// Proof of life from the sync loop, counted on the monotonic clock
+///
+/// The service steps the wall clock, so `updated_ts` cannot tell a stall:
+/// a backward NTP step makes a dead loop look fresh and a forward one makes
+/// a healthy loop look dead.
+#[derive(Clone)]
+struct Heartbeat {
+    origin: Instant,
+    last_ms: Arc<AtomicU64>,
+}
+
+impl Heartbeat {
+    fn new() -> Self {
+        Heartbeat {
+            origin: Instant::now(),
+            last_ms: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Called by the sync loop whenever the controller made progress
+    fn beat(&self) {
+        let now_ms = self.origin.elapsed().as_millis() as u64;
+        self.last_ms.store(now_ms, Ordering::Relaxed);
+    }
+
+    /// Time since the last beat
+    fn age(&self) -> Duration {
+        let now_ms = self.origin.elapsed().as_millis() as u64;
+        Duration::from_millis(now_ms.saturating_sub(self.last_ms.load(Ordering::Relaxed)))
+    }
+}
+
+/// React if the sync loop stops beating `heartbeat` for `timeout`.
+///
+/// Under systemd with `WatchdogSec` the thread pings WATCHDOG=1 while healthy
+/// and stops pinging when stalled, so systemd restarts the service. Without a
+/// systemd watchdog it sets `tripped` and stops the loop, so the process exits
+/// with an error for systemd `Restart=always` or SCM service recovery; a loop
+/// that does not stop within `WATCHDOG_EXIT_GRACE` is ended with `exit`.
+fn spawn_watchdog(
+    heartbeat: Heartbeat,
+    running: Arc<AtomicBool>,
+    tripped: Arc<AtomicBool>,
+    timeout: Duration,
+) {
+    #[cfg(unix)]
+    let systemd_watchdog_usec = {
+        let mut usec = 0u64;
+        if sd_notify::watchdog_enabled(false, &mut usec) {
+            Some(usec)
+        } else {
+            None
+        }
+    };
+    #[cfg(not(unix))]
+    let systemd_watchdog_usec: Option<u64> = None;
+
+    // Check often enough to ping systemd at half its interval
+    let mut check_interval = (timeout / 4).max(Duration::from_secs(1));
+    if let Some(usec) = systemd_watchdog_usec {
+        check_interval =
+            check_interval.min(Duration::from_micros(usec / 2).max(Duration::from_millis(100)));
+        info!("[Watchdog] systemd watchdog active ({}s)", usec / 1_000_000);
+    }
+    info!(
+        "[Watchdog] Restart if no progress for {}s",
+        timeout.as_secs()
+    );
+
+    let _ = thread::Builder::new()
+        .name("watchdog".to_string())
+        .spawn(move || {
+            let mut stalled_logged = false;
+            while running.load(Ordering::SeqCst) {
+                thread::sleep(check_interval);
+
+                let age = heartbeat.age();
+                if age < timeout {
+                    stalled_logged = false;
+                    #[cfg(unix)]
+                    if systemd_watchdog_usec.is_some() {
+                        let _ = sd_notify::notify(false, &[sd_notify::NotifyState::Watchdog]);
+                    }
+                    continue;
+                }
+
+                if !stalled_logged {
+                    error!(
+                        "[Watchdog] Sync loop stalled: no progress for {}s",
+                        age.as_secs()
+                    );
+                    stalled_logged = true;
+                }
+                if systemd_watchdog_usec.is_none() && running.load(Ordering::SeqCst) {
+                    error!("[Watchdog] Stopping so the service manager restarts DanteSync");
+                    tripped.store(true, Ordering::SeqCst);
+                    running.store(false, Ordering::SeqCst);
+                    thread::sleep(WATCHDOG_EXIT_GRACE);
+                    error!("[Watchdog] Sync loop did not stop - exiting");
+                    std::process::exit(2);
+                }
+                // systemd: stop pinging, WatchdogSec expiry restarts the service
+            }
+        });
+}
+
+// --- Registry status export ---
+/// Windows: copy key status values to `HKLM\SOFTWARE\DanteSync\Status` every
+/// `interval` for SCOM/Intune/WMI registry monitoring
+#[cfg(windows)]
+fn spawn_registry_status(
+    status: Arc<RwLock<SyncStatus>>,
+    running: Arc<AtomicBool>,
+    instance: Option<&str>,
+    interval: Duration,
+) {
+    let key = match dantesync::registry::RegistryStatus::open(instance) {
+        Ok(key) => key,
+        Err(e) => {
+            warn!("[Registry] Cannot open status key: {} - export disabled", e);
+            return;
+        }
+    };
+    info!(
+        "[Registry] Writing status to HKLM\\{} every {}s",
+        key.path(),
+        interval.as_secs()
+    );
+
+    let _ = thread::Builder::new()
+        .name("registry".to_string())
+        .spawn(move || {
+            let mut failed_logged = false;
+            while running.load(Ordering::SeqCst) {
+                let snapshot = match status.read() {
+                    Ok(s) => s.clone(),
+                    Err(_) => break,
+                };
+                match key.write(&snapshot) {
+                    Ok(()) => failed_logged = false,
+                    Err(e) if !failed_logged => {
+                        warn!("[Registry] Status write failed: {}", e);
+                        failed_logged = true;
+                    }
+                    Err(_) => {}
+                }
+                thread::sleep(interval);
+            }
+        });
+}
+
+/// Parse `--interface` into a selector (None = auto-select)
+fn interface_selector(args: &Args) -> Result<Option<net::InterfaceSelector>> {
+    Ok(args
+        .interface
+        .as_deref()
+        .map(net::InterfaceSelector::parse)
+        .transpose()?)
+}
+
+// --- Self-Test ---
+fn run_selftest(
+    args: &Args,
+    system: &SystemConfig,
+    packets: usize,
+    timeout_secs: u64,
+) -> Result<bool> {
+    let selector = interface_selector(args)?;
+    let (iface_name, iface_ip) =
+        select_ptp_interface(selector.as_ref(), &system.interface_policy())?;
+    let mut network = open_ptp_network(&iface_name, iface_ip, &system.qos, system.receive_backend)?;
+
+    info!(
+        "Self-test: waiting for {} PTP samples on {} ({}), timeout {}s...",
+        packets, iface_name, iface_ip, timeout_secs
+    );
+    let report = selftest::run(&mut network, packets, Duration::from_secs(timeout_secs))?;
+
+    info!("");
+    info!("=== Timestamping Self-Test ===");
+    info!(
+        "Timestamp source: {} ({})",
+        report.timestamp_source,
+        if report.backend_precise {
+            "active"
+        } else {
+            "NOT active"
+        }
+    );
+    info!("Samples:          {}/{}", report.samples, packets);
+    match report.jitter_sigma_us {
+        Some(sigma) => info!(
+            "Jitter sigma:     {:.2}us (limit {:.0}us)",
+            sigma,
+            selftest::MAX_JITTER_SIGMA_US
+        ),
+        None => info!("Jitter sigma:     n/a (not enough PTP traffic)"),
+    }
+    info!(
+        "Coarse ticks:     {:.0}% of timestamps",
+        report.coarse_fraction * 100.0
+    );
+    info!(
+        "RX latency:       {:.0}us median (timestamp vs system clock)",
+        report.median_rx_latency_us
+    );
+
+    let passed = report.passed();
+    info!("Result:           {}", if passed { "PASS" } else { "FAIL" });
+    Ok(passed)
+}
+
+// --- Live Packet Decode ---
+fn run_sniff(args: &Args, system: &SystemConfig, packets: u64) -> Result<()> {
+    let selector = interface_selector(args)?;
+    let (iface_name, iface_ip) =
+        select_ptp_interface(selector.as_ref(), &system.interface_policy())?;
+    let mut network = open_ptp_network(&iface_name, iface_ip, &system.qos, system.receive_backend)?;
+    let (timestamp_source, _) = network.timestamp_source();
+
+    info!(
+        "Sniffing PTP on {} ({}), timestamps: {} - Ctrl+C to stop",
+        iface_name, iface_ip, timestamp_source
+    );
+    let running = Arc::new(AtomicBool::new(true));
+    let r = running.clone();
+    ctrlc::set_handler(move || r.store(false, Ordering::SeqCst))?;
+
+    let summary = sniff::run(&mut network, packets, &running, &mut std::io::stdout())?;
+
+    info!("");
+    info!(
+        "Sync: {}  Follow_Up: {}  Announce: {}  other/malformed: {}",
+        summary.syncs, summary.follow_ups, summary.announces, summary.ignored
+    );
+    match summary.sync_interval.jitter_ms() {
+        Some(jitter) => info!(
+            "Sync interval: {:.3}ms mean, {:.3}ms jitter",
+            summary.sync_interval.mean_ms(),
+            jitter
+        ),
+        None => info!("Sync interval: n/a (fewer than 3 Syncs)"),
+    }
+    Ok(())
+}
+
+// --- Simulation (--simulate) ---
+
+/// Virtual clock at startup: 250ms behind UTC, oscillator 25ppm fast
+const SIM_INITIAL_OFFSET_NS: f64 = -250_000_000.0;
+const SIM_NATURAL_DRIFT_PPM: f64 = 25.0;
+/// Receive timestamp noise of the synthetic master (software timestamps)
+const SIM_JITTER_NS: i64 = 20_000;
+
+/// PTP source for `--simulate`
+enum SimNetwork {
+    Synthetic(simulate::SyntheticPtpNetwork),
+    Replay(simulate::ReplayNetwork),
+}
+
+impl PtpNetwork for SimNetwork {
+    fn recv_packet(&mut self) -> error::Result<Option<(Vec<u8>, usize, SystemTime)>> {
+        match self {
+            SimNetwork::Synthetic(network) => network.recv_packet(),
+            SimNetwork::Replay(network) => network.recv_packet(),
+        }
+    }
+
+    fn timestamp_source(&self) -> (String, bool) {
+        match self {
+            SimNetwork::Synthetic(network) => network.timestamp_source(),
+            SimNetwork::Replay(network) => network.timestamp_source(),
+        }
+    }
+}
+
+impl SimNetwork {
+    /// A replay has handed out its last packet
+    fn finished(&self) -> bool {
+        matches!(self, SimNetwork::Replay(network) if network.remaining() == 0)
+    }
+}
+
+/// Instance a simulation runs as: never the service's own, so it cannot
+/// take over the status socket/pipe the tray and healthcheck read
+fn simulate_instance_name(instance: Option<&str>) -> String {
+    match instance {
+        Some(name) => format!("{}-simulate", name),
+        None => "simulate".to_string(),
+    }
+}
+
+/// The full sync pipeline, unprivileged: no real clock or sockets, no
+/// conflicting-service management. Status and IPC commands work as in the
+/// service, under the `simulate_instance_name` instance.
+fn run_simulated(args: Args, running: Arc<AtomicBool>, system_config: SystemConfig) -> Result<()> {
+    let clock = simulate::SimulatedClock::new(SIM_INITIAL_OFFSET_NS, SIM_NATURAL_DRIFT_PPM);
+    let network = match &args.simulate_capture {
+        Some(path) => {
+            let packets = read_capture_file(path)?;
+            info!(
+                "[Simulate] Replaying {} packets from {}",
+                packets.len(),
+                path.display()
+            );
+            SimNetwork::Replay(simulate::ReplayNetwork::new(packets))
+        }
+        None => {
+            info!(
+                "[Simulate] Synthetic Dante master; virtual clock {:+.0}ms from UTC, oscillator {:+.1}ppm",
+                SIM_INITIAL_OFFSET_NS / 1e6,
+                SIM_NATURAL_DRIFT_PPM
+            );
+            SimNetwork::Synthetic(simulate::SyntheticPtpNetwork::new(
+                clock.clone(),
+                SIM_JITTER_NS,
+            ))
+        }
+    };
+    warn!("[Simulate] The system clock is never touched");
+
+    let status_shared = Arc::new(RwLock::new(SyncStatus::default()));
+    let (command_tx, command_rx) = mpsc::channel::<IpcCommand>();
+    // IPC only with the instance lock held (it replaces a stale socket)
+    let instance = simulate_instance_name(args.instance_name.as_deref());
+    let _lock_file = match acquire_singleton_lock(Some(&instance)) {
+        Ok(file) => {
+            info!("[Simulate] Running as instance \"{}\"", instance);
+            start_ipc_server(
+                status_shared.clone(),
+                command_tx,
+                &system_config.ipc_access_sid,
+                Some(&instance),
+            );
+            Some(file)
+        }
+        Err(e) => {
+            warn!("[Simulate] {} - status socket/pipe disabled", e);
+            None
+        }
+    };
+
+    let ntp_tracking = system_config.ntp_tracking && !args.no_ntp_tracking;
+    let ntp = simulate::SimulatedNtp::new(clock.clone());
+    let mut controller =
+        PtpController::new(clock.clone(), network, ntp, status_shared, system_config);
+    if !ntp_tracking {
+        controller.set_ntp_tracking(false);
+    }
+    controller.run_ntp_sync(args.skip_ntp || !ntp_tracking);
+    controller.apply_initial_frequency();
+
+    info!("Starting PTP Loop...");
+    let mut last_log = Instant::now();
+    while running.load(Ordering::SeqCst) && !controller.network().finished() {
+        if last_log.elapsed() >= Duration::from_secs(10) {
+            controller.log_status();
+            info!(
+                "[Simulate] Virtual clock {:+.3}ms from UTC, correction {:+.3}ppm, residual drift {:+.3}ppm",
+                clock.offset_ns() / 1e6,
+                clock.adjust_ppm(),
+                clock.residual_drift_ppm()
+            );
+            last_log = Instant::now();
+        }
+
+        if let Err(e) = controller.process_loop_iteration() {
+            warn!("Error in loop: {}", e);
+        }
+        while let Ok(command) = command_rx.try_recv() {
+            controller.handle_command(command);
+        }
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    let (adjustments, steps) = clock.counts();
+    info!(
+        "[Simulate] Done: {} frequency adjustments, {} steps; virtual clock {:+.3}ms from UTC",
+        adjustments,
+        steps,
+        clock.offset_ns() / 1e6
+    );
+    Ok(())
+}
+
+// --- Interface Listing ---
+fn list_interfaces(system: &SystemConfig) -> Result<()> {
+    let interfaces = net::list_interfaces()?;
+    #[cfg(windows)]
+    let npcap_devices = net_pcap::npcap_devices().unwrap_or_else(|e| {
+        warn!("Npcap device enumeration failed: {}", e);
+        Vec::new()
+    });
+
+    if interfaces.is_empty() {
+        println!("No IPv4 interfaces found.");
+        return Ok(());
+    }
+
+    let auto = net::choose_interface(None, &system.interface_policy()).ok();
+
+    for iface in &interfaces {
+        let ips: Vec<String> = iface.ips.iter().map(|ip| ip.to_string()).collect();
+        let selected = auto
+            .as_ref()
+            .map(|(n, _, _)| n == &iface.name)
+            .unwrap_or(false);
+        println!(
+            "{}{}",
+            iface.name,
+            if selected { "  [auto-selected]" } else { "" }
+        );
+        println!("    IPv4:     {}", ips.join(", "));
+        println!(
+            "    Type:     {}",
+            if iface.wireless { "wireless" } else { "wired" }
+        );
+        println!(
+            "    Bindable: {}",
+            if iface.bindable { "yes" } else { "no" }
+        );
+
+        #[cfg(windows)]
+        match npcap_devices
+            .iter()
+            .find(|d| d.ips.iter().any(|ip| iface.ips.contains(ip)))
+        {
+            Some(dev) => {
+                println!(
+                    "    Desc:     {}",
+                    dev.desc.as_deref().unwrap_or("(no description)")
+                );
+                println!("    Npcap:    {}", dev.name);
+            }
+            None => println!("    Npcap:    (no matching device)"),
+        }
+    }
+
+    Ok(())
+}
+
+// --- Packet Capture Dump ---
+#[cfg(unix)]
+const CAPTURE_TRIGGER_HINT: &str = "kill -USR1 <pid>";
+#[cfg(windows)]
+const CAPTURE_TRIGGER_HINT: &str = "tray menu";
+#[cfg(windows)]
+const CAPTURE_TRIGGER_FILE: &str = r"C:\ProgramData\DanteSync\capture.trigger";
+
+#[cfg(unix)]
+static CAPTURE_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(unix)]
+extern "C" fn on_sigusr1(_: libc::c_int) {
+    // Only async-signal-safe work here; the sync loop does the dump
+    CAPTURE_REQUESTED.store(true, Ordering::SeqCst);
+}
+
+#[cfg(unix)]
+fn install_capture_signal_handler() {
+    unsafe {
+        libc::signal(
+            libc::SIGUSR1,
+            on_sigusr1 as extern "C" fn(libc::c_int) as libc::sighandler_t,
+        );
+    }
+}
+
+#[cfg(unix)]
+static RELOAD_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(unix)]
+extern "C" fn on_sighup(_: libc::c_int) {
+    // Only async-signal-safe work here; the sync loop does the reload
+    RELOAD_REQUESTED.store(true, Ordering::SeqCst);
+}
+
+#[cfg(unix)]
+fn install_reload_signal_handler() {
+    unsafe {
+        libc::signal(
+            libc::SIGHUP,
+            on_sighup as extern "C" fn(libc::c_int) as libc::sighandler_t,
+        );
+    }
+}
+
+/// SIGHUP: reopen log files (logrotate) and re-read config.json, applying
+/// what can change without a restart. Sync keeps running throughout.
+#[cfg(unix)]
+fn reload_config<C: clock::SystemClock, N: PtpNetwork>(
+    controller: &mut PtpController<C, N, RealNtpSource>,
+    args: &Args,
+) {
+    RotatingFileWriter::reopen_all();
+    info!("[Config] SIGHUP: log files reopened, reloading configuration");
+
+    let (config, problems) = load_config(&config_path(args));
+    if !problems.is_empty() {
+        for problem in &problems {
+            error!("[Config] {}", problem);
+        }
+        warn!("[Config] Reload rejected - keeping the running configuration");
+        return;
+    }
+
+    let old = controller.config().clone();
+    for key in old.restart_required_changes(&config.system) {
+        warn!(
+            "[Config] system.{} changed - takes effect after a restart",
+            key
+        );
+    }
+
+    // An explicit --ntp-server / DANTESYNC_NTP_SERVER keeps precedence over the file
+    if args.ntp_server_from_config {
+        if let RealNtpSource::Udp(client) = controller.ntp_source_mut() {
+            if client.server() != config.ntp_server {
+                info!(
+                    "[Config] NTP server: {} -> {}",
+                    client.server(),
+                    config.ntp_server
+                );
+                client.set_server(&config.ntp_server);
+            }
+        }
+    }
+
+    if let RealNtpSource::Udp(client) = controller.ntp_source_mut() {
+        client.set_query_policy(
+            Duration::from_millis(config.system.ntp_query_timeout_ms),
+            config.system.ntp_query_retries,
+        );
+    }
+
+    let ntp_tracking = config.system.ntp_tracking;
+    controller.apply_config(config.system);
+    if ntp_tracking != old.ntp_tracking && !args.no_ntp_tracking {
+        controller.set_ntp_tracking(ntp_tracking);
+    }
+}
+
+/// Directory for capture dumps (`system.capture.dir` or the platform default)
+fn capture_dir(cfg: &config::CaptureConfig) -> PathBuf {
+    #[cfg(windows)]
+    let default_dir = r"C:\ProgramData\DanteSync";
+    #[cfg(not(windows))]
+    let default_dir = "/var/log/dantesync";
+    PathBuf::from(cfg.dir.as_deref().unwrap_or(default_dir))
+}
+
+/// Queue a capture dump on the writer thread (see `CaptureDumper`)
+fn dump_capture(dumper: &mut CaptureDumper, cfg: &config::CaptureConfig, reason: &str) {
+    if cfg.packets == 0 {
+        warn!(
+            "[Capture] Dump requested ({}) but capture is disabled (system.capture.packets = 0)",
+            reason
+        );
+        return;
+    }
+    dumper.dump(reason);
+}
+
+/// Tell systemd startup is complete (Type=notify units)
+#[cfg(unix)]
+fn notify_ready(detail: &str) {
+    let _ = sd_notify::notify(
+        false,
+        &[
+            sd_notify::NotifyState::Ready,
+            sd_notify::NotifyState::Status(
+                format!("v{} | {}", env!("CARGO_PKG_VERSION"), detail).as_str(),
+            ),
+        ],
+    );
+}
+
+// --- Sync Loop ---
+fn run_sync_loop(args: Args, running: Arc<AtomicBool>, system_config: SystemConfig) -> Result<()> {
+    // Notify systemd (Linux) that we are starting
+    #[cfg(unix)]
+    {
+        let _ = sd_notify::notify(
+            false,
+            &[sd_notify::NotifyState::Status(
+                format!("v{} | Starting...", env!("CARGO_PKG_VERSION")).as_str(),
+            )],
+        );
+    }
+
+    // Initialize Shared Status
+    let status_shared = Arc::new(RwLock::new(SyncStatus::default()));
+
+    // Start IPC Server immediately (so Tray App can connect even if network is down)
+    let (command_tx, command_rx) = mpsc::channel::<IpcCommand>();
+    start_ipc_server(
+        status_shared.clone(),
+        command_tx,
+        &system_config.ipc_access_sid,
+        args.instance_name.as_deref(),
+    );
+
+    if let Some(name) = &args.instance_name {
+        info!("Instance: {}", name);
+    }
+    // An observer leaves the clock (and whoever disciplines it) alone
+    if args.observe {
+        warn!("[Observe] Observe-only: the system clock will not be adjusted or stepped");
+    } else {
+        stop_conflicting_services(
+            system_config.manage_conflicting_services,
+            system_config.ntp_source,
+        );
+    }
+    if let Err(e) = enable_realtime_priority(&system_config.priority) {
+        error!("{}", e);
+        return Err(e);
+    }
+
+    let sys_clock = match clock::PlatformClock::new() {
+        Ok(c) => c,
+        Err(e) => {
+            error!("Failed to initialize system clock adjustment: {}", e);
+            return Err(e.into());
+        }
+    };
+    info!("System clock control initialized.");
+    #[cfg(windows)]
+    let clock_interference = sys_clock.interference_handle();
+    // Observing: NTP would only try to step the clock
+    let sys_clock = clock::ObservableClock::new(sys_clock, args.observe);
+    let args = Args {
+        skip_ntp: args.skip_ntp || args.observe,
+        no_ntp_tracking: args.no_ntp_tracking || args.observe,
+        ..args
+    };
+
+    // Network Interface Selection (Retry Loop)
+    // --interface accepts a name, a literal IPv4 address, or a CIDR subnet
+    let selector = interface_selector(&args)?;
+    if let Some(ref sel) = selector {
+        info!("Interface selector: {}", sel);
+    }
+    let interface_policy = system_config.interface_policy();
+    let (mut iface_name, iface_ip) =
+        match wait_for_interface(selector.as_ref(), &interface_policy, &running) {
+            Some(res) => res,
+            None => return Ok(()),
+        };
+
+    let qos = system_config.qos;
+    let capture_cfg = system_config.capture.clone();
+    let capture_ring = Arc::new(Mutex::new(PacketRing::new(capture_cfg.packets)));
+    let mut capture_dumper = CaptureDumper::new(capture_ring.clone(), capture_dir(&capture_cfg));
+    if capture_cfg.packets > 0 {
+        info!(
+            "[Capture] Keeping last {} packets (dump: {})",
+            capture_cfg.packets, CAPTURE_TRIGGER_HINT
+        );
+    }
+    // Always installed: the default SIGUSR1 action would kill the service
+    #[cfg(unix)]
+    install_capture_signal_handler();
+    let receive_backend = system_config.receive_backend;
+    let network = CapturingNetwork::new(
+        open_ptp_network(&iface_name, iface_ip, &qos, receive_backend)?,
+        capture_ring.clone(),
+    );
+    #[cfg(unix)]
+    install_reload_signal_handler();
+
+    if system_config.delay_mechanism == DelayMechanism::P2p {
+        // Frequency sync needs no path delay; gPTP Sync itself is PTPv2 over
+        // Ethernet, which the UDP/PTPv1 receive path does not handle.
+        warn!("delay_mechanism \"p2p\": Pdelay messages are parsed but no Pdelay_Req is sent; sync remains PTPv1 frequency-only");
+    }
+
+    let ntp_source_kind = system_config.ntp_source;
+    let system_config_watchdog_secs = system_config.watchdog_secs;
+    #[cfg(unix)]
+    let ready_timeout_secs = system_config.ready_timeout_secs;
+    let ntp_interface = system_config.ntp_interface.clone();
+    let csv_log_path = system_config.csv_log_path.clone();
+    let sample_fifo_path = system_config.sample_fifo_path.clone();
+    let telemetry_target = system_config.telemetry_target;
+    let registry_status_secs = system_config.registry_status_secs;
+    let log_cfg = system_config.logging.clone();
+    let ntp_follows_ptp = ntp_interface.is_none();
+    let ntp_bind_ip = resolve_ntp_bind_ip(ntp_interface.as_deref(), iface_ip);
+    let ntp_source = match ntp_source_kind {
+        NtpSourceKind::Udp => {
+            let mut client = ntp::NtpClient::new(&args.ntp_server, ntp_bind_ip);
+            client.set_query_policy(
+                Duration::from_millis(system_config.ntp_query_timeout_ms),
+                system_config.ntp_query_retries,
+            );
+            RealNtpSource::Udp(client)
+        }
+        NtpSourceKind::Chrony => RealNtpSource::Chrony(ntp::ChronyClient::new()),
+    };
+
+    let ntp_tracking = system_config.ntp_tracking && !args.no_ntp_tracking;
+    let skip_ntp = args.skip_ntp || !ntp_tracking;
+
+    let mut controller =
+        PtpController::new(sys_clock, network, ntp_source, status_shared, system_config);
+
+    if !ntp_tracking {
+        controller.set_ntp_tracking(false);
+    }
+
+    if let Some(path) = &csv_log_path {
+        match CsvSampleLog::open(path, log_cfg.max_size_bytes, log_cfg.max_files) {
+            Ok(log) => {
+                info!("[CSV] Logging samples to {}", path);
+                controller.set_sample_log(log);
+            }
+            Err(e) => warn!("[CSV] Cannot open {}: {} - sample log disabled", path, e),
+        }
+    }
+    if let Some(path) = &sample_fifo_path {
+        match SampleFifo::create(path) {
+            Ok(fifo) => {
+                info!(
+                    "[FIFO] Streaming samples to {} while a reader is attached",
+                    path
+                );
+                controller.set_sample_fifo(fifo);
+            }
+            Err(e) => warn!(
+                "[FIFO] Cannot create {}: {} - sample stream disabled",
+                path, e
+            ),
+        }
+    }
+    if let Some(target) = telemetry_target {
+        match TelemetrySender::new(target) {
+            Ok(telemetry) => {
+                info!("[Telemetry] Sending status to udp://{}", target);
+                controller.set_telemetry(telemetry);
+            }
+            Err(e) => warn!(
+                "[Telemetry] Cannot open socket: {} - UDP export disabled",
+                e
+            ),
+        }
+    }
+
+    if !skip_ntp {
+        match ntp_source_kind {
+            NtpSourceKind::Udp => info!("Using NTP Server: {}", args.ntp_server),
+            NtpSourceKind::Chrony => info!("Using NTP offset from local chronyd"),
+        }
+    }
+    controller.run_ntp_sync(skip_ntp);
+    controller.apply_initial_frequency();
+
+    info!("Starting PTP Loop...");
+
+    // Hold back systemd READY until PTP sync is established, so units ordered
+    // After=dantesync start with synchronized time. NTP-only hosts still get
+    // READY once ready_timeout_secs passes.
+    #[cfg(unix)]
+    let mut ready_deadline = if ready_timeout_secs == 0 {
+        notify_ready("PTP Loop Running");
+        None
+    } else {
+        let _ = sd_notify::notify(
+            false,
+            &[sd_notify::NotifyState::Status(
+                format!("v{} | Waiting for PTP...", env!("CARGO_PKG_VERSION")).as_str(),
+            )],
+        );
+        Some(Instant::now() + Duration::from_secs(ready_timeout_secs))
+    };
+
+    let heartbeat = Heartbeat::new();
+    let watchdog_tripped = Arc::new(AtomicBool::new(false));
+    if system_config_watchdog_secs > 0 {
+        spawn_watchdog(
+            heartbeat.clone(),
+            running.clone(),
+            watchdog_tripped.clone(),
+            Duration::from_secs(system_config_watchdog_secs),
+        );
+    }
+    #[cfg(windows)]
+    if registry_status_secs > 0 {
+        spawn_registry_status(
+            controller.get_status_shared(),
+            running.clone(),
+            args.instance_name.as_deref(),
+            Duration::from_secs(registry_status_secs),
+        );
+    }
+    #[cfg(not(windows))]
+    if registry_status_secs > 0 {
+        warn!("[Registry] registry_status_secs is only supported on Windows - ignored");
+    }
+
+    let mut last_log = Instant::now();
+    let mut last_rebuild_attempt = Instant::now();
+    let mut last_unlock_count = controller.unlock_count();
+    let mut last_progress = controller.progress();
 
     while running.load(Ordering::SeqCst) {
+        // Beat on work done, not on every pass: a loop that keeps returning
+        // without receiving anything is stalled too
+        if controller.progress() != last_progress {
+            last_progress = controller.progress();
+            heartbeat.beat();
+        }
         if last_log.elapsed() >= Duration::from_secs(10) {
             controller.log_status();
 
@@ -543,11 +2168,7 @@ fn run_sync_loop(args: Args, running: Arc<AtomicBool>, system_config: SystemConf
                 let s = controller.get_status_shared();
                 if let Ok(status) = s.read() {
                     let status_str = if status.settled {
-                        format!(
-                            "v{} | Locked | Offset: {:.3} µs",
-                            env!("CARGO_PKG_VERSION"),
-                            status.offset_ns as f64 / 1000.0
-                        )
+                        format!("v{} | {}", env!("CARGO_PKG_VERSION"), status.summary())
                     } else {
                         format!("v{} | Settling...", env!("CARGO_PKG_VERSION"))
                     };
@@ -556,21 +2177,110 @@ fn run_sync_loop(args: Args, running: Arc<AtomicBool>, system_config: SystemConf
                 };
             }
 
+            // Tray "Save Packet Capture" drops a trigger file
+            #[cfg(windows)]
+            if std::fs::remove_file(CAPTURE_TRIGGER_FILE).is_ok() {
+                dump_capture(&mut capture_dumper, &capture_cfg, "tray request");
+            }
+
+            // Something (W32Time?) reset our clock adjustment recently
+            #[cfg(windows)]
+            {
+                let recent = clock_interference
+                    .last_detected()
+                    .and_then(|t| t.elapsed().ok())
+                    .is_some_and(|age| age < CLOCK_INTERFERENCE_WINDOW);
+                if let Ok(mut status) = controller.get_status_shared().write() {
+                    status.external_clock_interference = recent;
+                }
+            }
+
             last_log = Instant::now();
         }
 
+        let ntp_leap = controller.ntp_source_mut().leap_second();
+        controller.set_ntp_leap(ntp_leap);
         if let Err(e) = controller.process_loop_iteration() {
             warn!("Error in loop: {}", e);
         }
 
+        #[cfg(unix)]
+        if let Some(deadline) = ready_deadline {
+            if controller.is_settled() {
+                notify_ready("PTP Sync Established");
+                ready_deadline = None;
+            } else if Instant::now() >= deadline {
+                warn!(
+                    "[systemd] No PTP sync after {}s - signalling ready (NTP only)",
+                    ready_timeout_secs
+                );
+                notify_ready("No PTP - NTP only");
+                ready_deadline = None;
+            }
+        }
+
+        // Tray runtime controls (force NTP, recalibrate, reset servo, ...)
+        while let Ok(command) = command_rx.try_recv() {
+            controller.handle_command(command);
+        }
+
+        #[cfg(unix)]
+        if CAPTURE_REQUESTED.swap(false, Ordering::SeqCst) {
+            dump_capture(&mut capture_dumper, &capture_cfg, "SIGUSR1");
+        }
+        #[cfg(unix)]
+        if RELOAD_REQUESTED.swap(false, Ordering::SeqCst) {
+            reload_config(&mut controller, &args);
+        }
+        if controller.unlock_count() != last_unlock_count {
+            last_unlock_count = controller.unlock_count();
+            if capture_cfg.dump_on_unlock && capture_cfg.packets > 0 {
+                capture_dumper.dump_on_unlock();
+            }
+        }
+
+        // NIC unplugged / device removed: re-select interface and rebuild the
+        // network while the controller keeps its learned frequency. Retries
+        // without blocking the loop so status (and the watchdog) keep updating.
+        if controller.network_failed()
+            && last_rebuild_attempt.elapsed() >= Duration::from_secs(NETWORK_RETRY_SECS)
+        {
+            last_rebuild_attempt = Instant::now();
+            // Recovering from a lost NIC is progress; a restart would not help
+            heartbeat.beat();
+            warn!(
+                "[Net] Interface {} failing - re-selecting interface...",
+                iface_name
+            );
+            match select_ptp_interface(selector.as_ref(), &interface_policy) {
+                Ok((name, ip)) => match open_ptp_network(&name, ip, &qos, receive_backend) {
+                    Ok(network) => {
+                        controller
+                            .replace_network(CapturingNetwork::new(network, capture_ring.clone()));
+                        if ntp_follows_ptp {
+                            if let RealNtpSource::Udp(client) = controller.ntp_source_mut() {
+                                client.set_bind_ip(Some(ip));
+                            }
+                        }
+                        iface_name = name;
+                    }
+                    Err(e) => {
+                        warn!("[Net] Failed to open {} ({}): {}. Retrying...", name, ip, e)
+                    }
+                },
+                Err(e) => warn!("Waiting for network interface... ({})", e),
+            }
+        }
+
         // Platform-specific polling intervals:
-        // - Windows: 50µs tight polling for lower jitter with software timestamps.
-        //   This achieves ~5% CPU usage while maintaining <50µs precision.
-        //   Tested across Intel/AMD systems; tighter values increase CPU without benefit.
+        // - Windows: tight polling (`windows_poll_us`, default 50µs) for lower jitter
+        //   with software timestamps. 50µs achieves ~5% CPU usage while maintaining
+        //   <50µs precision; tested across Intel/AMD systems, tighter values increase
+        //   CPU without benefit. Read each pass so a config reload applies at once.
         // - Linux: 1ms is sufficient since we use kernel SO_TIMESTAMPNS which provides
         //   accurate timestamps regardless of polling frequency.
         #[cfg(windows)]
-        thread::sleep(Duration::from_micros(50));
+        thread::sleep(Duration::from_micros(controller.config().windows_poll_us));
         #[cfg(not(windows))]
         thread::sleep(Duration::from_millis(1));
     }
@@ -580,6 +2290,9 @@ fn run_sync_loop(args: Args, running: Arc<AtomicBool>, system_config: SystemConf
     {
         let _ = sd_notify::notify(false, &[sd_notify::NotifyState::Stopping]);
     }
+    if watchdog_tripped.load(Ordering::SeqCst) {
+        return Err(anyhow!("watchdog: sync loop stalled"));
+    }
     Ok(())
 }
 
@@ -590,12 +2303,18 @@ const SERVICE_NAME: &str = "dantesync";
 #[cfg(windows)]
 const SERVICE_TYPE: ServiceType = ServiceType::OWN_PROCESS;
 
+/// Service log when `logging.file` is not set
+#[cfg(windows)]
+const SERVICE_LOG_FILE: &str = r"C:\ProgramData\DanteSync\dantesync.log";
+
 #[cfg(windows)]
 fn my_service_main(_arguments: Vec<OsString>) {
     // We need to reload config or pass it?
     // Windows Service entry doesn't allow easy closure capture without unsafe global.
-    // But we can just reload it, it's cheap.
-    let config = load_config();
+    // But we can just reload it, it's cheap. Problems were already logged by main().
+    let mut args = Args::parse();
+    let (config, _) = load_config(&config_path(&args));
+    args.apply_config(&config);
 
     let (shutdown_tx, shutdown_rx) = std::sync::mpsc::channel();
 
@@ -626,9 +2345,6 @@ fn my_service_main(_arguments: Vec<OsString>) {
         process_id: None,
     });
 
-    // Use global args via simple parse if needed, but here we just need default or what logic needs
-    let args = Args::parse();
-
     let running = Arc::new(AtomicBool::new(true));
     let r = running.clone();
 
@@ -670,64 +2386,136 @@ fn run_service_logic(_args: Args, _config: Config) -> Result<()> {
 
 fn main() -> Result<()> {
     let mut args = Args::parse();
-    let config = load_config();
 
-    // Use config if arg is default
-    if args.ntp_server == "10.77.8.2" {
-        args.ntp_server = config.ntp_server.clone();
+    // Probes run often: no config, logging or lock file, just the status read
+    if let Some(Commands::Healthcheck {
+        require_lock,
+        max_age_secs,
+    }) = args.command
+    {
+        std::process::exit(run_healthcheck(
+            args.instance_name.as_deref(),
+            require_lock,
+            max_age_secs,
+        ));
+    }
+    if let Some(Commands::ServoModel) = args.command {
+        std::process::exit(run_servo_model(args.instance_name.as_deref()));
     }
+    let (config, config_problems) = load_config(&config_path(&args));
 
-    #[cfg(windows)]
-    if args.service {
-        // Initialize File Logging for Service
-        let log_path = r"C:\ProgramData\DanteSync\dantesync.log";
+    if args.dump_config {
+        // stdout is the config; problems go to stderr so redirection stays valid JSON
+        for problem in &config_problems {
+            eprintln!("[Config] {}", problem);
+        }
+        println!("{}", serde_json::to_string_pretty(&config)?);
+        return Ok(());
+    }
 
-        // Log Rotation (Simple: 1MB limit check on startup)
-        if let Ok(metadata) = std::fs::metadata(log_path) {
-            if metadata.len() > 1_000_000 {
-                let old_path = format!("{}.old", log_path);
-                let _ = std::fs::rename(log_path, old_path);
-            }
+    args.apply_config(&config);
+
+    if args.print_effective_config {
+        for problem in &config_problems {
+            eprintln!("[Config] {}", problem);
         }
+        let effective = EffectiveConfig::new(&args, &config);
+        println!("{}", serde_json::to_string_pretty(&effective)?);
+        return Ok(());
+    }
 
-        if let Ok(file) = std::fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .write(true)
-            .open(log_path)
+    #[cfg(windows)]
+    if args.service {
+        // Initialize File Logging for Service (size-based rotation at runtime)
+        let log_cfg = &config.system.logging;
+        let log_level = effective_log_level(&args, log_cfg);
+        let log_path = log_cfg
+            .file
+            .clone()
+            .unwrap_or_else(|| SERVICE_LOG_FILE.to_string());
+
+        if let Ok(writer) =
+            RotatingFileWriter::open(&log_path, log_cfg.max_size_bytes, log_cfg.max_files)
         {
-            let target = env_logger::Target::Pipe(Box::new(file));
+            let target = env_logger::Target::Pipe(Box::new(writer));
             env_logger::builder()
                 .target(target)
-                .filter_level(log::LevelFilter::Info)
+                .filter_level(log_level)
                 .format_timestamp_millis()
                 .format_target(false) // Remove module path from logs
                 .format_level(false) // Remove INFO/WARN prefix
                 .init();
         } else {
             // Fallback
-            env_logger::builder()
-                .filter_level(log::LevelFilter::Info)
-                .init();
+            env_logger::builder().filter_level(log_level).init();
         }
 
         info!("Service Started: v{}", env!("CARGO_PKG_VERSION"));
+        for problem in &config_problems {
+            error!("[Config] {}", problem);
+        }
         return run_service_logic(args, config);
     }
 
-    // Console Mode Logging (clean format)
-    env_logger::builder()
-        .format_timestamp(None)
+    // Console Mode Logging (clean format, or rotating file if configured)
+    let mut builder = env_logger::builder();
+    builder
         .format_target(false) // Remove module path
         .format_level(false) // Remove INFO/WARN prefix
-        .filter_level(log::LevelFilter::Info)
-        .init();
+        .filter_level(effective_log_level(&args, &config.system.logging));
+    let log_cfg = &config.system.logging;
+    let file_writer = log_cfg.file.as_ref().map(|path| {
+        RotatingFileWriter::open(path, log_cfg.max_size_bytes, log_cfg.max_files)
+            .map_err(|e| (path.clone(), e))
+    });
+    match file_writer {
+        Some(Ok(writer)) => {
+            builder
+                .target(env_logger::Target::Pipe(Box::new(writer)))
+                .format_timestamp_millis();
+        }
+        Some(Err((path, e))) => {
+            eprintln!("Cannot open log file {}: {} (logging to stderr)", path, e);
+            builder.format_timestamp(None);
+        }
+        None => {
+            builder.format_timestamp(None);
+        }
+    }
+    builder.init();
 
     // Log Version immediately
     info!("DanteSync v{}", env!("CARGO_PKG_VERSION"));
+    for problem in &config_problems {
+        error!("[Config] {}", problem);
+    }
+
+    match args.command {
+        Some(Commands::Selftest {
+            packets,
+            timeout_secs,
+        }) => {
+            let passed = run_selftest(&args, &config.system, packets, timeout_secs)?;
+            std::process::exit(if passed { 0 } else { 1 });
+        }
+        Some(Commands::Sniff { packets }) => {
+            return run_sniff(&args, &config.system, packets);
+        }
+        Some(Commands::ListInterfaces) => return list_interfaces(&config.system),
+        Some(Commands::Healthcheck { .. }) | Some(Commands::ServoModel) | None => {}
+    }
+
+    // Touches neither the clock nor privileged ports: runs next to the
+    // service under its own instance (see run_simulated)
+    if args.simulate {
+        let running = Arc::new(AtomicBool::new(true));
+        let r = running.clone();
+        ctrlc::set_handler(move || r.store(false, Ordering::SeqCst))?;
+        return run_simulated(args, running, config.system);
+    }
 
     // Console Mode
-    let _lock_file = match acquire_singleton_lock() {
+    let _lock_file = match acquire_singleton_lock(args.instance_name.as_deref()) {
         Ok(f) => f,
         Err(e) => {
             error!("{}", e);
@@ -1,40 +1,134 @@
-use anyhow::{anyhow, Result};
+use crate::error::{DanteSyncError, Result};
 use socket2::{Domain, Protocol, Socket, Type};
 use std::net::{IpAddr, Ipv4Addr, SocketAddrV4, UdpSocket};
 
 #[cfg(unix)]
 use nix::sys::socket::{setsockopt, sockopt};
 
-pub fn get_default_interface() -> Result<(String, Ipv4Addr)> {
-    let ifaces = if_addrs::get_if_addrs()?;
+/// How the operator identified the PTP interface (`--interface`)
+///
+/// Interface names are unstable on imaged fleets (GUIDs on Windows, renumbered
+/// `enpXsY` on Linux), so the selector can also be a literal IPv4 address or a
+/// CIDR subnet such as `10.77.8.0/24`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InterfaceSelector {
+    /// Match by interface name (case-insensitive)
+    Name(String),
+    /// Match the interface holding this exact IPv4 address
+    Ip(Ipv4Addr),
+    /// Match the first interface with an address inside this subnet
+    Cidr(Ipv4Addr, u8),
+}
 
-    let mut best_iface = None;
+impl InterfaceSelector {
+    /// Parse a name, literal IPv4 address, or CIDR (`addr/prefix`)
+    pub fn parse(value: &str) -> Result<Self> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(DanteSyncError::Parse(
+                "Interface selector is empty".to_string(),
+            ));
+        }
 
-    for iface in &ifaces {
-        // Skip loopback and non-IPv4
-        let ip = match iface.addr.ip() {
-            IpAddr::V4(ip) if !ip.is_loopback() => ip,
-            _ => continue,
-        };
+        if let Some((addr, prefix)) = value.split_once('/') {
+            let addr: Ipv4Addr = addr
+                .parse()
+                .map_err(|_| DanteSyncError::Parse(format!("Invalid CIDR address '{}'", value)))?;
+            let prefix: u8 = prefix
+                .parse()
+                .map_err(|_| DanteSyncError::Parse(format!("Invalid CIDR prefix '{}'", value)))?;
+            if prefix > 32 {
+                return Err(DanteSyncError::Parse(format!(
+                    "CIDR prefix must be 0-32, got /{}",
+                    prefix
+                )));
+            }
+            return Ok(InterfaceSelector::Cidr(addr, prefix));
+        }
+
+        if let Ok(ip) = value.parse::<Ipv4Addr>() {
+            return Ok(InterfaceSelector::Ip(ip));
+        }
 
-        // Skip wireless interfaces if possible
-        let name_lower = iface.name.to_lowercase();
-        let is_wireless = name_lower.contains("wireless")
-            || name_lower.contains("wi-fi")
-            || name_lower.contains("wlan");
-
-        // Verify we can actually bind to this IP
-        if is_ip_bindable(ip) {
-            if !is_wireless {
-                return Ok((iface.name.clone(), ip));
-            } else if best_iface.is_none() {
-                best_iface = Some((iface.name.clone(), ip));
+        Ok(InterfaceSelector::Name(value.to_string()))
+    }
+
+    /// True if an interface with this name and address satisfies the selector
+    pub fn matches(&self, name: &str, ip: Ipv4Addr) -> bool {
+        match self {
+            InterfaceSelector::Name(n) => n.eq_ignore_ascii_case(name),
+            InterfaceSelector::Ip(want) => *want == ip,
+            InterfaceSelector::Cidr(net, prefix) => {
+                let mask = if *prefix == 0 {
+                    0
+                } else {
+                    u32::MAX << (32 - *prefix as u32)
+                };
+                (u32::from(*net) & mask) == (u32::from(ip) & mask)
             }
         }
     }
+}
 
-    if let Some(res) = best_iface {
-        return Ok(res);
+impl std::fmt::Display for InterfaceSelector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InterfaceSelector::Name(n) => write!(f, "{}", n),
+            InterfaceSelector::Ip(ip) => write!(f, "{}", ip),
+            InterfaceSelector::Cidr(net, prefix) => write!(f, "{}/{}", net, prefix),
+        }
+    }
+}
+
+/// Auto-selection rules for the PTP interface (`interface_preference`,
+/// `allow_wireless`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterfacePolicy {
+    /// Tried in order before the wired-first heuristic
+    pub preference: Vec<InterfaceSelector>,
+    /// Consider wireless interfaces (PTP over Wi-Fi has millisecond jitter)
+    pub allow_wireless: bool,
+}
+
+impl Default for InterfacePolicy {
+    fn default() -> Self {
+        InterfacePolicy {
+            preference: Vec::new(),
+            allow_wireless: true,
+        }
+    }
+}
+
+/// Select the PTP interface.
+///
+/// With a selector, returns the first bindable IPv4 interface matching it.
+/// Without one, auto-selects the first bindable non-loopback interface,
+/// preferring wired over wireless.
+pub fn get_default_interface(selector: Option<&InterfaceSelector>) -> Result<(String, Ipv4Addr)> {
+    choose_interface(selector, &InterfacePolicy::default()).map(|(name, ip, _)| (name, ip))
+}
+
+/// Select the PTP interface under `policy`, returning why it was chosen
+///
+/// An explicit selector (`--interface`) wins outright. Otherwise the
+/// `policy.preference` entries are tried in order, then the first wired
+/// interface, then (if allowed) the first wireless one.
+pub fn choose_interface(
+    selector: Option<&InterfaceSelector>,
+    policy: &InterfacePolicy,
+) -> Result<(String, Ipv4Addr, String)> {
+    let ifaces = if_addrs::get_if_addrs()?;
+    let candidates: Vec<(String, Ipv4Addr)> = ifaces
+        .iter()
+        .filter_map(|iface| match iface.addr.ip() {
+            // Skip loopback and non-IPv4
+            IpAddr::V4(ip) if !ip.is_loopback() => Some((iface.name.clone(), ip)),
+            _ => None,
+        })
+        .collect();
+
+    if let Some(choice) = pick_interface(&candidates, selector, policy, is_ip_bindable) {
+        return Ok(choice);
     }
 
     // Diagnostics
@@ -43,7 +137,120 @@ pub fn get_default_interface() -> Result<(String, Ipv4Addr)> {
         log::warn!(" - Name: {}, Addr: {:?}", iface.name, iface.addr);
     }
 
-    Err(anyhow!("No suitable IPv4 interface found"))
+    match selector {
+        Some(sel) => Err(DanteSyncError::Interface(format!(
+            "No IPv4 interface matches '{}'",
+            sel
+        ))),
+        None if !policy.allow_wireless
+            && candidates.iter().any(|(name, _)| is_wireless_name(name)) =>
+        {
+            Err(DanteSyncError::Interface(
+                "No suitable IPv4 interface found (wireless skipped: allow_wireless is false)"
+                    .to_string(),
+            ))
+        }
+        None => Err(DanteSyncError::Interface(
+            "No suitable IPv4 interface found".to_string(),
+        )),
+    }
+}
+
+/// Selection rules of `choose_interface` over (name, address) candidates in
+/// OS order; `bindable` verifies the address is usable
+fn pick_interface(
+    candidates: &[(String, Ipv4Addr)],
+    selector: Option<&InterfaceSelector>,
+    policy: &InterfacePolicy,
+    bindable: impl Fn(Ipv4Addr) -> bool,
+) -> Option<(String, Ipv4Addr, String)> {
+    // Explicit selection: first match wins, no wireless heuristics
+    if let Some(sel) = selector {
+        return candidates
+            .iter()
+            .find(|(name, ip)| sel.matches(name, *ip) && bindable(*ip))
+            .map(|(name, ip)| (name.clone(), *ip, format!("matches --interface {}", sel)));
+    }
+
+    let usable = |name: &str, ip: Ipv4Addr| {
+        (policy.allow_wireless || !is_wireless_name(name)) && bindable(ip)
+    };
+
+    for (index, pref) in policy.preference.iter().enumerate() {
+        if let Some((name, ip)) = candidates
+            .iter()
+            .find(|(name, ip)| pref.matches(name, *ip) && usable(name, *ip))
+        {
+            return Some((
+                name.clone(),
+                *ip,
+                format!("interface_preference[{}] {}", index, pref),
+            ));
+        }
+    }
+
+    if let Some((name, ip)) = candidates
+        .iter()
+        .find(|(name, ip)| !is_wireless_name(name) && bindable(*ip))
+    {
+        return Some((name.clone(), *ip, "first wired interface".to_string()));
+    }
+
+    if policy.allow_wireless {
+        if let Some((name, ip)) = candidates.iter().find(|(_, ip)| bindable(*ip)) {
+            return Some((
+                name.clone(),
+                *ip,
+                "no wired interface, falling back to wireless".to_string(),
+            ));
+        }
+    }
+
+    None
+}
+
+/// Name-based wireless detection (PTP over Wi-Fi has millisecond jitter)
+pub fn is_wireless_name(name: &str) -> bool {
+    let lower = name.to_lowercase();
+    lower.contains("wireless") || lower.contains("wi-fi") || lower.contains("wlan")
+}
+
+/// A non-loopback interface with its IPv4 addresses
+#[derive(Debug, Clone)]
+pub struct InterfaceInfo {
+    pub name: String,
+    pub ips: Vec<Ipv4Addr>,
+    pub wireless: bool,
+    /// At least one of `ips` can be bound (adapter is up and usable)
+    pub bindable: bool,
+}
+
+/// List candidate PTP interfaces, one entry per name, in OS order
+pub fn list_interfaces() -> Result<Vec<InterfaceInfo>> {
+    let mut result: Vec<InterfaceInfo> = Vec::new();
+
+    for iface in if_addrs::get_if_addrs()? {
+        let ip = match iface.addr.ip() {
+            IpAddr::V4(ip) if !ip.is_loopback() => ip,
+            _ => continue,
+        };
+        let bindable = is_ip_bindable(ip);
+
+        match result.iter_mut().find(|i| i.name == iface.name) {
+            Some(existing) => {
+                existing.ips.push(ip);
+                existing.bindable |= bindable;
+            }
+            None => result.push(InterfaceInfo {
+                wireless: is_wireless_name(&iface.name),
+                name: iface.name,
+                ips: vec![ip],
+                bindable,
+            }),
+        }
+    }
+
+    Ok(result)
 }
 
 fn is_ip_bindable(ip: Ipv4Addr) -> bool {
@@ -55,16 +262,81 @@ fn is_ip_bindable(ip: Ipv4Addr) -> bool {
     socket.bind(&addr.into()).is_ok()
 }
 
-pub fn create_multicast_socket(port: u16, interface_ip: Ipv4Addr) -> Result<UdpSocket> {
+/// IPv4 QoS marking for PTP sockets
+///
+/// On congested show networks untagged PTP is queued behind media traffic,
+/// adding jitter. DSCP lets switches prioritize it (CS6 = 48, EF = 46).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SocketQos {
+    /// DSCP code point (0-63), written to the upper 6 bits of IP_TOS
+    pub dscp: u8,
+    /// IP_MULTICAST_TTL for outgoing multicast
+    pub multicast_ttl: u32,
+}
+
+/// Apply DSCP (IP_TOS) and multicast TTL to a socket
+pub fn apply_socket_qos(socket: &Socket, qos: SocketQos) -> Result<()> {
+    if qos.dscp > 63 {
+        return Err(DanteSyncError::Config(format!(
+            "DSCP must be 0-63 (got {})",
+            qos.dscp
+        )));
+    }
+    socket.set_tos((qos.dscp as u32) << 2)?;
+    socket.set_multicast_ttl_v4(qos.multicast_ttl)?;
+    Ok(())
+}
+
+/// Pin a socket to `interface` (SO_BINDTODEVICE), so policy routing cannot
+/// receive or send its packets on another NIC. Needs CAP_NET_RAW.
+#[cfg(target_os = "linux")]
+pub fn bind_to_device(socket: &Socket, interface: &str) -> Result<()> {
+    setsockopt(
+        socket,
+        sockopt::BindToDevice,
+        &std::ffi::OsString::from(interface),
+    )?;
+    Ok(())
+}
+
+pub fn create_multicast_socket(
+    port: u16,
+    interface_name: &str,
+    interface_ip: Ipv4Addr,
+    qos: SocketQos,
+) -> Result<UdpSocket> {
     // Standard UDP socket creation for TX (Transmission) or legacy RX
     let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
 
     socket.set_reuse_address(true)?;
+    if let Err(e) = apply_socket_qos(&socket, qos) {
+        log::warn!("Failed to set DSCP/TTL on port {}: {}", port, e);
+    }
+
+    // The multicast join below picks the NIC by IP; pinning the socket
+    // also keeps policy-routed hosts from handling PTP on another one
+    #[cfg(target_os = "linux")]
+    match bind_to_device(&socket, interface_name) {
+        Ok(()) => log::debug!("Port {} pinned to {}", port, interface_name),
+        Err(e) if e.is_permission_denied() => log::warn!(
+            "Cannot pin port {} to {} (SO_BINDTODEVICE needs CAP_NET_RAW) - relying on the multicast join",
+            port,
+            interface_name
+        ),
+        Err(e) => log::warn!(
+            "Cannot pin port {} to {}: {} - relying on the multicast join",
+            port,
+            interface_name,
+            e
+        ),
+    }
+    #[cfg(not(target_os = "linux"))]
+    let _ = interface_name;
 
     let addr = SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, port);
     socket.bind(&addr.into())?;
 
-    let multi_addr: Ipv4Addr = "224.0.1.129".parse()?;
+    let multi_addr = Ipv4Addr::new(224, 0, 1, 129);
     socket.join_multicast_v4(&multi_addr, &interface_ip)?;
 
     socket.set_multicast_loop_v4(false)?;
@@ -83,6 +355,13 @@ pub fn create_multicast_socket(port: u16, interface_ip: Ipv4Addr) -> Result<UdpS
     Ok(udp_socket)
 }
 
+/// True if kernel receive timestamping (SO_TIMESTAMPNS) is active on the socket
+#[cfg(unix)]
+pub fn kernel_timestamping_enabled(sock: &UdpSocket) -> bool {
+    use nix::sys::socket::getsockopt;
+    getsockopt(sock, sockopt::ReceiveTimestampns).unwrap_or(false)
+}
+
 #[cfg(unix)]
 pub fn recv_with_timestamp(
     sock: &UdpSocket,
@@ -140,7 +419,7 @@ mod tests {
         // This test verifies the interface selection logic runs without panic
         // On systems with valid network interfaces, it should succeed
         // On systems without interfaces, it returns an error (which is valid)
-        let result = get_default_interface();
+        let result = get_default_interface(None);
         if let Ok((name, ip)) = result {
             assert!(!name.is_empty(), "Interface name should not be empty");
             assert!(!ip.is_loopback(), "Should not return loopback address");
@@ -148,6 +427,19 @@ mod tests {
         // Error case is acceptable on minimal test environments
     }
 
+    /// SO_BINDTODEVICE either pins the socket or, without CAP_NET_RAW,
+    /// reports permission denied for the caller's fallback
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_bind_to_device_loopback() {
+        let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP)).unwrap();
+        match bind_to_device(&socket, "lo") {
+            Ok(()) => {}
+            Err(e) => assert!(e.is_permission_denied(), "unexpected error: {}", e),
+        }
+        assert!(bind_to_device(&socket, "no-such-nic0").is_err());
+    }
+
     /// Test is_ip_bindable with loopback (should always work)
     #[test]
     fn test_is_ip_bindable_loopback() {
@@ -194,6 +486,89 @@ mod tests {
         assert!(result.unwrap().is_none());
     }
 
+    #[test]
+    fn test_interface_selector_parse() {
+        assert_eq!(
+            InterfaceSelector::parse("eth0").unwrap(),
+            InterfaceSelector::Name("eth0".to_string())
+        );
+        assert_eq!(
+            InterfaceSelector::parse("10.77.8.15").unwrap(),
+            InterfaceSelector::Ip(Ipv4Addr::new(10, 77, 8, 15))
+        );
+        assert_eq!(
+            InterfaceSelector::parse(" 10.77.8.0/24 ").unwrap(),
+            InterfaceSelector::Cidr(Ipv4Addr::new(10, 77, 8, 0), 24)
+        );
+        assert!(InterfaceSelector::parse("").is_err());
+        assert!(InterfaceSelector::parse("10.77.8.0/33").is_err());
+        assert!(InterfaceSelector::parse("10.77.8/24").is_err());
+        assert!(InterfaceSelector::parse("10.77.8.0/abc").is_err());
+    }
+
+    #[test]
+    fn test_interface_selector_matches() {
+        let ip = Ipv4Addr::new(10, 77, 8, 15);
+
+        let by_name = InterfaceSelector::parse("Ethernet 2").unwrap();
+        assert!(by_name.matches("ethernet 2", ip));
+        assert!(!by_name.matches("Ethernet 3", ip));
+
+        let by_ip = InterfaceSelector::parse("10.77.8.15").unwrap();
+        assert!(by_ip.matches("{GUID}", ip));
+        assert!(!by_ip.matches("{GUID}", Ipv4Addr::new(10, 77, 8, 16)));
+
+        let by_cidr = InterfaceSelector::parse("10.77.8.0/24").unwrap();
+        assert!(by_cidr.matches("enp3s0", ip));
+        assert!(!by_cidr.matches("enp3s0", Ipv4Addr::new(10, 77, 9, 15)));
+
+        let host_route = InterfaceSelector::parse("10.77.8.15/32").unwrap();
+        assert!(host_route.matches("eth0", ip));
+        assert!(!host_route.matches("eth0", Ipv4Addr::new(10, 77, 8, 14)));
+
+        let any = InterfaceSelector::parse("0.0.0.0/0").unwrap();
+        assert!(any.matches("eth0", ip));
+    }
+
+    #[test]
+    fn test_interface_selector_display_roundtrip() {
+        for value in ["eth0", "10.77.8.15", "10.77.8.0/24"] {
+            let sel = InterfaceSelector::parse(value).unwrap();
+            assert_eq!(sel.to_string(), value);
+        }
+    }
+
+    /// Test kernel timestamping is reported after create-style setsockopt
+    #[cfg(unix)]
+    #[test]
+    fn test_kernel_timestamping_enabled() {
+        let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP)).unwrap();
+        let udp_socket: UdpSocket = socket.into();
+        assert!(!kernel_timestamping_enabled(&udp_socket));
+
+        setsockopt(&udp_socket, sockopt::ReceiveTimestampns, &true).unwrap();
+        assert!(kernel_timestamping_enabled(&udp_socket));
+    }
+
+    /// Test DSCP is written to the upper 6 bits of IP_TOS and TTL is applied
+    #[test]
+    fn test_apply_socket_qos() {
+        let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP)).unwrap();
+        let qos = SocketQos {
+            dscp: 48, // CS6
+            multicast_ttl: 4,
+        };
+        apply_socket_qos(&socket, qos).expect("apply_socket_qos failed");
+        assert_eq!(socket.tos().unwrap(), 48 << 2);
+        assert_eq!(socket.multicast_ttl_v4().unwrap(), 4);
+
+        let invalid = SocketQos {
+            dscp: 64,
+            multicast_ttl: 1,
+        };
+        assert!(apply_socket_qos(&socket, invalid).is_err());
+    }
+
     /// Test wireless interface detection keywords
     #[test]
     fn test_wireless_interface_detection() {
@@ -202,17 +577,103 @@ mod tests {
         let wired_names = ["eth0", "Ethernet", "enp3s0", "Local Area Connection"];
 
         for name in &wireless_names {
-            let lower = name.to_lowercase();
-            let is_wireless =
-                lower.contains("wireless") || lower.contains("wi-fi") || lower.contains("wlan");
-            assert!(is_wireless, "{} should be detected as wireless", name);
+            assert!(
+                is_wireless_name(name),
+                "{} should be detected as wireless",
+                name
+            );
         }
 
         for name in &wired_names {
-            let lower = name.to_lowercase();
-            let is_wireless =
-                lower.contains("wireless") || lower.contains("wi-fi") || lower.contains("wlan");
-            assert!(!is_wireless, "{} should NOT be detected as wireless", name);
+            assert!(
+                !is_wireless_name(name),
+                "{} should NOT be detected as wireless",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_pick_interface_policy() {
+        let candidates = vec![
+            ("wlan0".to_string(), Ipv4Addr::new(192, 168, 1, 20)),
+            ("eth0".to_string(), Ipv4Addr::new(10, 0, 0, 5)),
+            ("eth1".to_string(), Ipv4Addr::new(10, 77, 8, 5)),
+        ];
+        let all = |_: Ipv4Addr| true;
+
+        // Heuristic: wired first, even if listed after wireless
+        let policy = InterfacePolicy::default();
+        let (name, _, reason) = pick_interface(&candidates, None, &policy, all).unwrap();
+        assert_eq!(name, "eth0");
+        assert_eq!(reason, "first wired interface");
+
+        // Preferences are tried in order; unmatched entries are skipped
+        let policy = InterfacePolicy {
+            preference: vec![
+                InterfaceSelector::parse("eth9").unwrap(),
+                InterfaceSelector::parse("10.77.8.0/24").unwrap(),
+                InterfaceSelector::parse("eth0").unwrap(),
+            ],
+            allow_wireless: true,
+        };
+        let (name, _, reason) = pick_interface(&candidates, None, &policy, all).unwrap();
+        assert_eq!(name, "eth1");
+        assert_eq!(reason, "interface_preference[1] 10.77.8.0/24");
+
+        // Wi-Fi-only laptop: preferring wireless picks it over wired
+        let policy = InterfacePolicy {
+            preference: vec![InterfaceSelector::parse("wlan0").unwrap()],
+            allow_wireless: true,
+        };
+        let (name, _, _) = pick_interface(&candidates, None, &policy, all).unwrap();
+        assert_eq!(name, "wlan0");
+
+        // Wireless forbidden: even a preferred wireless interface is skipped
+        let policy = InterfacePolicy {
+            allow_wireless: false,
+            ..policy
+        };
+        let (name, _, _) = pick_interface(&candidates, None, &policy, all).unwrap();
+        assert_eq!(name, "eth0");
+        let wireless_only = &candidates[..1];
+        assert!(pick_interface(wireless_only, None, &policy, all).is_none());
+        let policy = InterfacePolicy::default();
+        let (name, _, reason) = pick_interface(wireless_only, None, &policy, all).unwrap();
+        assert_eq!(name, "wlan0");
+        assert_eq!(reason, "no wired interface, falling back to wireless");
+
+        // Unbindable addresses are passed over
+        let (name, _, _) = pick_interface(&candidates, None, &policy, |ip: Ipv4Addr| {
+            ip.octets()[1] == 77
+        })
+        .unwrap();
+        assert_eq!(name, "eth1");
+
+        // An explicit --interface wins regardless of policy
+        let sel = InterfaceSelector::parse("wlan0").unwrap();
+        let policy = InterfacePolicy {
+            preference: vec![InterfaceSelector::parse("eth1").unwrap()],
+            allow_wireless: false,
+        };
+        let (name, _, reason) = pick_interface(&candidates, Some(&sel), &policy, all).unwrap();
+        assert_eq!(name, "wlan0");
+        assert_eq!(reason, "matches --interface wlan0");
+    }
+
+    /// Test list_interfaces excludes loopback and groups addresses per name
+    #[test]
+    fn test_list_interfaces() {
+        let ifaces = list_interfaces().expect("list_interfaces failed");
+        for iface in &ifaces {
+            assert!(!iface.ips.is_empty());
+            assert!(iface.ips.iter().all(|ip| !ip.is_loopback()));
+            assert_eq!(
+                ifaces.iter().filter(|i| i.name == iface.name).count(),
+                1,
+                "{} listed twice",
+                iface.name
+            );
         }
     }
 }
@@ -0,0 +1,305 @@
+//! AF_PACKET-based PTP network implementation for Linux.
+//!
+//! Mirrors the Npcap backend on Windows: PTP datagrams are read off the
+//! interface by a packet socket instead of UDP sockets bound to ports 319/320.
+//! The receive timestamp is taken at the packet tap, before IP/UDP delivery,
+//! so NICs whose UDP-socket timestamps are unreliable can still be disciplined
+//! with the same controller.
+//!
+//! A kernel BPF filter keeps Dante audio flows out of userspace. Multicast
+//! membership (IGMP) is held by a single UDP socket on an ephemeral port, so
+//! the group is joined once and nothing queues up on a socket nobody reads.
+
+use crate::error::{DanteSyncError, Result};
+use crate::ptp::{PTP_EVENT_PORT, PTP_GENERAL_PORT};
+use log::{debug, info, warn};
+use nix::sys::socket::{getsockopt, setsockopt, sockopt};
+use std::ffi::CString;
+use std::io;
+use std::net::{Ipv4Addr, UdpSocket};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::time::SystemTime;
+
+const PTP_MULTICAST: Ipv4Addr = Ipv4Addr::new(224, 0, 1, 129);
+
+const fn bpf(code: u16, jt: u8, jf: u8, k: u32) -> libc::sock_filter {
+    libc::sock_filter { code, jt, jf, k }
+}
+
+/// `udp and dst host 224.0.1.129 and (dst port 319 or dst port 320)`,
+/// unfragmented only. SOCK_DGRAM packet sockets strip the link layer, so
+/// offsets are relative to the IPv4 header.
+static PTP_FILTER: [libc::sock_filter; 12] = [
+    bpf(0x30, 0, 0, 9),           // ldb [9]            ; IP protocol
+    bpf(0x15, 0, 9, 17),          // jeq #17 (UDP)      ; else drop
+    bpf(0x20, 0, 0, 16),          // ld [16]            ; destination address
+    bpf(0x15, 0, 7, 0xe000_0181), // jeq #224.0.1.129   ; else drop
+    bpf(0x28, 0, 0, 6),           // ldh [6]            ; flags + fragment offset
+    bpf(0x45, 5, 0, 0x1fff),      // jset #0x1fff       ; later fragment: drop
+    bpf(0xb1, 0, 0, 0),           // ldxb 4*([0]&0xf)   ; IP header length
+    bpf(0x48, 0, 0, 2),           // ldh [x+2]          ; UDP destination port
+    bpf(0x15, 1, 0, PTP_EVENT_PORT as u32),
+    bpf(0x15, 0, 1, PTP_GENERAL_PORT as u32),
+    bpf(0x06, 0, 0, 0x0004_0000), // ret #262144        ; accept
+    bpf(0x06, 0, 0, 0),           // ret #0             ; drop
+];
+
+/// UDP payload of an IPv4 datagram addressed to PTP port 319 or 320
+///
+/// `packet` starts at the IPv4 header; Ethernet padding after the datagram is
+/// ignored.
+fn ptp_payload(packet: &[u8]) -> Option<&[u8]> {
+    if packet.len() < 20 || packet[0] >> 4 != 4 || packet[9] != 17 {
+        return None;
+    }
+    // More-fragments flag or a fragment offset: not a whole datagram
+    if u16::from_be_bytes([packet[6], packet[7]]) & 0x3fff != 0 {
+        return None;
+    }
+    let header_len = (packet[0] & 0x0f) as usize * 4;
+    let total_len = u16::from_be_bytes([packet[2], packet[3]]) as usize;
+    if header_len < 20 || total_len < header_len + 8 || packet.len() < total_len {
+        return None;
+    }
+    let udp = &packet[header_len..total_len];
+    let dst_port = u16::from_be_bytes([udp[2], udp[3]]);
+    if dst_port != PTP_EVENT_PORT && dst_port != PTP_GENERAL_PORT {
+        return None;
+    }
+    Some(&udp[8..])
+}
+
+/// Join the PTP group on `iface_ip` from a socket that receives nothing itself
+fn join_multicast(iface_ip: Ipv4Addr) -> Result<UdpSocket> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+    socket.join_multicast_v4(&PTP_MULTICAST, &iface_ip)?;
+    Ok(socket)
+}
+
+fn check(ret: libc::c_int, what: &str) -> Result<()> {
+    if ret < 0 {
+        Err(DanteSyncError::io(what, io::Error::last_os_error()))
+    } else {
+        Ok(())
+    }
+}
+
+/// PTP network reading from an AF_PACKET socket with kernel timestamps
+pub struct PacketPtpNetwork {
+    socket: OwnedFd,
+    // Keep socket alive for IGMP multicast membership
+    _igmp_sock: UdpSocket,
+    timestamping_enabled: bool,
+}
+
+impl PacketPtpNetwork {
+    /// Open a packet socket on `interface_name` (needs CAP_NET_RAW)
+    pub fn new(interface_name: &str, interface_ip: Ipv4Addr) -> Result<Self> {
+        info!(
+            "Initializing AF_PACKET capture on interface: {} ({})",
+            interface_name, interface_ip
+        );
+
+        let name = CString::new(interface_name).map_err(|_| {
+            DanteSyncError::Interface(format!("Invalid interface name '{}'", interface_name))
+        })?;
+        let ifindex = unsafe { libc::if_nametoindex(name.as_ptr()) };
+        if ifindex == 0 {
+            return Err(DanteSyncError::Interface(format!(
+                "Interface '{}' not found",
+                interface_name
+            )));
+        }
+
+        let protocol = (libc::ETH_P_IP as u16).to_be();
+        let fd = unsafe {
+            libc::socket(
+                libc::AF_PACKET,
+                libc::SOCK_DGRAM | libc::SOCK_NONBLOCK | libc::SOCK_CLOEXEC,
+                protocol as libc::c_int,
+            )
+        };
+        check(fd, "AF_PACKET socket (requires CAP_NET_RAW)")?;
+        let socket = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        // Filter before binding so no unrelated traffic is ever queued
+        let program = libc::sock_fprog {
+            len: PTP_FILTER.len() as u16,
+            filter: PTP_FILTER.as_ptr() as *mut libc::sock_filter,
+        };
+        check(
+            unsafe {
+                libc::setsockopt(
+                    socket.as_raw_fd(),
+                    libc::SOL_SOCKET,
+                    libc::SO_ATTACH_FILTER,
+                    &program as *const libc::sock_fprog as *const libc::c_void,
+                    std::mem::size_of::<libc::sock_fprog>() as libc::socklen_t,
+                )
+            },
+            "SO_ATTACH_FILTER",
+        )?;
+
+        let mut addr: libc::sockaddr_ll = unsafe { std::mem::zeroed() };
+        addr.sll_family = libc::AF_PACKET as libc::c_ushort;
+        addr.sll_protocol = protocol;
+        addr.sll_ifindex = ifindex as libc::c_int;
+        check(
+            unsafe {
+                libc::bind(
+                    socket.as_raw_fd(),
+                    &addr as *const libc::sockaddr_ll as *const libc::sockaddr,
+                    std::mem::size_of::<libc::sockaddr_ll>() as libc::socklen_t,
+                )
+            },
+            "bind AF_PACKET socket",
+        )?;
+        info!(
+            "[Filter] Applied BPF: udp and dst host 224.0.1.129 and (dst port 319 or dst port 320)"
+        );
+
+        if let Err(e) = setsockopt(&socket, sockopt::ReceiveTimestampns, &true) {
+            warn!("Failed to enable kernel timestamping: {}", e);
+        }
+        let timestamping_enabled =
+            getsockopt(&socket, sockopt::ReceiveTimestampns).unwrap_or(false);
+
+        let igmp_sock = join_multicast(interface_ip)?;
+        info!(
+            "Joined PTP multicast group 224.0.1.129 on {} ({}) - AF_PACKET capture",
+            interface_name, interface_ip
+        );
+
+        let mut network = PacketPtpNetwork {
+            socket,
+            _igmp_sock: igmp_sock,
+            timestamping_enabled,
+        };
+        network.drain();
+        Ok(network)
+    }
+
+    /// Next frame as (length, timestamp, sent by this host)
+    fn recv_frame(&self, buf: &mut [u8]) -> Result<Option<(usize, SystemTime, bool)>> {
+        use nix::sys::socket::{recvmsg, ControlMessageOwned, MsgFlags, SockaddrStorage};
+        use nix::sys::time::TimeSpec;
+        use std::time::{Duration, UNIX_EPOCH};
+
+        let mut iov = [io::IoSliceMut::new(buf)];
+        let mut cmsg_buf = nix::cmsg_space!(TimeSpec);
+
+        match recvmsg::<SockaddrStorage>(
+            self.socket.as_raw_fd(),
+            &mut iov,
+            Some(&mut cmsg_buf),
+            MsgFlags::empty(),
+        ) {
+            Ok(msg) => {
+                let timestamp = msg
+                    .cmsgs()
+                    .find_map(|cmsg| {
+                        if let ControlMessageOwned::ScmTimestampns(ts) = cmsg {
+                            let duration = Duration::new(ts.tv_sec() as u64, ts.tv_nsec() as u32);
+                            Some(UNIX_EPOCH + duration)
+                        } else {
+                            None
+                        }
+                    })
+                    .unwrap_or_else(SystemTime::now);
+                let outgoing = msg
+                    .address
+                    .as_ref()
+                    .and_then(|addr| addr.as_link_addr())
+                    .is_some_and(|link| link.pkttype() == libc::PACKET_OUTGOING);
+                Ok(Some((msg.bytes, timestamp, outgoing)))
+            }
+            Err(nix::errno::Errno::EAGAIN) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn drain(&mut self) {
+        let mut buf = [0u8; 2048];
+        while let Ok(Some(_)) = self.recv_frame(&mut buf) {}
+    }
+}
+
+impl crate::traits::PtpNetwork for PacketPtpNetwork {
+    fn recv_packet(&mut self) -> Result<Option<(Vec<u8>, usize, SystemTime)>> {
+        let mut buf = [0u8; 2048];
+        loop {
+            let Some((len, ts, outgoing)) = self.recv_frame(&mut buf)? else {
+                return Ok(None);
+            };
+            // Our own transmissions are looped back to packet sockets
+            if outgoing {
+                continue;
+            }
+            if let Some(payload) = ptp_payload(&buf[..len]) {
+                debug!("[AF_PACKET] PTP payload {} bytes", payload.len());
+                return Ok(Some((payload.to_vec(), payload.len(), ts)));
+            }
+        }
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        // Drain buffers to prevent processing old packets after a clock step
+        self.drain();
+        Ok(())
+    }
+
+    fn timestamp_source(&self) -> (String, bool) {
+        if self.timestamping_enabled {
+            ("AF_PACKET SO_TIMESTAMPNS".to_string(), true)
+        } else {
+            ("AF_PACKET userspace SystemTime::now()".to_string(), false)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ipv4_udp(dst_port: u16, payload: &[u8], padding: usize) -> Vec<u8> {
+        let total_len = (20 + 8 + payload.len()) as u16;
+        let mut packet = vec![0x45, 0x00];
+        packet.extend_from_slice(&total_len.to_be_bytes());
+        packet.extend_from_slice(&[0, 0, 0x40, 0x00, 1, 17, 0, 0]); // DF, TTL 1, UDP
+        packet.extend_from_slice(&[192, 168, 1, 10]);
+        packet.extend_from_slice(&PTP_MULTICAST.octets());
+        packet.extend_from_slice(&PTP_EVENT_PORT.to_be_bytes());
+        packet.extend_from_slice(&dst_port.to_be_bytes());
+        packet.extend_from_slice(&((8 + payload.len()) as u16).to_be_bytes());
+        packet.extend_from_slice(&[0, 0]);
+        packet.extend_from_slice(payload);
+        packet.resize(packet.len() + padding, 0);
+        packet
+    }
+
+    #[test]
+    fn test_ptp_payload_extraction() {
+        let payload = [0x10u8, 0x01, 0x02, 0x03];
+
+        // Ethernet minimum-frame padding is not part of the payload
+        let packet = ipv4_udp(PTP_EVENT_PORT, &payload, 14);
+        assert_eq!(ptp_payload(&packet), Some(&payload[..]));
+        let packet = ipv4_udp(PTP_GENERAL_PORT, &payload, 0);
+        assert_eq!(ptp_payload(&packet), Some(&payload[..]));
+
+        // Other UDP traffic (e.g. Dante audio) is rejected
+        assert_eq!(ptp_payload(&ipv4_udp(4321, &payload, 0)), None);
+
+        // Fragments and truncated datagrams are rejected
+        let mut fragment = ipv4_udp(PTP_EVENT_PORT, &payload, 0);
+        fragment[6] = 0x20; // more fragments
+        assert_eq!(ptp_payload(&fragment), None);
+        let packet = ipv4_udp(PTP_EVENT_PORT, &payload, 0);
+        assert_eq!(ptp_payload(&packet[..packet.len() - 1]), None);
+
+        // Not UDP
+        let mut tcp = ipv4_udp(PTP_EVENT_PORT, &payload, 0);
+        tcp[9] = 6;
+        assert_eq!(ptp_payload(&tcp), None);
+    }
+}
@@ -7,7 +7,8 @@
 //! Key: We use TimestampType::HostHighPrec which maps to PCAP_TSTAMP_HOST_HIPREC
 //! and uses KeQuerySystemTimePrecise() internally - NOT the default UNSYNCED mode.
 
-use anyhow::{anyhow, Result};
+use crate::error::{DanteSyncError, Result};
+use crate::net::{apply_socket_qos, SocketQos};
 use log::{debug, info, warn};
 use pcap::{Active, Capture, Device, TimestampType};
 use std::net::{Ipv4Addr, UdpSocket};
@@ -17,13 +18,38 @@ const PTP_EVENT_PORT: u16 = 319;
 const PTP_GENERAL_PORT: u16 = 320;
 const PTP_MULTICAST: Ipv4Addr = Ipv4Addr::new(224, 0, 1, 129);
 
+/// True when wpcap.dll can be loaded
+///
+/// The service binary delay-loads wpcap.dll (see build.rs) so it can start
+/// without Npcap; calling into pcap without the DLL would abort the process,
+/// so every entry point below checks this first.
+pub fn npcap_available() -> bool {
+    use windows::core::w;
+    use windows::Win32::System::LibraryLoader::LoadLibraryW;
+    unsafe { LoadLibraryW(w!("wpcap.dll")).is_ok() }
+}
+
+fn require_npcap() -> Result<()> {
+    if npcap_available() {
+        Ok(())
+    } else {
+        Err(DanteSyncError::Interface(
+            "wpcap.dll not found (is Npcap installed in WinPcap API-compatible mode?)".to_string(),
+        ))
+    }
+}
+
 /// Create a socket and join PTP multicast group (for IGMP membership)
-fn join_multicast(port: u16, iface_ip: Ipv4Addr) -> Result<UdpSocket> {
+fn join_multicast(port: u16, iface_ip: Ipv4Addr, qos: SocketQos) -> Result<UdpSocket> {
     use socket2::{Domain, Protocol, Socket, Type};
     use std::net::SocketAddrV4;
 
     let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
     socket.set_reuse_address(true)?;
+    // Windows ignores IP_TOS unless QoS policy allows it; marking is best effort
+    if let Err(e) = apply_socket_qos(&socket, qos) {
+        warn!("Failed to set DSCP/TTL on port {}: {}", port, e);
+    }
 
     let addr = SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, port);
     socket.bind(&addr.into())?;
@@ -45,29 +71,35 @@ pub struct NpcapPtpNetwork {
 }
 
 impl NpcapPtpNetwork {
-    pub fn new(interface_name: &str) -> Result<Self> {
+    pub fn new(
+        interface_name: &str,
+        interface_ip: Ipv4Addr,
+        event_qos: SocketQos,
+        general_qos: SocketQos,
+    ) -> Result<Self> {
         info!(
-            "Initializing Npcap capture on interface: {}",
-            interface_name
+            "Initializing Npcap capture on interface: {} ({})",
+            interface_name, interface_ip
         );
+        require_npcap()?;
 
-        // Find the device by name or description
+        // Find the device: exact IPv4 address match first (stable across
+        // adapter renames), then by name or description
         let devices = Device::list()?;
         let device = devices
             .iter()
             .find(|d| {
-                d.name.contains(interface_name)
-                    || d.desc
-                        .as_ref()
-                        .map(|desc| desc.contains(interface_name))
-                        .unwrap_or(false)
+                d.addresses
+                    .iter()
+                    .any(|addr| addr.addr == std::net::IpAddr::V4(interface_ip))
             })
             .or_else(|| {
-                // Try matching by IP address in description
                 devices.iter().find(|d| {
-                    d.addresses
-                        .iter()
-                        .any(|addr| format!("{:?}", addr.addr).contains(interface_name))
+                    d.name.contains(interface_name)
+                        || d.desc
+                            .as_ref()
+                            .map(|desc| desc.contains(interface_name))
+                            .unwrap_or(false)
                 })
             })
             .ok_or_else(|| {
@@ -75,34 +107,22 @@ impl NpcapPtpNetwork {
                     .iter()
                     .map(|d| format!("{} ({:?})", d.name, d.desc))
                     .collect();
-                anyhow!(
-                    "Interface '{}' not found. Available: {:?}",
-                    interface_name,
-                    available
-                )
+                DanteSyncError::Interface(format!(
+                    "Interface '{}' ({}) not found. Available: {:?}",
+                    interface_name, interface_ip, available
+                ))
             })?;
 
         info!("Found device: {} ({:?})", device.name, device.desc);
 
-        // Extract interface IP for multicast join
-        let iface_ip = device
-            .addresses
-            .iter()
-            .find_map(|a| {
-                if let std::net::IpAddr::V4(ip) = a.addr {
-                    if !ip.is_loopback() {
-                        return Some(ip);
-                    }
-                }
-                None
-            })
-            .ok_or_else(|| anyhow!("No IPv4 address found on device"))?;
+        // Use the selected interface IP for multicast join
+        let iface_ip = interface_ip;
 
         info!("Using interface IP {} for multicast join", iface_ip);
 
         // CRITICAL: Join multicast group via sockets to trigger IGMP
-        let igmp_sock_319 = join_multicast(PTP_EVENT_PORT, iface_ip)?;
-        let igmp_sock_320 = join_multicast(PTP_GENERAL_PORT, iface_ip)?;
+        let igmp_sock_319 = join_multicast(PTP_EVENT_PORT, iface_ip, event_qos)?;
+        let igmp_sock_320 = join_multicast(PTP_GENERAL_PORT, iface_ip, general_qos)?;
         info!("Joined PTP multicast group 224.0.1.129 on ports 319 and 320");
 
         // Create capture handle with HostHighPrec timestamps
@@ -225,10 +245,50 @@ impl crate::traits::PtpNetwork for NpcapPtpNetwork {
         // Npcap doesn't need explicit reset
         Ok(())
     }
+
+    fn timestamp_source(&self) -> (String, bool) {
+        if self.using_hiprec {
+            (
+                "Npcap HostHighPrec (KeQuerySystemTimePrecise)".to_string(),
+                true,
+            )
+        } else {
+            ("Npcap default timestamps".to_string(), false)
+        }
+    }
+}
+
+/// Npcap capture device with its IPv4 addresses
+#[derive(Debug, Clone)]
+pub struct NpcapDevice {
+    pub name: String,
+    pub desc: Option<String>,
+    pub ips: Vec<Ipv4Addr>,
+}
+
+/// Enumerate Npcap devices (used to map OS interfaces to capture devices)
+pub fn npcap_devices() -> Result<Vec<NpcapDevice>> {
+    require_npcap()?;
+    Ok(Device::list()?
+        .into_iter()
+        .map(|d| NpcapDevice {
+            ips: d
+                .addresses
+                .iter()
+                .filter_map(|a| match a.addr {
+                    std::net::IpAddr::V4(ip) => Some(ip),
+                    _ => None,
+                })
+                .collect(),
+            name: d.name,
+            desc: d.desc,
+        })
+        .collect())
 }
 
 /// Get list of available Npcap devices
 pub fn list_npcap_devices() -> Result<Vec<String>> {
+    require_npcap()?;
     let devices = Device::list()?;
     Ok(devices
         .iter()
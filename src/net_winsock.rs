@@ -9,7 +9,7 @@
 //! - WSARecvMsg to receive packets with control messages
 //! - SO_TIMESTAMP control message contains QPC timestamp
 
-use anyhow::{anyhow, Result};
+use crate::error::{DanteSyncError, Result};
 use log::{debug, info, warn};
 use std::mem;
 use std::net::Ipv4Addr;
@@ -71,6 +71,11 @@ type WsaRecvMsgFn = unsafe extern "system" fn(
     *mut std::ffi::c_void, // LPWSAOVERLAPPED_COMPLETION_ROUTINE
 ) -> i32;
 
+/// A Winsock error code as an I/O error (WSAEACCES reads as permission denied)
+fn wsa_error(what: impl std::fmt::Display, code: i32) -> DanteSyncError {
+    DanteSyncError::io(what, std::io::Error::from_raw_os_error(code))
+}
+
 /// PTP network using Winsock with SO_TIMESTAMP for precise timestamps
 pub struct WinsockPtpNetwork {
     socket_319: SOCKET,
@@ -89,7 +94,7 @@ impl WinsockPtpNetwork {
             let mut wsa_data: WSADATA = mem::zeroed();
             let result = WSAStartup(0x0202, &mut wsa_data);
             if result != 0 {
-                return Err(anyhow!("WSAStartup failed: {}", result));
+                return Err(wsa_error("WSAStartup failed", result));
             }
         }
 
@@ -138,7 +143,7 @@ impl WinsockPtpNetwork {
             // Create UDP socket
             let sock = socket(AF_INET.0 as i32, SOCK_DGRAM, IPPROTO_UDP.0 as i32);
             if sock == INVALID_SOCKET {
-                return Err(anyhow!("Failed to create socket: {}", WSAGetLastError().0));
+                return Err(wsa_error("Failed to create socket", WSAGetLastError().0));
             }
 
             // Enable address reuse
@@ -170,10 +175,9 @@ impl WinsockPtpNetwork {
             ) == SOCKET_ERROR
             {
                 closesocket(sock);
-                return Err(anyhow!(
-                    "Failed to bind port {}: {}",
-                    port,
-                    WSAGetLastError().0
+                return Err(wsa_error(
+                    format!("Failed to bind port {}", port),
+                    WSAGetLastError().0,
                 ));
             }
 
@@ -194,7 +198,7 @@ impl WinsockPtpNetwork {
             ) == SOCKET_ERROR
             {
                 closesocket(sock);
-                return Err(anyhow!("Failed to join multicast: {}", WSAGetLastError().0));
+                return Err(wsa_error("Failed to join multicast", WSAGetLastError().0));
             }
 
             // Disable multicast loopback
@@ -345,7 +349,7 @@ impl WinsockPtpNetwork {
                     // WSAEWOULDBLOCK
                     return Ok(None);
                 }
-                return Err(anyhow!("WSARecvMsg failed: {}", err));
+                return Err(wsa_error("WSARecvMsg failed", err));
             }
 
             if bytes_received == 0 {
@@ -471,7 +475,7 @@ impl WinsockPtpNetwork {
                     // WSAEWOULDBLOCK
                     return Ok(None);
                 }
-                return Err(anyhow!("recv failed: {}", err));
+                return Err(wsa_error("recv failed", err));
             }
 
             if result == 0 {
@@ -510,6 +514,14 @@ impl crate::traits::PtpNetwork for WinsockPtpNetwork {
         // No state to reset for Winsock sockets
         Ok(())
     }
+
+    fn timestamp_source(&self) -> (String, bool) {
+        if self.timestamping_enabled {
+            ("Winsock SO_TIMESTAMP (QPC)".to_string(), true)
+        } else {
+            ("Winsock userspace SystemTime::now()".to_string(), false)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1,15 +1,95 @@
-use anyhow::Result;
-use rsntp::SntpClient;
+use crate::clock::LeapSecond;
+use crate::error::{DanteSyncError, Result};
+use log::debug;
+use rsntp::{Config, SntpClient};
+use std::cell::Cell;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::process::Command;
 use std::time::Duration;
 
+/// Per-attempt NTP timeout; with the default retries the worst case matches
+/// rsntp's single 3s wait
+pub const DEFAULT_QUERY_TIMEOUT_MS: u64 = 1000;
+pub const DEFAULT_QUERY_RETRIES: u32 = 2;
+
+/// Path quality of one NTP answer, next to its offset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NtpQuality {
+    /// Round-trip delay to the server (chrony: root delay to its reference)
+    pub rtt: Duration,
+    /// Server stratum (chrony: its own stratum, one above its source)
+    pub stratum: u8,
+}
+
 pub struct NtpClient {
     server: String,
+    /// Source address for queries (None = let the OS pick the route)
+    bind_ip: Option<Ipv4Addr>,
+    /// Leap indicator from the last successful response
+    leap: Cell<Option<LeapSecond>>,
+    /// Round-trip delay and stratum of the last successful response
+    quality: Cell<Option<NtpQuality>>,
+    /// Wait for one response before retrying
+    timeout: Duration,
+    /// Extra attempts within one query before it counts as failed
+    retries: u32,
 }
 
 impl NtpClient {
-    pub fn new(server: &str) -> Self {
+    /// `bind_ip` pins NTP traffic to one interface on multi-homed hosts
+    pub fn new(server: &str, bind_ip: Option<Ipv4Addr>) -> Self {
         NtpClient {
             server: server.to_string(),
+            bind_ip,
+            leap: Cell::new(None),
+            quality: Cell::new(None),
+            timeout: Duration::from_millis(DEFAULT_QUERY_TIMEOUT_MS),
+            retries: DEFAULT_QUERY_RETRIES,
+        }
+    }
+
+    /// Per-attempt timeout and retry count (config reload); next query on
+    pub fn set_query_policy(&mut self, timeout: Duration, retries: u32) {
+        self.timeout = timeout;
+        self.retries = retries;
+    }
+
+    /// Leap second announced by the server in its last response
+    pub fn leap_second(&self) -> Option<LeapSecond> {
+        self.leap.get()
+    }
+
+    /// Round-trip delay and stratum of the server's last response
+    pub fn quality(&self) -> Option<NtpQuality> {
+        self.quality.get()
+    }
+
+    pub fn server(&self) -> &str {
+        &self.server
+    }
+
+    /// Switch servers (config reload); takes effect on the next query
+    pub fn set_server(&mut self, server: &str) {
+        self.server = server.to_string();
+    }
+
+    /// Re-bind after the interface changed (e.g. network rebuild)
+    pub fn set_bind_ip(&mut self, bind_ip: Option<Ipv4Addr>) {
+        self.bind_ip = bind_ip;
+    }
+
+    /// A local server (chrony/ntpd on 127.0.0.1) is unreachable from a LAN address
+    fn effective_bind_ip(&self) -> Option<Ipv4Addr> {
+        let server_is_loopback = self
+            .server
+            .split(':')
+            .next()
+            .and_then(|host| host.parse::<Ipv4Addr>().ok())
+            .is_some_and(|ip| ip.is_loopback());
+        if server_is_loopback {
+            None
+        } else {
+            self.bind_ip
         }
     }
 
@@ -17,23 +97,243 @@ impl NtpClient {
     /// Returns the offset required to apply to the local system time (Local + Offset = True Time).
     /// Positive offset means local clock is behind (needs to step forward).
     pub fn get_offset(&self) -> Result<(Duration, i8)> {
-        let client = SntpClient::new();
-        let result = client.synchronize(&self.server)?;
+        let (offset, retries) = self.query()?;
+        if retries > 0 {
+            debug!(
+                "[NTP] {} answered after {} retr{}",
+                self.server,
+                retries,
+                if retries == 1 { "y" } else { "ies" }
+            );
+        }
+        Ok(offset)
+    }
 
-        let offset = result.clock_offset();
-        let offset_secs = offset.as_secs_f64();
+    /// One logical query: up to `1 + retries` requests, so a single lost
+    /// packet is not a failure. Also returns the retries it took.
+    pub fn query(&self) -> Result<((Duration, i8), u32)> {
+        let mut config = Config::default().timeout(self.timeout);
+        if let Some(ip) = self.effective_bind_ip() {
+            config = config.bind_address(SocketAddr::from((ip, 0)));
+        }
+        let client = SntpClient::with_config(config);
+        with_retries(self.retries, || {
+            let result = client.synchronize(&self.server).map_err(sntp_error)?;
+            self.leap
+                .set(leap_from_indicator(result.leap_indicator() as u8));
+            self.quality.set(Some(NtpQuality {
+                rtt: Duration::from_secs_f64(result.round_trip_delay().as_secs_f64().max(0.0)),
+                stratum: result.stratum(),
+            }));
+            Ok(offset_from_secs(result.clock_offset().as_secs_f64()))
+        })
+        .map_err(|e| {
+            let message = format!("{} (after {} attempts)", e, self.retries + 1);
+            if e.is_transient() {
+                DanteSyncError::NtpTimeout(message)
+            } else {
+                DanteSyncError::Ntp(message)
+            }
+        })
+    }
+}
 
-        let sign = if offset_secs < 0.0 { -1 } else { 1 };
-        let abs_secs = offset_secs.abs();
+/// Timeouts (no answer) are transient; anything else is a bad response
+fn sntp_error(err: rsntp::SynchronizationError) -> DanteSyncError {
+    match err {
+        rsntp::SynchronizationError::IOError(e)
+            if matches!(
+                e.kind(),
+                std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock
+            ) =>
+        {
+            DanteSyncError::NtpTimeout(e.to_string())
+        }
+        other => DanteSyncError::Ntp(other.to_string()),
+    }
+}
 
-        // Convert abs_secs to Duration
-        let secs = abs_secs.trunc() as u64;
-        let nanos = (abs_secs.fract() * 1_000_000_000.0) as u32;
+/// Run `attempt` until it succeeds, at most `1 + retries` times. Returns the
+/// value and the number of retries used, or the last error.
+fn with_retries<T>(retries: u32, mut attempt: impl FnMut() -> Result<T>) -> Result<(T, u32)> {
+    let mut used = 0;
+    loop {
+        match attempt() {
+            Ok(value) => return Ok((value, used)),
+            Err(e) if used >= retries => return Err(e),
+            Err(_) => used += 1,
+        }
+    }
+}
+
+/// NTP LI field: 1 = last minute has 61 seconds, 2 = 59 seconds
+/// (0 = no warning, 3 = unsynchronized)
+fn leap_from_indicator(li: u8) -> Option<LeapSecond> {
+    match li {
+        1 => Some(LeapSecond::Insert),
+        2 => Some(LeapSecond::Delete),
+        _ => None,
+    }
+}
+
+/// Convert a signed offset in seconds to the `(magnitude, sign)` form used by `NtpSource`
+fn offset_from_secs(offset_secs: f64) -> (Duration, i8) {
+    let sign = if offset_secs < 0.0 { -1 } else { 1 };
+    let abs_secs = offset_secs.abs();
+
+    // Convert abs_secs to Duration
+    let secs = abs_secs.trunc() as u64;
+    let nanos = (abs_secs.fract() * 1_000_000_000.0) as u32;
+
+    (Duration::new(secs, nanos), sign)
+}
+
+/// Reads the offset from a local chronyd instead of querying the server directly.
+///
+/// Uses `chronyc -c tracking`, so DanteSync consumes chrony's disciplined
+/// estimate rather than running a second, competing NTP client.
+pub struct ChronyClient {
+    chronyc: String,
+    /// Leap status from the last `chronyc tracking`
+    leap: Cell<Option<LeapSecond>>,
+    /// Root delay and stratum from the last `chronyc tracking`
+    quality: Cell<Option<NtpQuality>>,
+}
+
+impl ChronyClient {
+    pub fn new() -> Self {
+        ChronyClient {
+            chronyc: "chronyc".to_string(),
+            leap: Cell::new(None),
+            quality: Cell::new(None),
+        }
+    }
+
+    /// Leap second chrony reported as pending in its last tracking output
+    pub fn leap_second(&self) -> Option<LeapSecond> {
+        self.leap.get()
+    }
+
+    /// Root delay and stratum chrony reported in its last tracking output
+    pub fn quality(&self) -> Option<NtpQuality> {
+        self.quality.get()
+    }
+
+    /// Same contract as `NtpClient::get_offset`: positive = local clock behind.
+    pub fn get_offset(&self) -> Result<(Duration, i8)> {
+        let output = Command::new(&self.chronyc)
+            .args(["-c", "tracking"])
+            .output()
+            .map_err(|e| DanteSyncError::io(format!("Failed to run {}", self.chronyc), e))?;
+        if !output.status.success() {
+            return Err(DanteSyncError::Ntp(format!(
+                "chronyc tracking failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let correction = parse_chrony_tracking(&stdout)?;
+        self.leap.set(parse_chrony_leap(&stdout));
+        self.quality.set(parse_chrony_quality(&stdout));
+        Ok(offset_from_secs(correction))
+    }
+}
 
-        Ok((Duration::new(secs, nanos), sign))
+impl Default for ChronyClient {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
+/// Fields in one line of `chronyc -c tracking`
+const CHRONY_TRACKING_FIELDS: usize = 14;
+
+/// Parse `chronyc -c tracking` CSV into the system clock correction (seconds).
+///
+/// Field 4 is "System time": positive means the system clock is slow of NTP
+/// time, i.e. the same sign convention as `NtpClient::get_offset`. The last
+/// field is the leap status, "Not synchronised" when chrony has no source;
+/// a stratum outside 1-15 or a zero reference time also mean chrony has no
+/// usable estimate, so its offset is not trusted.
+pub fn parse_chrony_tracking(csv: &str) -> Result<f64> {
+    let line = csv
+        .lines()
+        .find(|l| !l.trim().is_empty())
+        .ok_or_else(|| DanteSyncError::Ntp("Empty chronyc tracking output".to_string()))?;
+    let fields: Vec<&str> = line.trim().split(',').collect();
+    if fields.len() != CHRONY_TRACKING_FIELDS {
+        return Err(DanteSyncError::Ntp(format!(
+            "Unexpected chronyc tracking output ({} fields): {}",
+            fields.len(),
+            line
+        )));
+    }
+
+    let leap_status = fields[13];
+    if leap_status.eq_ignore_ascii_case("Not synchronised") {
+        return Err(DanteSyncError::Ntp(
+            "chrony is not synchronised".to_string(),
+        ));
+    }
+    match fields[2].parse::<u8>() {
+        Ok(1..=15) => {}
+        _ => {
+            return Err(DanteSyncError::Ntp(format!(
+                "chrony is not synchronised (stratum {})",
+                fields[2]
+            )))
+        }
+    }
+    match fields[3].parse::<f64>() {
+        Ok(ref_time) if ref_time > 0.0 => {}
+        _ => {
+            return Err(DanteSyncError::Ntp(format!(
+                "chrony has no reference time ({})",
+                fields[3]
+            )))
+        }
+    }
+
+    let correction: f64 = fields[4].parse().map_err(|_| {
+        DanteSyncError::Ntp(format!("Invalid chrony system time offset: {}", fields[4]))
+    })?;
+    if !correction.is_finite() {
+        return Err(DanteSyncError::Ntp(format!(
+            "Invalid chrony system time offset: {}",
+            fields[4]
+        )));
+    }
+    Ok(correction)
+}
+
+/// Leap status (last field) of `chronyc -c tracking`
+pub fn parse_chrony_leap(csv: &str) -> Option<LeapSecond> {
+    let line = csv.lines().find(|l| !l.trim().is_empty())?;
+    match line.trim().rsplit(',').next()? {
+        "Insert second" => Some(LeapSecond::Insert),
+        "Delete second" => Some(LeapSecond::Delete),
+        _ => None,
+    }
+}
+
+/// Stratum (field 2) and root delay (field 10, seconds) of `chronyc -c tracking`
+pub fn parse_chrony_quality(csv: &str) -> Option<NtpQuality> {
+    let line = csv.lines().find(|l| !l.trim().is_empty())?;
+    let fields: Vec<&str> = line.trim().split(',').collect();
+    if fields.len() < 14 {
+        return None;
+    }
+    let stratum = fields[2].parse().ok()?;
+    let root_delay: f64 = fields[10].parse().ok()?;
+    if !root_delay.is_finite() || root_delay < 0.0 {
+        return None;
+    }
+    Some(NtpQuality {
+        rtt: Duration::from_secs_f64(root_delay),
+        stratum,
+    })
+}
+
 // ============================================================================
 // TESTS
 // ============================================================================
@@ -122,7 +422,120 @@ mod tests {
 
     #[test]
     fn test_ntp_client_new() {
-        let client = super::NtpClient::new("pool.ntp.org");
+        let client = super::NtpClient::new("pool.ntp.org", None);
         assert_eq!(client.server, "pool.ntp.org");
     }
+
+    #[test]
+    fn test_ntp_client_bind_ip() {
+        let lan = std::net::Ipv4Addr::new(10, 77, 8, 15);
+        let client = super::NtpClient::new("10.77.8.2", Some(lan));
+        assert_eq!(client.effective_bind_ip(), Some(lan));
+
+        // Local NTP daemon is only reachable unbound
+        let client = super::NtpClient::new("127.0.0.1:123", Some(lan));
+        assert_eq!(client.effective_bind_ip(), None);
+    }
+
+    #[test]
+    fn test_offset_from_secs_helper() {
+        let (d, sign) = super::offset_from_secs(-0.000250);
+        assert_eq!(sign, -1);
+        assert_eq!(d.subsec_micros(), 250);
+    }
+
+    const CHRONY_SLOW: &str = "A9FEA97B,169.254.169.123,4,1700000000.123456789,0.000012345,-0.000001000,0.000020000,-12.345,-0.001,0.012,0.000345000,0.000123000,64.2,Normal\n";
+
+    #[test]
+    fn test_parse_chrony_tracking_slow_clock() {
+        // Positive "System time" = system clock slow → step forward (sign +1)
+        let correction = super::parse_chrony_tracking(CHRONY_SLOW).unwrap();
+        assert!((correction - 0.000012345).abs() < 1e-12);
+        let (d, sign) = super::offset_from_secs(correction);
+        assert_eq!(sign, 1);
+        assert_eq!(d.subsec_micros(), 12);
+    }
+
+    #[test]
+    fn test_parse_chrony_tracking_fast_clock() {
+        let csv = CHRONY_SLOW.replace(",0.000012345,", ",-0.002000000,");
+        let correction = super::parse_chrony_tracking(&csv).unwrap();
+        assert!((correction + 0.002).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_parse_chrony_tracking_rejects_unsynced_and_garbage() {
+        let unsynced = "00000000,,0,0.000000000,0.000000000,0.000000000,0.000000000,0.000,0.000,0.000,1.000000000,1.000000000,0.0,Not synchronised\n";
+        assert!(super::parse_chrony_tracking(unsynced).is_err());
+        assert!(super::parse_chrony_tracking("").is_err());
+        assert!(super::parse_chrony_tracking("506 Cannot talk to daemon").is_err());
+        let bad = CHRONY_SLOW.replace(",0.000012345,", ",abc,");
+        assert!(super::parse_chrony_tracking(&bad).is_err());
+
+        // A leap status that still reads Normal is not enough on its own
+        let stratum_16 = CHRONY_SLOW.replace(",4,", ",16,");
+        assert!(super::parse_chrony_tracking(&stratum_16).is_err());
+        let stratum_0 = CHRONY_SLOW.replace(",4,", ",0,");
+        assert!(super::parse_chrony_tracking(&stratum_0).is_err());
+        let no_ref_time = CHRONY_SLOW.replace(",1700000000.123456789,", ",0.000000000,");
+        assert!(super::parse_chrony_tracking(&no_ref_time).is_err());
+        let extra_field = CHRONY_SLOW.replace(",Normal", ",Normal,extra");
+        assert!(super::parse_chrony_tracking(&extra_field).is_err());
+    }
+
+    #[test]
+    fn test_leap_second_parsing() {
+        use crate::clock::LeapSecond;
+
+        assert_eq!(super::leap_from_indicator(0), None);
+        assert_eq!(super::leap_from_indicator(1), Some(LeapSecond::Insert));
+        assert_eq!(super::leap_from_indicator(2), Some(LeapSecond::Delete));
+        assert_eq!(super::leap_from_indicator(3), None);
+
+        assert_eq!(super::parse_chrony_leap(CHRONY_SLOW), None);
+        let insert = CHRONY_SLOW.replace(",Normal", ",Insert second");
+        assert_eq!(super::parse_chrony_leap(&insert), Some(LeapSecond::Insert));
+        assert_eq!(super::parse_chrony_leap(""), None);
+    }
+
+    #[test]
+    fn test_parse_chrony_quality() {
+        let quality = super::parse_chrony_quality(CHRONY_SLOW).unwrap();
+        assert_eq!(quality.stratum, 4);
+        assert!((quality.rtt.as_secs_f64() - 0.000345).abs() < 1e-9);
+        assert_eq!(super::parse_chrony_quality(""), None);
+        let bad = CHRONY_SLOW.replace(",0.000345000,", ",-1.0,");
+        assert_eq!(super::parse_chrony_quality(&bad), None);
+    }
+
+    #[test]
+    fn test_query_retries_transient_failures() {
+        use crate::error::{DanteSyncError, Result};
+
+        let timeout = |n: u32| DanteSyncError::NtpTimeout(format!("timeout {}", n));
+
+        // Two lost packets, then an answer: one query, two retries
+        let mut calls = 0;
+        let result = super::with_retries(2, || {
+            calls += 1;
+            if calls < 3 {
+                Err(timeout(calls))
+            } else {
+                Ok(42)
+            }
+        });
+        assert_eq!(result.unwrap(), (42, 2));
+
+        // First answer needs no retry
+        assert_eq!(super::with_retries(2, || Ok(7)).unwrap(), (7, 0));
+
+        // Out of retries: the last error is reported after 1 + retries attempts
+        let mut calls = 0;
+        let result: Result<(u32, u32)> = super::with_retries(1, || {
+            calls += 1;
+            Err(timeout(calls))
+        });
+        assert_eq!(result.unwrap_err().to_string(), "timeout 2");
+        assert_eq!(calls, 2);
+    }
 }
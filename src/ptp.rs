@@ -1,10 +1,21 @@
-use anyhow::{anyhow, Result};
+use crate::clock::LeapSecond;
+use crate::error::{DanteSyncError, Result};
 use byteorder::{BigEndian, ReadBytesExt};
 use std::io::Cursor;
 
 pub const PTP_EVENT_PORT: u16 = 319;
 pub const PTP_GENERAL_PORT: u16 = 320;
 
+/// PTPv1 header flag bits (IEEE 1588-2002): leap second at the end of today
+pub const PTP_LI_61: u16 = 0x0001;
+pub const PTP_LI_59: u16 = 0x0002;
+
+/// PTPv2 flagField bits (IEEE 1588-2008, octet 0 in the high byte): Syncs
+/// from an alternate master or unicast negotiation are not the timing source
+pub const PTP_V2_ALTERNATE_MASTER: u16 = 0x0100;
+pub const PTP_V2_TWO_STEP: u16 = 0x0200;
+pub const PTP_V2_UNICAST: u16 = 0x0400;
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum PtpV1Control {
     Sync = 0,
@@ -36,14 +47,22 @@ pub struct PtpV1Header {
     pub source_uuid: [u8; 6],
     pub sequence_id: u16,
     pub control: u8,
+    pub flags: u16,
 }
 
 impl PtpV1Header {
     pub const SIZE: usize = 36;
 
+    /// Message body following the header (empty if the packet is truncated)
+    pub fn body(packet: &[u8]) -> &[u8] {
+        packet.get(Self::SIZE..).unwrap_or(&[])
+    }
+
     pub fn parse(data: &[u8]) -> Result<Self> {
         if data.len() < Self::SIZE {
-            return Err(anyhow!("Packet too short for PTP header"));
+            return Err(DanteSyncError::Parse(
+                "Packet too short for PTP header".to_string(),
+            ));
         }
         let mut rdr = Cursor::new(data);
 
@@ -67,6 +86,8 @@ impl PtpV1Header {
         let _source_port_id = rdr.read_u16::<BigEndian>()?;
         let sequence_id = rdr.read_u16::<BigEndian>()?;
         let control = rdr.read_u8()?;
+        let _reserved = rdr.read_u8()?;
+        let flags = rdr.read_u16::<BigEndian>()?;
 
         let message_type = PtpV1Control::from(control);
 
@@ -77,8 +98,20 @@ impl PtpV1Header {
             source_uuid,
             sequence_id,
             control,
+            flags,
         })
     }
+
+    /// Leap second announced by the master via PTP_LI_61 / PTP_LI_59
+    pub fn leap_second(&self) -> Option<LeapSecond> {
+        if self.flags & PTP_LI_61 != 0 {
+            Some(LeapSecond::Insert)
+        } else if self.flags & PTP_LI_59 != 0 {
+            Some(LeapSecond::Delete)
+        } else {
+            None
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -101,7 +134,7 @@ impl PtpTimestamp {
 
 #[derive(Debug)]
 pub struct PtpV1SyncMessageBody {
-    // originTimestamp (8)
+    pub origin_timestamp: PtpTimestamp,
     // epochNumber (2)
     // currentUtcOffset (2)
     // grandmasterCommTech (1)
@@ -115,11 +148,16 @@ impl PtpV1SyncMessageBody {
 
     pub fn parse(data: &[u8]) -> Result<Self> {
         if data.len() < Self::MIN_SIZE {
-            return Err(anyhow!("Packet too short for Sync body"));
+            return Err(DanteSyncError::Parse(
+                "Packet too short for Sync body".to_string(),
+            ));
         }
         let mut rdr = Cursor::new(data);
 
-        // Skip originTimestamp (8), epoch (2), utcOffset (2), commTech (1) = 13 bytes
+        let seconds = rdr.read_u32::<BigEndian>()?;
+        let nanoseconds = rdr.read_u32::<BigEndian>()?;
+
+        // Skip epoch (2), utcOffset (2), commTech (1) = byte 13
         rdr.set_position(13);
 
         let mut gm_uuid = [0u8; 6];
@@ -128,6 +166,10 @@ impl PtpV1SyncMessageBody {
         }
 
         Ok(PtpV1SyncMessageBody {
+            origin_timestamp: PtpTimestamp {
+                seconds,
+                nanoseconds,
+            },
             grandmaster_clock_uuid: gm_uuid,
         })
     }
@@ -144,7 +186,9 @@ impl PtpV1FollowUpBody {
 
     pub fn parse(data: &[u8]) -> Result<Self> {
         if data.len() < Self::SIZE {
-            return Err(anyhow!("Packet too short for FollowUp body"));
+            return Err(DanteSyncError::Parse(
+                "Packet too short for FollowUp body".to_string(),
+            ));
         }
         let mut rdr = Cursor::new(data);
 
@@ -165,6 +209,348 @@ impl PtpV1FollowUpBody {
     }
 }
 
+// ============================================================================
+// TLV SUFFIX
+// ============================================================================
+// Vendors may append type/length/value records after the fixed message
+// (IEEE 1588-2008 §14). Organization extensions carry the vendor's OUI, which
+// together with the clock UUID (a MAC address) identifies Dante masters.
+
+/// Fixed length of a PTPv1 Sync / Delay_Req message; TLVs follow
+pub const PTP_V1_SYNC_LEN: usize = 124;
+
+/// tlvType of an organization extension
+pub const TLV_ORGANIZATION_EXTENSION: u16 = 0x0003;
+
+/// Audinate (Dante) IEEE OUI
+pub const AUDINATE_OUI: [u8; 3] = [0x00, 0x1D, 0xC1];
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct PtpTlv<'a> {
+    pub tlv_type: u16,
+    pub value: &'a [u8],
+}
+
+/// Decoded ORGANIZATION_EXTENSION TLV
+#[derive(Debug, PartialEq, Eq)]
+pub struct OrganizationTlv<'a> {
+    pub oui: [u8; 3],
+    pub subtype: [u8; 3],
+    pub data: &'a [u8],
+}
+
+impl<'a> PtpTlv<'a> {
+    /// Organization id and subtype, if this is an organization extension
+    pub fn organization(&self) -> Option<OrganizationTlv<'a>> {
+        if self.tlv_type != TLV_ORGANIZATION_EXTENSION || self.value.len() < 6 {
+            return None;
+        }
+        Some(OrganizationTlv {
+            oui: [self.value[0], self.value[1], self.value[2]],
+            subtype: [self.value[3], self.value[4], self.value[5]],
+            data: &self.value[6..],
+        })
+    }
+}
+
+/// TLVs following the first `fixed_len` bytes of a packet. Stops at a
+/// truncated record, so padding or a malformed suffix yields what parsed.
+pub fn parse_tlvs(packet: &[u8], fixed_len: usize) -> Vec<PtpTlv<'_>> {
+    let mut tlvs = Vec::new();
+    let mut rest = packet.get(fixed_len..).unwrap_or(&[]);
+    while rest.len() >= 4 {
+        let tlv_type = u16::from_be_bytes([rest[0], rest[1]]);
+        let len = u16::from_be_bytes([rest[2], rest[3]]) as usize;
+        let Some(value) = rest.get(4..4 + len) else {
+            break;
+        };
+        tlvs.push(PtpTlv { tlv_type, value });
+        rest = &rest[4 + len..];
+    }
+    tlvs
+}
+
+/// Clock UUID (MAC address) assigned to Audinate
+pub fn is_audinate_uuid(uuid: &[u8; 6]) -> bool {
+    uuid[..3] == AUDINATE_OUI
+}
+
+/// A Dante master: Audinate clock UUID or an Audinate organization TLV
+pub fn is_dante_master(gm_uuid: &[u8; 6], tlvs: &[PtpTlv]) -> bool {
+    is_audinate_uuid(gm_uuid)
+        || tlvs
+            .iter()
+            .filter_map(PtpTlv::organization)
+            .any(|org| org.oui == AUDINATE_OUI)
+}
+
+// ============================================================================
+// PTPv2 / 802.1AS PEER DELAY
+// ============================================================================
+// gPTP links measure per-hop delay with Pdelay_Req/Pdelay_Resp/
+// Pdelay_Resp_Follow_Up instead of end-to-end Delay_Req/Delay_Resp.
+// Only the parsing lives here; see `DelayMechanism` in config.
+
+/// PTPv2 messageType (low nibble of the first header byte)
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PtpV2MessageType {
+    Sync = 0x0,
+    DelayReq = 0x1,
+    PdelayReq = 0x2,
+    PdelayResp = 0x3,
+    FollowUp = 0x8,
+    DelayResp = 0x9,
+    PdelayRespFollowUp = 0xA,
+    Announce = 0xB,
+    Signaling = 0xC,
+    Management = 0xD,
+    Other = 0xF,
+}
+
+impl From<u8> for PtpV2MessageType {
+    fn from(v: u8) -> Self {
+        match v & 0x0F {
+            0x0 => PtpV2MessageType::Sync,
+            0x1 => PtpV2MessageType::DelayReq,
+            0x2 => PtpV2MessageType::PdelayReq,
+            0x3 => PtpV2MessageType::PdelayResp,
+            0x8 => PtpV2MessageType::FollowUp,
+            0x9 => PtpV2MessageType::DelayResp,
+            0xA => PtpV2MessageType::PdelayRespFollowUp,
+            0xB => PtpV2MessageType::Announce,
+            0xC => PtpV2MessageType::Signaling,
+            0xD => PtpV2MessageType::Management,
+            _ => PtpV2MessageType::Other,
+        }
+    }
+}
+
+/// clockIdentity (8) + portNumber (2)
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct PortIdentity {
+    pub clock_identity: [u8; 8],
+    pub port_number: u16,
+}
+
+impl PortIdentity {
+    pub const SIZE: usize = 10;
+
+    fn read(rdr: &mut Cursor<&[u8]>) -> Result<Self> {
+        let mut clock_identity = [0u8; 8];
+        for byte in &mut clock_identity {
+            *byte = rdr.read_u8()?;
+        }
+        let port_number = rdr.read_u16::<BigEndian>()?;
+        Ok(PortIdentity {
+            clock_identity,
+            port_number,
+        })
+    }
+}
+
+/// Read a PTPv2 10-byte timestamp (48-bit seconds + 32-bit nanoseconds)
+fn read_v2_timestamp(rdr: &mut Cursor<&[u8]>) -> Result<PtpTimestamp> {
+    let seconds_hi = rdr.read_u16::<BigEndian>()?;
+    let seconds = rdr.read_u32::<BigEndian>()?;
+    let nanoseconds = rdr.read_u32::<BigEndian>()?;
+    if seconds_hi != 0 {
+        return Err(DanteSyncError::Parse(
+            "PTPv2 timestamp beyond 32-bit seconds".to_string(),
+        ));
+    }
+    Ok(PtpTimestamp {
+        seconds,
+        nanoseconds,
+    })
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct PtpV2Header {
+    pub message_type: PtpV2MessageType,
+    pub version_ptp: u8,
+    pub message_length: u16,
+    pub domain_number: u8,
+    /// flagField (`PTP_V2_*` bits)
+    pub flags: u16,
+    /// correctionField in ns (scaled 2^-16 ns on the wire)
+    pub correction_ns: i64,
+    pub source_port_identity: PortIdentity,
+    pub sequence_id: u16,
+}
+
+impl PtpV2Header {
+    pub const SIZE: usize = 34;
+
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        if data.len() < Self::SIZE {
+            return Err(DanteSyncError::Parse(
+                "Packet too short for PTPv2 header".to_string(),
+            ));
+        }
+        let mut rdr = Cursor::new(data);
+
+        let message_type = PtpV2MessageType::from(rdr.read_u8()?);
+        let version_ptp = rdr.read_u8()? & 0x0F;
+        if version_ptp != 2 {
+            return Err(DanteSyncError::Parse(format!(
+                "Not a PTPv2 packet (version {})",
+                version_ptp
+            )));
+        }
+        let message_length = rdr.read_u16::<BigEndian>()?;
+        let domain_number = rdr.read_u8()?;
+
+        // Skip reserved (1)
+        rdr.set_position(6);
+        let flags = rdr.read_u16::<BigEndian>()?;
+        let correction_ns = rdr.read_i64::<BigEndian>()? >> 16;
+
+        // Skip reserved (4)
+        rdr.set_position(20);
+        let source_port_identity = PortIdentity::read(&mut rdr)?;
+        let sequence_id = rdr.read_u16::<BigEndian>()?;
+
+        Ok(PtpV2Header {
+            message_type,
+            version_ptp,
+            message_length,
+            domain_number,
+            flags,
+            correction_ns,
+            source_port_identity,
+            sequence_id,
+        })
+    }
+
+    /// Flag marking the message as not from the multicast timing source:
+    /// `"alternateMaster"` or `"unicast"` (None = authoritative)
+    pub fn non_authoritative_flag(&self) -> Option<&'static str> {
+        if self.flags & PTP_V2_ALTERNATE_MASTER != 0 {
+            Some("alternateMaster")
+        } else if self.flags & PTP_V2_UNICAST != 0 {
+            Some("unicast")
+        } else {
+            None
+        }
+    }
+}
+
+/// Sync / Follow_Up body: (precise)originTimestamp (10)
+#[derive(Debug)]
+pub struct PtpV2TimestampBody {
+    pub origin_timestamp: PtpTimestamp,
+}
+
+impl PtpV2TimestampBody {
+    pub const SIZE: usize = 10;
+
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        if data.len() < Self::SIZE {
+            return Err(DanteSyncError::Parse(
+                "Packet too short for Sync/Follow_Up body".to_string(),
+            ));
+        }
+        let mut rdr = Cursor::new(data);
+        Ok(PtpV2TimestampBody {
+            origin_timestamp: read_v2_timestamp(&mut rdr)?,
+        })
+    }
+}
+
+/// Announce body up to grandmasterIdentity: originTimestamp (10),
+/// currentUtcOffset (2), reserved (1), priority1 (1), clockQuality (4),
+/// priority2 (1), grandmasterIdentity (8)
+#[derive(Debug)]
+pub struct PtpV2AnnounceBody {
+    pub origin_timestamp: PtpTimestamp,
+    pub grandmaster_priority1: u8,
+    pub grandmaster_identity: [u8; 8],
+}
+
+impl PtpV2AnnounceBody {
+    pub const MIN_SIZE: usize = 27;
+
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        if data.len() < Self::MIN_SIZE {
+            return Err(DanteSyncError::Parse(
+                "Packet too short for Announce body".to_string(),
+            ));
+        }
+        let mut rdr = Cursor::new(data);
+        let origin_timestamp = read_v2_timestamp(&mut rdr)?;
+        rdr.set_position(13);
+        let grandmaster_priority1 = rdr.read_u8()?;
+        rdr.set_position(19);
+        let mut grandmaster_identity = [0u8; 8];
+        for byte in &mut grandmaster_identity {
+            *byte = rdr.read_u8()?;
+        }
+        Ok(PtpV2AnnounceBody {
+            origin_timestamp,
+            grandmaster_priority1,
+            grandmaster_identity,
+        })
+    }
+}
+
+/// Pdelay_Req body: originTimestamp (10) + reserved (10)
+#[derive(Debug)]
+pub struct PtpV2PdelayReqBody {
+    pub origin_timestamp: PtpTimestamp,
+}
+
+impl PtpV2PdelayReqBody {
+    pub const SIZE: usize = 20;
+
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        if data.len() < Self::SIZE {
+            return Err(DanteSyncError::Parse(
+                "Packet too short for Pdelay_Req body".to_string(),
+            ));
+        }
+        let mut rdr = Cursor::new(data);
+        Ok(PtpV2PdelayReqBody {
+            origin_timestamp: read_v2_timestamp(&mut rdr)?,
+        })
+    }
+}
+
+/// Pdelay_Resp / Pdelay_Resp_Follow_Up body: a timestamp (10) + requestingPortIdentity (10)
+///
+/// For Pdelay_Resp the timestamp is requestReceiptTimestamp (t2); for
+/// Pdelay_Resp_Follow_Up it is responseOriginTimestamp (t3).
+#[derive(Debug)]
+pub struct PtpV2PdelayRespBody {
+    pub timestamp: PtpTimestamp,
+    pub requesting_port_identity: PortIdentity,
+}
+
+impl PtpV2PdelayRespBody {
+    pub const SIZE: usize = 20;
+
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        if data.len() < Self::SIZE {
+            return Err(DanteSyncError::Parse(
+                "Packet too short for Pdelay_Resp body".to_string(),
+            ));
+        }
+        let mut rdr = Cursor::new(data);
+        let timestamp = read_v2_timestamp(&mut rdr)?;
+        let requesting_port_identity = PortIdentity::read(&mut rdr)?;
+        Ok(PtpV2PdelayRespBody {
+            timestamp,
+            requesting_port_identity,
+        })
+    }
+}
+
+/// Mean link delay from one peer-delay exchange (all values in ns):
+/// t1 = Pdelay_Req sent, t2 = received by peer, t3 = Pdelay_Resp sent by peer,
+/// t4 = received locally. The peer's turnaround (t3 - t2) is excluded.
+pub fn peer_mean_path_delay_ns(t1: i64, t2: i64, t3: i64, t4: i64) -> i64 {
+    ((t4 - t1) - (t3 - t2)) / 2
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -211,6 +597,61 @@ mod tests {
         assert_eq!(header.message_type, PtpV1Control::Sync);
         assert_eq!(header.sequence_id, 258);
         assert_eq!(header.source_uuid, [0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF]);
+        assert_eq!(header.leap_second(), None);
+    }
+
+    #[test]
+    fn test_parse_header_leap_flags() {
+        let mut data = vec![0u8; 36];
+        data[0] = 0x10;
+        data[35] = PTP_LI_61 as u8;
+        let header = PtpV1Header::parse(&data).unwrap();
+        assert_eq!(header.flags, PTP_LI_61);
+        assert_eq!(header.leap_second(), Some(LeapSecond::Insert));
+
+        data[35] = PTP_LI_59 as u8;
+        let header = PtpV1Header::parse(&data).unwrap();
+        assert_eq!(header.leap_second(), Some(LeapSecond::Delete));
+    }
+
+    #[test]
+    fn test_parse_tlvs() {
+        let mut packet = vec![0u8; PTP_V1_SYNC_LEN];
+        // Organization extension: Audinate OUI, subtype 000001, 2 data bytes
+        packet.extend_from_slice(&[0x00, 0x03, 0x00, 0x08]);
+        packet.extend_from_slice(&[0x00, 0x1D, 0xC1, 0x00, 0x00, 0x01, 0xAB, 0xCD]);
+        // Unknown TLV type with empty value
+        packet.extend_from_slice(&[0x7F, 0x00, 0x00, 0x00]);
+        // Truncated record (claims 16 bytes, has 2)
+        packet.extend_from_slice(&[0x00, 0x03, 0x00, 0x10, 0x00, 0x1D]);
+
+        let tlvs = parse_tlvs(&packet, PTP_V1_SYNC_LEN);
+        assert_eq!(tlvs.len(), 2);
+        let org = tlvs[0].organization().unwrap();
+        assert_eq!(org.oui, AUDINATE_OUI);
+        assert_eq!(org.subtype, [0x00, 0x00, 0x01]);
+        assert_eq!(org.data, &[0xAB, 0xCD]);
+        assert_eq!(tlvs[1].tlv_type, 0x7F00);
+        assert!(tlvs[1].organization().is_none());
+
+        // No suffix, or a packet shorter than the fixed part
+        assert!(parse_tlvs(&packet[..PTP_V1_SYNC_LEN], PTP_V1_SYNC_LEN).is_empty());
+        assert!(parse_tlvs(&packet[..10], PTP_V1_SYNC_LEN).is_empty());
+    }
+
+    #[test]
+    fn test_is_dante_master() {
+        let dante_gm = [0x00, 0x1D, 0xC1, 0x12, 0x34, 0x56];
+        let other_gm = [0xEC, 0x46, 0x70, 0x12, 0x34, 0x56];
+        assert!(is_dante_master(&dante_gm, &[]));
+        assert!(!is_dante_master(&other_gm, &[]));
+
+        let value = [0x00, 0x1D, 0xC1, 0x00, 0x00, 0x01];
+        let tlvs = [PtpTlv {
+            tlv_type: TLV_ORGANIZATION_EXTENSION,
+            value: &value,
+        }];
+        assert!(is_dante_master(&other_gm, &tlvs));
     }
 
     #[test]
@@ -249,7 +690,9 @@ mod tests {
     #[test]
     fn test_parse_sync_body_gm_uuid() {
         let mut data = vec![0u8; 20];
-        // 13 bytes skip
+        // originTimestamp: 7s + 500ns
+        data[3] = 7;
+        data[4..8].copy_from_slice(&500u32.to_be_bytes());
         // 13: GM UUID start
         data[13] = 0x11;
         data[14] = 0x22;
@@ -263,5 +706,160 @@ mod tests {
             body.grandmaster_clock_uuid,
             [0x11, 0x22, 0x33, 0x44, 0x55, 0x66]
         );
+        assert_eq!(body.origin_timestamp.to_nanos(), 7_000_000_500);
+    }
+
+    fn v2_header(msg_type: u8, seq: u16) -> Vec<u8> {
+        let mut data = vec![0u8; PtpV2Header::SIZE];
+        data[0] = 0x10 | msg_type; // transportSpecific = 1 (802.1AS)
+        data[1] = 0x02;
+        data[4] = 0; // domain
+                     // correctionField = 1.5ns (scaled by 2^16)
+        data[8..16].copy_from_slice(&((3i64 << 16) / 2).to_be_bytes());
+        data[20..28].copy_from_slice(&[0x00, 0x1D, 0xC1, 0xFF, 0xFE, 0x12, 0x34, 0x56]);
+        data[28..30].copy_from_slice(&1u16.to_be_bytes());
+        data[30..32].copy_from_slice(&seq.to_be_bytes());
+        data
+    }
+
+    #[test]
+    fn test_ptp_v2_message_type_from() {
+        assert_eq!(PtpV2MessageType::from(0x02), PtpV2MessageType::PdelayReq);
+        assert_eq!(PtpV2MessageType::from(0x03), PtpV2MessageType::PdelayResp);
+        assert_eq!(
+            PtpV2MessageType::from(0x1A),
+            PtpV2MessageType::PdelayRespFollowUp
+        );
+        assert_eq!(PtpV2MessageType::from(0x07), PtpV2MessageType::Other);
+    }
+
+    #[test]
+    fn test_parse_v2_header() {
+        let header = PtpV2Header::parse(&v2_header(0x3, 77)).unwrap();
+        assert_eq!(header.message_type, PtpV2MessageType::PdelayResp);
+        assert_eq!(header.version_ptp, 2);
+        assert_eq!(header.correction_ns, 1);
+        assert_eq!(header.source_port_identity.port_number, 1);
+        assert_eq!(header.sequence_id, 77);
+        assert_eq!(header.flags, 0);
+        assert_eq!(header.non_authoritative_flag(), None);
+
+        // PTPv1 packets are rejected
+        let mut v1 = v2_header(0x0, 1);
+        v1[0] = 0x10;
+        v1[1] = 0x01;
+        assert!(PtpV2Header::parse(&v1).is_err());
+        assert!(PtpV2Header::parse(&[0u8; 20]).is_err());
+    }
+
+    #[test]
+    fn test_parse_v2_flags() {
+        let mut data = v2_header(0x0, 1);
+        data[6] = 0x02; // twoStepFlag only
+        let header = PtpV2Header::parse(&data).unwrap();
+        assert_eq!(header.flags, PTP_V2_TWO_STEP);
+        assert_eq!(header.non_authoritative_flag(), None);
+        assert_eq!(header.correction_ns, 1);
+
+        data[6] = 0x03; // alternateMasterFlag + twoStepFlag
+        let header = PtpV2Header::parse(&data).unwrap();
+        assert_eq!(header.non_authoritative_flag(), Some("alternateMaster"));
+
+        data[6] = 0x06; // unicastFlag + twoStepFlag
+        let header = PtpV2Header::parse(&data).unwrap();
+        assert_eq!(header.non_authoritative_flag(), Some("unicast"));
+    }
+
+    #[test]
+    fn test_parse_pdelay_bodies() {
+        let mut req = vec![0u8; PtpV2PdelayReqBody::SIZE];
+        req[2..6].copy_from_slice(&100u32.to_be_bytes());
+        req[6..10].copy_from_slice(&500u32.to_be_bytes());
+        let body = PtpV2PdelayReqBody::parse(&req).unwrap();
+        assert_eq!(body.origin_timestamp.to_nanos(), 100_000_000_500);
+
+        let mut resp = vec![0u8; PtpV2PdelayRespBody::SIZE];
+        resp[2..6].copy_from_slice(&7u32.to_be_bytes());
+        resp[10..18].copy_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        resp[18..20].copy_from_slice(&2u16.to_be_bytes());
+        let body = PtpV2PdelayRespBody::parse(&resp).unwrap();
+        assert_eq!(body.timestamp.seconds, 7);
+        assert_eq!(
+            body.requesting_port_identity,
+            PortIdentity {
+                clock_identity: [1, 2, 3, 4, 5, 6, 7, 8],
+                port_number: 2
+            }
+        );
+
+        // 48-bit seconds beyond u32 are rejected rather than truncated
+        resp[0] = 1;
+        assert!(PtpV2PdelayRespBody::parse(&resp).is_err());
+        assert!(PtpV2PdelayRespBody::parse(&resp[..19]).is_err());
+    }
+
+    #[test]
+    fn test_parse_v2_sync_and_announce_bodies() {
+        let mut sync = vec![0u8; PtpV2TimestampBody::SIZE];
+        sync[5] = 9;
+        let body = PtpV2TimestampBody::parse(&sync).unwrap();
+        assert_eq!(body.origin_timestamp.seconds, 9);
+        assert!(PtpV2TimestampBody::parse(&sync[..9]).is_err());
+
+        let mut announce = vec![0u8; 64];
+        announce[5] = 42;
+        announce[13] = 128;
+        announce[19..27].copy_from_slice(&[0x00, 0x1D, 0xC1, 0xFF, 0xFE, 0x01, 0x02, 0x03]);
+        let body = PtpV2AnnounceBody::parse(&announce).unwrap();
+        assert_eq!(body.origin_timestamp.seconds, 42);
+        assert_eq!(body.grandmaster_priority1, 128);
+        assert_eq!(
+            body.grandmaster_identity,
+            [0x00, 0x1D, 0xC1, 0xFF, 0xFE, 0x01, 0x02, 0x03]
+        );
+        assert!(PtpV2AnnounceBody::parse(&announce[..26]).is_err());
+    }
+
+    #[test]
+    fn test_peer_mean_path_delay() {
+        // 500ns each way, peer holds the request for 10µs
+        assert_eq!(peer_mean_path_delay_ns(1_000, 1_500, 11_500, 12_000), 500);
+    }
+
+    /// Every parser must return Err (never panic) on untrusted input
+    fn parse_all(data: &[u8]) {
+        let _ = PtpV1Header::parse(data);
+        let _ = PtpV1SyncMessageBody::parse(PtpV1Header::body(data));
+        let _ = PtpV1FollowUpBody::parse(PtpV1Header::body(data));
+        let _ = PtpV1SyncMessageBody::parse(data);
+        let _ = PtpV1FollowUpBody::parse(data);
+        let _ = PtpV2Header::parse(data);
+        let _ = PtpV2PdelayReqBody::parse(data);
+        let _ = PtpV2PdelayRespBody::parse(data);
+        let _ = PtpV2TimestampBody::parse(data);
+        let _ = PtpV2AnnounceBody::parse(data);
+    }
+
+    #[test]
+    fn test_parsers_handle_every_truncation() {
+        let mut packet = [0xFFu8; 80];
+        packet[0] = 0x10;
+        packet[1] = 0x02;
+        for len in 0..=packet.len() {
+            parse_all(&packet[..len]);
+        }
+        assert!(PtpV1Header::body(&packet[..10]).is_empty());
+    }
+
+    #[test]
+    fn test_parsers_never_panic_on_random_input() {
+        use rand::Rng;
+        let mut rng = rand::rng();
+        for _ in 0..20_000 {
+            let len = rng.random_range(0..128);
+            let mut data = vec![0u8; len];
+            rng.fill(&mut data[..]);
+            parse_all(&data);
+        }
     }
 }
@@ -0,0 +1,178 @@
+//! Windows registry status export for enterprise monitoring
+//!
+//! With `registry_status_secs` set, the service writes key `SyncStatus`
+//! values under `HKLM\SOFTWARE\DanteSync\Status` (`Status-<name>` for a named
+//! instance) so SCOM, Intune or WMI registry providers can read them without
+//! speaking the pipe protocol. `UpdatedTs` lets a monitor spot a stale key
+//! (service stopped or hung).
+
+use crate::status::SyncStatus;
+
+/// Registry key under HKLM holding the status of an instance
+pub fn status_key_path(instance: Option<&str>) -> String {
+    format!(
+        r"SOFTWARE\DanteSync\Status{}",
+        crate::status::instance_suffix(instance)
+    )
+}
+
+/// One registry value: REG_SZ, REG_DWORD or REG_QWORD
+#[derive(Debug, Clone, PartialEq)]
+pub enum RegistryValue {
+    String(String),
+    Dword(u32),
+    Qword(u64),
+}
+
+/// Values written for `status`, by value name
+///
+/// Signed numbers are stored as REG_QWORD two's complement (read back as
+/// signed 64-bit); the drift is a string since the registry has no floats.
+pub fn status_values(status: &SyncStatus) -> Vec<(&'static str, RegistryValue)> {
+    vec![
+        ("Mode", RegistryValue::String(status.mode_str().to_string())),
+        ("Locked", RegistryValue::Dword(status.is_locked as u32)),
+        ("OffsetNs", RegistryValue::Qword(status.offset_ns as u64)),
+        (
+            "DriftPpm",
+            RegistryValue::String(format!("{:.3}", status.drift_ppm)),
+        ),
+        (
+            "NtpOffsetUs",
+            RegistryValue::Qword(status.ntp_offset_us as u64),
+        ),
+        (
+            "LockedDurationSecs",
+            RegistryValue::Qword(status.locked_duration_secs),
+        ),
+        ("UnlockCount", RegistryValue::Qword(status.unlock_count)),
+        ("UpdatedTs", RegistryValue::Qword(status.updated_ts)),
+        ("Summary", RegistryValue::String(status.summary())),
+    ]
+}
+
+/// Open registry key the status is written to
+#[cfg(windows)]
+pub struct RegistryStatus {
+    key: windows::Win32::System::Registry::HKEY,
+    path: String,
+}
+
+#[cfg(windows)]
+impl RegistryStatus {
+    /// Create (or open) the instance's status key under HKLM
+    pub fn open(instance: Option<&str>) -> crate::error::Result<Self> {
+        use windows::core::PCWSTR;
+        use windows::Win32::System::Registry::{
+            RegCreateKeyExW, HKEY, HKEY_LOCAL_MACHINE, KEY_SET_VALUE, REG_OPTION_NON_VOLATILE,
+        };
+
+        let path = status_key_path(instance);
+        let wide = to_wide(&path);
+        let mut key = HKEY::default();
+        unsafe {
+            RegCreateKeyExW(
+                HKEY_LOCAL_MACHINE,
+                PCWSTR(wide.as_ptr()),
+                0,
+                PCWSTR::null(),
+                REG_OPTION_NON_VOLATILE,
+                KEY_SET_VALUE,
+                None,
+                &mut key,
+                None,
+            )
+        }
+        .ok()
+        .map_err(|e| crate::error::DanteSyncError::io(format!("HKLM\\{}", path), e.into()))?;
+        Ok(Self { key, path })
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Write all `status_values`
+    pub fn write(&self, status: &SyncStatus) -> crate::error::Result<()> {
+        use windows::core::PCWSTR;
+        use windows::Win32::System::Registry::{RegSetValueExW, REG_DWORD, REG_QWORD, REG_SZ};
+
+        for (name, value) in status_values(status) {
+            let name_wide = to_wide(name);
+            let (kind, data) = match value {
+                RegistryValue::String(text) => (
+                    REG_SZ,
+                    to_wide(&text)
+                        .iter()
+                        .flat_map(|c| c.to_le_bytes())
+                        .collect::<Vec<u8>>(),
+                ),
+                RegistryValue::Dword(v) => (REG_DWORD, v.to_le_bytes().to_vec()),
+                RegistryValue::Qword(v) => (REG_QWORD, v.to_le_bytes().to_vec()),
+            };
+            unsafe { RegSetValueExW(self.key, PCWSTR(name_wide.as_ptr()), 0, kind, Some(&data)) }
+                .ok()
+                .map_err(|e| crate::error::DanteSyncError::io(name, e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+impl Drop for RegistryStatus {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = windows::Win32::System::Registry::RegCloseKey(self.key);
+        }
+    }
+}
+
+/// NUL-terminated UTF-16
+#[cfg(windows)]
+fn to_wide(text: &str) -> Vec<u16> {
+    text.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_key_path_per_instance() {
+        assert_eq!(status_key_path(None), r"SOFTWARE\DanteSync\Status");
+        assert_eq!(
+            status_key_path(Some("studio-b")),
+            r"SOFTWARE\DanteSync\Status-studio-b"
+        );
+    }
+
+    #[test]
+    fn test_status_values() {
+        let status = SyncStatus {
+            mode: "LOCK".to_string(),
+            is_locked: true,
+            offset_ns: -1_500,
+            drift_ppm: 33.4567,
+            ntp_offset_us: -12,
+            locked_duration_secs: 7_200,
+            updated_ts: 1_700_000_000,
+            ..Default::default()
+        };
+        let values = status_values(&status);
+        let get = |name: &str| {
+            values
+                .iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, v)| v.clone())
+                .unwrap()
+        };
+        assert_eq!(get("Mode"), RegistryValue::String("LOCK".to_string()));
+        assert_eq!(get("Locked"), RegistryValue::Dword(1));
+        assert_eq!(get("OffsetNs"), RegistryValue::Qword((-1_500i64) as u64));
+        assert_eq!(get("DriftPpm"), RegistryValue::String("33.457".to_string()));
+        assert_eq!(get("NtpOffsetUs"), RegistryValue::Qword((-12i64) as u64));
+        assert_eq!(get("LockedDurationSecs"), RegistryValue::Qword(7_200));
+        assert_eq!(get("UpdatedTs"), RegistryValue::Qword(1_700_000_000));
+        assert!(matches!(get("Summary"), RegistryValue::String(s) if s.starts_with("LOCK |")));
+    }
+}
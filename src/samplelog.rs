@@ -0,0 +1,302 @@
+//! Per-sample output: CSV log for offline analysis, FIFO for live plotting
+//!
+//! One row per servo sample so a whole show can be graphed in a spreadsheet
+//! afterwards. Rows are buffered and written in batches (at most every
+//! `FLUSH_INTERVAL`), and the file rotates with the main log's size limits;
+//! every rotated file starts with the column header.
+//!
+//! `SampleFifo` streams a shorter line per sample to a named pipe as it
+//! happens, for plotjuggler or a script watching the servo live.
+
+use crate::logfile::RotatingFileWriter;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+pub const FIFO_HEADER: &str = "epoch_s,offset_ns,rate_us_s,adj_ppm,mode\n";
+
+pub const CSV_HEADER: &str = "epoch_s,offset_ns,raw_rate_us_s,filtered_rate_us_s,smoothed_rate_us_s,applied_ppm,mode,is_spike,ntp_offset_us\n";
+
+/// One servo sample
+#[derive(Debug, Clone)]
+pub struct SampleRecord {
+    pub timestamp: SystemTime,
+    pub offset_ns: i64,
+    pub raw_rate: f64,
+    pub filtered_rate: f64,
+    pub smoothed_rate: f64,
+    pub applied_ppm: f64,
+    pub mode: &'static str,
+    pub is_spike: bool,
+    pub ntp_offset_us: i64,
+}
+
+impl SampleRecord {
+    fn to_csv_row(&self) -> String {
+        let epoch = self
+            .timestamp
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+        format!(
+            "{:.3},{},{:.3},{:.3},{:.3},{:.3},{},{},{}\n",
+            epoch,
+            self.offset_ns,
+            self.raw_rate,
+            self.filtered_rate,
+            self.smoothed_rate,
+            self.applied_ppm,
+            self.mode,
+            self.is_spike as u8,
+            self.ntp_offset_us
+        )
+    }
+
+    fn to_fifo_line(&self) -> String {
+        let epoch = self
+            .timestamp
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+        format!(
+            "{:.3},{},{:.3},{:.3},{}\n",
+            epoch, self.offset_ns, self.smoothed_rate, self.applied_ppm, self.mode
+        )
+    }
+}
+
+pub struct CsvSampleLog {
+    writer: Box<dyn Write + Send>,
+    pending: String,
+    last_flush: Instant,
+}
+
+impl CsvSampleLog {
+    /// Append to `path`, rotating like the main log (see `RotatingFileWriter`)
+    pub fn open(path: impl AsRef<Path>, max_bytes: u64, max_files: usize) -> io::Result<Self> {
+        let writer =
+            RotatingFileWriter::open(path, max_bytes, max_files)?.with_header(CSV_HEADER)?;
+        Ok(CsvSampleLog {
+            writer: Box::new(writer),
+            pending: String::new(),
+            last_flush: Instant::now(),
+        })
+    }
+
+    /// Queue a row; writes the batch once `FLUSH_INTERVAL` has passed
+    pub fn record(&mut self, sample: &SampleRecord) -> io::Result<()> {
+        self.pending.push_str(&sample.to_csv_row());
+        if self.last_flush.elapsed() >= FLUSH_INTERVAL {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Write all queued rows in one batch (rotation never splits a row)
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.last_flush = Instant::now();
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let batch = std::mem::take(&mut self.pending);
+        self.writer.write_all(batch.as_bytes())?;
+        self.writer.flush()
+    }
+}
+
+impl Drop for CsvSampleLog {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+/// Live sample stream on a named pipe (`sample_fifo_path`)
+///
+/// Never blocks the sync loop: with no reader the line is dropped, and a
+/// reader that falls behind loses lines. After a reader disconnects the next
+/// one is picked up on the following sample; every connection starts with
+/// `FIFO_HEADER`.
+pub struct SampleFifo {
+    path: PathBuf,
+    writer: Option<File>,
+}
+
+impl SampleFifo {
+    /// Create the FIFO at `path`, or reuse one that already exists
+    #[cfg(unix)]
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        use std::os::unix::fs::FileTypeExt;
+
+        let path = path.as_ref().to_path_buf();
+        match std::fs::metadata(&path) {
+            Ok(meta) if meta.file_type().is_fifo() => {}
+            Ok(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!("{} exists and is not a FIFO", path.display()),
+                ))
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                let mode = nix::sys::stat::Mode::from_bits_truncate(0o644);
+                nix::unistd::mkfifo(&path, mode).map_err(io::Error::from)?;
+            }
+            Err(e) => return Err(e),
+        }
+        Ok(SampleFifo { path, writer: None })
+    }
+
+    #[cfg(not(unix))]
+    pub fn create(_path: impl AsRef<Path>) -> io::Result<Self> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "sample FIFOs need a Unix named pipe",
+        ))
+    }
+
+    /// The pipe's path (for log messages)
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Write one line; false if it was dropped (no reader, or reader behind)
+    pub fn send(&mut self, sample: &SampleRecord) -> bool {
+        if self.writer.is_none() {
+            self.writer = self.connect();
+        }
+        let Some(writer) = self.writer.as_mut() else {
+            return false;
+        };
+        // Lines are far below PIPE_BUF, so each write is all-or-nothing and
+        // a full pipe never leaves half a line behind
+        match writer.write(sample.to_fifo_line().as_bytes()) {
+            Ok(_) => true,
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => false,
+            Err(_) => {
+                // Reader went away (EPIPE); wait for the next one
+                self.writer = None;
+                false
+            }
+        }
+    }
+
+    /// Open the write end if a reader is waiting (ENXIO otherwise)
+    #[cfg(unix)]
+    fn connect(&self) -> Option<File> {
+        use std::os::unix::fs::OpenOptionsExt;
+
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(&self.path)
+            .ok()?;
+        file.write_all(FIFO_HEADER.as_bytes()).ok()?;
+        Some(file)
+    }
+
+    #[cfg(not(unix))]
+    fn connect(&self) -> Option<File> {
+        None
+    }
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(offset_ns: i64, is_spike: bool) -> SampleRecord {
+        SampleRecord {
+            timestamp: UNIX_EPOCH + Duration::from_millis(1_700_000_000_250),
+            offset_ns,
+            raw_rate: 12.5,
+            filtered_rate: 3.25,
+            smoothed_rate: -0.5,
+            applied_ppm: 41.125,
+            mode: "LOCK",
+            is_spike,
+            ntp_offset_us: -120,
+        }
+    }
+
+    #[test]
+    fn test_csv_row_format() {
+        assert_eq!(
+            sample(1500, true).to_csv_row(),
+            "1700000000.250,1500,12.500,3.250,-0.500,41.125,LOCK,1,-120\n"
+        );
+        assert_eq!(CSV_HEADER.matches(',').count(), 8);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_fifo_follows_reader_reconnects() {
+        use std::io::Read;
+        use std::os::unix::fs::OpenOptionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("samples.fifo");
+        let mut fifo = SampleFifo::create(&path).unwrap();
+        // No reader yet: dropped, not blocked
+        assert!(!fifo.send(&sample(1, false)));
+
+        let open_reader = || {
+            std::fs::OpenOptions::new()
+                .read(true)
+                .custom_flags(libc::O_NONBLOCK)
+                .open(&path)
+                .unwrap()
+        };
+        let read_all = |reader: &mut File| {
+            let mut buf = [0u8; 4096];
+            let n = reader.read(&mut buf).unwrap();
+            String::from_utf8_lossy(&buf[..n]).into_owned()
+        };
+
+        let mut reader = open_reader();
+        assert!(fifo.send(&sample(2, false)));
+        assert_eq!(
+            read_all(&mut reader),
+            format!("{}1700000000.250,2,-0.500,41.125,LOCK\n", FIFO_HEADER)
+        );
+
+        // Reader disconnects: the write fails quietly and the writer resets
+        drop(reader);
+        assert!(!fifo.send(&sample(3, false)));
+        assert!(fifo.writer.is_none());
+
+        // A new reader gets the header again
+        let mut reader = open_reader();
+        assert!(fifo.send(&sample(4, false)));
+        assert!(read_all(&mut reader).starts_with(FIFO_HEADER));
+
+        // An existing FIFO is reused; a regular file is refused
+        assert!(SampleFifo::create(&path).is_ok());
+        let file = dir.path().join("plain");
+        std::fs::write(&file, "x").unwrap();
+        assert!(SampleFifo::create(&file).is_err());
+    }
+
+    #[test]
+    fn test_rows_buffered_until_flush() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("samples.csv");
+        let mut log = CsvSampleLog::open(&path, 0, 1).unwrap();
+
+        log.record(&sample(1, false)).unwrap();
+        log.record(&sample(2, false)).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), CSV_HEADER);
+
+        drop(log);
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1].split(',').nth(1), Some("1"));
+        assert_eq!(lines[2].split(',').nth(1), Some("2"));
+    }
+}
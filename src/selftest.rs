@@ -0,0 +1,276 @@
+//! Timestamping self-test
+//!
+//! Answers "is this machine good enough?" before a show. Receives a batch of
+//! PTP Sync/FollowUp pairs from the live network and checks:
+//! - **Jitter sigma**: stddev of the sample-to-sample change in (T2 - T1).
+//!   Differencing removes the constant epoch offset and linear drift, leaving
+//!   timestamp noise. The result is divided by √2 because each difference
+//!   contains the noise of two samples.
+//! - **Precision**: receive timestamps must not be quantized to a coarse
+//!   tick (e.g. 0.5ms / 15.6ms on Windows without HostHighPrec).
+//! - **Synchronization**: receive timestamps must track the system clock
+//!   (we adjust the system clock, so timestamps from an unsynced counter
+//!   would be meaningless).
+
+use crate::error::Result;
+use crate::ptp::{PtpV1Control, PtpV1FollowUpBody, PtpV1Header};
+use crate::traits::PtpNetwork;
+use std::collections::HashMap;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Pass threshold for jitter sigma (project precision target is <50µs)
+pub const MAX_JITTER_SIGMA_US: f64 = 50.0;
+
+/// Timestamps whose sub-second part is a multiple of this are "coarse"
+const COARSE_TICK_NS: u32 = 100_000; // 100µs
+
+/// Fail if more than this fraction of timestamps land on a coarse tick
+const MAX_COARSE_FRACTION: f64 = 0.5;
+
+/// Fail if timestamps differ from the system clock by more than this (median)
+const MAX_RX_LATENCY_US: f64 = 5_000.0;
+
+/// Outcome of a timestamping self-test
+#[derive(Debug, Clone)]
+pub struct SelfTestReport {
+    /// Timestamp source reported by the network backend
+    pub timestamp_source: String,
+    /// Backend claims precise (kernel/driver) timestamps are enabled
+    pub backend_precise: bool,
+    /// Number of matched Sync/FollowUp pairs
+    pub samples: usize,
+    /// Per-sample timestamp jitter (µs), None if too few samples
+    pub jitter_sigma_us: Option<f64>,
+    /// Fraction of receive timestamps quantized to a coarse tick (0.0-1.0)
+    pub coarse_fraction: f64,
+    /// Median |now - rx timestamp| (µs) - large values mean unsynced timestamps
+    pub median_rx_latency_us: f64,
+}
+
+impl SelfTestReport {
+    /// True if timestamps are precise, synced, and jitter is within target
+    pub fn passed(&self) -> bool {
+        self.backend_precise
+            && self.coarse_fraction <= MAX_COARSE_FRACTION
+            && self.median_rx_latency_us <= MAX_RX_LATENCY_US
+            && self
+                .jitter_sigma_us
+                .map(|s| s < MAX_JITTER_SIGMA_US)
+                .unwrap_or(false)
+    }
+}
+
+/// Per-sample jitter sigma (ns) from a series of (T2 - T1) offsets
+///
+/// Returns None with fewer than 3 offsets (need at least 2 differences).
+pub fn jitter_sigma_ns(offsets_ns: &[i64]) -> Option<f64> {
+    if offsets_ns.len() < 3 {
+        return None;
+    }
+    let diffs: Vec<f64> = offsets_ns
+        .windows(2)
+        .map(|w| (w[1] - w[0]) as f64)
+        .collect();
+    let n = diffs.len() as f64;
+    let mean = diffs.iter().sum::<f64>() / n;
+    let variance = diffs.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / n;
+    Some(variance.sqrt() / std::f64::consts::SQRT_2)
+}
+
+/// Fraction of timestamps whose sub-second part falls on a coarse tick
+pub fn coarse_fraction(timestamps: &[SystemTime]) -> f64 {
+    if timestamps.is_empty() {
+        return 0.0;
+    }
+    let coarse = timestamps
+        .iter()
+        .filter(|ts| {
+            ts.duration_since(UNIX_EPOCH)
+                .map(|d| d.subsec_nanos() % COARSE_TICK_NS == 0)
+                .unwrap_or(true)
+        })
+        .count();
+    coarse as f64 / timestamps.len() as f64
+}
+
+/// Receive `target_samples` Sync/FollowUp pairs (or until `timeout`) and report
+pub fn run<N: PtpNetwork>(
+    network: &mut N,
+    target_samples: usize,
+    timeout: Duration,
+) -> Result<SelfTestReport> {
+    let (timestamp_source, backend_precise) = network.timestamp_source();
+
+    let start = Instant::now();
+    let mut pending: HashMap<u16, SystemTime> = HashMap::new();
+    let mut offsets_ns: Vec<i64> = Vec::with_capacity(target_samples);
+    let mut rx_timestamps: Vec<SystemTime> = Vec::with_capacity(target_samples);
+    let mut rx_latencies_us: Vec<f64> = Vec::with_capacity(target_samples);
+
+    while offsets_ns.len() < target_samples && start.elapsed() < timeout {
+        let (buf, size, t2) = match network.recv_packet()? {
+            Some(pkt) => pkt,
+            None => {
+                std::thread::sleep(Duration::from_millis(1));
+                continue;
+            }
+        };
+        let now = SystemTime::now();
+
+        let header = match PtpV1Header::parse(&buf[..size]) {
+            Ok(h) => h,
+            Err(_) => continue,
+        };
+
+        match header.message_type {
+            PtpV1Control::Sync => {
+                pending.insert(header.sequence_id, t2);
+                rx_timestamps.push(t2);
+                let latency = match now.duration_since(t2) {
+                    Ok(d) => d.as_secs_f64() * 1e6,
+                    Err(e) => e.duration().as_secs_f64() * 1e6,
+                };
+                rx_latencies_us.push(latency);
+            }
+            PtpV1Control::FollowUp => {
+                if let Ok(body) = PtpV1FollowUpBody::parse(&buf[PtpV1Header::SIZE..size]) {
+                    if let Some(t2) = pending.remove(&body.associated_sequence_id) {
+                        let t2_ns =
+                            t2.duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos() as i64;
+                        offsets_ns.push(t2_ns - body.precise_origin_timestamp.to_nanos());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    rx_latencies_us.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let median_rx_latency_us = rx_latencies_us
+        .get(rx_latencies_us.len() / 2)
+        .copied()
+        .unwrap_or(f64::MAX);
+
+    Ok(SelfTestReport {
+        timestamp_source,
+        backend_precise,
+        samples: offsets_ns.len(),
+        jitter_sigma_us: jitter_sigma_ns(&offsets_ns).map(|s| s / 1000.0),
+        coarse_fraction: coarse_fraction(&rx_timestamps),
+        median_rx_latency_us,
+    })
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jitter_sigma_requires_three_samples() {
+        assert!(jitter_sigma_ns(&[]).is_none());
+        assert!(jitter_sigma_ns(&[1, 2]).is_none());
+        assert!(jitter_sigma_ns(&[1, 2, 3]).is_some());
+    }
+
+    #[test]
+    fn test_jitter_sigma_ignores_offset_and_drift() {
+        // Constant epoch offset + linear drift, no noise → zero jitter
+        let offsets: Vec<i64> = (0..50).map(|i| 182_000_000 + i * 1_250).collect();
+        let sigma = jitter_sigma_ns(&offsets).unwrap();
+        assert!(sigma.abs() < 1e-6, "Expected 0 jitter, got {}", sigma);
+    }
+
+    #[test]
+    fn test_jitter_sigma_alternating_noise() {
+        // ±10µs alternating noise: diffs are ±20µs, stddev 20µs, /√2 ≈ 14.1µs
+        let offsets: Vec<i64> = (0..100)
+            .map(|i| if i % 2 == 0 { 10_000 } else { -10_000 })
+            .collect();
+        let sigma = jitter_sigma_ns(&offsets).unwrap();
+        assert!((sigma - 20_000.0 / 2f64.sqrt()).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_coarse_fraction() {
+        let precise = UNIX_EPOCH + Duration::new(100, 123_456_789);
+        let coarse = UNIX_EPOCH + Duration::new(100, 15_600_000);
+        assert_eq!(coarse_fraction(&[]), 0.0);
+        assert_eq!(coarse_fraction(&[precise, precise]), 0.0);
+        assert_eq!(coarse_fraction(&[precise, coarse]), 0.5);
+        assert_eq!(coarse_fraction(&[coarse]), 1.0);
+    }
+
+    fn report(jitter: Option<f64>) -> SelfTestReport {
+        SelfTestReport {
+            timestamp_source: "test".to_string(),
+            backend_precise: true,
+            samples: 100,
+            jitter_sigma_us: jitter,
+            coarse_fraction: 0.0,
+            median_rx_latency_us: 100.0,
+        }
+    }
+
+    #[test]
+    fn test_report_pass_fail() {
+        assert!(report(Some(5.0)).passed());
+        assert!(!report(Some(80.0)).passed());
+        assert!(!report(None).passed());
+
+        let mut r = report(Some(5.0));
+        r.backend_precise = false;
+        assert!(!r.passed());
+
+        let mut r = report(Some(5.0));
+        r.coarse_fraction = 0.9;
+        assert!(!r.passed());
+
+        let mut r = report(Some(5.0));
+        r.median_rx_latency_us = 2_000_000.0;
+        assert!(!r.passed());
+    }
+
+    #[test]
+    fn test_run_with_mock_network() {
+        use crate::traits::MockPtpNetwork;
+        use byteorder::{BigEndian, ByteOrder};
+
+        let mut mock_net = MockPtpNetwork::new();
+        mock_net
+            .expect_timestamp_source()
+            .returning(|| ("mock".to_string(), true));
+
+        let mut seq: u16 = 0;
+        let mut send_followup = false;
+        mock_net.expect_recv_packet().returning(move || {
+            let mut buf = vec![0u8; 60];
+            buf[0] = 0x10;
+            BigEndian::write_u16(&mut buf[30..32], seq);
+            // Sync received "now" with µs noise so timestamps are not coarse
+            let now = SystemTime::now();
+            let jitter_ns = if seq % 2 == 0 { 1_234 } else { 3_210 };
+            if send_followup {
+                buf[32] = 0x02;
+                BigEndian::write_u16(&mut buf[42..44], seq);
+                let t1 = now.duration_since(UNIX_EPOCH).unwrap();
+                BigEndian::write_u32(&mut buf[44..48], t1.as_secs() as u32);
+                BigEndian::write_u32(&mut buf[48..52], t1.subsec_nanos());
+                seq = seq.wrapping_add(1);
+            } else {
+                buf[32] = 0x00;
+            }
+            send_followup = !send_followup;
+            Ok(Some((buf, 60, now + Duration::from_nanos(jitter_ns))))
+        });
+
+        let report = run(&mut mock_net, 20, Duration::from_secs(5)).unwrap();
+        assert_eq!(report.samples, 20);
+        assert_eq!(report.timestamp_source, "mock");
+        assert!(report.jitter_sigma_us.is_some());
+        assert!(report.coarse_fraction < 0.5);
+    }
+}
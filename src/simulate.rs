@@ -0,0 +1,366 @@
+//! Software clock and PTP/NTP sources for `--simulate`
+//!
+//! Runs the real controller without root/admin rights: `SimulatedClock`
+//! keeps a virtual offset and frequency in memory instead of adjusting the
+//! OS clock, `SyntheticPtpNetwork` plays a Dante master sending Sync and
+//! Follow_Up at 125ms, and `SimulatedNtp` reports the virtual clock's
+//! distance from UTC. `ReplayNetwork` feeds a packet capture instead of the
+//! synthetic master.
+//!
+//! The virtual clock runs in real time (the servo measures its interval with
+//! `Instant`), drifting at `natural_drift_ppm` plus whatever the controller
+//! applied, so the loop is closed: corrections change the next timestamps.
+
+use crate::capture::CapturedPacket;
+use crate::clock::SystemClock;
+use crate::error::Result;
+use crate::ptp::{AUDINATE_OUI, PTP_V1_SYNC_LEN};
+use crate::traits::{NtpSource, PtpNetwork};
+use byteorder::{BigEndian, ByteOrder};
+use log::{debug, info};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Dante's Sync interval
+pub const SIM_SYNC_INTERVAL: Duration = Duration::from_millis(125);
+
+/// Clock UUID of the synthetic master (Audinate OUI, so it counts as Dante)
+const SIM_MASTER_UUID: [u8; 6] = [
+    AUDINATE_OUI[0],
+    AUDINATE_OUI[1],
+    AUDINATE_OUI[2],
+    0x51,
+    0x4D,
+    0x01,
+];
+
+struct ClockModel {
+    /// Local minus true time (ns); positive = clock ahead
+    offset_ns: f64,
+    /// Oscillator error before correction (ppm); positive = runs fast
+    natural_drift_ppm: f64,
+    /// Frequency correction last applied by the controller (ppm)
+    adjust_ppm: f64,
+    updated: Instant,
+    adjustments: u64,
+    steps: u64,
+}
+
+impl ClockModel {
+    /// Integrate the offset up to now
+    fn advance(&mut self) {
+        let now = Instant::now();
+        let dt_secs = now.duration_since(self.updated).as_secs_f64();
+        self.offset_ns += (self.natural_drift_ppm + self.adjust_ppm) * 1000.0 * dt_secs;
+        self.updated = now;
+    }
+}
+
+/// In-memory `SystemClock`: logs what it would do instead of touching the OS
+///
+/// Clones share the same virtual clock, so the network and NTP doubles see
+/// the controller's corrections.
+#[derive(Clone)]
+pub struct SimulatedClock {
+    model: Arc<Mutex<ClockModel>>,
+}
+
+impl SimulatedClock {
+    pub fn new(initial_offset_ns: f64, natural_drift_ppm: f64) -> Self {
+        Self {
+            model: Arc::new(Mutex::new(ClockModel {
+                offset_ns: initial_offset_ns,
+                natural_drift_ppm,
+                adjust_ppm: 0.0,
+                updated: Instant::now(),
+                adjustments: 0,
+                steps: 0,
+            })),
+        }
+    }
+
+    fn with_model<T>(&self, f: impl FnOnce(&mut ClockModel) -> T) -> T {
+        let mut model = self.model.lock().unwrap_or_else(|e| e.into_inner());
+        model.advance();
+        f(&mut model)
+    }
+
+    /// Virtual clock minus true time, now (ns)
+    pub fn offset_ns(&self) -> f64 {
+        self.with_model(|m| m.offset_ns)
+    }
+
+    /// Frequency correction currently applied (ppm)
+    pub fn adjust_ppm(&self) -> f64 {
+        self.with_model(|m| m.adjust_ppm)
+    }
+
+    /// Remaining drift of the corrected clock (ppm); 0 = frequency matched
+    pub fn residual_drift_ppm(&self) -> f64 {
+        self.with_model(|m| m.natural_drift_ppm + m.adjust_ppm)
+    }
+
+    /// (frequency adjustments, steps) the controller has made
+    pub fn counts(&self) -> (u64, u64) {
+        self.with_model(|m| (m.adjustments, m.steps))
+    }
+
+    /// The virtual clock's reading of `SystemTime::now()`, plus `extra_ns`
+    fn now(&self, extra_ns: i64) -> SystemTime {
+        let ns = self.offset_ns() as i64 + extra_ns;
+        let magnitude = Duration::from_nanos(ns.unsigned_abs());
+        if ns >= 0 {
+            SystemTime::now() + magnitude
+        } else {
+            SystemTime::now() - magnitude
+        }
+    }
+}
+
+impl SystemClock for SimulatedClock {
+    fn adjust_frequency(&mut self, factor: f64) -> Result<()> {
+        let ppm = (factor - 1.0) * 1_000_000.0;
+        debug!("[Simulate] Would adjust frequency to {:+.3} ppm", ppm);
+        self.with_model(|m| {
+            m.adjust_ppm = ppm;
+            m.adjustments += 1;
+        });
+        Ok(())
+    }
+
+    fn step_clock(&mut self, offset: Duration, sign: i8) -> Result<()> {
+        info!(
+            "[Simulate] Would step clock by {}{:?}",
+            if sign < 0 { "-" } else { "+" },
+            offset
+        );
+        let ns = offset.as_nanos() as f64;
+        self.with_model(|m| {
+            m.offset_ns += if sign < 0 { -ns } else { ns };
+            m.steps += 1;
+        });
+        Ok(())
+    }
+}
+
+/// NTP reference for the virtual clock (positive = clock behind, as `NtpClient`)
+pub struct SimulatedNtp {
+    clock: SimulatedClock,
+}
+
+impl SimulatedNtp {
+    pub fn new(clock: SimulatedClock) -> Self {
+        Self { clock }
+    }
+}
+
+impl NtpSource for SimulatedNtp {
+    fn get_offset(&self) -> Result<(Duration, i8)> {
+        let ns = -self.clock.offset_ns();
+        let sign = if ns < 0.0 { -1 } else { 1 };
+        Ok((Duration::from_nanos(ns.abs() as u64), sign))
+    }
+}
+
+/// A Dante master sending PTPv1 Sync + Follow_Up every 125ms
+///
+/// Origin timestamps are the master's uptime; receive timestamps come from
+/// the virtual clock plus uniform jitter.
+pub struct SyntheticPtpNetwork {
+    clock: SimulatedClock,
+    started: Instant,
+    next_sync: Instant,
+    seq: u16,
+    pending_follow_up: Option<(u16, Duration)>,
+    jitter_ns: i64,
+    rng_state: u64,
+}
+
+impl SyntheticPtpNetwork {
+    pub fn new(clock: SimulatedClock, jitter_ns: i64) -> Self {
+        let now = Instant::now();
+        Self {
+            clock,
+            started: now,
+            next_sync: now,
+            seq: 0,
+            pending_follow_up: None,
+            jitter_ns: jitter_ns.abs(),
+            rng_state: 0x9E37_79B9_7F4A_7C15,
+        }
+    }
+
+    // xorshift64*
+    fn next_random(&mut self) -> u64 {
+        let mut x = self.rng_state;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.rng_state = x;
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    fn jitter(&mut self) -> i64 {
+        if self.jitter_ns == 0 {
+            return 0;
+        }
+        let span = 2 * self.jitter_ns as u64 + 1;
+        (self.next_random() % span) as i64 - self.jitter_ns
+    }
+}
+
+/// PTPv1 header for the synthetic master (control 0 = Sync, 2 = Follow_Up)
+fn v1_packet(control: u8, seq: u16, len: usize) -> Vec<u8> {
+    let mut buf = vec![0u8; len];
+    buf[0] = 0x10; // versionPTP 1
+    BigEndian::write_u16(&mut buf[2..4], len as u16);
+    buf[22..28].copy_from_slice(&SIM_MASTER_UUID);
+    BigEndian::write_u16(&mut buf[30..32], seq);
+    buf[32] = control;
+    buf
+}
+
+fn write_timestamp(buf: &mut [u8], at: usize, t: Duration) {
+    BigEndian::write_u32(&mut buf[at..at + 4], t.as_secs() as u32);
+    BigEndian::write_u32(&mut buf[at + 4..at + 8], t.subsec_nanos());
+}
+
+impl PtpNetwork for SyntheticPtpNetwork {
+    fn recv_packet(&mut self) -> Result<Option<(Vec<u8>, usize, SystemTime)>> {
+        if let Some((seq, t1)) = self.pending_follow_up.take() {
+            let mut buf = v1_packet(0x02, seq, 52);
+            BigEndian::write_u16(&mut buf[42..44], seq);
+            write_timestamp(&mut buf, 44, t1);
+            let len = buf.len();
+            return Ok(Some((buf, len, self.clock.now(0))));
+        }
+
+        let now = Instant::now();
+        if now < self.next_sync {
+            return Ok(None);
+        }
+        self.next_sync += SIM_SYNC_INTERVAL;
+        self.seq = self.seq.wrapping_add(1);
+
+        // The master's clock runs true; our timestamp carries the drift
+        let t1 = now.duration_since(self.started);
+        let jitter = self.jitter();
+        let mut buf = v1_packet(0x00, self.seq, PTP_V1_SYNC_LEN);
+        write_timestamp(&mut buf, 36, t1);
+        buf[49..55].copy_from_slice(&SIM_MASTER_UUID);
+        self.pending_follow_up = Some((self.seq, t1));
+
+        let len = buf.len();
+        Ok(Some((buf, len, self.clock.now(jitter))))
+    }
+
+    fn timestamp_source(&self) -> (String, bool) {
+        ("simulated".to_string(), false)
+    }
+}
+
+/// Network that hands out captured packets once their original gap has elapsed
+///
+/// Open-loop: the captured timestamps already contain the corrections the
+/// original machine applied.
+pub struct ReplayNetwork {
+    packets: VecDeque<CapturedPacket>,
+    first_ts: SystemTime,
+    started: Instant,
+}
+
+impl ReplayNetwork {
+    /// `packets` are sorted by receive time; replay starts now
+    pub fn new(mut packets: Vec<CapturedPacket>) -> Self {
+        packets.sort_by_key(|p| p.timestamp);
+        let first_ts = packets
+            .first()
+            .map(|p| p.timestamp)
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        Self {
+            packets: VecDeque::from(packets),
+            first_ts,
+            started: Instant::now(),
+        }
+    }
+
+    /// Packets not yet handed out
+    pub fn remaining(&self) -> usize {
+        self.packets.len()
+    }
+}
+
+impl PtpNetwork for ReplayNetwork {
+    fn recv_packet(&mut self) -> Result<Option<(Vec<u8>, usize, SystemTime)>> {
+        let due = match self.packets.front() {
+            Some(p) => {
+                let offset = p
+                    .timestamp
+                    .duration_since(self.first_ts)
+                    .unwrap_or(Duration::ZERO);
+                self.started.elapsed() >= offset
+            }
+            None => false,
+        };
+        if !due {
+            return Ok(None);
+        }
+        let p = self.packets.pop_front().unwrap();
+        let len = p.data.len();
+        Ok(Some((p.data, len, p.timestamp)))
+    }
+
+    fn timestamp_source(&self) -> (String, bool) {
+        ("replay".to_string(), false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ptp::{PtpV1Control, PtpV1FollowUpBody, PtpV1Header, PtpV1SyncMessageBody};
+
+    #[test]
+    fn test_simulated_clock_tracks_adjustments_and_steps() {
+        let mut clock = SimulatedClock::new(-250_000_000.0, 0.0);
+        let ntp = SimulatedNtp::new(clock.clone());
+
+        // 250ms behind: NTP says step forward
+        let (offset, sign) = ntp.get_offset().unwrap();
+        assert_eq!(sign, 1);
+        assert!((offset.as_millis() as i64 - 250).abs() <= 1);
+
+        clock.step_clock(offset, sign).unwrap();
+        assert!(clock.offset_ns().abs() < 1_000_000.0);
+
+        clock.adjust_frequency(1.0 + 12e-6).unwrap();
+        assert!((clock.adjust_ppm() - 12.0).abs() < 1e-6);
+        assert!((clock.residual_drift_ppm() - 12.0).abs() < 1e-6);
+        assert_eq!(clock.counts(), (1, 1));
+    }
+
+    #[test]
+    fn test_synthetic_network_sends_dante_sync_and_follow_up() {
+        let clock = SimulatedClock::new(0.0, 0.0);
+        let mut network = SyntheticPtpNetwork::new(clock, 0);
+
+        let (sync, len, _) = network.recv_packet().unwrap().expect("first Sync is due");
+        assert_eq!(len, PTP_V1_SYNC_LEN);
+        let header = PtpV1Header::parse(&sync).unwrap();
+        assert_eq!(header.version_ptp, 1);
+        assert_eq!(header.message_type, PtpV1Control::Sync);
+        let body = PtpV1SyncMessageBody::parse(PtpV1Header::body(&sync)).unwrap();
+        assert_eq!(body.grandmaster_clock_uuid, SIM_MASTER_UUID);
+
+        let (follow_up, _, _) = network.recv_packet().unwrap().expect("Follow_Up follows");
+        let fu_header = PtpV1Header::parse(&follow_up).unwrap();
+        assert_eq!(fu_header.message_type, PtpV1Control::FollowUp);
+        let fu = PtpV1FollowUpBody::parse(PtpV1Header::body(&follow_up)).unwrap();
+        assert_eq!(fu.associated_sequence_id, header.sequence_id);
+        assert_eq!(fu.precise_origin_timestamp, body.origin_timestamp);
+
+        // Next Sync waits for the interval
+        assert!(network.recv_packet().unwrap().is_none());
+    }
+}
@@ -0,0 +1,370 @@
+//! Live PTP decode (`dantesync sniff`)
+//!
+//! Field troubleshooting without touching the clock: prints every Sync,
+//! Follow_Up and Announce as this crate's own parser sees it, with the
+//! running Sync inter-arrival jitter. Answers "do packets even arrive, and
+//! from whom?" before looking at the servo.
+
+use crate::error::Result;
+use crate::ptp::{
+    PtpTimestamp, PtpV1Control, PtpV1FollowUpBody, PtpV1Header, PtpV1SyncMessageBody,
+    PtpV2AnnounceBody, PtpV2Header, PtpV2MessageType, PtpV2TimestampBody,
+};
+use crate::status::format_mac;
+use crate::traits::PtpNetwork;
+use std::fmt;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime};
+
+/// Message types `sniff` prints (everything else is counted as ignored)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SniffKind {
+    Sync,
+    FollowUp,
+    Announce,
+}
+
+impl SniffKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SniffKind::Sync => "Sync",
+            SniffKind::FollowUp => "Follow_Up",
+            SniffKind::Announce => "Announce",
+        }
+    }
+}
+
+/// One decoded packet
+#[derive(Debug)]
+pub struct SniffedPacket {
+    /// PTP version (1 = Dante / IEEE 1588-2002, 2 = IEEE 1588-2008)
+    pub version: u8,
+    pub kind: SniffKind,
+    pub sequence_id: u16,
+    /// Follow_Up: sequence id of the Sync it completes
+    pub associated_sequence_id: Option<u16>,
+    /// Sender UUID (v1) or clock identity (v2)
+    pub source: String,
+    /// Grandmaster UUID / identity, where the message carries it
+    pub grandmaster: Option<String>,
+    pub origin: Option<PtpTimestamp>,
+    pub arrival: SystemTime,
+}
+
+/// Format an 8-byte PTPv2 clock identity (e.g. "00:1D:C1:FF:FE:12:34:56")
+pub fn format_clock_identity(id: &[u8; 8]) -> String {
+    id.iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Decode a Sync / Follow_Up / Announce; None for other or malformed packets
+pub fn decode(packet: &[u8], arrival: SystemTime) -> Option<SniffedPacket> {
+    if let Ok(header) = PtpV2Header::parse(packet) {
+        let body = packet.get(PtpV2Header::SIZE..).unwrap_or(&[]);
+        let source = format!(
+            "{}-{}",
+            format_clock_identity(&header.source_port_identity.clock_identity),
+            header.source_port_identity.port_number
+        );
+        let (kind, grandmaster, origin) = match header.message_type {
+            PtpV2MessageType::Sync | PtpV2MessageType::FollowUp => {
+                let kind = if header.message_type == PtpV2MessageType::Sync {
+                    SniffKind::Sync
+                } else {
+                    SniffKind::FollowUp
+                };
+                let body = PtpV2TimestampBody::parse(body).ok()?;
+                (kind, None, body.origin_timestamp)
+            }
+            PtpV2MessageType::Announce => {
+                let body = PtpV2AnnounceBody::parse(body).ok()?;
+                (
+                    SniffKind::Announce,
+                    Some(format_clock_identity(&body.grandmaster_identity)),
+                    body.origin_timestamp,
+                )
+            }
+            _ => return None,
+        };
+        return Some(SniffedPacket {
+            version: 2,
+            kind,
+            sequence_id: header.sequence_id,
+            associated_sequence_id: None,
+            source,
+            grandmaster,
+            origin: Some(origin),
+            arrival,
+        });
+    }
+
+    let header = PtpV1Header::parse(packet).ok()?;
+    let body = PtpV1Header::body(packet);
+    let (kind, associated, grandmaster, origin) = match header.message_type {
+        PtpV1Control::Sync => {
+            let body = PtpV1SyncMessageBody::parse(body).ok()?;
+            (
+                SniffKind::Sync,
+                None,
+                Some(format_mac(&body.grandmaster_clock_uuid)),
+                body.origin_timestamp,
+            )
+        }
+        PtpV1Control::FollowUp => {
+            let body = PtpV1FollowUpBody::parse(body).ok()?;
+            (
+                SniffKind::FollowUp,
+                Some(body.associated_sequence_id),
+                None,
+                body.precise_origin_timestamp,
+            )
+        }
+        _ => return None,
+    };
+    Some(SniffedPacket {
+        version: 1,
+        kind,
+        sequence_id: header.sequence_id,
+        associated_sequence_id: associated,
+        source: format_mac(&header.source_uuid),
+        grandmaster,
+        origin: Some(origin),
+        arrival,
+    })
+}
+
+impl fmt::Display for SniffedPacket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let arrival: chrono::DateTime<chrono::Local> = self.arrival.into();
+        write!(
+            f,
+            "{} v{} {:<9} seq={:<5} src={}",
+            arrival.format("%H:%M:%S%.6f"),
+            self.version,
+            self.kind.as_str(),
+            self.sequence_id,
+            self.source
+        )?;
+        if let Some(associated) = self.associated_sequence_id {
+            write!(f, " for={}", associated)?;
+        }
+        if let Some(gm) = &self.grandmaster {
+            write!(f, " gm={}", gm)?;
+        }
+        if let Some(origin) = &self.origin {
+            write!(f, " origin={}.{:09}", origin.seconds, origin.nanoseconds)?;
+        }
+        Ok(())
+    }
+}
+
+/// Running mean / std-dev of Sync inter-arrival times (Welford)
+#[derive(Debug, Default)]
+pub struct InterArrival {
+    last: Option<SystemTime>,
+    count: u64,
+    mean_ms: f64,
+    m2: f64,
+}
+
+impl InterArrival {
+    /// Record an arrival, returning the gap to the previous one (ms)
+    pub fn record(&mut self, arrival: SystemTime) -> Option<f64> {
+        let gap_ms = self.last.map(|last| match arrival.duration_since(last) {
+            Ok(d) => d.as_secs_f64() * 1000.0,
+            Err(e) => -e.duration().as_secs_f64() * 1000.0,
+        });
+        self.last = Some(arrival);
+        if let Some(gap) = gap_ms {
+            self.count += 1;
+            let delta = gap - self.mean_ms;
+            self.mean_ms += delta / self.count as f64;
+            self.m2 += delta * (gap - self.mean_ms);
+        }
+        gap_ms
+    }
+
+    pub fn mean_ms(&self) -> f64 {
+        self.mean_ms
+    }
+
+    /// Std-dev of the gaps (ms); None until two gaps are known
+    pub fn jitter_ms(&self) -> Option<f64> {
+        (self.count >= 2).then(|| (self.m2 / self.count as f64).sqrt())
+    }
+}
+
+/// Totals printed when sniffing stops
+#[derive(Debug, Default)]
+pub struct SniffSummary {
+    pub syncs: u64,
+    pub follow_ups: u64,
+    pub announces: u64,
+    /// Packets that are not Sync / Follow_Up / Announce or failed to parse
+    pub ignored: u64,
+    pub sync_interval: InterArrival,
+}
+
+/// Print decoded packets to `out` until `max_packets` are shown (0 = no
+/// limit) or `running` is cleared
+pub fn run<N: PtpNetwork, W: Write>(
+    network: &mut N,
+    max_packets: u64,
+    running: &AtomicBool,
+    out: &mut W,
+) -> Result<SniffSummary> {
+    let mut summary = SniffSummary::default();
+    let mut shown = 0u64;
+
+    while running.load(Ordering::SeqCst) && (max_packets == 0 || shown < max_packets) {
+        let (buf, size, arrival) = match network.recv_packet()? {
+            Some(pkt) => pkt,
+            None => {
+                std::thread::sleep(Duration::from_millis(1));
+                continue;
+            }
+        };
+        let Some(packet) = decode(&buf[..size.min(buf.len())], arrival) else {
+            summary.ignored += 1;
+            continue;
+        };
+        shown += 1;
+
+        match packet.kind {
+            SniffKind::Sync => {
+                summary.syncs += 1;
+                let gap = summary.sync_interval.record(arrival);
+                write!(out, "{}", packet)?;
+                if let Some(gap) = gap {
+                    write!(out, " dt={:.3}ms", gap)?;
+                }
+                if let Some(jitter) = summary.sync_interval.jitter_ms() {
+                    write!(out, " jitter={:.3}ms", jitter)?;
+                }
+                writeln!(out)?;
+            }
+            SniffKind::FollowUp => {
+                summary.follow_ups += 1;
+                writeln!(out, "{}", packet)?;
+            }
+            SniffKind::Announce => {
+                summary.announces += 1;
+                writeln!(out, "{}", packet)?;
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+// ============================================================================
+// TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::MockPtpNetwork;
+    use std::time::UNIX_EPOCH;
+
+    fn v1_packet(control: u8, seq: u16) -> Vec<u8> {
+        let mut buf = vec![0u8; 60];
+        buf[1] = 0x01;
+        buf[22..28].copy_from_slice(&[0x00, 0x1D, 0xC1, 0xAA, 0xBB, 0xCC]);
+        buf[30..32].copy_from_slice(&seq.to_be_bytes());
+        buf[32] = control;
+        buf
+    }
+
+    #[test]
+    fn test_decode_v1_sync_and_follow_up() {
+        let arrival = UNIX_EPOCH + Duration::from_secs(1_000);
+
+        let mut sync = v1_packet(0x00, 7);
+        sync[39] = 5; // originTimestamp seconds
+        sync[49..55].copy_from_slice(&[0x00, 0x1D, 0xC1, 0x11, 0x22, 0x33]);
+        let packet = decode(&sync, arrival).unwrap();
+        assert_eq!(packet.version, 1);
+        assert_eq!(packet.kind, SniffKind::Sync);
+        assert_eq!(packet.sequence_id, 7);
+        assert_eq!(packet.source, "00:1D:C1:AA:BB:CC");
+        assert_eq!(packet.grandmaster.as_deref(), Some("00:1D:C1:11:22:33"));
+        assert_eq!(packet.origin.as_ref().unwrap().seconds, 5);
+
+        let mut follow_up = v1_packet(0x02, 8);
+        follow_up[42..44].copy_from_slice(&7u16.to_be_bytes());
+        let packet = decode(&follow_up, arrival).unwrap();
+        assert_eq!(packet.kind, SniffKind::FollowUp);
+        assert_eq!(packet.associated_sequence_id, Some(7));
+        let line = packet.to_string();
+        assert!(
+            line.contains("Follow_Up") && line.contains("for=7"),
+            "{}",
+            line
+        );
+
+        // Delay_Req and truncated packets are not shown
+        assert!(decode(&v1_packet(0x01, 9), arrival).is_none());
+        assert!(decode(&sync[..40], arrival).is_none());
+    }
+
+    #[test]
+    fn test_decode_v2_announce() {
+        let mut buf = vec![0u8; PtpV2Header::SIZE + 64];
+        buf[0] = 0x0B;
+        buf[1] = 0x02;
+        buf[20..28].copy_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        buf[28..30].copy_from_slice(&1u16.to_be_bytes());
+        buf[PtpV2Header::SIZE + 19..PtpV2Header::SIZE + 27]
+            .copy_from_slice(&[0x00, 0x1D, 0xC1, 0xFF, 0xFE, 0x01, 0x02, 0x03]);
+        let packet = decode(&buf, UNIX_EPOCH).unwrap();
+        assert_eq!(packet.version, 2);
+        assert_eq!(packet.kind, SniffKind::Announce);
+        assert_eq!(packet.source, "01:02:03:04:05:06:07:08-1");
+        assert_eq!(
+            packet.grandmaster.as_deref(),
+            Some("00:1D:C1:FF:FE:01:02:03")
+        );
+    }
+
+    #[test]
+    fn test_inter_arrival_jitter() {
+        let mut stats = InterArrival::default();
+        let start = UNIX_EPOCH + Duration::from_secs(100);
+        assert_eq!(stats.record(start), None);
+        // Gaps of 120ms and 130ms alternate: mean 125ms, std-dev 5ms
+        let mut t = start;
+        for i in 0..10 {
+            t += Duration::from_millis(if i % 2 == 0 { 120 } else { 130 });
+            stats.record(t);
+        }
+        assert!((stats.mean_ms() - 125.0).abs() < 1e-6);
+        assert!((stats.jitter_ms().unwrap() - 5.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_run_prints_until_count() {
+        let mut mock_net = MockPtpNetwork::new();
+        let mut seq: u16 = 0;
+        mock_net.expect_recv_packet().returning(move || {
+            seq = seq.wrapping_add(1);
+            let control = if seq % 3 == 0 { 0x01 } else { 0x00 };
+            let buf = v1_packet(control, seq);
+            let arrival = UNIX_EPOCH + Duration::from_millis(125 * seq as u64);
+            Ok(Some((buf, 60, arrival)))
+        });
+
+        let running = AtomicBool::new(true);
+        let mut out = Vec::new();
+        let summary = run(&mut mock_net, 4, &running, &mut out).unwrap();
+
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text.lines().count(), 4);
+        assert_eq!(summary.syncs, 4);
+        assert_eq!(summary.ignored, 1);
+        assert!(text.contains("jitter="), "{}", text);
+    }
+}
@@ -27,6 +27,7 @@
 
 use log::debug;
 use std::collections::VecDeque;
+use std::time::{Duration, Instant};
 
 // ============================================================================
 // SPIKE FILTER CONSTANTS
@@ -46,12 +47,16 @@ const K_NANO: f64 = 8.0;
 
 /// Default rolling window size (~20 seconds at 1 sample/sec)
 const DEFAULT_WINDOW_SIZE: usize = 20;
-/// Minimum MAD floor to prevent over-sensitivity on ultra-stable systems (µs/s)
-const MIN_MAD_FLOOR: f64 = 0.5;
+/// Default minimum MAD floor to prevent over-sensitivity on ultra-stable
+/// systems (µs/s). Hardware-timestamped hosts can go far lower
+/// (`filters.spike_mad_floor_us_s`)
+pub const MIN_MAD_FLOOR: f64 = 0.5;
 /// Minimum samples before spike detection activates
 const WARMUP_SAMPLES: usize = 5;
 /// Accept as real step change after this many consecutive "spikes"
 const MAX_CONSECUTIVE_SPIKES: usize = 5;
+/// Default interval for the rolling spike count (5 minutes)
+pub const DEFAULT_COUNTER_WINDOW: Duration = Duration::from_secs(300);
 
 /// Operating mode for threshold selection
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -94,14 +99,19 @@ pub struct SpikeFilter {
     /// Maximum consecutive spikes before accepting as real
     max_consecutive_spikes: usize,
 
-    /// Statistics
+    /// Statistics (since creation or `reset_counters`)
     total_samples: u64,
     rejected_spikes: u64,
 
+    /// Rejected spikes within the last counter window
+    recent_spikes: WindowedCounter,
+
     /// Last computed statistics (for debugging/status)
     last_median: f64,
     last_mad: f64,
     last_threshold: f64,
+    /// Whether the MAD floor, not the measured MAD, set the last threshold
+    last_mad_floored: bool,
 }
 
 /// Result of filtering a sample
@@ -119,6 +129,8 @@ pub struct FilterResult {
     pub median: f64,
     /// Current MAD of window
     pub mad: f64,
+    /// The MAD floor set the threshold (measured MAD was below it)
+    pub mad_floored: bool,
 }
 
 impl Default for SpikeFilter {
@@ -153,9 +165,12 @@ impl SpikeFilter {
             total_samples: 0,
             rejected_spikes: 0,
 
+            recent_spikes: WindowedCounter::new(DEFAULT_COUNTER_WINDOW),
+
             last_median: 0.0,
             last_mad: 0.0,
             last_threshold: 0.0,
+            last_mad_floored: false,
         }
     }
 
@@ -193,6 +208,7 @@ impl SpikeFilter {
                 threshold: f64::MAX,
                 median: raw_rate,
                 mad: 0.0,
+                mad_floored: false,
             };
         }
 
@@ -207,6 +223,7 @@ impl SpikeFilter {
         let mad = deviations[deviations.len() / 2];
 
         // Apply minimum MAD floor
+        let mad_floored = mad < self.min_mad;
         let effective_mad = mad.max(self.min_mad);
 
         // Get threshold multiplier based on mode
@@ -224,6 +241,7 @@ impl SpikeFilter {
         self.last_median = median;
         self.last_mad = mad;
         self.last_threshold = threshold;
+        self.last_mad_floored = mad_floored;
 
         // Check if this is a spike
         let deviation = (raw_rate - median).abs();
@@ -242,9 +260,10 @@ impl SpikeFilter {
                 raw_rate // Accept the value
             } else {
                 self.rejected_spikes += 1;
+                self.recent_spikes.record();
                 debug!(
-                    "[Spike] Rejected: {:+.1}us/s (median={:+.1}, MAD={:.2}, k={:.1}, threshold={:.1})",
-                    raw_rate, median, mad, k, threshold
+                    "[Spike] Rejected: {:+.2}us/s (median={:+.2}, MAD={:.3}, floor={:.3}, k={:.1}, threshold={:.2})",
+                    raw_rate, median, mad, self.min_mad, k, threshold
                 );
                 median // Replace with median
             }
@@ -261,10 +280,11 @@ impl SpikeFilter {
             threshold,
             median,
             mad,
+            mad_floored,
         }
     }
 
-    /// Get spike rejection statistics
+    /// Get spike rejection statistics (since creation or `reset_counters`)
     pub fn stats(&self) -> (u64, u64, f64) {
         let ratio = if self.total_samples > 0 {
             self.rejected_spikes as f64 / self.total_samples as f64 * 100.0
@@ -279,6 +299,20 @@ impl SpikeFilter {
         (self.last_median, self.last_mad, self.last_threshold)
     }
 
+    /// Whether the MAD floor set the last threshold
+    pub fn last_mad_floored(&self) -> bool {
+        self.last_mad_floored
+    }
+
+    /// Set the MAD floor (µs/s); below it the measured MAD is not trusted
+    pub fn set_min_mad(&mut self, floor: f64) {
+        self.min_mad = floor;
+    }
+
+    pub fn min_mad(&self) -> f64 {
+        self.min_mad
+    }
+
     /// Clear history (call after NTP step or major event)
     pub fn clear(&mut self) {
         self.rate_history.clear();
@@ -286,12 +320,103 @@ impl SpikeFilter {
         self.last_median = 0.0;
         self.last_mad = 0.0;
         self.last_threshold = 0.0;
+        self.last_mad_floored = false;
     }
 
     /// Get current window size
     pub fn window_len(&self) -> usize {
         self.rate_history.len()
     }
+
+    /// Spikes rejected within the last counter window
+    pub fn recent_spikes(&self) -> u64 {
+        self.recent_spikes.count()
+    }
+
+    /// Set the interval `recent_spikes` counts over
+    pub fn set_counter_window(&mut self, window: Duration) {
+        self.recent_spikes.set_window(window);
+    }
+
+    /// Zero the statistics (filter history is kept)
+    pub fn reset_counters(&mut self) {
+        self.total_samples = 0;
+        self.rejected_spikes = 0;
+        self.recent_spikes.reset();
+    }
+}
+
+// ============================================================================
+// WINDOWED COUNTER - Event Rate for Monitoring
+// ============================================================================
+
+/// Counts events over a rolling time window, plus a resettable total
+///
+/// Lifetime totals only grow; alerting wants "spikes in the last 5 minutes",
+/// which falls back to zero once the problem goes away.
+#[derive(Debug)]
+pub struct WindowedCounter {
+    window: Duration,
+    /// Event times within the window (oldest first)
+    events: VecDeque<Instant>,
+    total: u64,
+}
+
+impl WindowedCounter {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            events: VecDeque::new(),
+            total: 0,
+        }
+    }
+
+    /// Record an event now
+    pub fn record(&mut self) {
+        self.record_at(Instant::now());
+    }
+
+    pub fn record_at(&mut self, now: Instant) {
+        self.events.push_back(now);
+        self.total += 1;
+        // Keep memory bounded by the window
+        while let Some(&oldest) = self.events.front() {
+            if now.saturating_duration_since(oldest) < self.window {
+                break;
+            }
+            self.events.pop_front();
+        }
+    }
+
+    /// Events within the window ending now
+    pub fn count(&self) -> u64 {
+        self.count_at(Instant::now())
+    }
+
+    pub fn count_at(&self, now: Instant) -> u64 {
+        self.events
+            .iter()
+            .filter(|&&t| now.saturating_duration_since(t) < self.window)
+            .count() as u64
+    }
+
+    /// Events since creation or the last `reset`
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    pub fn set_window(&mut self, window: Duration) {
+        self.window = window;
+    }
+
+    pub fn reset(&mut self) {
+        self.events.clear();
+        self.total = 0;
+    }
 }
 
 // ============================================================================
@@ -467,6 +592,77 @@ impl Default for JitterEstimator {
     }
 }
 
+// ============================================================================
+// NOISE FLOOR ESTIMATOR - Long-run rate variance for auto lock thresholds
+// ============================================================================
+
+/// Long-run noise floor of the drift rate
+///
+/// Exponential moving mean and variance of the filtered rate, with a time
+/// constant of a few minutes. Unlike `JitterEstimator` (30-sample window,
+/// reacts to bursts) this settles on the host's steady noise, from which
+/// `auto_thresholds` derives the lock/unlock thresholds.
+#[derive(Debug)]
+pub struct NoiseFloorEstimator {
+    alpha: f64,
+    min_samples: usize,
+    mean: f64,
+    variance: f64,
+    samples: usize,
+}
+
+impl NoiseFloorEstimator {
+    /// ~100-sample time constant, valid after 60 samples
+    pub fn new() -> Self {
+        Self::with_params(0.01, 60)
+    }
+
+    pub fn with_params(alpha: f64, min_samples: usize) -> Self {
+        Self {
+            alpha,
+            min_samples,
+            mean: 0.0,
+            variance: 0.0,
+            samples: 0,
+        }
+    }
+
+    /// Add a rate sample (µs/s)
+    pub fn add_sample(&mut self, rate: f64) {
+        if self.samples == 0 {
+            self.mean = rate;
+        } else {
+            // West's incremental EMA variance
+            let diff = rate - self.mean;
+            let incr = self.alpha * diff;
+            self.mean += incr;
+            self.variance = (1.0 - self.alpha) * (self.variance + diff * incr);
+        }
+        self.samples += 1;
+    }
+
+    /// Standard deviation of the rate (µs/s); None until enough samples
+    pub fn stddev(&self) -> Option<f64> {
+        (self.samples >= self.min_samples).then(|| self.variance.sqrt())
+    }
+
+    pub fn sample_count(&self) -> usize {
+        self.samples
+    }
+
+    pub fn clear(&mut self) {
+        self.mean = 0.0;
+        self.variance = 0.0;
+        self.samples = 0;
+    }
+}
+
+impl Default for NoiseFloorEstimator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 // ============================================================================
 // TESTS
 // ============================================================================
@@ -661,6 +857,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_tiny_mad_floor_catches_small_step() {
+        // Hardware-timestamped host: rate noise of a few ns/s
+        let stable = |filter: &mut SpikeFilter| {
+            for i in 0..20 {
+                let noise = if i % 2 == 0 { 0.005 } else { -0.005 };
+                filter.filter(noise, FilterMode::Nano);
+            }
+        };
+
+        // Default floor: a 0.3 µs/s step hides under the 0.5 µs/s floor
+        let mut filter = SpikeFilter::new();
+        stable(&mut filter);
+        let result = filter.filter(0.3, FilterMode::Nano);
+        assert!(!result.is_spike);
+        assert!(result.mad_floored);
+        assert!(filter.last_mad_floored());
+
+        // Floor near the real noise: the same step is caught
+        let mut filter = SpikeFilter::new();
+        filter.set_min_mad(0.01);
+        stable(&mut filter);
+        let result = filter.filter(0.3, FilterMode::Nano);
+        assert!(result.is_spike, "0.3 µs/s step should be rejected");
+        assert!(result.threshold < 0.3);
+        assert_eq!(result.value, result.median);
+    }
+
     // ========================================================================
     // CONSECUTIVE SPIKE (STEP CHANGE) TESTS
     // ========================================================================
@@ -749,6 +973,46 @@ mod tests {
         assert!((ratio - (1.0 / 16.0 * 100.0)).abs() < 0.1);
     }
 
+    #[test]
+    fn test_reset_counters_keeps_history() {
+        let mut filter = SpikeFilter::new();
+        for _ in 0..15 {
+            filter.filter(0.0, FilterMode::Lock);
+        }
+        filter.filter(100.0, FilterMode::Lock);
+        assert_eq!(filter.recent_spikes(), 1);
+
+        filter.reset_counters();
+
+        assert_eq!(filter.stats(), (0, 0, 0.0));
+        assert_eq!(filter.recent_spikes(), 0);
+        assert_eq!(filter.window_len(), 16);
+        // Detection still active (no new warmup)
+        assert!(filter.filter(100.0, FilterMode::Lock).is_spike);
+        assert_eq!(filter.recent_spikes(), 1);
+    }
+
+    #[test]
+    fn test_windowed_counter_expires_events() {
+        let start = Instant::now();
+        let mut counter = WindowedCounter::new(Duration::from_secs(60));
+        counter.record_at(start);
+        counter.record_at(start + Duration::from_secs(30));
+        assert_eq!(counter.count_at(start + Duration::from_secs(59)), 2);
+        assert_eq!(counter.count_at(start + Duration::from_secs(60)), 1);
+        assert_eq!(counter.count_at(start + Duration::from_secs(90)), 0);
+        assert_eq!(counter.total(), 2);
+
+        // Old events are pruned as new ones arrive
+        counter.record_at(start + Duration::from_secs(120));
+        assert_eq!(counter.events.len(), 1);
+        assert_eq!(counter.total(), 3);
+
+        counter.reset();
+        assert_eq!(counter.count_at(start + Duration::from_secs(120)), 0);
+        assert_eq!(counter.total(), 0);
+    }
+
     #[test]
     fn test_clear_resets_state() {
         let mut filter = SpikeFilter::new();
@@ -1149,4 +1413,29 @@ mod tests {
             estimator.last_jitter()
         );
     }
+
+    #[test]
+    fn test_noise_floor_estimator() {
+        let mut estimator = NoiseFloorEstimator::with_params(0.01, 60);
+        // ±1µs/s around a constant residual drift: σ = 1
+        for i in 0..59 {
+            estimator.add_sample(3.0 + if i % 2 == 0 { 1.0 } else { -1.0 });
+        }
+        assert_eq!(estimator.stddev(), None);
+        for i in 59..2_000 {
+            estimator.add_sample(3.0 + if i % 2 == 0 { 1.0 } else { -1.0 });
+        }
+        let sigma = estimator.stddev().unwrap();
+        assert!((sigma - 1.0).abs() < 0.05, "sigma {:.3}", sigma);
+
+        // A 10σ burst is damped rather than taken as the new floor
+        for _ in 0..5 {
+            estimator.add_sample(13.0);
+        }
+        assert!(estimator.stddev().unwrap() < 3.0);
+
+        estimator.clear();
+        assert_eq!(estimator.sample_count(), 0);
+        assert_eq!(estimator.stddev(), None);
+    }
 }
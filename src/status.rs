@@ -1,13 +1,45 @@
+use crate::error::{DanteSyncError, Result};
 use serde::{Deserialize, Serialize};
 
+/// IPC status schema version
+///
+/// Bump when a field is added, removed, or changes meaning so the tray can
+/// tell it is talking to a newer service than it understands.
+/// History: 0 = unversioned (≤ v1.8.7), 1 = adds `schema_version`,
+/// 2 = adds `sync_source_uuid` and `observed_sync_interval_ms`,
+/// 3 = adds `external_clock_interference`, 4 = adds `ntp_tracking`,
+/// 5 = adds `time_to_lock_secs` and `time_to_nano_secs`,
+/// 6 = adds `offset_jitter_ns`, 7 = adds `rejected_sync_pairs`,
+/// 8 = adds `network_quality`, 9 = adds `raw_offset_ns`,
+/// 10 = adds `leap_pending`, 11 = adds `display_rate_ppm`,
+/// 12 = adds `master_is_dante`, 13 = adds the spike/NTP failure counters,
+/// 14 = adds `gm_mismatch`, 15 = adds `resettling`,
+/// 16 = adds `spike_threshold_us_s` and `spike_threshold_floored`,
+/// 17 = adds `ntp_step_threshold_us`, 18 = adds `oscillator_fault`,
+/// 19 = adds `lock_threshold_us_s`, 20 = adds `ntp_rtt_us` and `ntp_stratum`,
+/// 21 = adds `master_epoch_changed` and `epoch_shift_ns`,
+/// 22 = adds `ptp_flood_dropped`, 23 = adds `paused`,
+/// 24 = adds `unmatched_sync_rate` and `unmatched_syncs_total`,
+/// 25 = adds `drift_baseline_ppm`,
+/// 26 = adds `flag_ignored_alternate_master` and `flag_ignored_unicast`.
+pub const STATUS_SCHEMA_VERSION: u32 = 26;
+
 /// Sync status shared via IPC between service and tray app
 ///
 /// This struct contains all the information needed for the tray app to:
 /// - Display sync state (locked, acquiring, offline)
 /// - Animate the icon based on drift rate
 /// - Show detailed status in tooltips and menus
+///
+/// Both the service and `dantesync-tray` use this one definition. Fields
+/// added after the core set must be `#[serde(default)]` so either side can
+/// decode the other's older payloads.
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct SyncStatus {
+    /// Schema version of the sender (`STATUS_SCHEMA_VERSION`); 0 if unversioned
+    #[serde(default)]
+    pub schema_version: u32,
+
     // ========================================================================
     // Core PTP Status (existing fields)
     // ========================================================================
@@ -15,15 +47,68 @@ pub struct SyncStatus {
     /// Note: Absolute value is meaningless for Dante (device uptime, not UTC)
     pub offset_ns: i64,
 
+    /// `offset_ns` before the timestamp calibration offset is removed
+    /// (raw - offset = calibration offset; should stay constant)
+    #[serde(default)]
+    pub raw_offset_ns: i64,
+
+    /// Offset noise within the last sample window (ns, detrended std-dev)
+    /// Timestamp quality: ~1µs on good NICs, tens of µs on poor ones
+    #[serde(default)]
+    pub offset_jitter_ns: u64,
+
     /// Current frequency adjustment being applied (PPM)
     pub drift_ppm: f64,
 
+    /// Learned oscillator frequency error the servo integrates around (PPM)
+    /// Stable per machine; `drift_ppm` is this plus the proportional term
+    #[serde(default)]
+    pub drift_baseline_ppm: f64,
+
     /// Grandmaster clock UUID (from PTP Sync messages)
     pub gm_uuid: Option<[u8; 6]>,
 
+    /// Grandmaster is a Dante device (Audinate UUID or organization TLV)
+    #[serde(default)]
+    pub master_is_dante: bool,
+
+    /// Grandmaster is not `expected_gm_uuid`: its Syncs are ignored and we
+    /// do not lock (alarm: rogue or misconfigured master on the segment)
+    #[serde(default)]
+    pub gm_mismatch: bool,
+
+    /// UUID of the device actually sending Sync messages
+    /// Differs from `gm_uuid` behind a boundary clock
+    #[serde(default)]
+    pub sync_source_uuid: Option<[u8; 6]>,
+
+    /// Observed Sync interval from inter-arrival times (ms, 0 = unknown)
+    #[serde(default)]
+    pub observed_sync_interval_ms: u32,
+
     /// True once sync is established (receiving valid packets)
     pub settled: bool,
 
+    /// The clock was just stepped (NTP); offset and drift jump while the
+    /// servo re-settles (~2s). Expected - not an unlock or a sync problem
+    #[serde(default)]
+    pub resettling: bool,
+
+    /// The servo needs more correction than `max_healthy_ppm` and is clamped
+    /// at the band edge: the oscillator is out of its healthy range
+    #[serde(default)]
+    pub oscillator_fault: bool,
+
+    /// The master-vs-system epoch offset jumped by more than
+    /// `epoch_change_alarm_ms` in the last 10 minutes: the grandmaster
+    /// restarted or changed reference, not a drift problem
+    #[serde(default)]
+    pub master_epoch_changed: bool,
+
+    /// Size of the last master epoch change (ns, 0 = none seen)
+    #[serde(default)]
+    pub epoch_shift_ns: i64,
+
     /// Unix timestamp of last status update
     pub updated_ts: u64,
 
@@ -32,41 +117,534 @@ pub struct SyncStatus {
     // ========================================================================
     /// True when frequency is locked (rate stable < 5us/s)
     /// Used for icon badge color (green = locked)
+    #[serde(default)]
     pub is_locked: bool,
 
     /// Smoothed rate of offset change (us/s)
     /// Used for icon animation speed - higher rate = faster pulse
+    #[serde(default)]
     pub smoothed_rate_ppm: f64,
 
+    /// Drift rate averaged over `display_rate_samples` servo samples (us/s),
+    /// calmer than `smoothed_rate_ppm` for display
+    #[serde(default)]
+    pub display_rate_ppm: f64,
+
     /// Last NTP offset measurement (microseconds)
     /// Used for NTP status display in tray menu
+    #[serde(default)]
     pub ntp_offset_us: i64,
 
-    /// Current operating mode: "ACQ" (acquiring), "PROD" (production), "LOCK" (locked), "NTP-only"
+    /// Round-trip delay of the last NTP answer (µs; chrony: root delay).
+    /// 0 = unknown
+    #[serde(default)]
+    pub ntp_rtt_us: i64,
+
+    /// Stratum of the last NTP answer (0 = unknown). A rising stratum or
+    /// RTT is an early sign the upstream reference degraded
+    #[serde(default)]
+    pub ntp_stratum: u8,
+
+    /// NTP offset (µs) beyond which the clock is stepped: the configured
+    /// floor, raised on noisy NTP paths by the measured offset jitter
+    #[serde(default)]
+    pub ntp_step_threshold_us: i64,
+
+    /// Drift rate (µs/s) below which the servo locks: `lock_rate_us_s`, or
+    /// derived from the measured rate noise with `auto_thresholds`
+    #[serde(default)]
+    pub lock_threshold_us_s: f64,
+
+    /// Current operating mode: "ACQ" (acquiring), "PROD" (production), "LOCK" (locked), "NTP-only",
+    /// "PAUSED" (clock corrections halted from the tray)
     /// Used for status display and icon state
+    #[serde(default)]
     pub mode: String,
 
     /// True when NTP sync has failed (can't reach server)
+    #[serde(default)]
     pub ntp_failed: bool,
+
+    /// Seconds continuously locked (0 when not locked)
+    /// Used for SLA reporting ("Locked for 2h13m")
+    #[serde(default)]
+    pub locked_duration_secs: u64,
+
+    /// Number of LOCK → UNLOCK transitions since service start
+    #[serde(default)]
+    pub unlock_count: u64,
+
+    /// Sync pairs rejected because master time jumped backward or by > 2s
+    /// A steadily rising count points at a misbehaving master
+    #[serde(default)]
+    pub rejected_sync_pairs: u64,
+
+    /// PTP packets discarded unparsed because the group was flooded far
+    /// beyond the expected Sync rate (cumulative)
+    #[serde(default)]
+    pub ptp_flood_dropped: u64,
+
+    /// Rate samples rejected by the spike filter since `counters_since_ts`
+    #[serde(default)]
+    pub spikes_total: u64,
+
+    /// Spikes rejected within the last `counter_window_secs`
+    /// Falls back to 0 once the disturbance ends, unlike the total
+    #[serde(default)]
+    pub spikes_recent: u64,
+
+    /// Deviation from the median rate (µs/s) above which the spike filter
+    /// currently rejects a sample (0 until the filter has warmed up)
+    #[serde(default)]
+    pub spike_threshold_us_s: f64,
+
+    /// The threshold comes from `filters.spike_mad_floor_us_s`, not the
+    /// measured noise: a lower floor would catch smaller spikes
+    #[serde(default)]
+    pub spike_threshold_floored: bool,
+
+    /// Failed NTP queries since `counters_since_ts`
+    #[serde(default)]
+    pub ntp_failures_total: u64,
+
+    /// Percent of recent Syncs (about the last 100) that never got a
+    /// Follow_Up: Follow_Up loss, or a one-step master
+    #[serde(default)]
+    pub unmatched_sync_rate: f64,
+
+    /// Syncs without a Follow_Up since `counters_since_ts`
+    #[serde(default)]
+    pub unmatched_syncs_total: u64,
+
+    /// PTPv2 Sync/Follow_Ups ignored since `counters_since_ts` because they
+    /// carry the alternateMasterFlag (a secondary source, not the grandmaster)
+    #[serde(default)]
+    pub flag_ignored_alternate_master: u64,
+
+    /// PTPv2 Sync/Follow_Ups ignored since `counters_since_ts` because they
+    /// carry the unicastFlag (unicast negotiation, not the multicast source)
+    #[serde(default)]
+    pub flag_ignored_unicast: u64,
+
+    /// Failed NTP queries within the last `counter_window_secs`
+    #[serde(default)]
+    pub ntp_failures_recent: u64,
+
+    /// Interval the `*_recent` counters cover (seconds)
+    #[serde(default)]
+    pub counter_window_secs: u64,
+
+    /// Unix time the counters started (service start or `ResetCounters`)
+    #[serde(default)]
+    pub counters_since_ts: u64,
+
+    /// PTP path quality judged from sustained offset jitter
+    /// Anything but `good` usually means the switches are not prioritizing PTP
+    #[serde(default)]
+    pub network_quality: NetworkQuality,
+
+    /// Another process (e.g. W32Time) changed our clock adjustment recently
+    #[serde(default)]
+    pub external_clock_interference: bool,
+
+    /// Periodic NTP UTC tracking is enabled (tray "NTP Tracking" toggle)
+    #[serde(default)]
+    pub ntp_tracking: bool,
+
+    /// A leap second is scheduled for the coming UTC midnight
+    #[serde(default)]
+    pub leap_pending: bool,
+
+    /// Clock corrections are paused (tray "Pause Clock Corrections"); PTP is
+    /// still measured and reported but the clock is left alone
+    #[serde(default)]
+    pub paused: bool,
+
+    /// Seconds from warmup completion to the first LOCK (None until reached)
+    /// Commissioning KPI; survives later unlocks
+    #[serde(default)]
+    pub time_to_lock_secs: Option<u64>,
+
+    /// Seconds from warmup completion to the first NANO (None until reached)
+    #[serde(default)]
+    pub time_to_nano_secs: Option<u64>,
 }
 
 impl Default for SyncStatus {
     fn default() -> Self {
         SyncStatus {
+            schema_version: STATUS_SCHEMA_VERSION,
+
             // Core fields
             offset_ns: 0,
+            raw_offset_ns: 0,
+            offset_jitter_ns: 0,
             drift_ppm: 0.0,
+            drift_baseline_ppm: 0.0,
             gm_uuid: None,
+            master_is_dante: false,
+            gm_mismatch: false,
+            sync_source_uuid: None,
+            observed_sync_interval_ms: 0,
             settled: false,
+            resettling: false,
+            oscillator_fault: false,
+            master_epoch_changed: false,
+            epoch_shift_ns: 0,
             updated_ts: 0,
 
             // Extended fields for tray app
             is_locked: false,
             smoothed_rate_ppm: 0.0,
+            display_rate_ppm: 0.0,
             ntp_offset_us: 0,
+            ntp_rtt_us: 0,
+            ntp_stratum: 0,
+            ntp_step_threshold_us: 0,
+            lock_threshold_us_s: 0.0,
             mode: "ACQ".to_string(),
             ntp_failed: false,
+            locked_duration_secs: 0,
+            unlock_count: 0,
+            rejected_sync_pairs: 0,
+            ptp_flood_dropped: 0,
+            spikes_total: 0,
+            spikes_recent: 0,
+            spike_threshold_us_s: 0.0,
+            spike_threshold_floored: false,
+            ntp_failures_total: 0,
+            unmatched_sync_rate: 0.0,
+            unmatched_syncs_total: 0,
+            flag_ignored_alternate_master: 0,
+            flag_ignored_unicast: 0,
+            ntp_failures_recent: 0,
+            counter_window_secs: 0,
+            counters_since_ts: 0,
+            network_quality: NetworkQuality::Good,
+            external_clock_interference: false,
+            ntp_tracking: true,
+            leap_pending: false,
+            paused: false,
+            time_to_lock_secs: None,
+            time_to_nano_secs: None,
+        }
+    }
+}
+
+impl SyncStatus {
+    /// True when the sender uses a newer schema than this build understands
+    /// (the reader may be missing fields and should be updated)
+    pub fn is_newer_schema(&self) -> bool {
+        self.schema_version > STATUS_SCHEMA_VERSION
+    }
+
+    /// Mode name, derived from `is_locked` for senders that leave it empty
+    pub fn mode_str(&self) -> &str {
+        if !self.mode.is_empty() {
+            &self.mode
+        } else if self.is_locked {
+            "LOCK"
+        } else {
+            "ACQ"
+        }
+    }
+
+    /// Drift rate to show (µs/s); services before schema 11 only send the
+    /// servo's rate
+    pub fn display_rate(&self) -> f64 {
+        if self.schema_version >= 11 {
+            self.display_rate_ppm
+        } else {
+            self.smoothed_rate_ppm
+        }
+    }
+
+    /// Canonical one-line summary for logs, systemd and the tray, e.g.
+    /// `LOCK | off:+1.2µs drift:+0.3µs/s adj:+33.5ppm ntp:+12µs`
+    pub fn summary(&self) -> String {
+        let drift = if self.resettling {
+            "re-settling".to_string()
+        } else {
+            format!("{:+.1}µs/s", self.display_rate())
+        };
+        // Services before schema 4 did not report the tracking switch
+        let ntp = if self.schema_version >= 4 && !self.ntp_tracking {
+            "off".to_string()
+        } else if self.ntp_failed {
+            "unreachable".to_string()
+        } else {
+            format!("{:+}µs", self.ntp_offset_us)
+        };
+        format!(
+            "{} | off:{:+.1}µs drift:{} adj:{:+.1}ppm ntp:{}",
+            self.mode_str(),
+            self.offset_ns as f64 / 1000.0,
+            drift,
+            self.drift_ppm,
+            ntp
+        )
+    }
+
+    /// Reasons this status fails the `healthcheck` probe (empty = healthy):
+    /// the loop must have refreshed it within `max_age_secs` (liveness) and
+    /// PTP sync must be established (readiness), optionally also locked
+    pub fn health_problems(
+        &self,
+        now_secs: u64,
+        max_age_secs: u64,
+        require_lock: bool,
+    ) -> Vec<String> {
+        let mut problems = Vec::new();
+        let age_secs = now_secs.saturating_sub(self.updated_ts);
+        if self.updated_ts == 0 || age_secs > max_age_secs {
+            problems.push(format!(
+                "status not updated for {}s (sync loop stalled?)",
+                age_secs
+            ));
+        }
+        if !self.settled {
+            problems.push("PTP sync not established".to_string());
+        }
+        if require_lock && !self.is_locked {
+            problems.push(format!("not locked (mode {})", self.mode));
         }
+        problems
+    }
+}
+
+/// Learned servo model of one machine, for fleet-wide collection
+///
+/// Printed by `dantesync servo-model` as one JSON object. The baseline is the
+/// oscillator's frequency error, so comparing it across machines and over time
+/// spots ageing or temperature-sensitive hardware.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ServoModel {
+    pub hostname: String,
+    /// Service instance (None = default instance)
+    pub instance: Option<String>,
+    /// Version of the tool that produced the record
+    pub version: String,
+    /// Unix time the status was sampled (`SyncStatus::updated_ts`)
+    pub sampled_ts: u64,
+    pub mode: String,
+    pub is_locked: bool,
+    pub drift_baseline_ppm: f64,
+    pub applied_freq_ppm: f64,
+    pub locked_duration_secs: u64,
+    pub unlock_count: u64,
+    pub gm_uuid: Option<[u8; 6]>,
+}
+
+impl ServoModel {
+    pub fn from_status(status: &SyncStatus, hostname: &str, instance: Option<&str>) -> Self {
+        ServoModel {
+            hostname: hostname.to_string(),
+            instance: instance.map(str::to_string),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            sampled_ts: status.updated_ts,
+            mode: status.mode_str().to_string(),
+            is_locked: status.is_locked,
+            drift_baseline_ppm: status.drift_baseline_ppm,
+            applied_freq_ppm: status.drift_ppm,
+            locked_duration_secs: status.locked_duration_secs,
+            unlock_count: status.unlock_count,
+            gm_uuid: status.gm_uuid,
+        }
+    }
+}
+
+/// PTP network quality, serialized as `"good"`, `"marginal"` or `"poor"`
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum NetworkQuality {
+    #[default]
+    Good,
+    Marginal,
+    Poor,
+}
+
+impl NetworkQuality {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NetworkQuality::Good => "good",
+            NetworkQuality::Marginal => "marginal",
+            NetworkQuality::Poor => "poor",
+        }
+    }
+}
+
+/// Runtime command sent by the tray to the service over the IPC pipe
+///
+/// Framed like the status: a little-endian u32 length, then JSON
+/// (e.g. `"ResetServo"`). Commands need no elevation, so they are limited to
+/// actions that cannot harm the host.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IpcCommand {
+    /// Query NTP now instead of waiting for the next 30s check
+    ForceNtpSync,
+    /// Redo timestamp calibration and warmup, keeping the learned frequency
+    Recalibrate,
+    /// Forget the learned frequency and re-acquire from scratch
+    ResetServo,
+    /// Enable/disable periodic NTP UTC stepping
+    ToggleNtpTracking,
+    /// Zero the spike and NTP failure counters (monitoring baseline)
+    ResetCounters,
+    /// Stop adjusting the clock but keep measuring PTP (e.g. while another
+    /// tool disciplines the clock)
+    Pause,
+    /// Resume clock corrections with the learned frequency
+    Resume,
+}
+
+/// Largest command frame the service accepts
+pub const MAX_IPC_COMMAND_BYTES: usize = 256;
+
+/// Largest status frame a client accepts
+pub const MAX_IPC_STATUS_BYTES: usize = 64 * 1024;
+
+/// Status encoding a client can ask for after the initial JSON frame
+///
+/// Every connection still starts with a JSON status frame, so older clients
+/// are unaffected. A newer client may then send a one-byte frame holding the
+/// highest encoding it understands (a JSON command is never that short); the
+/// service answers with one more status frame in
+/// `IpcEncoding::negotiate(byte)`, whose first byte names the encoding used.
+/// Older services ignore the request as an unknown command and close. The
+/// Windows pipe keeps streaming the status in that encoding.
+///
+/// Bincode is not self-describing: `#[serde(default)]` cannot fill in
+/// missing fields, so clients must only request it when the JSON frame
+/// reports their own `STATUS_SCHEMA_VERSION`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum IpcEncoding {
+    Json = 0,
+    Bincode = 1,
+}
+
+impl IpcEncoding {
+    /// Highest encoding this build understands
+    pub const LATEST: IpcEncoding = IpcEncoding::Bincode;
+
+    /// Encoding to answer a request with: the requested one, or the best
+    /// this build has if the client is newer
+    pub fn negotiate(requested: u8) -> IpcEncoding {
+        match requested {
+            0 => IpcEncoding::Json,
+            _ => IpcEncoding::LATEST,
+        }
+    }
+
+    /// Status frame body: the encoding byte, then the encoded status
+    pub fn encode_status(self, status: &SyncStatus) -> Result<Vec<u8>> {
+        let mut frame = vec![self as u8];
+        match self {
+            IpcEncoding::Json => serde_json::to_writer(&mut frame, status)?,
+            IpcEncoding::Bincode => bincode::serialize_into(&mut frame, status)
+                .map_err(|e| DanteSyncError::Parse(format!("bincode status: {}", e)))?,
+        }
+        Ok(frame)
+    }
+
+    /// Decode a frame body produced by `encode_status`, or a raw JSON frame
+    /// (sent before the request was seen)
+    pub fn decode_status(frame: &[u8]) -> Result<SyncStatus> {
+        match frame.split_first() {
+            Some((&b'{', _)) => Ok(serde_json::from_slice(frame)?),
+            Some((&0, body)) => Ok(serde_json::from_slice(body)?),
+            Some((&1, body)) => bincode::deserialize(body)
+                .map_err(|e| DanteSyncError::Parse(format!("bincode status: {}", e))),
+            Some((&other, _)) => Err(DanteSyncError::Parse(format!(
+                "unknown IPC status encoding {}",
+                other
+            ))),
+            None => Err(DanteSyncError::Parse("empty IPC status frame".to_string())),
+        }
+    }
+}
+
+/// Check an `--instance-name`: it becomes part of the lock file, pipe and
+/// socket names, so only letters, digits, '-' and '_' (1-32 characters)
+pub fn validate_instance_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > 32 {
+        return Err(format!(
+            "instance name must be 1-32 characters (got {})",
+            name.len()
+        ));
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "instance name may only contain letters, digits, '-' and '_' (got \"{}\")",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// Suffix naming an instance's lock file and IPC endpoint ("" = default instance)
+pub fn instance_suffix(instance: Option<&str>) -> String {
+    instance
+        .map(|name| format!("-{}", name))
+        .unwrap_or_default()
+}
+
+/// Windows status pipe of an instance (default `\\.\pipe\dantesync`)
+pub fn ipc_pipe_name(instance: Option<&str>) -> String {
+    format!(r"\\.\pipe\dantesync{}", instance_suffix(instance))
+}
+
+/// Linux status socket of an instance (default `/run/dantesync.sock`)
+pub fn ipc_socket_path(instance: Option<&str>) -> String {
+    format!("/run/dantesync{}.sock", instance_suffix(instance))
+}
+
+/// Format a 6-byte UUID/MAC as a readable string (e.g., "00:1D:C1:AB:CD:EF")
+pub fn format_mac(uuid: &[u8; 6]) -> String {
+    format!(
+        "{:02X}:{:02X}:{:02X}:{:02X}:{:02X}:{:02X}",
+        uuid[0], uuid[1], uuid[2], uuid[3], uuid[4], uuid[5]
+    )
+}
+
+/// Parse a UUID/MAC written as "00:1D:C1:AB:CD:EF", "00-1d-c1-ab-cd-ef" or
+/// "001DC1ABCDEF" (inverse of `format_mac`)
+pub fn parse_mac(text: &str) -> Result<[u8; 6], String> {
+    let hex: String = text
+        .trim()
+        .chars()
+        .filter(|c| *c != ':' && *c != '-')
+        .collect();
+    if hex.len() != 12 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!(
+            "expected 6 hex bytes like 00:1D:C1:AB:CD:EF (got {:?})",
+            text
+        ));
+    }
+    let mut uuid = [0u8; 6];
+    for (i, byte) in uuid.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).map_err(|e| e.to_string())?;
+    }
+    Ok(uuid)
+}
+
+/// Format a duration as a compact human string (e.g. "2h13m", "5m07s", "42s")
+pub fn format_duration(secs: u64) -> String {
+    let days = secs / 86_400;
+    let hours = (secs % 86_400) / 3600;
+    let mins = (secs % 3600) / 60;
+    let s = secs % 60;
+    if days > 0 {
+        format!("{}d{}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h{:02}m", hours, mins)
+    } else if mins > 0 {
+        format!("{}m{:02}s", mins, s)
+    } else {
+        format!("{}s", s)
     }
 }
 
@@ -85,18 +663,291 @@ mod tests {
 
     #[test]
     fn test_sync_status_serde_roundtrip() {
-        let mut status = SyncStatus::default();
-        status.is_locked = true;
-        status.mode = "LOCK".to_string();
-        status.smoothed_rate_ppm = 2.5;
-        status.ntp_offset_us = 150;
+        let status = SyncStatus {
+            is_locked: true,
+            mode: "LOCK".to_string(),
+            smoothed_rate_ppm: 2.5,
+            ntp_offset_us: 150,
+            ..Default::default()
+        };
 
         let json = serde_json::to_string(&status).expect("serialize failed");
         let restored: SyncStatus = serde_json::from_str(&json).expect("deserialize failed");
 
-        assert_eq!(restored.is_locked, true);
+        assert!(restored.is_locked);
         assert_eq!(restored.mode, "LOCK");
         assert!((restored.smoothed_rate_ppm - 2.5).abs() < f64::EPSILON);
         assert_eq!(restored.ntp_offset_us, 150);
     }
+
+    #[test]
+    fn test_lock_duration_fields_optional_in_json() {
+        // Status written by older services lacks lock duration fields
+        let json = r#"{"offset_ns":0,"drift_ppm":0.0,"gm_uuid":null,"settled":true,
+            "updated_ts":0,"is_locked":true,"smoothed_rate_ppm":0.0,"ntp_offset_us":0,
+            "mode":"LOCK","ntp_failed":false}"#;
+        let status: SyncStatus = serde_json::from_str(json).expect("deserialize failed");
+        assert_eq!(status.locked_duration_secs, 0);
+        assert_eq!(status.unlock_count, 0);
+        assert_eq!(status.time_to_lock_secs, None);
+        assert_eq!(status.offset_jitter_ns, 0);
+        assert_eq!(status.raw_offset_ns, 0);
+        assert!(!status.leap_pending);
+        assert!(!status.paused);
+        assert_eq!(status.display_rate_ppm, 0.0);
+        assert!(!status.master_is_dante);
+        assert!(!status.gm_mismatch);
+        assert!(!status.resettling);
+        assert!(!status.oscillator_fault);
+        assert!(!status.master_epoch_changed);
+        assert_eq!(status.ptp_flood_dropped, 0);
+        assert_eq!(status.epoch_shift_ns, 0);
+        assert_eq!(status.spikes_recent, 0);
+        assert_eq!(status.spike_threshold_us_s, 0.0);
+        assert!(!status.spike_threshold_floored);
+        assert_eq!(status.ntp_failures_total, 0);
+        assert_eq!(status.unmatched_sync_rate, 0.0);
+        assert_eq!(status.unmatched_syncs_total, 0);
+        assert_eq!(status.flag_ignored_alternate_master, 0);
+        assert_eq!(status.flag_ignored_unicast, 0);
+        assert_eq!(status.drift_baseline_ppm, 0.0);
+        assert_eq!(status.ntp_step_threshold_us, 0);
+        assert_eq!(status.ntp_rtt_us, 0);
+        assert_eq!(status.ntp_stratum, 0);
+        assert_eq!(status.lock_threshold_us_s, 0.0);
+        assert_eq!(status.time_to_nano_secs, None);
+        assert_eq!(status.network_quality, NetworkQuality::Good);
+    }
+
+    #[test]
+    fn test_servo_model_from_status() {
+        let status = SyncStatus {
+            mode: String::new(),
+            is_locked: true,
+            drift_ppm: 34.2,
+            drift_baseline_ppm: 33.5,
+            locked_duration_secs: 3_600,
+            unlock_count: 2,
+            updated_ts: 1_700_000_000,
+            gm_uuid: Some([0, 0x1D, 0xC1, 1, 2, 3]),
+            ..Default::default()
+        };
+        let model = ServoModel::from_status(&status, "studio-pc", Some("b"));
+        assert_eq!(model.hostname, "studio-pc");
+        assert_eq!(model.instance.as_deref(), Some("b"));
+        assert_eq!(model.mode, "LOCK");
+        assert_eq!(model.drift_baseline_ppm, 33.5);
+        assert_eq!(model.applied_freq_ppm, 34.2);
+        assert_eq!(model.locked_duration_secs, 3_600);
+        assert_eq!(model.unlock_count, 2);
+        assert_eq!(model.sampled_ts, 1_700_000_000);
+
+        let json = serde_json::to_string(&model).unwrap();
+        assert!(json.contains(r#""drift_baseline_ppm":33.5"#));
+        let restored: ServoModel = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, model);
+    }
+
+    #[test]
+    fn test_network_quality_serde() {
+        let json = serde_json::to_string(&NetworkQuality::Marginal).unwrap();
+        assert_eq!(json, r#""marginal""#);
+        let quality: NetworkQuality = serde_json::from_str(r#""poor""#).unwrap();
+        assert_eq!(quality, NetworkQuality::Poor);
+        assert_eq!(quality.as_str(), "poor");
+    }
+
+    #[test]
+    fn test_ipc_command_serde() {
+        let json = serde_json::to_string(&IpcCommand::ToggleNtpTracking).unwrap();
+        assert_eq!(json, r#""ToggleNtpTracking""#);
+        assert!(json.len() <= MAX_IPC_COMMAND_BYTES);
+        let cmd: IpcCommand = serde_json::from_str(r#""ResetServo""#).unwrap();
+        assert_eq!(cmd, IpcCommand::ResetServo);
+        assert!(serde_json::from_str::<IpcCommand>(r#""Reboot""#).is_err());
+    }
+
+    #[test]
+    fn test_ipc_encoding_round_trip() {
+        let status = SyncStatus {
+            mode: "NANO".to_string(),
+            offset_ns: -1_234,
+            gm_uuid: Some([0x00, 0x1D, 0xC1, 0x51, 0xD0, 0xD9]),
+            network_quality: NetworkQuality::Marginal,
+            time_to_lock_secs: Some(42),
+            ..Default::default()
+        };
+        let json = IpcEncoding::Json.encode_status(&status).unwrap();
+        let binary = IpcEncoding::Bincode.encode_status(&status).unwrap();
+        assert_eq!(json[0], 0);
+        assert_eq!(binary[0], 1);
+        assert!(binary.len() < json.len());
+        assert!(binary.len() <= MAX_IPC_STATUS_BYTES);
+        for frame in [json, binary] {
+            let decoded = IpcEncoding::decode_status(&frame).unwrap();
+            assert_eq!(decoded.mode, "NANO");
+            assert_eq!(decoded.offset_ns, -1_234);
+            assert_eq!(decoded.gm_uuid, status.gm_uuid);
+            assert_eq!(decoded.network_quality, NetworkQuality::Marginal);
+            assert_eq!(decoded.time_to_lock_secs, Some(42));
+        }
+
+        // A raw JSON frame sent before the request took effect
+        let raw = serde_json::to_vec(&status).unwrap();
+        assert_eq!(IpcEncoding::decode_status(&raw).unwrap().offset_ns, -1_234);
+
+        assert!(IpcEncoding::decode_status(&[]).is_err());
+        assert!(IpcEncoding::decode_status(&[7, 0]).is_err());
+    }
+
+    #[test]
+    fn test_ipc_encoding_negotiation() {
+        assert_eq!(IpcEncoding::negotiate(0), IpcEncoding::Json);
+        assert_eq!(IpcEncoding::negotiate(1), IpcEncoding::Bincode);
+        // A newer client gets the best this build has
+        assert_eq!(IpcEncoding::negotiate(200), IpcEncoding::LATEST);
+        // A request byte can never be mistaken for a JSON command
+        let shortest = serde_json::to_vec(&IpcCommand::Pause).unwrap();
+        assert!(shortest.len() > 1);
+    }
+
+    #[test]
+    fn test_schema_version_serialized_and_detected() {
+        let json = serde_json::to_string(&SyncStatus::default()).unwrap();
+        assert!(json.contains(&format!("\"schema_version\":{}", STATUS_SCHEMA_VERSION)));
+
+        // Unversioned (older) service decodes as version 0
+        let old = r#"{"offset_ns":0,"drift_ppm":0.0,"gm_uuid":null,"settled":true,"updated_ts":0}"#;
+        let status: SyncStatus = serde_json::from_str(old).unwrap();
+        assert_eq!(status.schema_version, 0);
+        assert!(!status.is_newer_schema());
+
+        // A newer service with unknown fields still decodes but is flagged
+        let newer = format!(
+            r#"{{"schema_version":{},"offset_ns":0,"drift_ppm":0.0,"gm_uuid":null,
+            "settled":true,"updated_ts":0,"future_field":42}}"#,
+            STATUS_SCHEMA_VERSION + 1
+        );
+        let status: SyncStatus = serde_json::from_str(&newer).unwrap();
+        assert!(status.is_newer_schema());
+    }
+
+    #[test]
+    fn test_instance_endpoints() {
+        assert_eq!(ipc_pipe_name(None), r"\\.\pipe\dantesync");
+        assert_eq!(ipc_socket_path(None), "/run/dantesync.sock");
+        assert_eq!(ipc_pipe_name(Some("net-b")), r"\\.\pipe\dantesync-net-b");
+        assert_eq!(ipc_socket_path(Some("net-b")), "/run/dantesync-net-b.sock");
+
+        assert!(validate_instance_name("studio_2").is_ok());
+        assert!(validate_instance_name("").is_err());
+        assert!(validate_instance_name("../etc").is_err());
+        assert!(validate_instance_name("a b").is_err());
+        assert!(validate_instance_name(&"x".repeat(33)).is_err());
+    }
+
+    #[test]
+    fn test_summary() {
+        let mut status = SyncStatus {
+            schema_version: STATUS_SCHEMA_VERSION,
+            mode: "LOCK".to_string(),
+            offset_ns: 1_234,
+            display_rate_ppm: 0.3,
+            smoothed_rate_ppm: 0.9,
+            drift_ppm: 33.46,
+            ntp_offset_us: 12,
+            ..SyncStatus::default()
+        };
+        assert_eq!(
+            status.summary(),
+            "LOCK | off:+1.2µs drift:+0.3µs/s adj:+33.5ppm ntp:+12µs"
+        );
+
+        status.resettling = true;
+        status.ntp_failed = true;
+        assert_eq!(
+            status.summary(),
+            "LOCK | off:+1.2µs drift:re-settling adj:+33.5ppm ntp:unreachable"
+        );
+
+        status.resettling = false;
+        status.ntp_tracking = false;
+        assert!(status.summary().ends_with("ntp:off"));
+
+        // Old service: no mode, no display rate, no tracking switch
+        let old = SyncStatus {
+            schema_version: 0,
+            mode: String::new(),
+            is_locked: true,
+            smoothed_rate_ppm: -2.04,
+            ntp_tracking: false,
+            ntp_offset_us: -7,
+            ..SyncStatus::default()
+        };
+        assert_eq!(
+            old.summary(),
+            "LOCK | off:+0.0µs drift:-2.0µs/s adj:+0.0ppm ntp:-7µs"
+        );
+    }
+
+    #[test]
+    fn test_health_problems() {
+        let now = 1_000_000;
+        let mut status = SyncStatus {
+            updated_ts: now - 5,
+            settled: true,
+            ..SyncStatus::default()
+        };
+        assert!(status.health_problems(now, 30, false).is_empty());
+        assert_eq!(status.health_problems(now, 30, true).len(), 1);
+
+        status.is_locked = true;
+        assert!(status.health_problems(now, 30, true).is_empty());
+
+        // Stale status fails liveness
+        assert_eq!(status.health_problems(now + 60, 30, false).len(), 1);
+
+        // Never updated, not settled
+        let problems = SyncStatus::default().health_problems(now, 30, false);
+        assert_eq!(problems.len(), 2, "{:?}", problems);
+    }
+
+    #[test]
+    fn test_format_mac() {
+        assert_eq!(
+            format_mac(&[0x00, 0x1D, 0xC1, 0xAB, 0xCD, 0xEF]),
+            "00:1D:C1:AB:CD:EF"
+        );
+    }
+
+    #[test]
+    fn test_parse_mac() {
+        let uuid = [0x00, 0x1D, 0xC1, 0xAB, 0xCD, 0xEF];
+        for text in [
+            "00:1D:C1:AB:CD:EF",
+            "00-1d-c1-ab-cd-ef",
+            "001dc1ABCDEF",
+            " 00:1D:C1:AB:CD:EF ",
+        ] {
+            assert_eq!(parse_mac(text), Ok(uuid), "{}", text);
+        }
+        assert_eq!(parse_mac(&format_mac(&uuid)), Ok(uuid));
+        for bad in [
+            "",
+            "00:1D:C1:AB:CD",
+            "00:1D:C1:AB:CD:EF:01",
+            "00:1D:C1:AB:CD:EG",
+        ] {
+            assert!(parse_mac(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(0), "0s");
+        assert_eq!(format_duration(42), "42s");
+        assert_eq!(format_duration(307), "5m07s");
+        assert_eq!(format_duration(2 * 3600 + 13 * 60 + 5), "2h13m");
+        assert_eq!(format_duration(3 * 86_400 + 4 * 3600), "3d4h");
+    }
 }
@@ -0,0 +1,112 @@
+//! UDP status export for dashboards and broadcast automation
+//!
+//! Sends the serialized `SyncStatus` as one JSON datagram to
+//! `telemetry_target` whenever the controller refreshes its status. Sending
+//! never blocks the sync loop: the socket is non-blocking and failures
+//! (nobody listening, full buffer) are dropped. Bursts of status updates
+//! within `MIN_SEND_INTERVAL` are coalesced.
+
+use crate::status::SyncStatus;
+use log::trace;
+use std::cell::Cell;
+use std::io;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket};
+use std::time::{Duration, Instant};
+
+/// Several status updates can happen in one loop pass; send one of them
+const MIN_SEND_INTERVAL: Duration = Duration::from_millis(100);
+
+pub struct TelemetrySender {
+    socket: UdpSocket,
+    target: SocketAddr,
+    last_sent: Cell<Option<Instant>>,
+}
+
+impl TelemetrySender {
+    /// Bind an ephemeral port of the target's address family
+    pub fn new(target: SocketAddr) -> io::Result<Self> {
+        let local: SocketAddr = if target.is_ipv4() {
+            (Ipv4Addr::UNSPECIFIED, 0).into()
+        } else {
+            (Ipv6Addr::UNSPECIFIED, 0).into()
+        };
+        let socket = UdpSocket::bind(local)?;
+        socket.set_nonblocking(true)?;
+        if target.ip().is_multicast() || target.ip() == Ipv4Addr::BROADCAST {
+            // Broadcast automation often listens on a broadcast/multicast group
+            let _ = socket.set_broadcast(true);
+        }
+        Ok(Self {
+            socket,
+            target,
+            last_sent: Cell::new(None),
+        })
+    }
+
+    pub fn target(&self) -> SocketAddr {
+        self.target
+    }
+
+    /// Send `status` unless a datagram went out within `MIN_SEND_INTERVAL`.
+    /// Returns whether one was sent.
+    pub fn send(&self, status: &SyncStatus) -> bool {
+        let now = Instant::now();
+        if self
+            .last_sent
+            .get()
+            .is_some_and(|last| now.duration_since(last) < MIN_SEND_INTERVAL)
+        {
+            return false;
+        }
+        self.last_sent.set(Some(now));
+
+        let payload = match serde_json::to_vec(status) {
+            Ok(payload) => payload,
+            Err(e) => {
+                trace!("[Telemetry] Cannot serialize status: {}", e);
+                return false;
+            }
+        };
+        match self.socket.send_to(&payload, self.target) {
+            Ok(_) => true,
+            Err(e) => {
+                trace!("[Telemetry] Send to {} failed: {}", self.target, e);
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_telemetry_sends_status_datagram_rate_limited() {
+        let receiver = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        receiver
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .unwrap();
+        let sender = TelemetrySender::new(receiver.local_addr().unwrap()).unwrap();
+
+        let status = SyncStatus {
+            offset_ns: 1234,
+            mode: "LOCK".to_string(),
+            is_locked: true,
+            ..SyncStatus::default()
+        };
+        assert!(sender.send(&status));
+        // Same loop pass: coalesced
+        assert!(!sender.send(&status));
+
+        let mut buf = [0u8; 65536];
+        let len = receiver.recv(&mut buf).unwrap();
+        let received: SyncStatus = serde_json::from_slice(&buf[..len]).unwrap();
+        assert_eq!(received.offset_ns, 1234);
+        assert_eq!(received.mode, "LOCK");
+        assert!(received.is_locked);
+
+        std::thread::sleep(MIN_SEND_INTERVAL);
+        assert!(sender.send(&status));
+    }
+}
@@ -0,0 +1,152 @@
+//! Test doubles for driving `PtpController` from integration tests
+//!
+//! Built for unit tests and with the `testing` feature. `SimulatedNtpSource`
+//! replays a realistic NTP reference (fixed offset, slow drift, per-query
+//! jitter, failed queries) so tests don't each hand-roll an `NtpSource`.
+//! Jitter comes from a seeded PRNG, so runs are reproducible.
+
+use crate::error::{DanteSyncError, Result};
+use crate::traits::NtpSource;
+use std::cell::Cell;
+use std::collections::HashSet;
+use std::time::Duration;
+
+/// `NtpSource` returning `base + drift * n ± jitter` on the n-th query
+/// (positive = local clock behind, as for `NtpClient`)
+pub struct SimulatedNtpSource {
+    base_offset_ns: i64,
+    drift_ns_per_call: i64,
+    jitter_ns: i64,
+    fail_every: u64,
+    fail_calls: HashSet<u64>,
+    calls: Cell<u64>,
+    rng_state: Cell<u64>,
+}
+
+impl SimulatedNtpSource {
+    pub fn new(base_offset_ns: i64) -> Self {
+        Self {
+            base_offset_ns,
+            drift_ns_per_call: 0,
+            jitter_ns: 0,
+            fail_every: 0,
+            fail_calls: HashSet::new(),
+            calls: Cell::new(0),
+            rng_state: Cell::new(0x9E37_79B9_7F4A_7C15),
+        }
+    }
+
+    /// Offset change per query (NTP reference and PTP master disagree)
+    pub fn with_drift(mut self, drift_ns_per_call: i64) -> Self {
+        self.drift_ns_per_call = drift_ns_per_call;
+        self
+    }
+
+    /// Uniform noise in `[-jitter_ns, jitter_ns]` added to every answer
+    pub fn with_jitter(mut self, jitter_ns: i64) -> Self {
+        self.jitter_ns = jitter_ns.abs();
+        self
+    }
+
+    /// PRNG seed for the jitter (0 is mapped to a fixed non-zero seed)
+    pub fn with_seed(self, seed: u64) -> Self {
+        self.rng_state.set(if seed == 0 { 1 } else { seed });
+        self
+    }
+
+    /// Fail every n-th query (n, 2n, ... counting from 1; 0 = never)
+    pub fn fail_every(mut self, n: u64) -> Self {
+        self.fail_every = n;
+        self
+    }
+
+    /// Fail these queries (0-based call indices)
+    pub fn fail_on(mut self, calls: impl IntoIterator<Item = u64>) -> Self {
+        self.fail_calls.extend(calls);
+        self
+    }
+
+    /// Queries answered or failed so far
+    pub fn calls(&self) -> u64 {
+        self.calls.get()
+    }
+
+    // xorshift64*
+    fn next_random(&self) -> u64 {
+        let mut x = self.rng_state.get();
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.rng_state.set(x);
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+}
+
+impl NtpSource for SimulatedNtpSource {
+    fn get_offset(&self) -> Result<(Duration, i8)> {
+        let call = self.calls.get();
+        self.calls.set(call + 1);
+
+        if self.fail_calls.contains(&call)
+            || (self.fail_every > 0 && (call + 1) % self.fail_every == 0)
+        {
+            return Err(DanteSyncError::NtpTimeout(format!(
+                "simulated NTP timeout (query {})",
+                call
+            )));
+        }
+
+        let mut offset_ns = self.base_offset_ns + self.drift_ns_per_call * call as i64;
+        if self.jitter_ns > 0 {
+            let span = 2 * self.jitter_ns as u64 + 1;
+            offset_ns += (self.next_random() % span) as i64 - self.jitter_ns;
+        }
+        let sign = if offset_ns >= 0 { 1 } else { -1 };
+        Ok((Duration::from_nanos(offset_ns.unsigned_abs()), sign))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signed_ns(result: Result<(Duration, i8)>) -> i64 {
+        let (magnitude, sign) = result.expect("query failed");
+        magnitude.as_nanos() as i64 * sign as i64
+    }
+
+    #[test]
+    fn test_base_offset_and_drift() {
+        let ntp = SimulatedNtpSource::new(-2_000).with_drift(1_500);
+        assert_eq!(signed_ns(ntp.get_offset()), -2_000);
+        assert_eq!(signed_ns(ntp.get_offset()), -500);
+        assert_eq!(signed_ns(ntp.get_offset()), 1_000);
+        assert_eq!(ntp.calls(), 3);
+    }
+
+    #[test]
+    fn test_jitter_bounded_and_reproducible() {
+        let a = SimulatedNtpSource::new(10_000)
+            .with_jitter(500)
+            .with_seed(7);
+        let b = SimulatedNtpSource::new(10_000)
+            .with_jitter(500)
+            .with_seed(7);
+        let mut distinct = HashSet::new();
+        for _ in 0..100 {
+            let offset = signed_ns(a.get_offset());
+            assert!((9_500..=10_500).contains(&offset), "{}", offset);
+            assert_eq!(offset, signed_ns(b.get_offset()));
+            distinct.insert(offset);
+        }
+        assert!(distinct.len() > 10, "jitter should vary between queries");
+    }
+
+    #[test]
+    fn test_failure_schedule() {
+        let ntp = SimulatedNtpSource::new(0).fail_every(3).fail_on([0]);
+        let failed: Vec<bool> = (0..7).map(|_| ntp.get_offset().is_err()).collect();
+        assert_eq!(failed, [true, false, true, false, false, true, false]);
+        assert_eq!(ntp.calls(), 7);
+    }
+}
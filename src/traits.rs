@@ -1,9 +1,16 @@
-use anyhow::Result;
+use crate::error::Result;
+use crate::ntp::NtpQuality;
 use std::time::Duration;
 
 #[cfg_attr(test, mockall::automock)]
 pub trait NtpSource {
     fn get_offset(&self) -> Result<(Duration, i8)>;
+
+    /// Round-trip delay and stratum behind the last successful `get_offset`.
+    /// Default impl reports none (offset-only sources).
+    fn quality(&self) -> Option<NtpQuality> {
+        None
+    }
 }
 
 #[cfg_attr(test, mockall::automock)]
@@ -16,4 +23,11 @@ pub trait PtpNetwork {
     fn reset(&mut self) -> Result<()> {
         Ok(())
     }
+
+    /// Describe the receive-timestamp source for diagnostics.
+    /// Returns (description, precise) where `precise` means kernel/driver-level
+    /// timestamps are enabled. Default impl reports userspace timestamps.
+    fn timestamp_source(&self) -> (String, bool) {
+        ("userspace SystemTime::now()".to_string(), false)
+    }
 }
@@ -1,11 +1,13 @@
-use anyhow::Result;
 use dantesync::clock::SystemClock;
 use dantesync::config::SystemConfig;
 use dantesync::controller::PtpController;
+use dantesync::error::Result;
 use dantesync::status::SyncStatus;
+use dantesync::testing::SimulatedNtpSource;
 use dantesync::traits::{NtpSource, PtpNetwork};
 use std::cell::RefCell;
 use std::f64::consts::PI;
+use std::rc::Rc;
 use std::sync::{Arc, RwLock};
 use std::time::{Duration, SystemTime};
 
@@ -29,6 +31,14 @@ struct PhysicsEngine {
     natural_drift_ppm: f64,
     current_adj_ppm: f64,
     step_offset_ns: f64,
+    /// Largest change between consecutive frequency adjustments (ppm)
+    max_adj_step_ppm: f64,
+    /// UTC - Master (ns), as seen by `UtcNtp`
+    utc_offset_ns: f64,
+    /// PTP masters silent: the network delivers no packets
+    ptp_outage: bool,
+    /// Clock steps applied so far
+    clock_steps: usize,
 }
 
 impl PhysicsEngine {
@@ -39,6 +49,10 @@ impl PhysicsEngine {
             natural_drift_ppm: drift,
             current_adj_ppm: 0.0,
             step_offset_ns: 0.0,
+            max_adj_step_ppm: 0.0,
+            utc_offset_ns: 0.0,
+            ptp_outage: false,
+            clock_steps: 0,
         }
     }
 
@@ -55,24 +69,30 @@ struct SharedPhysics {
 }
 
 #[derive(Clone)]
-struct SimClockRef(Arc<SharedPhysics>);
+struct SimClockRef(Rc<SharedPhysics>);
 
 impl SystemClock for SimClockRef {
     fn adjust_frequency(&mut self, freq: f64) -> Result<()> {
         let ppm = (freq - 1.0) * 1_000_000.0;
-        self.0.engine.borrow_mut().current_adj_ppm = ppm;
+        let mut engine = self.0.engine.borrow_mut();
+        engine.max_adj_step_ppm = engine
+            .max_adj_step_ppm
+            .max((ppm - engine.current_adj_ppm).abs());
+        engine.current_adj_ppm = ppm;
         Ok(())
     }
 
     fn step_clock(&mut self, step: Duration, sign: i8) -> Result<()> {
         let ns = step.as_nanos() as i64 * sign as i64;
-        self.0.engine.borrow_mut().step_offset_ns += ns as f64;
+        let mut engine = self.0.engine.borrow_mut();
+        engine.step_offset_ns += ns as f64;
+        engine.clock_steps += 1;
         Ok(())
     }
 }
 
 struct StatefulNetwork {
-    physics: Arc<SharedPhysics>,
+    physics: Rc<SharedPhysics>,
     jitter_sigma_ns: f64,
     seq: u16,
     pending_followup: Option<(u16, u64)>, // (seq, t1)
@@ -82,6 +102,11 @@ impl PtpNetwork for StatefulNetwork {
     fn recv_packet(&mut self) -> Result<Option<(Vec<u8>, usize, SystemTime)>> {
         let mut phys = self.physics.engine.borrow_mut();
 
+        if phys.ptp_outage {
+            self.pending_followup = None;
+            return Ok(None);
+        }
+
         if let Some((seq, t1)) = self.pending_followup {
             self.pending_followup = None;
             let t2_sys = SystemTime::UNIX_EPOCH;
@@ -139,7 +164,7 @@ impl PtpNetwork for StatefulNetwork {
 }
 
 struct SimNtp {
-    physics: Arc<SharedPhysics>,
+    physics: Rc<SharedPhysics>,
 }
 impl NtpSource for SimNtp {
     fn get_offset(&self) -> Result<(Duration, i8)> {
@@ -150,19 +175,17 @@ impl NtpSource for SimNtp {
     }
 }
 
-/// NTP source with independent drift (simulates Dante frequency ≠ NTP reference)
-struct DriftingNtp {
-    offset_us: std::cell::Cell<i64>, // Grows over time if Dante and NTP disagree
-    drift_us_per_call: i64,          // How much NTP offset grows per query
+/// NTP reference `utc_offset_ns` away from the PTP master (positive =
+/// local clock behind UTC, as for `NtpClient`)
+struct UtcNtp {
+    physics: Rc<SharedPhysics>,
 }
-
-impl NtpSource for DriftingNtp {
+impl NtpSource for UtcNtp {
     fn get_offset(&self) -> Result<(Duration, i8)> {
-        // Simulate NTP offset growing because Dante frequency ≠ NTP reference
-        let current = self.offset_us.get();
-        self.offset_us.set(current + self.drift_us_per_call);
-        let sign = if current >= 0 { 1 } else { -1 };
-        Ok((Duration::from_micros(current.abs() as u64), sign))
+        let phys = self.physics.engine.borrow();
+        let behind_ns = phys.utc_offset_ns - (phys.offset_ns + phys.step_offset_ns);
+        let sign = if behind_ns >= 0.0 { 1 } else { -1 };
+        Ok((Duration::from_nanos(behind_ns.abs() as u64), sign))
     }
 }
 
@@ -180,6 +203,10 @@ struct SimulationResult {
     max_rate_us_per_s: f64,
     /// True if rate converged to stable (< 5us/s)
     rate_locked: bool,
+    /// Largest single change in applied frequency (ppm)
+    max_adj_step_ppm: f64,
+    /// Loop iteration at which the controller first reported LOCK
+    lock_step: Option<usize>,
 }
 
 /// Run physics simulation with timing support for rate-based servo
@@ -193,7 +220,7 @@ fn run_simulation(
     drift_ppm: f64,
     duration_secs: usize,
 ) -> SimulationResult {
-    let physics = Arc::new(SharedPhysics {
+    let physics = Rc::new(SharedPhysics {
         engine: RefCell::new(PhysicsEngine::new(drift_ppm)),
     });
 
@@ -213,7 +240,7 @@ fn run_simulation(
 
     // Save window_size before config is moved into controller
     let window_size = config.filters.sample_window_size;
-    let mut controller = PtpController::new(clock, network, ntp, status, config);
+    let mut controller = PtpController::new(clock, network, ntp, status.clone(), config);
 
     let steps = duration_secs * 16; // 8 packets/sec * 2 (Sync+FU)
     let mut max_offset_steady = 0.0;
@@ -226,10 +253,15 @@ fn run_simulation(
     // Add timing delays for rate-based servo to work in simulation
     // The servo needs dt_secs > 0.1 between offset measurements
     let mut sample_count = 0;
+    let mut lock_step = None;
 
     for i in 0..steps {
         controller.process_loop_iteration().unwrap();
 
+        if lock_step.is_none() && status.read().unwrap().is_locked {
+            lock_step = Some(i);
+        }
+
         // Minimal delay at servo decision points (when sample window completes)
         sample_count += 1;
         if sample_count >= window_size * 2 {
@@ -268,6 +300,7 @@ fn run_simulation(
     };
     let max_rate = rates.iter().map(|r| r.abs()).fold(0.0f64, f64::max);
     let rate_locked = avg_rate.abs() < 5.0 && max_rate < 20.0;
+    let max_adj_step_ppm = physics.engine.borrow().max_adj_step_ppm;
 
     SimulationResult {
         final_offset_ns: final_offset,
@@ -275,6 +308,8 @@ fn run_simulation(
         avg_rate_us_per_s: avg_rate,
         max_rate_us_per_s: max_rate,
         rate_locked,
+        max_adj_step_ppm,
+        lock_step,
     }
 }
 
@@ -367,6 +402,84 @@ fn test_regression_high_gain_low_jitter() {
     // so this test may not show the same instability as before
 }
 
+/// Slew limiting: frequency ramps instead of jumping, and still converges
+#[test]
+fn test_slew_limit_shrinks_frequency_steps() {
+    let run = |max_step: f64| {
+        let mut config = SystemConfig::default();
+        config.filters.sample_window_size = 4;
+        config.filters.calibration_samples = 0;
+        config.filters.warmup_secs = 0.0;
+        config.max_ppm_step_per_sample = max_step;
+        // 20us jitter, 50ppm drift
+        run_simulation(config, 20_000.0, 50.0, 100)
+    };
+
+    let unlimited = run(0.0);
+    let limited = run(10.0);
+
+    println!(
+        "Slew: unlimited MaxStep={:.1}ppm Lock@{:?} AvgRate={:.2}us/s | limited MaxStep={:.1}ppm Lock@{:?} AvgRate={:.2}us/s",
+        unlimited.max_adj_step_ppm,
+        unlimited.lock_step,
+        unlimited.avg_rate_us_per_s,
+        limited.max_adj_step_ppm,
+        limited.lock_step,
+        limited.avg_rate_us_per_s
+    );
+
+    assert!(
+        limited.max_adj_step_ppm <= 10.0 + 1e-6,
+        "Frequency step {:.1}ppm exceeds the 10ppm limit",
+        limited.max_adj_step_ppm
+    );
+    assert!(unlimited.max_adj_step_ppm > limited.max_adj_step_ppm);
+
+    // Ramping must still converge. Lock time is only printed: with random
+    // jitter and wall-clock rate timing it varies several-fold between runs.
+    assert!(
+        limited.avg_rate_us_per_s.abs() < 20.0,
+        "Average drift rate {:.2}us/s too high with slew limiting",
+        limited.avg_rate_us_per_s
+    );
+}
+
+#[test]
+fn test_cold_start_locks_faster_on_large_drift() {
+    let run = |cold_start_secs: u64| {
+        let mut config = SystemConfig::default();
+        config.filters.sample_window_size = 4;
+        config.filters.calibration_samples = 0;
+        config.filters.warmup_secs = 0.0;
+        config.cold_start_secs = cold_start_secs;
+        // 10us jitter, 100ppm drift
+        run_simulation(config, 10_000.0, 100.0, 150)
+    };
+
+    let normal = run(0);
+    let cold = run(300);
+
+    println!(
+        "Cold start: normal Lock@{:?} AvgRate={:.2}us/s | cold Lock@{:?} AvgRate={:.2}us/s",
+        normal.lock_step, normal.avg_rate_us_per_s, cold.lock_step, cold.avg_rate_us_per_s
+    );
+
+    let cold_lock = cold.lock_step.expect("cold start never locked");
+    if let Some(normal_lock) = normal.lock_step {
+        assert!(
+            cold_lock < normal_lock,
+            "Cold start locked at step {}, normal acquisition at {}",
+            cold_lock,
+            normal_lock
+        );
+    }
+    assert!(
+        cold.avg_rate_us_per_s.abs() < 20.0,
+        "Average drift rate {:.2}us/s too high after cold start",
+        cold.avg_rate_us_per_s
+    );
+}
+
 /// Critical test: drift RATE must converge to stable (<5us/s = frequencies matched)
 /// NOTE: With rate-based servo, absolute offset doesn't need to be zero.
 /// Dante timestamps are device uptime, not UTC. NTP handles UTC alignment.
@@ -382,7 +495,7 @@ fn test_rate_convergence_stability() {
     config.filters.warmup_secs = 0.0;
     config.filters.min_delta_ns = 100_000_000; // 100ms - allow samples at Dante rate
 
-    let physics = Arc::new(SharedPhysics {
+    let physics = Rc::new(SharedPhysics {
         engine: RefCell::new(PhysicsEngine::new(20.0)), // 20ppm drift
     });
 
@@ -426,7 +539,7 @@ fn test_rate_convergence_stability() {
             rates.push(rate);
         }
 
-        if i < 5 || i > 195 {
+        if !(5..=195).contains(&i) {
             let rate_str = if rates.is_empty() {
                 "N/A".to_string()
             } else {
@@ -494,7 +607,7 @@ fn test_auto_adaptive_drift_rates() {
         config.filters.warmup_secs = 0.0;
         config.filters.min_delta_ns = 100_000_000;
 
-        let physics = Arc::new(SharedPhysics {
+        let physics = Rc::new(SharedPhysics {
             engine: RefCell::new(PhysicsEngine::new(drift_ppm)),
         });
 
@@ -569,7 +682,7 @@ fn test_ptp_rate_stable_during_ntp_drift() {
     config.filters.warmup_secs = 0.0;
     config.filters.min_delta_ns = 100_000_000;
 
-    let physics = Arc::new(SharedPhysics {
+    let physics = Rc::new(SharedPhysics {
         engine: RefCell::new(PhysicsEngine::new(20.0)),
     });
 
@@ -585,10 +698,8 @@ fn test_ptp_rate_stable_during_ntp_drift() {
     // Drifting NTP: simulates Dante running faster than NTP reference
     // This is normal - Dante is PTP-locked, not NTP-locked
     // NTP handles UTC alignment via stepping, PTP handles frequency
-    let ntp = DriftingNtp {
-        offset_us: std::cell::Cell::new(0),
-        drift_us_per_call: 1500, // 1.5ms drift per NTP check
-    };
+    // NTP offset grows because Dante frequency ≠ NTP reference: 1.5ms per check
+    let ntp = SimulatedNtpSource::new(0).with_drift(1_500_000);
     let clock = SimClockRef(physics.clone());
 
     let mut controller = PtpController::new(clock, network, ntp, status, config);
@@ -639,6 +750,149 @@ fn test_ptp_rate_stable_during_ntp_drift() {
     );
 }
 
+// ============================================================================
+// PTP OUTAGE E2E TEST
+// ============================================================================
+// PTP masters go silent: after PTP_TIMEOUT_SECS (10s) the controller falls
+// back to NTP-only stepping to keep UTC while holding the learned frequency.
+// When PTP returns, the preserved frequency re-locks without re-acquiring.
+// Runs in virtual time: advance_time() moves the controller's timers with the
+// physics instead of sleeping.
+// ============================================================================
+
+/// Full failover cycle: PTP lock → outage with NTP-only stepping → PTP returns
+#[test]
+fn test_ptp_outage_ntp_fallback_and_relock() {
+    // One loop iteration (Sync or Follow_Up) = half the 125ms Sync interval
+    const TICK: Duration = Duration::from_micros(62_500);
+    const TICKS_PER_SEC: usize = 16;
+    const OUTAGE_SECS: usize = 120;
+    // UTC pulls away from the silent master: 50us/s, 6ms over the outage
+    const UTC_DRIFT_NS_PER_S: f64 = 50_000.0;
+
+    let mut config = SystemConfig::default();
+    config.filters.sample_window_size = 4;
+    config.filters.calibration_samples = 0;
+    config.filters.warmup_secs = 0.0;
+
+    let physics = Rc::new(SharedPhysics {
+        engine: RefCell::new(PhysicsEngine::new(20.0)),
+    });
+    let status = Arc::new(RwLock::new(SyncStatus::default()));
+    let network = StatefulNetwork {
+        physics: physics.clone(),
+        jitter_sigma_ns: 1_000.0,
+        seq: 0,
+        pending_followup: None,
+    };
+    let ntp = UtcNtp {
+        physics: physics.clone(),
+    };
+    let clock = SimClockRef(physics.clone());
+    let mut controller = PtpController::new(clock, network, ntp, status.clone(), config);
+
+    let ptp_locked = |status: &Arc<RwLock<SyncStatus>>| {
+        let s = status.read().unwrap();
+        s.is_locked && s.mode != "NTP-only"
+    };
+
+    // 1. Lock on PTP, then let the learned frequency settle
+    let mut lock_secs = None;
+    for i in 0..600 * TICKS_PER_SEC {
+        controller.process_loop_iteration().unwrap();
+        controller.advance_time(TICK);
+        if ptp_locked(&status) {
+            lock_secs = Some(i / TICKS_PER_SEC);
+            break;
+        }
+    }
+    let lock_secs = lock_secs.expect("never locked on PTP");
+    for _ in 0..60 * TICKS_PER_SEC {
+        controller.process_loop_iteration().unwrap();
+        controller.advance_time(TICK);
+    }
+    let learned_ppm = physics.engine.borrow().current_adj_ppm;
+
+    // 2. Masters go silent; NTP alone has to keep UTC
+    physics.engine.borrow_mut().ptp_outage = true;
+    let steps_before = physics.engine.borrow().clock_steps;
+    for i in 0..OUTAGE_SECS * TICKS_PER_SEC {
+        {
+            let mut phys = physics.engine.borrow_mut();
+            phys.advance(TICK.as_secs_f64());
+            phys.utc_offset_ns += UTC_DRIFT_NS_PER_S * TICK.as_secs_f64();
+        }
+        controller.process_loop_iteration().unwrap();
+        controller.advance_time(TICK);
+        if i > 11 * TICKS_PER_SEC {
+            assert_eq!(
+                status.read().unwrap().mode,
+                "NTP-only",
+                "{}s into the outage",
+                i / TICKS_PER_SEC
+            );
+        }
+    }
+    {
+        let phys = physics.engine.borrow();
+        let ntp_steps = phys.clock_steps - steps_before;
+        let utc_error_us = (phys.offset_ns + phys.step_offset_ns - phys.utc_offset_ns) / 1000.0;
+        println!(
+            "PTP outage: locked after {}s at {:+.2}ppm | {} NTP steps, UTC error {:+.0}us",
+            lock_secs, learned_ppm, ntp_steps, utc_error_us
+        );
+        assert!(
+            ntp_steps >= 2,
+            "NTP-only mode stepped {} times in {}s",
+            ntp_steps,
+            OUTAGE_SECS
+        );
+        // At most 30s (1.5ms) of UTC drift since the last step
+        assert!(
+            utc_error_us.abs() < 2_000.0,
+            "UTC error {:.0}us after the outage - NTP-only stepping did not keep up",
+            utc_error_us
+        );
+        assert!(
+            (phys.current_adj_ppm - learned_ppm).abs() < 0.01,
+            "Frequency moved from {:+.2}ppm to {:+.2}ppm without PTP",
+            learned_ppm,
+            phys.current_adj_ppm
+        );
+    }
+
+    // 3. PTP returns: the preserved frequency locks again at once
+    physics.engine.borrow_mut().ptp_outage = false;
+    let mut relock_secs = None;
+    let mut max_freq_dev_ppm = 0.0f64;
+    for i in 0..60 * TICKS_PER_SEC {
+        controller.process_loop_iteration().unwrap();
+        controller.advance_time(TICK);
+        let adj_ppm = physics.engine.borrow().current_adj_ppm;
+        max_freq_dev_ppm = max_freq_dev_ppm.max((adj_ppm - learned_ppm).abs());
+        if relock_secs.is_none() && ptp_locked(&status) {
+            relock_secs = Some(i / TICKS_PER_SEC);
+        }
+    }
+    println!(
+        "PTP returned: re-locked after {:?}s, max frequency deviation {:.2}ppm",
+        relock_secs, max_freq_dev_ppm
+    );
+    let relock_secs = relock_secs.expect("no re-lock after PTP returned");
+    assert!(
+        relock_secs <= 10,
+        "Re-lock took {}s - the learned frequency should make it immediate",
+        relock_secs
+    );
+    // Re-acquiring from scratch would swing through the full 20ppm
+    assert!(
+        max_freq_dev_ppm < 5.0,
+        "Frequency moved {:.2}ppm from the preserved {:+.2}ppm after PTP returned",
+        max_freq_dev_ppm,
+        learned_ppm
+    );
+}
+
 // ============================================================================
 // NANO MODE E2E TESTS
 // ============================================================================
@@ -659,7 +913,7 @@ fn test_nano_mode_achievable_with_low_jitter() {
     config.filters.min_delta_ns = 100_000_000;
 
     // Ultra-low jitter (1µs) and low drift (5ppm) - ideal for NANO mode
-    let physics = Arc::new(SharedPhysics {
+    let physics = Rc::new(SharedPhysics {
         engine: RefCell::new(PhysicsEngine::new(5.0)), // 5ppm drift
     });
 
@@ -722,7 +976,7 @@ fn test_high_jitter_affects_rate_variance() {
     config.filters.min_delta_ns = 100_000_000;
 
     // High jitter (500µs) - causes rate variance
-    let physics = Arc::new(SharedPhysics {
+    let physics = Rc::new(SharedPhysics {
         engine: RefCell::new(PhysicsEngine::new(20.0)), // 20ppm drift
     });
 
@@ -779,7 +1033,7 @@ fn test_high_jitter_affects_rate_variance() {
 
     // High jitter should produce measurable rate variance
     // (The exact variance depends on simulation timing, so we just verify it runs)
-    assert!(rates.len() > 0, "Should have collected rate samples");
+    assert!(!rates.is_empty(), "Should have collected rate samples");
 }
 
 /// Test mode stability during extended operation
@@ -794,7 +1048,7 @@ fn test_mode_stability_during_extended_operation() {
     config.filters.min_delta_ns = 100_000_000;
 
     // Moderate jitter for realistic simulation
-    let physics = Arc::new(SharedPhysics {
+    let physics = Rc::new(SharedPhysics {
         engine: RefCell::new(PhysicsEngine::new(15.0)), // 15ppm drift
     });
 